@@ -1,7 +1,7 @@
 //! Benchmarks for the Pell equation solver
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
-use pell991::{pell_min_solution, pell_solution_k, pell_solutions};
+use pell991::{all_solvers, pell_min_solution, pell_solution_k, pell_solutions, BENCHMARK_D_VALUES};
 
 fn bench_minimal_solutions(c: &mut Criterion) {
     let mut group = c.benchmark_group("minimal_solutions");
@@ -93,12 +93,29 @@ fn bench_large_d_values(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_algorithms(c: &mut Criterion) {
+    let mut group = c.benchmark_group("algorithms");
+    // The slowest solver takes hundreds of milliseconds on the longest period
+    group.sample_size(10);
+
+    for solver in all_solvers() {
+        for &d in &BENCHMARK_D_VALUES {
+            group.bench_with_input(BenchmarkId::new(solver.name(), d), &d, |b, &d| {
+                b.iter(|| solver.min_solution(black_box(d)))
+            });
+        }
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_minimal_solutions,
     bench_kth_solutions,
     bench_multiple_solutions,
     bench_solution_comparison,
-    bench_large_d_values
+    bench_large_d_values,
+    bench_algorithms
 );
 criterion_main!(benches);
\ No newline at end of file
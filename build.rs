@@ -0,0 +1,15 @@
+//! Generates the scaffolding of the `uniffi` feature from `src/pell991.udl`
+
+fn main() {
+    #[cfg(feature = "uniffi")]
+    {
+        use std::{env, fs, path::Path};
+
+        uniffi::generate_scaffolding("src/pell991.udl").expect("src/pell991.udl should be a valid interface definition");
+
+        // uniffi 0.28 writes `#[no_mangle]`, which edition 2024 requires to be marked unsafe
+        let path = Path::new(&env::var("OUT_DIR").expect("cargo sets OUT_DIR")).join("pell991.uniffi.rs");
+        let scaffolding = fs::read_to_string(&path).expect("the scaffolding was just generated");
+        fs::write(&path, scaffolding.replace("#[no_mangle]", "#[unsafe(no_mangle)]")).expect("OUT_DIR is writable");
+    }
+}
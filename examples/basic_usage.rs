@@ -1,6 +1,6 @@
 //! Basic usage example for the Pell equation solver library
 
-use pell991::{pell_min_solution, pell_solution_k, pell_solutions, verify_pell_solution};
+use pell991::{decimal_digits, pell_min_solution, pell_solution_k, pell_solutions, verify_pell_solution};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("=== Pell Equation Solver - Basic Usage ===\n");
@@ -42,7 +42,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     for k in 1..=3 {
         let (xk, yk) = pell_solution_k(d, &x1, &y1, k)?;
         println!("Solution {}: x = {} ({} digits), y = {} ({} digits)", 
-                 k, xk, xk.to_string().len(), yk, yk.to_string().len());
+                 k, xk, decimal_digits(&xk), yk, decimal_digits(&yk));
     }
     
     println!("\n=== All examples completed successfully! ===");
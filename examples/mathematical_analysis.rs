@@ -7,7 +7,14 @@ use pell991::{
     is_prime, 
     estimate_period_length,
     fundamental_discriminant,
-    PellSolutionIterator
+    decimal_digits,
+    analyze_d,
+    classify_d,
+    survey,
+    DFamily,
+    PellSolutionIterator,
+    SolveOptions,
+    SurveyRow,
 };
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -32,7 +39,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let est_period = estimate_period_length(d).unwrap_or(0);
             
             let (x, y) = pell_min_solution(d)?;
-            let solution_digits = x.to_string().len().max(y.to_string().len());
+            let solution_digits = decimal_digits(&x).max(decimal_digits(&y));
             let verified = verify_pell_solution(d, &x, &y);
             
             println!("{:>4} | {:>6} | {:>8} | {:>6} | {:>12} | {:>8}", 
@@ -46,6 +53,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     println!();
 
+    let report = analyze_d(991);
+    println!("Full report for D = 991:");
+    println!("  Period length:             {}", report.period.unwrap_or(0));
+    println!("  x² - 991·y² = -1 solvable: {}", report.negative_pell_solvable.unwrap_or(false));
+    println!("  Regulator:                 {:.6}", report.regulator.unwrap_or(0.0));
+    println!("  Solution digits (x, y):    ({}, {})", report.x_digits.unwrap_or(0), report.y_digits.unwrap_or(0));
+    println!("  Class number:              {}", report.class_number.unwrap_or(0));
+    println!();
+
     // Analysis 2: Solution growth patterns
     println!("📈 Analysis 2: Solution Growth Patterns (D=2)");
     println!("{}", "-".repeat(40));
@@ -61,8 +77,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     for (k, (x, y)) in iter.take(15).enumerate() {
         let k = k + 1;
-        let x_digits = x.to_string().len();
-        let y_digits = y.to_string().len();
+        let x_digits = decimal_digits(&x);
+        let y_digits = decimal_digits(&y);
         
         let x_growth = if prev_x_digits > 0 { 
             x_digits as f64 / prev_x_digits as f64 
@@ -83,74 +99,37 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     println!();
 
+    // One structured row per D drives the remaining tables
+    let rows = survey(2..=99, &SolveOptions::new())?;
+    let max_digits = |row: &SurveyRow| row.x_digits.max(row.y_digits);
+
     // Analysis 3: Prime vs Composite D values
     println!("🔢 Analysis 3: Prime vs Composite D Performance");
     println!("{}", "-".repeat(45));
-    
-    let mut prime_d_values = Vec::new();
-    let mut composite_d_values = Vec::new();
-    
-    for d in 2..100 {
-        if is_valid_pell_d(d) {
-            if is_prime(d) {
-                prime_d_values.push(d);
-            } else {
-                composite_d_values.push(d);
-            }
+
+    let (prime_rows, composite_rows): (Vec<&SurveyRow>, Vec<&SurveyRow>) = rows.iter().partition(|row| row.is_prime);
+    let average = |rows: &[&SurveyRow]| rows.iter().map(|row| max_digits(row) as f64).sum::<f64>() / rows.len() as f64;
+
+    for (label, group) in [("Prime", &prime_rows), ("Composite", &composite_rows)] {
+        println!("{} D values (first 10):", label);
+        for row in group.iter().take(10) {
+            println!("  D={:2}: {} digits, period {}, solved in {:?}", row.d, max_digits(row), row.period, row.solve_time);
         }
+        println!();
     }
-    
-    // Take first 10 of each for analysis
-    prime_d_values.truncate(10);
-    composite_d_values.truncate(10);
-    
-    println!("Prime D values (first 10):");
-    let mut prime_avg_digits = 0.0;
-    for &d in &prime_d_values {
-        let (x, y) = pell_min_solution(d)?;
-        let max_digits = x.to_string().len().max(y.to_string().len());
-        prime_avg_digits += max_digits as f64;
-        println!("  D={:2}: {} digits", d, max_digits);
-    }
-    prime_avg_digits /= prime_d_values.len() as f64;
-    
-    println!("\\nComposite D values (first 10):");
-    let mut composite_avg_digits = 0.0;
-    for &d in &composite_d_values {
-        let (x, y) = pell_min_solution(d)?;
-        let max_digits = x.to_string().len().max(y.to_string().len());
-        composite_avg_digits += max_digits as f64;
-        println!("  D={:2}: {} digits", d, max_digits);
-    }
-    composite_avg_digits /= composite_d_values.len() as f64;
-    
-    println!("\\nAverage solution size:");
-    println!("  Prime D values:     {:.1} digits", prime_avg_digits);
-    println!("  Composite D values: {:.1} digits", composite_avg_digits);
+
+    println!("Average solution size:");
+    println!("  Prime D values:     {:.1} digits", average(&prime_rows));
+    println!("  Composite D values: {:.1} digits", average(&composite_rows));
     println!();
 
     // Analysis 4: Special cases and patterns
     println!("🎯 Analysis 4: Special Cases and Mathematical Patterns");
     println!("{}", "-".repeat(50));
-    
-    // Check for D values of the form n² + 1
-    println!("D values of the form n² + 1:");
-    for n in 1..10 {
-        let d = n * n + 1;
-        if is_valid_pell_d(d) {
-            let (x, y) = pell_min_solution(d)?;
-            println!("  D={} ({}²+1): minimal solution has {} digits", 
-                     d, n, x.to_string().len().max(y.to_string().len()));
-        }
-    }
-    
-    println!("\\nD values of the form n² - 1:");
-    for n in 2..10 {
-        let d = n * n - 1;
-        if is_valid_pell_d(d) {
-            let (x, y) = pell_min_solution(d)?;
-            println!("  D={} ({}²-1): minimal solution has {} digits", 
-                     d, n, x.to_string().len().max(y.to_string().len()));
+
+    for row in &rows {
+        if let family @ (DFamily::SquarePlusOne { .. } | DFamily::SquareMinusOne { .. }) = classify_d(row.d) {
+            println!("  D={} ({}): minimal solution has {} digits", row.d, family, max_digits(row));
         }
     }
     println!();
@@ -158,31 +137,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Analysis 5: Relationship between D and solution complexity
     println!("📐 Analysis 5: D vs Solution Complexity Correlation");
     println!("{}", "-".repeat(45));
-    
-    let analysis_d_values: Vec<u64> = (2..50).filter(|&d| is_valid_pell_d(d)).collect();
-    let mut complexity_data = Vec::new();
-    
-    for &d in &analysis_d_values {
-        let (x, y) = pell_min_solution(d)?;
-        let complexity = x.to_string().len() + y.to_string().len();
-        complexity_data.push((d, complexity));
-    }
-    
-    // Sort by complexity
-    complexity_data.sort_by_key(|&(_, complexity)| complexity);
-    
+
+    let mut by_complexity: Vec<&SurveyRow> = rows.iter().filter(|row| row.d < 50).collect();
+    by_complexity.sort_by_key(|row| row.x_digits + row.y_digits);
+
     println!("Simplest solutions (lowest complexity):");
-    for &(d, complexity) in complexity_data.iter().take(5) {
-        println!("  D={:2}: total digits = {}", d, complexity);
+    for row in by_complexity.iter().take(5) {
+        println!("  D={:2}: total digits = {}, regulator = {:.3}", row.d, row.x_digits + row.y_digits, row.regulator);
     }
-    
-    println!("\\nMost complex solutions (highest complexity):");
-    for &(d, complexity) in complexity_data.iter().rev().take(5) {
-        println!("  D={:2}: total digits = {}", d, complexity);
+
+    println!("\nMost complex solutions (highest complexity):");
+    for row in by_complexity.iter().rev().take(5) {
+        println!("  D={:2}: total digits = {}, regulator = {:.3}", row.d, row.x_digits + row.y_digits, row.regulator);
     }
-    
-    let avg_complexity: f64 = complexity_data.iter().map(|(_, c)| *c as f64).sum::<f64>() / complexity_data.len() as f64;
-    println!("\\nAverage complexity: {:.1} total digits", avg_complexity);
+
+    let avg_complexity = by_complexity.iter().map(|row| (row.x_digits + row.y_digits) as f64).sum::<f64>() / by_complexity.len() as f64;
+    println!("\nAverage complexity: {:.1} total digits", avg_complexity);
     println!();
 
     // Analysis 6: Verification of mathematical properties
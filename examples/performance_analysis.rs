@@ -1,6 +1,6 @@
 //! Performance analysis example for different D values and solution generation methods
 
-use pell991::{pell_min_solution, pell_solution_k, pell_solutions};
+use pell991::{decimal_digits, pell_min_solution, pell_solution_k, pell_solutions};
 use std::time::Instant;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -20,7 +20,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let duration = start.elapsed();
         
         println!("D = {:4}: {:8.2}μs | Solution digits: x={}, y={}", 
-                 d, duration.as_micros(), x.to_string().len(), y.to_string().len());
+                 d, duration.as_micros(), decimal_digits(&x), decimal_digits(&y));
     }
     
     println!();
@@ -39,7 +39,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let duration = start.elapsed();
         
         println!("k = {:3}: {:8.2}μs | Solution digits: x={}, y={}", 
-                 k, duration.as_micros(), xk.to_string().len(), yk.to_string().len());
+                 k, duration.as_micros(), decimal_digits(&xk), decimal_digits(&yk));
     }
     
     println!();
@@ -86,8 +86,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     for &d in &memory_test_d_values {
         let (x, y) = pell_min_solution(d)?;
-        let x_bytes = x.to_string().len();
-        let y_bytes = y.to_string().len();
+        let x_bytes = decimal_digits(&x);
+        let y_bytes = decimal_digits(&y);
         let estimated_memory = (x_bytes + y_bytes) * 8; // Rough estimate
         
         println!("D = {:3}: ~{:6} bytes | x={} digits, y={} digits", 
@@ -105,8 +105,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     for &k in &growth_k_values {
         let (xk, yk) = pell_solution_k(d, &x1, &y1, k)?;
-        let x_len = xk.to_string().len();
-        let y_len = yk.to_string().len();
+        let x_len = decimal_digits(&xk);
+        let y_len = decimal_digits(&yk);
         
         let growth_factor = if prev_x_len > 0 { 
             x_len as f64 / prev_x_len as f64 
@@ -1,6 +1,6 @@
 //! Solve the 991 Pell Puzzle - Find the magical values that make √(991·n² + 1) a perfect integer
 
-use pell991::{pell_min_solution, verify_pell_solution};
+use pell991::{decimal_digits, pell_min_solution, verify_pell_solution};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🔢 The 991 Pell Puzzle Solver");
@@ -25,8 +25,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Show the magnitude
     println!();
     println!("📊 Magnitude of the discovery:");
-    println!("   n has {} digits", n.to_string().len());
-    println!("   m has {} digits", m.to_string().len());
+    println!("   n has {} digits", decimal_digits(&n));
+    println!("   m has {} digits", decimal_digits(&m));
     println!();
     
     // Explain what this means
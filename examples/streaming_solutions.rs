@@ -1,6 +1,6 @@
 //! Demonstration of the streaming solution iterator for memory-efficient processing
 
-use pell991::{PellSolutionIterator, verify_pell_solution};
+use pell991::{decimal_digits, PellSolutionIterator, verify_pell_solution};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🌊 Pell Equation Solver - Streaming Solutions");
@@ -25,23 +25,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("💾 Example 2: Memory-Efficient Large Sequence Processing");
     println!("{}", "-".repeat(50));
     
-    let mut iter = PellSolutionIterator::new(3)?;
     let mut max_digits = 0;
     let mut solution_count = 0;
     
-    // Process solutions until we find one with more than 20 digits
-    for (x, y) in iter.by_ref() {
+    // Process solutions until they grow beyond 20 digits
+    for (x, y) in PellSolutionIterator::new(3)?.take_while_digits(20) {
         solution_count += 1;
-        let digits = x.to_string().len().max(y.to_string().len());
+        let digits = decimal_digits(&x).max(decimal_digits(&y));
         max_digits = max_digits.max(digits);
         
-        if digits > 20 {
-            println!("Found solution with {} digits at k={}", digits, solution_count);
-            println!("  x = {}", x);
-            println!("  y = {}", y);
-            break;
-        }
-        
+        #[allow(clippy::if_same_then_else)] // the first ten, then every fifth
         if solution_count <= 10 {
             println!("  k={}: {} digits", solution_count, digits);
         } else if solution_count % 5 == 0 {
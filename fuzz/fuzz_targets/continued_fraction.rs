@@ -0,0 +1,23 @@
+//! The continued fraction expansion of √D for D below 2³²
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pell991::invariants::check_cf_invariants;
+use pell991::{continued_fraction, is_valid_pell_d, isqrt_u64};
+
+fuzz_target!(|d: u32| {
+    let d = u64::from(d);
+    let Ok(cf) = continued_fraction(d) else {
+        assert!(!is_valid_pell_d(d), "expansion of valid D = {d} failed");
+        return;
+    };
+
+    assert_eq!(cf.a0, isqrt_u64(d));
+    // Recovering D walks the convergents of one period, so skip the longest
+    if cf.period.len() <= 2_000 {
+        if let Err(err) = check_cf_invariants(&cf) {
+            panic!("√{d}: {err}");
+        }
+    }
+});
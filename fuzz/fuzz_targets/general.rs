@@ -0,0 +1,32 @@
+//! The generalized solver for x² - D·y² = N with small D and N
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use num_bigint::BigInt;
+use num_traits::Signed;
+use pell991::{is_valid_pell_d, pell_general_class_solutions, pell_general_fundamental_solutions, PellError};
+
+fuzz_target!(|input: (u16, i16)| {
+    let (d, n) = (u64::from(input.0), i64::from(input.1));
+    let classes = match pell_general_fundamental_solutions(d, n) {
+        Ok(classes) => classes,
+        Err(PellError::InvalidD(_) | PellError::PerfectSquare(_)) => return assert!(!is_valid_pell_d(d)),
+        Err(PellError::InvalidN(_)) => return assert_eq!(n, 0),
+        Err(err) => panic!("unexpected error for x² - {d}·y² = {n}: {err}"),
+    };
+
+    let (big_d, big_n) = (BigInt::from(d), BigInt::from(n));
+    for (x, y) in &classes {
+        assert!(!y.is_negative(), "fundamental solutions have y ≥ 0");
+        assert_eq!(x * x - &big_d * y * y, big_n, "({x}, {y}) does not solve x² - {d}·y² = {n}");
+
+        for (u, v) in pell_general_class_solutions(d, x, y, 3).unwrap() {
+            assert_eq!(&u * &u - &big_d * &v * &v, big_n, "class member ({u}, {v}) of x² - {d}·y² = {n}");
+        }
+    }
+    assert!(
+        classes.windows(2).all(|pair| (&pair[0].1, &pair[0].0) < (&pair[1].1, &pair[1].0)),
+        "classes are distinct and ordered by y, then x"
+    );
+});
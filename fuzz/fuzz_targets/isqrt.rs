@@ -0,0 +1,15 @@
+//! `isqrt_u64` and `is_square_u64` on every u64
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pell991::{is_square_u64, is_valid_pell_d, isqrt_u64};
+
+fuzz_target!(|n: u64| {
+    let root = isqrt_u64(n);
+    let (square, next_square) = (u128::from(root) * u128::from(root), (u128::from(root) + 1).pow(2));
+    assert!(square <= u128::from(n) && u128::from(n) < next_square, "isqrt({n}) = {root}");
+
+    assert_eq!(is_square_u64(n), square == u128::from(n), "is_square_u64({n})");
+    assert_eq!(is_valid_pell_d(n), n > 1 && square != u128::from(n), "is_valid_pell_d({n})");
+});
@@ -0,0 +1,24 @@
+//! The continued fraction solver on arbitrary D, with a step limit so huge
+//! periods give up instead of timing out
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pell991::invariants::check_solution_invariants;
+use pell991::{is_square_u64, is_valid_pell_d, pell_min_solution_limited, PellError};
+
+const MAX_STEPS: u64 = 20_000;
+
+fuzz_target!(|d: u64| {
+    match pell_min_solution_limited(d, MAX_STEPS) {
+        Ok(solution) => {
+            if let Err(err) = check_solution_invariants(d, &[solution]) {
+                panic!("D = {d}: {err}");
+            }
+        }
+        Err(PellError::InvalidD(bad)) => assert!(bad == d && d <= 1),
+        Err(PellError::PerfectSquare(bad)) => assert!(bad == d && d > 1 && is_square_u64(d)),
+        Err(PellError::LimitExceeded { steps }) => assert!(is_valid_pell_d(d) && steps == MAX_STEPS),
+        Err(err) => panic!("unexpected error for D = {d}: {err}"),
+    }
+});
@@ -0,0 +1,39 @@
+//! D within a small distance of a perfect square, up to the top of the u64
+//! range
+//!
+//! s² + c and s² - c for small c have short periods, so even the largest D
+//! solve quickly, exercising the 128-bit intermediate arithmetic and the
+//! perfect-square rejection at its boundary.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pell991::{continued_fraction, is_square_u64, pell_min_solution_limited, verify_pell_solution, PellError};
+
+const MAX_STEPS: u64 = 20_000;
+
+fuzz_target!(|input: (u32, i16)| {
+    let (root, offset) = input;
+    let Some(d) = (u64::from(root) * u64::from(root)).checked_add_signed(i64::from(offset)) else {
+        return;
+    };
+
+    match pell_min_solution_limited(d, MAX_STEPS) {
+        Ok((x, y)) => {
+            assert!(verify_pell_solution(d, &x, &y), "wrong solution for D = {d}");
+            // The period is no longer than the steps taken, so expanding it is cheap
+            let cf = continued_fraction(d).expect("a solved D has an expansion");
+            assert_eq!(cf.period.last(), Some(&(2 * cf.a0)), "period of √{d} must end in 2a₀");
+        }
+        Err(PellError::LimitExceeded { .. }) => {}
+        Err(PellError::PerfectSquare(_)) => {
+            assert!(is_square_u64(d));
+            assert_eq!(continued_fraction(d), Err(PellError::PerfectSquare(d)));
+        }
+        Err(PellError::InvalidD(_)) => {
+            assert!(d <= 1);
+            assert_eq!(continued_fraction(d), Err(PellError::InvalidD(d)));
+        }
+        Err(err) => panic!("unexpected error for D = {d}: {err}"),
+    }
+});
@@ -0,0 +1,26 @@
+//! Verification of arbitrary pairs: the exact, modular and D-inferring checks
+//! must agree
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use num_bigint::BigInt;
+use pell991::{verify_pell_solution, verify_pell_solution_mod, PellSolution};
+
+const PRIMES: [u64; 3] = [18_446_744_073_709_551_557, 18_446_744_073_709_551_533, 18_446_744_073_709_551_521];
+
+fuzz_target!(|input: (u64, u128, u128)| {
+    let (d, x, y) = input;
+    let (big_x, big_y) = (BigInt::from(x), BigInt::from(y));
+
+    let exact = big_x.pow(2) - BigInt::from(d) * big_y.pow(2) == BigInt::from(1);
+    assert_eq!(verify_pell_solution(d, &big_x, &big_y), exact, "verify({d}, {x}, {y})");
+    if exact {
+        assert!(verify_pell_solution_mod(d, &big_x, &big_y, &PRIMES), "a solution must verify modulo any prime");
+        if d > 1 && y > 0 {
+            let inferred = PellSolution::try_from((big_x, big_y)).expect("D is recoverable from a solution");
+            assert_eq!(inferred.d, d, "recovered D of ({x}, {y})");
+        }
+    }
+    assert_eq!(PellSolution::from((d, x, y)).verify(), exact);
+});
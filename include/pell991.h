@@ -0,0 +1,122 @@
+#ifndef PELL991_H
+#define PELL991_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Outcome of a call into the C interface
+typedef enum Pell991Status {
+  // The call succeeded and its out-pointers were written
+  PELL991_STATUS_OK = 0,
+  // D must be greater than 1
+  PELL991_STATUS_INVALID_D = 1,
+  // D must not be a perfect square
+  PELL991_STATUS_PERFECT_SQUARE = 2,
+  // k must be greater than 0
+  PELL991_STATUS_INVALID_K = 3,
+  // A pointer argument was null, or a string was not a decimal integer
+  PELL991_STATUS_INVALID_ARGUMENT = 4,
+  // Any other solver error
+  PELL991_STATUS_FAILED = 5,
+  // The library panicked; this is a bug
+  PELL991_STATUS_INTERNAL = 6,
+} Pell991Status;
+
+// The magnitude of an integer as 64-bit limbs, least significant first
+//
+// An empty array (`len` 0, `limbs` null) is zero.
+typedef struct Pell991Limbs {
+  // The limbs, owned by the library
+  uint64_t *limbs;
+  // Number of limbs
+  size_t len;
+} Pell991Limbs;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Find the fundamental solution of x² - D·y² = 1 as decimal strings
+//
+// On success `*x_out` and `*y_out` point to NUL-terminated strings to be
+// released with [`pell991_string_free`]; on failure they are set to null.
+//
+// # Safety
+//
+// `x_out` and `y_out` must each be null or valid for writing a pointer.
+enum Pell991Status pell991_min_solution(uint64_t d, char **x_out, char **y_out);
+
+// Find the k-th solution of x² - D·y² = 1 as decimal strings
+//
+// Strings are returned as in [`pell991_min_solution`].
+//
+// # Safety
+//
+// `x_out` and `y_out` must each be null or valid for writing a pointer.
+enum Pell991Status pell991_solution_k(uint64_t d, uint64_t k, char **x_out, char **y_out);
+
+// Find the fundamental solution of x² - D·y² = 1 as limb arrays
+//
+// On success `*x_out` and `*y_out` hold arrays to be released with
+// [`pell991_limbs_free`]; on failure they are set to empty arrays.
+//
+// # Safety
+//
+// `x_out` and `y_out` must each be null or valid for writing a `Pell991Limbs`.
+enum Pell991Status pell991_min_solution_limbs(uint64_t d,
+                                              struct Pell991Limbs *x_out,
+                                              struct Pell991Limbs *y_out);
+
+// Find the k-th solution of x² - D·y² = 1 as limb arrays
+//
+// Arrays are returned as in [`pell991_min_solution_limbs`].
+//
+// # Safety
+//
+// `x_out` and `y_out` must each be null or valid for writing a `Pell991Limbs`.
+enum Pell991Status pell991_solution_k_limbs(uint64_t d,
+                                            uint64_t k,
+                                            struct Pell991Limbs *x_out,
+                                            struct Pell991Limbs *y_out);
+
+// Check whether the decimal strings `x` and `y` satisfy x² - D·y² = 1
+//
+// Writes the answer to `*valid_out`.
+//
+// # Safety
+//
+// `x` and `y` must be null or NUL-terminated strings, and `valid_out` must be
+// null or valid for writing a `bool`.
+enum Pell991Status pell991_verify(uint64_t d, const char *x, const char *y, bool *valid_out);
+
+// Release a string returned by this library; null is ignored
+//
+// # Safety
+//
+// `s` must be null or a string returned by this library that has not been freed.
+void pell991_string_free(char *s);
+
+// Release a limb array returned by this library; empty arrays are ignored
+//
+// # Safety
+//
+// `limbs` must be empty or an array returned by this library that has not been freed.
+void pell991_limbs_free(struct Pell991Limbs limbs);
+
+// A static, NUL-terminated description of the status code `status`
+//
+// Takes a plain `int`, since C can pass any value where an enum is expected;
+// codes that are not a [`Pell991Status`] get "unknown status". The string
+// must not be freed.
+const char *pell991_status_message(int status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PELL991_H */
@@ -0,0 +1,256 @@
+//! Interchangeable Pell equation solving algorithms
+//!
+//! The [`Solver`] trait lets applications pick an algorithm at runtime, for
+//! example from a configuration value, and compare algorithms on the same inputs.
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use num_bigint::BigInt;
+use crate::bsgs::{pell_min_solution_bsgs, pell_min_solution_bsgs_with};
+use crate::chakravala::pell_min_solution_chakravala;
+use crate::error::PellError;
+use crate::half_period::pell_min_solution_half_period;
+use crate::solver::pell_min_solution;
+use crate::utils::estimate_period_length;
+
+/// Estimated period length from which [`AutoSolver`] switches from the
+/// half-period method to baby-step giant-step
+///
+/// In `compare_solvers` timings the two break even around D = 2 000 000 011
+/// (period 31 502, about 18 ms each), and BSGS is 1.7× faster at
+/// D = 10 000 000 019 (period 124 134). [`estimate_period_length`] is √D/2, so
+/// the switch comes at D ≈ 2.5·10⁹.
+const AUTO_BSGS_MIN_PERIOD: u64 = 25_000;
+
+/// An algorithm for finding the minimal solution of x² - D·y² = 1
+///
+/// Implementations are object safe, so they can be stored as `Box<dyn Solver>`
+/// and selected at runtime.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{solver_by_name, Solver};
+/// let solver = solver_by_name("chakravala").unwrap();
+/// let (x, y) = solver.min_solution(13).unwrap();
+/// assert_eq!((x.to_string(), y.to_string()), ("649".to_string(), "180".to_string()));
+/// ```
+pub trait Solver: Send + Sync {
+    /// Short, stable name of the algorithm (as accepted by [`solver_by_name`])
+    fn name(&self) -> &'static str;
+
+    /// Find the minimal positive solution (x, y) for the given D
+    ///
+    /// # Errors
+    ///
+    /// Returns `PellError::InvalidD` if `d` ≤ 1.
+    /// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+    fn min_solution(&self, d: u64) -> Result<(BigInt, BigInt), PellError>;
+}
+
+/// Continued fraction expansion of √D (see [`pell_min_solution`])
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CfSolver;
+
+impl Solver for CfSolver {
+    fn name(&self) -> &'static str {
+        "cf"
+    }
+
+    fn min_solution(&self, d: u64) -> Result<(BigInt, BigInt), PellError> {
+        pell_min_solution(d)
+    }
+}
+
+/// The continued fraction stopped at the middle of the period (see [`pell_min_solution_half_period`])
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HalfPeriodSolver;
+
+impl Solver for HalfPeriodSolver {
+    fn name(&self) -> &'static str {
+        "half-period"
+    }
+
+    fn min_solution(&self, d: u64) -> Result<(BigInt, BigInt), PellError> {
+        pell_min_solution_half_period(d)
+    }
+}
+
+/// The chakravala method (see [`pell_min_solution_chakravala`])
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChakravalaSolver;
+
+impl Solver for ChakravalaSolver {
+    fn name(&self) -> &'static str {
+        "chakravala"
+    }
+
+    fn min_solution(&self, d: u64) -> Result<(BigInt, BigInt), PellError> {
+        pell_min_solution_chakravala(d)
+    }
+}
+
+/// Baby-step giant-step in the infrastructure (see [`pell_min_solution_bsgs`])
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BsgsSolver {
+    baby_steps: Option<usize>,
+}
+
+impl BsgsSolver {
+    /// Create a solver using the default baby-step heuristic
+    pub fn new() -> Self {
+        BsgsSolver::default()
+    }
+
+    /// Use a fixed number of baby steps instead of the heuristic
+    ///
+    /// Values below the safe minimum for a given D are raised to that minimum.
+    pub fn with_baby_steps(baby_steps: usize) -> Self {
+        BsgsSolver { baby_steps: Some(baby_steps) }
+    }
+}
+
+impl Solver for BsgsSolver {
+    fn name(&self) -> &'static str {
+        "bsgs"
+    }
+
+    fn min_solution(&self, d: u64) -> Result<(BigInt, BigInt), PellError> {
+        match self.baby_steps {
+            Some(baby_steps) => pell_min_solution_bsgs_with(d, baby_steps),
+            None => pell_min_solution_bsgs(d),
+        }
+    }
+}
+
+/// Picks an algorithm per D, for callers who do not want to weigh the tradeoffs
+///
+/// Uses the half-period method while the estimated period is below 25 000
+/// (D up to about 2.5·10⁹), and baby-step giant-step beyond, where its
+/// sublinear number of large steps beats walking half the period. The plain
+/// continued fraction takes two to four times as long as the half-period
+/// method, and the chakravala method longer still, so neither is chosen.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_min_solution, AutoSolver, Solver};
+/// assert_eq!(AutoSolver.choose(991).name(), "half-period");
+/// assert_eq!(AutoSolver.choose(10_000_000_019).name(), "bsgs");
+/// assert_eq!(AutoSolver.min_solution(991), pell_min_solution(991));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AutoSolver;
+
+impl AutoSolver {
+    /// The solver used for `d`
+    pub fn choose(&self, d: u64) -> &'static dyn Solver {
+        match estimate_period_length(d) {
+            Some(period) if period >= AUTO_BSGS_MIN_PERIOD => &BsgsSolver { baby_steps: None },
+            _ => &HalfPeriodSolver,
+        }
+    }
+}
+
+impl Solver for AutoSolver {
+    fn name(&self) -> &'static str {
+        "auto"
+    }
+
+    fn min_solution(&self, d: u64) -> Result<(BigInt, BigInt), PellError> {
+        self.choose(d).min_solution(d)
+    }
+}
+
+/// Look up a solver by its name
+///
+/// # Arguments
+///
+/// * `name` - One of the names returned by [`Solver::name`], or `"auto"`
+///
+/// # Returns
+///
+/// The matching solver, or `None` if the name is unknown
+pub fn solver_by_name(name: &str) -> Option<Box<dyn Solver>> {
+    if name == AutoSolver.name() {
+        return Some(Box::new(AutoSolver));
+    }
+    all_solvers().into_iter().find(|solver| solver.name() == name)
+}
+
+/// D values exercising the algorithms at a range of period lengths, used by the
+/// `bench_algorithms` benchmarks
+///
+/// Ordered by period: 60, 458, 1506, 8096 and 31 502. The periods of nearby D
+/// vary widely, so these were picked for their periods rather than their size.
+pub const BENCHMARK_D_VALUES: [u64; 5] = [991, 1_000_003, 3_999_971, 9_999_991, 2_000_000_011];
+
+/// The time one solver took for one D
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolverTiming {
+    /// Name of the solver (see [`Solver::name`])
+    pub solver: &'static str,
+    /// The D that was solved
+    pub d: u64,
+    /// Wall time of the solve
+    pub elapsed: core::time::Duration,
+}
+
+/// Time every algorithm of [`all_solvers`] on each of `ds`
+///
+/// Each D is solved once per solver, so the timings are single measurements:
+/// good for comparing algorithms on the current machine, while the criterion
+/// benchmarks are the place for precise numbers. The continued fraction
+/// solver answers from the process-wide cache when it is enabled, so disable
+/// the cache first for a fair comparison.
+///
+/// # Arguments
+///
+/// * `ds` - The D values to solve, for example [`BENCHMARK_D_VALUES`]
+///
+/// # Returns
+///
+/// One timing per solver and D, grouped by D in the order of `ds`
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` or `PellError::PerfectSquare` for the first
+/// invalid D, without timing any further values.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::compare_solvers;
+/// let timings = compare_solvers(&[61, 991]).unwrap();
+/// assert_eq!(timings.len(), 2 * 4);
+/// let fastest = timings.iter().filter(|timing| timing.d == 991).min_by_key(|timing| timing.elapsed).unwrap();
+/// println!("fastest for 991: {} in {:?}", fastest.solver, fastest.elapsed);
+/// ```
+#[cfg(feature = "std")]
+pub fn compare_solvers(ds: &[u64]) -> Result<Vec<SolverTiming>, PellError> {
+    let solvers = all_solvers();
+    let mut timings = Vec::with_capacity(ds.len() * solvers.len());
+    for &d in ds {
+        for solver in &solvers {
+            let started = std::time::Instant::now();
+            solver.min_solution(d)?;
+            timings.push(SolverTiming { solver: solver.name(), d, elapsed: started.elapsed() });
+        }
+    }
+    Ok(timings)
+}
+
+/// All available algorithms, for comparisons and benchmarks
+///
+/// [`AutoSolver`] is not included, since it delegates to these.
+pub fn all_solvers() -> Vec<Box<dyn Solver>> {
+    vec![
+        Box::new(CfSolver),
+        Box::new(HalfPeriodSolver),
+        Box::new(ChakravalaSolver),
+        Box::new(BsgsSolver::new()),
+    ]
+}
@@ -0,0 +1,369 @@
+//! Structured analysis of D values
+
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use num_bigint::BigInt;
+
+use crate::estimate::log10_fundamental_unit;
+use crate::forms::{class_number, class_number_until};
+use crate::error::PellError;
+use crate::options::SolveOptions;
+use crate::solver::solve_cached;
+use crate::utils::{decimal_digits, is_prime, is_valid_pell_d, squarefree_part};
+
+/// The largest D whose class number [`analyze_d`] and [`regulator_table`] compute
+///
+/// Counting the ideal classes enumerates the reduced ideals in O(D) time,
+/// which takes a few hundredths of a second at this bound in a release build
+/// and grows linearly past it, to minutes around 10¹⁰, while the Pell equation
+/// of such a D often solves instantly.
+/// Above the bound the class number is left as `None`; call
+/// [`class_number`](crate::class_number) directly to compute it anyway.
+pub const CLASS_NUMBER_MAX_D: u64 = 10_000_000;
+
+/// A summary of the arithmetic of a D value and its Pell equation
+///
+/// Fields that only make sense for valid Pell D values (D > 1, non-square) are
+/// `None` when D is invalid.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DReport {
+    /// The analyzed value
+    pub d: u64,
+    /// Whether D > 1 and D is not a perfect square
+    pub is_valid: bool,
+    /// Whether D is prime
+    pub is_prime: bool,
+    /// The squarefree part s of D = s·f²
+    pub squarefree_part: u64,
+    /// Length of the period of the continued fraction of √D
+    pub period: Option<u64>,
+    /// Whether x² - D·y² = -1 has a solution (exactly when the period is odd)
+    pub negative_pell_solvable: Option<bool>,
+    /// The regulator ln ε of Z[√D], where ε is the fundamental unit
+    pub regulator: Option<f64>,
+    /// Decimal digits of x in the fundamental solution of x² - D·y² = 1
+    pub x_digits: Option<u64>,
+    /// Decimal digits of y in the fundamental solution of x² - D·y² = 1
+    pub y_digits: Option<u64>,
+    /// The class number of Z[√D], for D up to [`CLASS_NUMBER_MAX_D`]
+    pub class_number: Option<u64>,
+}
+
+/// Analyze a D value in one call
+///
+/// Bundles the properties commonly tabulated for Pell equations: validity,
+/// primality, squarefree part, exact period, solvability of the negative Pell
+/// equation, regulator, the size of the fundamental solution, and class number
+/// (for D ≤ [`CLASS_NUMBER_MAX_D`]).
+///
+/// # Arguments
+///
+/// * `d` - The value to analyze
+///
+/// # Returns
+///
+/// A `DReport`. Invalid D values produce a report with `is_valid` set to false
+/// and the Pell-specific fields set to `None`.
+///
+/// # Performance
+///
+/// Solves the Pell equation for D and, up to [`CLASS_NUMBER_MAX_D`], counts the
+/// cycles of reduced ideals, so the cost is dominated by whichever of those is
+/// larger for the given D.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::analyze_d;
+/// let report = analyze_d(991);
+/// assert!(report.is_prime);
+/// assert_eq!(report.period, Some(60));
+/// assert_eq!(report.negative_pell_solvable, Some(false));
+/// assert_eq!(report.x_digits, Some(30));
+/// assert_eq!(report.class_number, Some(1));
+/// ```
+pub fn analyze_d(d: u64) -> DReport {
+    analyze_d_with_options(d, &SolveOptions::default(), CLASS_NUMBER_MAX_D).expect("no limits are set")
+}
+
+/// Analyze a D value, solving its Pell equation under the limits of `options`
+///
+/// Invalid D values still produce a report, as in [`analyze_d`]; only the
+/// limits of `options` make this fail. The class number is computed for
+/// D ≤ `class_number_max_d`, and the timeout of `options` covers it as well
+/// as the solve, so the whole report fits in the deadline.
+pub(crate) fn analyze_d_with_options(
+    d: u64,
+    options: &SolveOptions,
+    class_number_max_d: u64,
+) -> Result<DReport, PellError> {
+    let check_deadline = deadline(options);
+    let mut report = basic_report(d);
+
+    let ((x, y), period) = match solve_cached(d, options) {
+        Ok(solved) => solved,
+        Err(PellError::InvalidD(_) | PellError::PerfectSquare(_)) => return Ok(report),
+        Err(err) => return Err(err),
+    };
+
+    add_solution(&mut report, &x, &y, period);
+    if d <= class_number_max_d {
+        report.class_number = Some(class_number_until(d, check_deadline)?);
+    }
+
+    Ok(report)
+}
+
+/// [`analyze_d`] from the already computed fundamental solution (x₁, y₁) and
+/// the period of √D
+///
+/// Skips solving the Pell equation again; the pair and period are trusted as
+/// given. Invalid D values produce the same report as in [`analyze_d`].
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{analyze_d, analyze_d_with_fundamental, pell_min_solution_with_period};
+/// let ((x1, y1), period) = pell_min_solution_with_period(991).unwrap();
+/// assert_eq!(analyze_d_with_fundamental(991, &x1, &y1, period), analyze_d(991));
+/// ```
+pub fn analyze_d_with_fundamental(d: u64, x1: &BigInt, y1: &BigInt, period: u64) -> DReport {
+    let mut report = basic_report(d);
+    if report.is_valid {
+        add_solution(&mut report, x1, y1, period);
+        if d <= CLASS_NUMBER_MAX_D {
+            report.class_number = Some(class_number(d).expect("D is valid"));
+        }
+    }
+    report
+}
+
+/// The report of the properties every D has, with the Pell-specific fields unset
+fn basic_report(d: u64) -> DReport {
+    DReport {
+        d,
+        is_valid: is_valid_pell_d(d),
+        is_prime: is_prime(d),
+        squarefree_part: squarefree_part(d),
+        period: None,
+        negative_pell_solvable: None,
+        regulator: None,
+        x_digits: None,
+        y_digits: None,
+        class_number: None,
+    }
+}
+
+/// Fill in the fields that follow from the fundamental solution and period
+fn add_solution(report: &mut DReport, x: &BigInt, y: &BigInt, period: u64) {
+    report.period = Some(period);
+    report.negative_pell_solvable = Some(period % 2 == 1);
+    report.regulator = Some(regulator(x, period));
+    report.x_digits = Some(decimal_digits(x));
+    report.y_digits = Some(decimal_digits(y));
+}
+
+/// A check failing with `PellError::Timeout` once the timeout of `options`
+/// has passed since the call
+#[cfg(feature = "std")]
+fn deadline(options: &SolveOptions) -> impl Fn() -> Result<(), PellError> + use<> {
+    let started = std::time::Instant::now();
+    let timeout = options.timeout;
+    move || match timeout {
+        Some(limit) if started.elapsed() >= limit => Err(PellError::Timeout { limit }),
+        _ => Ok(()),
+    }
+}
+
+/// A check that always passes, as there is no clock without `std`
+#[cfg(not(feature = "std"))]
+fn deadline(_options: &SolveOptions) -> impl Fn() -> Result<(), PellError> + use<> {
+    || Ok(())
+}
+
+/// The regulator ln ε from the fundamental solution x and the period of √D
+pub(crate) fn regulator(x: &BigInt, period: u64) -> f64 {
+    let log_solution = log10_fundamental_unit(x) * core::f64::consts::LN_10;
+    // With an odd period the fundamental solution is the square of the unit
+    if period % 2 == 1 { log_solution / 2.0 } else { log_solution }
+}
+
+/// One row of a [`survey`]: a valid D, the size of its fundamental solution,
+/// and how long solving took
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SurveyRow {
+    /// The coefficient D
+    pub d: u64,
+    /// Whether D is prime
+    pub is_prime: bool,
+    /// The squarefree part s of D = s·f²
+    pub squarefree_part: u64,
+    /// Length of the period of the continued fraction of √D
+    pub period: u64,
+    /// Decimal digits of x in the fundamental solution
+    pub x_digits: u64,
+    /// Decimal digits of y in the fundamental solution
+    pub y_digits: u64,
+    /// The regulator ln ε of Z[√D], estimated from the leading digits of x
+    pub regulator: f64,
+    /// Wall-clock time taken to solve the Pell equation for D
+    pub solve_time: std::time::Duration,
+}
+
+/// Survey every valid D in `range`: period, solution size and solving time
+///
+/// The structured form of the tables in the `mathematical_analysis` example,
+/// one row per D, ready to be serialized (with the `serde` feature) or
+/// plotted. D ≤ 1 and perfect squares are skipped. The D values are solved
+/// one at a time, so the timings are not skewed by competing threads; with
+/// a solution cache enabled, cached D are timed as cache hits.
+///
+/// # Arguments
+///
+/// * `range` - The D values to survey
+/// * `options` - Limits applied to each solve
+///
+/// # Returns
+///
+/// A `Result` containing one `SurveyRow` per valid D in increasing order, or
+/// the `PellError` of the first solve that exceeded the limits of `options`.
+///
+/// # Errors
+///
+/// Returns `PellError::LimitExceeded` or `PellError::Timeout` if solving
+/// some D exceeds the limits of `options`.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{survey, SolveOptions};
+/// let rows = survey(2..=1000, &SolveOptions::new()).unwrap();
+/// let widest = rows.iter().max_by_key(|row| row.x_digits).unwrap();
+/// assert_eq!((widest.d, widest.x_digits), (661, 38));
+/// ```
+#[cfg(feature = "std")]
+pub fn survey(range: RangeInclusive<u64>, options: &SolveOptions) -> Result<Vec<SurveyRow>, PellError> {
+    range
+        .filter(|&d| is_valid_pell_d(d))
+        .map(|d| {
+            let started = std::time::Instant::now();
+            let ((x, y), period) = solve_cached(d, options)?;
+            let solve_time = started.elapsed();
+            Ok(SurveyRow {
+                d,
+                is_prime: is_prime(d),
+                squarefree_part: squarefree_part(d),
+                period,
+                x_digits: decimal_digits(&x),
+                y_digits: decimal_digits(&y),
+                regulator: regulator(&x, period),
+                solve_time,
+            })
+        })
+        .collect()
+}
+
+/// One row of a regulator table
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RegulatorRow {
+    /// The coefficient D
+    pub d: u64,
+    /// The regulator ln ε of Z[√D]
+    pub regulator: f64,
+    /// Length of the period of the continued fraction of √D
+    pub period: u64,
+    /// The class number of Z[√D], for D up to [`CLASS_NUMBER_MAX_D`]
+    pub class_number: Option<u64>,
+}
+
+/// Tabulate the regulator, period and class number for every valid D in `range`
+///
+/// Class numbers are only computed for D ≤ [`CLASS_NUMBER_MAX_D`], as in
+/// [`analyze_d`], and are `None` above it.
+///
+/// D ≤ 1 and perfect squares are skipped; the rows are in increasing D. With
+/// the `rayon` feature the D values are processed concurrently. Write the
+/// table with [`write_regulator_csv`], or as Parquet with the `parquet`
+/// feature.
+///
+/// # Arguments
+///
+/// * `range` - The D values to tabulate
+///
+/// # Returns
+///
+/// One `RegulatorRow` per valid D
+///
+/// # Performance
+///
+/// Each row solves the Pell equation and, for D ≤ [`CLASS_NUMBER_MAX_D`],
+/// counts the ideal classes, as [`analyze_d`] does.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::regulator_table;
+/// let table = regulator_table(2..=10);
+/// assert_eq!(table.len(), 7);
+/// // ε = 1 + √2, so R = ln(1 + √2)
+/// assert!((table[0].regulator - 0.881374).abs() < 1e-6);
+/// assert_eq!((table[0].period, table[0].class_number), (1, Some(1)));
+/// ```
+pub fn regulator_table(range: RangeInclusive<u64>) -> Vec<RegulatorRow> {
+    let row = |d| {
+        let ((x, _), period) = solve_cached(d, &SolveOptions::default()).expect("only valid D are tabulated");
+        let class_number = (d <= CLASS_NUMBER_MAX_D)
+            .then(|| class_number(d).expect("only valid D are tabulated"));
+        RegulatorRow { d, regulator: regulator(&x, period), period, class_number }
+    };
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        range.into_par_iter().filter(|&d| is_valid_pell_d(d)).map(row).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        range.filter(|&d| is_valid_pell_d(d)).map(row).collect()
+    }
+}
+
+/// Write a regulator table to `w` as CSV
+///
+/// The header is `d,regulator,period,class_number`, followed by one line per
+/// row. Regulators are written with full `f64` precision, and a missing class
+/// number as an empty field.
+///
+/// # Errors
+///
+/// Returns any error from writing to `w`.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{regulator_table, write_regulator_csv};
+/// let mut csv = Vec::new();
+/// write_regulator_csv(&regulator_table(2..=3), &mut csv).unwrap();
+/// let csv = String::from_utf8(csv).unwrap();
+/// let lines: Vec<&str> = csv.lines().collect();
+/// assert_eq!(lines[0], "d,regulator,period,class_number");
+/// assert!(lines[1].starts_with("2,0.88137358") && lines[1].ends_with(",1,1"));
+/// assert_eq!(lines.len(), 3);
+/// ```
+#[cfg(feature = "std")]
+pub fn write_regulator_csv<W: std::io::Write>(rows: &[RegulatorRow], mut w: W) -> std::io::Result<()> {
+    writeln!(w, "d,regulator,period,class_number")?;
+    for RegulatorRow { d, regulator, period, class_number } in rows {
+        let class_number = class_number.map(|h| h.to_string()).unwrap_or_default();
+        writeln!(w, "{d},{regulator},{period},{class_number}")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//! Leading digits of the solutions and Benford's law
+//!
+//! xₖ = (εᵏ + ε⁻ᵏ)/2 with ε = x₁ + y₁√D, so log₁₀ xₖ is k·log₁₀ ε - log₁₀ 2
+//! up to a vanishing term. Since log₁₀ ε is irrational, the fractional parts
+//! of these logarithms are equidistributed, and the leading digits of xₖ
+//! follow Benford's law P(j) = log₁₀(1 + 1/j): 30.1% ones, 17.6% twos, down to
+//! 4.6% nines. Once xₖ outgrows a `u64`, the digits are read off the
+//! logarithms, so no large xₖ is ever expanded.
+//!
+//! # Examples
+//!
+//! ```
+//! # use pell991::benford::leading_digit_distribution;
+//! let stats = leading_digit_distribution(991, 10_000).unwrap();
+//! assert_eq!(stats.total, 10_000);
+//! assert!((stats.buckets[0].observed - 0.301).abs() < 0.01);
+//! assert!(stats.total_variation < 0.01);
+//! ```
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use num_traits::ToPrimitive;
+
+use crate::error::PellError;
+use crate::estimate::{log10_fundamental_unit, log10_solution_k};
+use crate::solver::PellSolutionIterator;
+
+/// Observed and expected frequency of one leading digit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DigitBucket {
+    /// The leading digit j, from 1 to 9
+    pub digit: u8,
+    /// How many xₖ start with j
+    pub count: u64,
+    /// `count` as a fraction of all xₖ counted
+    pub observed: f64,
+    /// The Benford probability of j
+    pub expected: f64,
+}
+
+/// The leading digits of x₁, …, xₙ, compared against Benford's law
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenfordStats {
+    /// Number of solutions counted
+    pub total: u64,
+    /// One bucket per leading digit 1, …, 9
+    pub buckets: [DigitBucket; 9],
+    /// Pearson's χ² statistic of the counts, with 8 degrees of freedom
+    pub chi_squared: f64,
+    /// Total variation distance ½·Σ|observed - expected| from the law
+    pub total_variation: f64,
+}
+
+/// The Benford probability log₁₀(1 + 1/j) of the leading digit j
+///
+/// # Panics
+///
+/// Panics if `digit` is not in 1..=9.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::benford::benford_probability;
+/// assert!((benford_probability(1) - 0.30103).abs() < 1e-5);
+/// ```
+pub fn benford_probability(digit: u8) -> f64 {
+    assert!((1..=9).contains(&digit), "leading digits are 1 to 9");
+    (1.0 / f64::from(digit)).ln_1p() / core::f64::consts::LN_10
+}
+
+/// The leading digit of a positive number given its log₁₀
+fn leading_digit(log10: f64) -> u8 {
+    let mantissa = 10f64.powf(log10 - log10.floor());
+    (mantissa.floor() as u8).clamp(1, 9)
+}
+
+/// The leading digit of a positive integer
+fn leading_digit_exact(mut n: u64) -> u8 {
+    while n >= 10 {
+        n /= 10;
+    }
+    n as u8
+}
+
+/// Count the leading digits of x₁, …, xₙ and compare them with Benford's law
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation (must be > 1 and non-square)
+/// * `n` - Number of solutions to count, starting from k = 1
+///
+/// # Returns
+///
+/// A `Result` containing the `BenfordStats`, or a `PellError` if D is invalid.
+/// For n = 0 every count and both statistics are 0.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Accuracy
+///
+/// The xₖ that fit in a `u64` are read exactly. Beyond them only the
+/// fractional part of k·log₁₀ ε matters, and it keeps about
+/// 16 - log₁₀(k·log₁₀ ε) correct digits, so a digit can come out wrong only
+/// when xₖ starts with a long run of nines or of zeros after its first digit.
+pub fn leading_digit_distribution(d: u64, n: u64) -> Result<BenfordStats, PellError> {
+    let mut iter = PellSolutionIterator::new(d)?;
+    let log10_unit = log10_fundamental_unit(iter.current().0);
+
+    // The logarithm of a small xₖ can land just below an integer power, as
+    // log₁₀ 8 does, so those are read exactly
+    let mut counts = [0u64; 9];
+    let mut k = 1;
+    while k <= n {
+        let Some(x) = iter.current().0.to_u64() else { break };
+        counts[usize::from(leading_digit_exact(x) - 1)] += 1;
+        iter.advance();
+        k += 1;
+    }
+    for k in k..=n {
+        let (log10_x, _) = log10_solution_k(d, log10_unit, k);
+        counts[usize::from(leading_digit(log10_x) - 1)] += 1;
+    }
+
+    let buckets = core::array::from_fn(|i| {
+        let digit = i as u8 + 1;
+        DigitBucket {
+            digit,
+            count: counts[i],
+            observed: if n == 0 { 0.0 } else { counts[i] as f64 / n as f64 },
+            expected: benford_probability(digit),
+        }
+    });
+    let (mut chi_squared, mut total_variation) = (0.0, 0.0);
+    if n > 0 {
+        for bucket in &buckets {
+            let expected_count = bucket.expected * n as f64;
+            chi_squared += (bucket.count as f64 - expected_count).powi(2) / expected_count;
+            total_variation += (bucket.observed - bucket.expected).abs() / 2.0;
+        }
+    }
+    Ok(BenfordStats { total: n, buckets, chi_squared, total_variation })
+}
@@ -0,0 +1,10 @@
+//! Generates the Kotlin and Swift bindings of the `uniffi` feature
+//!
+//! ```text
+//! cargo run --features uniffi-bindgen --bin uniffi-bindgen -- \
+//!     generate src/pell991.udl --language kotlin --out-dir bindings
+//! ```
+
+fn main() {
+    uniffi::uniffi_bindgen_main()
+}
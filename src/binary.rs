@@ -0,0 +1,214 @@
+//! Compact binary encoding of solutions
+//!
+//! Decimal text takes about 2.4 bytes per 64 bits of a coordinate and has to
+//! be converted in both directions. The binary form stores x and y as their
+//! raw 64-bit limbs instead, so encoding and decoding are plain copies and a
+//! solution with millions of digits can be cached or sent to another process
+//! at its in-memory size.
+//!
+//! # Layout
+//!
+//! All integers are little-endian, and every field starts at a multiple of 8
+//! bytes, so a buffer that is 8-byte aligned can have its limbs read in place.
+//!
+//! | Offset | Size | Field |
+//! |--------|------|-------|
+//! | 0 | 4 | magic `PELL` |
+//! | 4 | 1 | format version, currently 1 |
+//! | 5 | 1 | flags: bit 0 k present, bit 1 x negative, bit 2 y negative |
+//! | 6 | 2 | reserved, zero |
+//! | 8 | 8 | D |
+//! | 16 | 8 | k, zero when absent |
+//! | 24 | 8 | number of limbs of \|x\| |
+//! | 32 | 8 | number of limbs of \|y\| |
+//! | 40 | 8·n | limbs of \|x\|, least significant first |
+//! | … | 8·m | limbs of \|y\|, least significant first |
+
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+use crate::error::PellError;
+use crate::solution::PellSolution;
+
+/// First bytes of every encoded solution
+const MAGIC: [u8; 4] = *b"PELL";
+
+/// Version of the layout written by [`encode_solution`]
+const VERSION: u8 = 1;
+
+/// Size in bytes of everything before the limbs
+const HEADER_LEN: usize = 40;
+
+const FLAG_K: u8 = 1;
+const FLAG_X_NEGATIVE: u8 = 1 << 1;
+const FLAG_Y_NEGATIVE: u8 = 1 << 2;
+
+/// Encode a solution in the compact binary layout
+///
+/// # Arguments
+///
+/// * `solution` - The solution to encode
+///
+/// # Returns
+///
+/// The encoded bytes: a 40-byte header followed by the limbs of x and y.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{decode_solution, encode_solution, pell_min_solution, pell_solution_k, PellSolution};
+/// let (x1, y1) = pell_min_solution(991).unwrap();
+/// let (x, y) = pell_solution_k(991, &x1, &y1, 100).unwrap();
+/// let solution = PellSolution::with_index(991, 100, x, y);
+///
+/// let bytes = encode_solution(&solution);
+/// assert!(bytes.len() < solution.to_string().len() / 2);
+/// assert_eq!(decode_solution(&bytes).unwrap(), solution);
+/// ```
+pub fn encode_solution(solution: &PellSolution) -> Vec<u8> {
+    let x_limbs = solution.x.magnitude().iter_u64_digits();
+    let y_limbs = solution.y.magnitude().iter_u64_digits();
+    let (x_len, y_len) = (x_limbs.len(), y_limbs.len());
+
+    let mut flags = 0;
+    if solution.k.is_some() {
+        flags |= FLAG_K;
+    }
+    if solution.x.sign() == Sign::Minus {
+        flags |= FLAG_X_NEGATIVE;
+    }
+    if solution.y.sign() == Sign::Minus {
+        flags |= FLAG_Y_NEGATIVE;
+    }
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + 8 * (x_len + y_len));
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&[VERSION, flags, 0, 0]);
+    bytes.extend_from_slice(&solution.d.to_le_bytes());
+    bytes.extend_from_slice(&solution.k.unwrap_or(0).to_le_bytes());
+    bytes.extend_from_slice(&(x_len as u64).to_le_bytes());
+    bytes.extend_from_slice(&(y_len as u64).to_le_bytes());
+    for limb in x_limbs.chain(y_limbs) {
+        bytes.extend_from_slice(&limb.to_le_bytes());
+    }
+    bytes
+}
+
+/// Decode a solution written by [`encode_solution`]
+///
+/// The pair is not verified; use [`PellSolution::verify`] for that.
+///
+/// # Errors
+///
+/// Returns `PellError::Decode` if `bytes` is not exactly one encoded solution
+/// of a supported version.
+pub fn decode_solution(bytes: &[u8]) -> Result<PellSolution, PellError> {
+    SolutionView::new(bytes).map(|view| view.to_solution())
+}
+
+/// A borrowed view of an encoded solution
+///
+/// Reads the header and locates the limbs without copying them, so large
+/// encoded solutions can be inspected (D, k, sizes) or have their limbs handed
+/// on without building `BigInt`s.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{encode_solution, PellSolution, SolutionView};
+/// let solution: PellSolution = "x=17, y=12, d=2, k=2".parse().unwrap();
+/// let bytes = encode_solution(&solution);
+///
+/// let view = SolutionView::new(&bytes).unwrap();
+/// assert_eq!((view.d(), view.k()), (2, Some(2)));
+/// assert_eq!(view.x_limbs(), 17u64.to_le_bytes());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolutionView<'a> {
+    d: u64,
+    k: Option<u64>,
+    flags: u8,
+    x_limbs: &'a [u8],
+    y_limbs: &'a [u8],
+}
+
+impl<'a> SolutionView<'a> {
+    /// Check the header of `bytes` and locate the limbs
+    ///
+    /// # Errors
+    ///
+    /// Returns `PellError::Decode` if the magic, version or reserved bytes are
+    /// wrong, or if the length of `bytes` does not match the limb counts.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, PellError> {
+        let header = bytes
+            .get(..HEADER_LEN)
+            .ok_or_else(|| PellError::Decode(format!("{} bytes is shorter than the {HEADER_LEN}-byte header", bytes.len())))?;
+        if header[..4] != MAGIC {
+            return Err(PellError::Decode("missing PELL magic".to_string()));
+        }
+        if header[4] != VERSION {
+            return Err(PellError::Decode(format!("unsupported version {}", header[4])));
+        }
+        let flags = header[5];
+        if flags & !(FLAG_K | FLAG_X_NEGATIVE | FLAG_Y_NEGATIVE) != 0 || header[6..8] != [0, 0] {
+            return Err(PellError::Decode("reserved bits are set".to_string()));
+        }
+
+        let d = read_u64(header, 8);
+        let k = read_u64(header, 16);
+        let limb_bytes = |offset| usize::try_from(read_u64(header, offset)).ok().and_then(|len| len.checked_mul(8));
+        let (x_bytes, y_bytes) = limb_bytes(24)
+            .zip(limb_bytes(32))
+            .ok_or_else(|| PellError::Decode("limb count overflows".to_string()))?;
+
+        let body = &bytes[HEADER_LEN..];
+        if x_bytes.checked_add(y_bytes) != Some(body.len()) {
+            return Err(PellError::Decode(format!(
+                "expected {x_bytes} + {y_bytes} bytes of limbs, found {}",
+                body.len()
+            )));
+        }
+        let (x_limbs, y_limbs) = body.split_at(x_bytes);
+
+        Ok(SolutionView { d, k: (flags & FLAG_K != 0).then_some(k), flags, x_limbs, y_limbs })
+    }
+
+    /// The coefficient D
+    pub fn d(&self) -> u64 {
+        self.d
+    }
+
+    /// The solution index, if it was recorded
+    pub fn k(&self) -> Option<u64> {
+        self.k
+    }
+
+    /// The limbs of |x| as little-endian bytes, least significant limb first
+    pub fn x_limbs(&self) -> &'a [u8] {
+        self.x_limbs
+    }
+
+    /// The limbs of |y| as little-endian bytes, least significant limb first
+    pub fn y_limbs(&self) -> &'a [u8] {
+        self.y_limbs
+    }
+
+    /// Build the owned solution
+    pub fn to_solution(&self) -> PellSolution {
+        let x = signed(self.x_limbs, self.flags & FLAG_X_NEGATIVE != 0);
+        let y = signed(self.y_limbs, self.flags & FLAG_Y_NEGATIVE != 0);
+        PellSolution { d: self.d, x, y, k: self.k }
+    }
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().expect("8 bytes"))
+}
+
+fn signed(limbs: &[u8], negative: bool) -> BigInt {
+    let sign = if negative { Sign::Minus } else { Sign::Plus };
+    BigInt::from_biguint(sign, BigUint::from_bytes_le(limbs))
+}
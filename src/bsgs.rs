@@ -0,0 +1,335 @@
+//! Baby-step giant-step solving in the infrastructure of Z[√D]
+//!
+//! The reduced principal ideals of Z[√D] form a cycle whose length is the period
+//! of the continued fraction of √D. Each ideal carries a generator γ with
+//! a = γ·Z[√D], and walking once around the cycle multiplies γ by the fundamental
+//! unit. Instead of walking the whole cycle, Shanks' baby-step giant-step method
+//! stores a short stretch of it (baby steps) and then jumps ahead by multiplying
+//! ideals (giant steps) until it lands back in the stored stretch. The quotient of
+//! the two generators is then the fundamental unit.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use crate::error::PellError;
+use crate::solver::validate_d;
+use crate::utils::isqrt_u64;
+
+/// Solve the Pell equation x² - D·y² = 1 with baby-step giant-step in the infrastructure.
+///
+/// For long continued fraction periods this needs roughly the square root of the
+/// number of big multiplications of the continued fraction method. Short periods
+/// are finished during the baby steps, where it behaves like the continued
+/// fraction method.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation (must be > 1 and non-square)
+///
+/// # Returns
+///
+/// A `Result` containing the minimal solution `(x, y)`, or a `PellError` if the
+/// input is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+/// Returns `PellError::LimitExceeded` if the giant steps go past the largest
+/// regulator possible for D without finding the unit, which would be a bug.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_min_solution, pell_min_solution_bsgs};
+/// assert_eq!(pell_min_solution_bsgs(991).unwrap(), pell_min_solution(991).unwrap());
+/// ```
+pub fn pell_min_solution_bsgs(d: u64) -> Result<(BigInt, BigInt), PellError> {
+    pell_min_solution_bsgs_with(d, default_baby_steps(d))
+}
+
+/// Baby-step giant-step with an explicit number of baby steps
+///
+/// The count is raised to a safe minimum that guarantees giant steps cannot
+/// jump over the stored stretch of the cycle.
+pub(crate) fn pell_min_solution_bsgs_with(d: u64, baby_steps: usize) -> Result<(BigInt, BigInt), PellError> {
+    validate_d(d)?;
+
+    let infra = Infrastructure::new(d);
+    let baby_steps = baby_steps.max(min_baby_steps(d));
+
+    // Baby steps: walk the principal cycle with machine integers, recording each
+    // reduced ideal and the logarithm of its generator
+    let mut table: BTreeMap<(u64, u64), usize> = BTreeMap::new();
+    let mut distances = Vec::with_capacity(baby_steps + 1);
+    table.insert((infra.root, 1), 0);
+    distances.push(0.0);
+
+    let sqrt_d = (d as f64).sqrt();
+    let (mut p, mut q) = (0u64, 1u64);
+    let mut distance = 0.0;
+    for step in 1..=baby_steps {
+        let (p_next, q_next) = infra.rho_small(p, q);
+        distance += ((p_next as f64 + sqrt_d) / q as f64).ln();
+        (p, q) = (p_next, q_next);
+
+        if (p, q) == (infra.root, 1) {
+            // The whole period fits in the baby steps
+            let unit = infra.cycle_generator(step);
+            return Ok(infra.unit_to_solution(unit));
+        }
+        table.insert((p, q), step);
+        distances.push(distance);
+    }
+
+    // Giant step: the ideal at about half the stored distance, so that every giant
+    // step (plus the drift from reduction) stays shorter than the stored stretch
+    let half = distances[baby_steps] / 2.0;
+    let giant_index = distances.iter().rposition(|&dist| dist <= half).unwrap_or(1).max(1);
+    let giant_ideal = infra.cycle_ideal(giant_index);
+    let giant_generator = infra.cycle_generator(giant_index);
+
+    let mut current = giant_ideal.clone();
+    let mut generator = giant_generator.clone();
+
+    // Hua's bound h·R < √Δ·(ln Δ / 2 + 1) with Δ = 4D caps the regulator, and the
+    // drift from reduction takes back well under half of each giant step
+    let discriminant = 4.0 * d as f64;
+    let max_regulator = discriminant.sqrt() * (discriminant.ln() / 2.0 + 1.0);
+    let max_giant_steps = (4.0 * max_regulator / half) as u64 + 4;
+
+    for _ in 0..max_giant_steps {
+        let (ideal, content) = infra.multiply(&current, &giant_ideal);
+        generator = generator.mul(&giant_generator, &infra.big_d).div_integer(&content);
+        current = ideal;
+        infra.reduce(&mut current, &mut generator);
+
+        let key = current.key();
+        if let Some(&index) = key.and_then(|key| table.get(&key)) {
+            let baby_generator = infra.cycle_generator(index);
+            let unit = generator.div_exact(&baby_generator, &infra.big_d);
+            // A match before wrapping around the cycle only finds the trivial unit
+            if !unit.b.is_zero() {
+                return Ok(infra.unit_to_solution(unit));
+            }
+        }
+    }
+    Err(PellError::LimitExceeded { steps: max_giant_steps })
+}
+
+/// Heuristic baby-step count: about the fourth root of D, balancing baby and giant work
+fn default_baby_steps(d: u64) -> usize {
+    4 * isqrt_u64(isqrt_u64(d)) as usize
+}
+
+/// Minimum baby-step count for which giant steps cannot skip the stored stretch
+///
+/// Reducing a product of ideals shifts the distance by at most a few multiples
+/// of ln D, while every two continued fraction steps advance it by at least ln 2.
+fn min_baby_steps(d: u64) -> usize {
+    let log_d = 64 - d.leading_zeros() as usize;
+    16 * log_d + 64
+}
+
+/// An element a + b√D of Z[√D]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct QuadraticInteger {
+    a: BigInt,
+    b: BigInt,
+}
+
+impl QuadraticInteger {
+    fn new(a: BigInt, b: BigInt) -> Self {
+        QuadraticInteger { a, b }
+    }
+
+    fn mul(&self, other: &QuadraticInteger, d: &BigInt) -> QuadraticInteger {
+        count!(bigint_multiplications += 5);
+        QuadraticInteger {
+            a: &self.a * &other.a + d * &self.b * &other.b,
+            b: &self.a * &other.b + &self.b * &other.a,
+        }
+    }
+
+    /// Divide by an integer that is known to divide both components
+    fn div_integer(self, divisor: &BigInt) -> QuadraticInteger {
+        if divisor.is_one() {
+            return self;
+        }
+        debug_assert!((&self.a % divisor).is_zero() && (&self.b % divisor).is_zero());
+        QuadraticInteger { a: self.a / divisor, b: self.b / divisor }
+    }
+
+    /// Divide by another element when the quotient is known to lie in Z[√D]
+    fn div_exact(&self, other: &QuadraticInteger, d: &BigInt) -> QuadraticInteger {
+        let norm = &other.a * &other.a - d * &other.b * &other.b;
+        count!(bigint_multiplications += 3);
+        let conjugate = QuadraticInteger::new(other.a.clone(), -&other.b);
+        self.mul(&conjugate, d).div_integer(&norm)
+    }
+}
+
+/// A primitive ideal [Q, P + √D] of Z[√D] with Q > 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Ideal {
+    q: BigInt,
+    p: BigInt,
+}
+
+impl Ideal {
+    /// Hash key of a reduced ideal, whose P and Q always fit in machine integers
+    fn key(&self) -> Option<(u64, u64)> {
+        Some((self.p.to_u64()?, self.q.to_u64()?))
+    }
+}
+
+/// Arithmetic on the ideals of Z[√D] for a fixed D
+struct Infrastructure {
+    d: u64,
+    root: u64,
+    big_d: BigInt,
+    big_root: BigInt,
+}
+
+impl Infrastructure {
+    fn new(d: u64) -> Self {
+        let root = isqrt_u64(d);
+        Infrastructure { d, root, big_d: BigInt::from(d), big_root: BigInt::from(root) }
+    }
+
+    /// One continued fraction step on a reduced ideal, in machine integers
+    fn rho_small(&self, p: u64, q: u64) -> (u64, u64) {
+        let a = (p + self.root) / q;
+        let p_next = a * q - p;
+        let q_next = ((self.d as u128 - (p_next as u128) * (p_next as u128)) / q as u128) as u64;
+        count!(cf_steps += 1);
+        (p_next, q_next)
+    }
+
+    /// The reduced ideal reached after `steps` steps around the principal cycle
+    fn cycle_ideal(&self, steps: usize) -> Ideal {
+        let (mut p, mut q) = (0u64, 1u64);
+        for _ in 0..steps {
+            (p, q) = self.rho_small(p, q);
+        }
+        Ideal { q: BigInt::from(q), p: BigInt::from(p) }
+    }
+
+    /// The generator pₙ₋₁ + qₙ₋₁√D of the ideal after n steps around the principal cycle
+    fn cycle_generator(&self, steps: usize) -> QuadraticInteger {
+        let (mut p_prev, mut p) = (BigInt::zero(), BigInt::one());
+        let (mut q_prev, mut q) = (BigInt::one(), BigInt::zero());
+        let (mut m, mut den) = (0u64, 1u64);
+
+        for _ in 0..steps {
+            let a = BigInt::from((m + self.root) / den);
+            let p_next = &a * &p + &p_prev;
+            let q_next = &a * &q + &q_prev;
+            count!(bigint_multiplications += 2);
+            p_prev = core::mem::replace(&mut p, p_next);
+            q_prev = core::mem::replace(&mut q, q_next);
+            (m, den) = self.rho_small(m, den);
+        }
+
+        QuadraticInteger::new(p, q)
+    }
+
+    /// Multiply two ideals, returning the primitive part and the content C with a·b = C·[Q, P + √D]
+    fn multiply(&self, first: &Ideal, second: &Ideal) -> (Ideal, BigInt) {
+        let (q1, p1) = (&first.q, &first.p);
+        let (q2, p2) = (&second.q, &second.p);
+
+        // The product is spanned by Q₁Q₂, Q₁(P₂ + √D), Q₂(P₁ + √D) and (P₁ + √D)(P₂ + √D).
+        // Its Hermite normal form is Z·A + Z·(B + C√D).
+        let sum = p1 + p2;
+        let (g, u1, u2) = extended_gcd(q1, q2);
+        let (c, v, w) = extended_gcd(&g, &sum);
+
+        let generators = [
+            (q1 * p2, q1.clone()),
+            (q2 * p1, q2.clone()),
+            (p1 * p2 + &self.big_d, sum.clone()),
+        ];
+        let b = &v * &u1 * &generators[0].0 + &v * &u2 * &generators[1].0 + &w * &generators[2].0;
+
+        let mut a = q1 * q2;
+        for (rational, irrational) in &generators {
+            a = a.gcd(&(rational - (irrational / &c) * &b));
+        }
+
+        debug_assert!((&a % &c).is_zero() && (&b % &c).is_zero());
+        let q = a / &c;
+        let p = (b / &c).mod_floor(&q);
+        (Ideal { q, p }, c)
+    }
+
+    /// Apply ρ until the ideal is reduced, keeping the generator in step
+    fn reduce(&self, ideal: &mut Ideal, generator: &mut QuadraticInteger) {
+        while !self.is_reduced(ideal) {
+            self.rho(ideal, generator);
+        }
+    }
+
+    /// Reduced means 0 < P < √D and √D - P < Q < √D + P
+    fn is_reduced(&self, ideal: &Ideal) -> bool {
+        ideal.p.is_positive()
+            && ideal.p <= self.big_root
+            && &ideal.q + &ideal.p > self.big_root
+            && &ideal.q - &ideal.p <= self.big_root
+    }
+
+    /// One reduction step: a' = ((P' + √D)/Q)·a with P' ≡ -P (mod Q) just below √D
+    fn rho(&self, ideal: &mut Ideal, generator: &mut QuadraticInteger) {
+        let a = (&ideal.p + &self.big_root).div_floor(&ideal.q);
+        let p_next = a * &ideal.q - &ideal.p;
+        let q_next = (&self.big_d - &p_next * &p_next) / &ideal.q;
+        count!(cf_steps += 1, bigint_multiplications += 2);
+
+        let factor = QuadraticInteger::new(p_next.clone(), BigInt::one());
+        *generator = generator.mul(&factor, &self.big_d).div_integer(&ideal.q);
+
+        ideal.p = p_next;
+        ideal.q = q_next.abs();
+    }
+
+    /// Turn the fundamental unit (of norm ±1) into the minimal solution of the +1 equation
+    fn unit_to_solution(&self, unit: QuadraticInteger) -> (BigInt, BigInt) {
+        let x = unit.a.abs();
+        let y = unit.b.abs();
+        let norm = &x * &x - &self.big_d * &y * &y;
+        if norm.is_one() {
+            (x, y)
+        } else {
+            // Norm -1: the square is the fundamental solution of x² - D·y² = 1
+            (&x * &x + &self.big_d * &y * &y, BigInt::from(2) * &x * &y)
+        }
+    }
+}
+
+/// Extended Euclid on BigInts, returning (g, s, t) with s·a + t·b = g ≥ 0
+fn extended_gcd(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
+    let (mut old_r, mut r) = (a.clone(), b.clone());
+    let (mut old_s, mut s) = (BigInt::one(), BigInt::zero());
+    let (mut old_t, mut t) = (BigInt::zero(), BigInt::one());
+
+    while !r.is_zero() {
+        let quotient = old_r.div_floor(&r);
+        let next_r = &old_r - &quotient * &r;
+        let next_s = &old_s - &quotient * &s;
+        let next_t = &old_t - &quotient * &t;
+        old_r = core::mem::replace(&mut r, next_r);
+        old_s = core::mem::replace(&mut s, next_s);
+        old_t = core::mem::replace(&mut t, next_t);
+    }
+
+    if old_r.is_negative() {
+        (-old_r, -old_s, -old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
@@ -0,0 +1,236 @@
+//! LRU caching of fundamental solutions
+//!
+//! Solving for a hard D can take a long time, while every other solution for that
+//! D follows cheaply from the fundamental one. A [`SolutionCache`] remembers the
+//! fundamental solutions (and continued fraction periods) of the most recently
+//! used D values.
+//!
+//! A cache can be owned by the caller, or installed process-wide with
+//! [`enable_solution_cache`]; while the process-wide cache is enabled,
+//! `pell_min_solution`, `pell_solutions`, `PellSolutionIterator` and the other
+//! functions built on the continued fraction solver consult it automatically.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use num_bigint::BigInt;
+use crate::error::PellError;
+use crate::solver::pell_min_solution_with_period;
+
+/// A fundamental solution together with the period of the continued fraction of √D
+type Entry = ((BigInt, BigInt), u64);
+
+/// The process-wide cache, `None` until enabled
+static GLOBAL_CACHE: Mutex<Option<SolutionCache>> = Mutex::new(None);
+
+/// Whether the process-wide cache is enabled with a non-zero capacity
+///
+/// Lets the solvers skip the mutex entirely while there is nothing to consult.
+static GLOBAL_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// A least-recently-used cache of fundamental solutions keyed by D
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::SolutionCache;
+/// let mut cache = SolutionCache::new(16);
+/// let first = cache.get_or_solve(991).unwrap();
+/// let second = cache.get_or_solve(991).unwrap(); // served from the cache
+/// assert_eq!(first, second);
+/// assert_eq!((cache.hits(), cache.misses()), (1, 1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SolutionCache {
+    capacity: usize,
+    entries: HashMap<u64, (Entry, u64)>,
+    /// D values by the clock tick of their last use, oldest first
+    recency: BTreeMap<u64, u64>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl SolutionCache {
+    /// Create an empty cache holding at most `capacity` D values
+    ///
+    /// A capacity of 0 disables caching: every lookup misses.
+    pub fn new(capacity: usize) -> Self {
+        SolutionCache {
+            capacity,
+            entries: HashMap::with_capacity(capacity),
+            recency: BTreeMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Maximum number of D values kept
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of D values currently cached
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache holds no solutions
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of lookups answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Number of lookups that had to solve
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Whether the fundamental solution for `d` is cached (does not update recency)
+    pub fn contains(&self, d: u64) -> bool {
+        self.entries.contains_key(&d)
+    }
+
+    /// Remove all cached solutions and reset the hit and miss counters
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    /// Look up the fundamental solution for `d`, marking it as recently used
+    ///
+    /// # Returns
+    ///
+    /// The cached minimal solution `(x, y)`, or `None` if `d` is not cached
+    pub fn get(&mut self, d: u64) -> Option<(BigInt, BigInt)> {
+        self.get_with_period(d).map(|(solution, _)| solution)
+    }
+
+    /// Look up the fundamental solution and continued fraction period for `d`
+    pub fn get_with_period(&mut self, d: u64) -> Option<Entry> {
+        self.clock += 1;
+        match self.entries.get_mut(&d) {
+            Some((entry, last_used)) => {
+                self.recency.remove(last_used);
+                self.recency.insert(self.clock, d);
+                *last_used = self.clock;
+                self.hits += 1;
+                Some(entry.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store the fundamental solution and period for `d`
+    ///
+    /// Evicts the least recently used D when the cache is full.
+    pub fn insert(&mut self, d: u64, solution: (BigInt, BigInt), period: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&d) {
+            if let Some((_, oldest)) = self.recency.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.clock += 1;
+        if let Some((_, last_used)) = self.entries.insert(d, ((solution, period), self.clock)) {
+            self.recency.remove(&last_used);
+        }
+        self.recency.insert(self.clock, d);
+    }
+
+    /// Return the cached fundamental solution for `d`, solving and caching it on a miss
+    ///
+    /// # Errors
+    ///
+    /// Returns `PellError::InvalidD` if `d` ≤ 1.
+    /// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+    pub fn get_or_solve(&mut self, d: u64) -> Result<(BigInt, BigInt), PellError> {
+        self.get_or_solve_with_period(d).map(|(solution, _)| solution)
+    }
+
+    /// Like [`get_or_solve`](Self::get_or_solve), also returning the period
+    ///
+    /// # Errors
+    ///
+    /// Returns `PellError::InvalidD` if `d` ≤ 1.
+    /// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+    pub fn get_or_solve_with_period(&mut self, d: u64) -> Result<Entry, PellError> {
+        if let Some(entry) = self.get_with_period(d) {
+            return Ok(entry);
+        }
+        let (solution, period) = pell_min_solution_with_period(d)?;
+        self.insert(d, solution.clone(), period);
+        Ok((solution, period))
+    }
+}
+
+/// Install a process-wide cache holding up to `capacity` fundamental solutions
+///
+/// Replaces (and empties) any previously enabled process-wide cache. With a
+/// capacity of 0 the solvers skip the cache without locking it.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{disable_solution_cache, enable_solution_cache, pell_min_solution};
+/// enable_solution_cache(128);
+/// pell_min_solution(991).unwrap(); // solved and cached
+/// pell_min_solution(991).unwrap(); // instant
+/// disable_solution_cache();
+/// ```
+pub fn enable_solution_cache(capacity: usize) {
+    let mut global = lock_global();
+    *global = Some(SolutionCache::new(capacity));
+    GLOBAL_ACTIVE.store(capacity > 0, Ordering::Release);
+}
+
+/// Remove the process-wide cache, releasing its solutions
+pub fn disable_solution_cache() {
+    let mut global = lock_global();
+    *global = None;
+    GLOBAL_ACTIVE.store(false, Ordering::Release);
+}
+
+/// A snapshot of the process-wide cache, or `None` if it is disabled
+pub fn solution_cache_snapshot() -> Option<SolutionCache> {
+    lock_global().clone()
+}
+
+/// Look up `d` in the process-wide cache, if enabled
+pub(crate) fn global_get(d: u64) -> Option<Entry> {
+    if !GLOBAL_ACTIVE.load(Ordering::Acquire) {
+        return None;
+    }
+    lock_global().as_mut().and_then(|cache| cache.get_with_period(d))
+}
+
+/// Store a freshly computed solution in the process-wide cache, if enabled
+pub(crate) fn global_insert(d: u64, solution: &(BigInt, BigInt), period: u64) {
+    if !GLOBAL_ACTIVE.load(Ordering::Acquire) {
+        return;
+    }
+    if let Some(cache) = lock_global().as_mut() {
+        cache.insert(d, solution.clone(), period);
+    }
+}
+
+/// Lock the process-wide cache, recovering from a poisoned lock
+///
+/// The cache only holds plain data, so a panic while it was locked cannot leave
+/// it in a state that matters to later callers.
+fn lock_global() -> std::sync::MutexGuard<'static, Option<SolutionCache>> {
+    GLOBAL_CACHE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
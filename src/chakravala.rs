@@ -0,0 +1,101 @@
+//! The chakravala (cyclic) method for Pell equations
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::ToPrimitive;
+use crate::error::PellError;
+use crate::solver::validate_d;
+use crate::utils::isqrt_u64;
+
+/// Solve the Pell equation x² - D·y² = 1 with the chakravala method.
+///
+/// The chakravala method of Jayadeva and Bhāskara II maintains a triple (a, b, k)
+/// with a² - D·b² = k and repeatedly composes it with (m, 1, m² - D), choosing m
+/// so that the result stays integral and |k| stays small. It terminates at k = 1
+/// with the minimal solution.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation (must be > 1 and non-square)
+///
+/// # Returns
+///
+/// A `Result` containing the minimal solution `(x, y)`, or a `PellError` if the
+/// input is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigInt;
+/// # use pell991::pell_min_solution_chakravala;
+///
+/// // Bhāskara's classic example
+/// let (x, y) = pell_min_solution_chakravala(61).unwrap();
+/// assert_eq!(x, BigInt::from(1766319049u64));
+/// assert_eq!(y, BigInt::from(226153980u64));
+/// ```
+pub fn pell_min_solution_chakravala(d: u64) -> Result<(BigInt, BigInt), PellError> {
+    validate_d(d)?;
+
+    let d_i = d as i128;
+    let root = isqrt_u64(d) as i128;
+
+    // Start from the integer nearest √D
+    let start = if (root + 1) * (root + 1) - d_i < d_i - root * root { root + 1 } else { root };
+    let mut a = BigInt::from(start);
+    let mut b = BigInt::from(1);
+    let mut k = start * start - d_i;
+
+    let big_d = BigInt::from(d);
+
+    while k != 1 {
+        let abs_k = k.abs();
+        let m = choose_m(&a, &b, abs_k, d_i, root);
+
+        let big_m = BigInt::from(m);
+        let big_abs_k = BigInt::from(abs_k);
+        let a_next = (&a * &big_m + &big_d * &b) / &big_abs_k;
+        let b_next = (&a + &b * &big_m) / &big_abs_k;
+        count!(cf_steps += 1, bigint_multiplications += 3);
+
+        a = a_next;
+        b = b_next;
+        k = (m * m - d_i) / k;
+    }
+
+    Ok((a, b))
+}
+
+/// Choose m > 0 with a + b·m ≡ 0 (mod |k|) minimising |m² - D|
+fn choose_m(a: &BigInt, b: &BigInt, abs_k: i128, d: i128, root: i128) -> i128 {
+    // a + b·m ≡ 0 (mod |k|) ⇔ m ≡ -a·b⁻¹ (mod |k|); gcd(b, k) = 1 throughout the method
+    let modulus = BigInt::from(abs_k);
+    let a_mod = a.mod_floor(&modulus).to_i128().unwrap_or(0);
+    let b_mod = b.mod_floor(&modulus).to_i128().unwrap_or(0);
+    let residue = (-a_mod * mod_inverse(b_mod, abs_k)).rem_euclid(abs_k);
+
+    // Candidates in the residue class on either side of √D
+    let below = root - (root - residue).rem_euclid(abs_k);
+    let above = below + abs_k;
+
+    if below >= 1 && (d - below * below).abs() <= (above * above - d).abs() {
+        below
+    } else {
+        above
+    }
+}
+
+/// Inverse of `value` modulo `modulus` (which must be coprime to it)
+fn mod_inverse(value: i128, modulus: i128) -> i128 {
+    if modulus == 1 {
+        return 0;
+    }
+    let gcd = value.extended_gcd(&modulus);
+    debug_assert_eq!(gcd.gcd, 1, "b must be invertible modulo k");
+    gcd.x.rem_euclid(modulus)
+}
@@ -0,0 +1,279 @@
+//! Arrow record batches and Parquet files of solutions and D reports
+//!
+//! Results in columnar form load straight into pandas, Polars, DuckDB or
+//! Spark. Solutions become a table with columns `d`, `k`, `x` and `y`;
+//! [`DReport`]s become one row per D with a column per field.
+//!
+//! x and y outgrow every Arrow integer type, so they are stored either as
+//! decimal strings or as binary, see [`BigIntEncoding`].
+
+#[cfg(feature = "parquet")]
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow_array::builder::{BinaryBuilder, StringBuilder};
+use arrow_array::{ArrayRef, BooleanArray, Float64Array, RecordBatch, UInt64Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use num_bigint::BigInt;
+
+use crate::analysis::{DReport, RegulatorRow};
+use crate::solution::PellSolution;
+
+/// How the x and y columns store big integers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BigIntEncoding {
+    /// Decimal strings (`Utf8`), readable by any tool
+    #[default]
+    Decimal,
+    /// Two's complement bytes, least significant first (`Binary`), as written
+    /// by `BigInt::to_signed_bytes_le`; about 2.4 times smaller than decimal
+    Binary,
+}
+
+impl BigIntEncoding {
+    fn data_type(self) -> DataType {
+        match self {
+            BigIntEncoding::Decimal => DataType::Utf8,
+            BigIntEncoding::Binary => DataType::Binary,
+        }
+    }
+
+    fn column<'a>(self, values: impl ExactSizeIterator<Item = &'a BigInt>) -> ArrayRef {
+        match self {
+            BigIntEncoding::Decimal => {
+                let mut builder = StringBuilder::with_capacity(values.len(), 0);
+                for value in values {
+                    builder.append_value(value.to_string());
+                }
+                Arc::new(builder.finish())
+            }
+            BigIntEncoding::Binary => {
+                let mut builder = BinaryBuilder::with_capacity(values.len(), 0);
+                for value in values {
+                    builder.append_value(value.to_signed_bytes_le());
+                }
+                Arc::new(builder.finish())
+            }
+        }
+    }
+}
+
+/// Schema of the batches built by [`solutions_to_record_batch`]
+///
+/// `d` is a non-null `UInt64`, `k` a nullable `UInt64`, and `x` and `y` are
+/// non-null columns of the type chosen by `encoding`.
+pub fn solutions_schema(encoding: BigIntEncoding) -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("d", DataType::UInt64, false),
+        Field::new("k", DataType::UInt64, true),
+        Field::new("x", encoding.data_type(), false),
+        Field::new("y", encoding.data_type(), false),
+    ]))
+}
+
+/// Build an Arrow record batch with one row per solution
+///
+/// # Arguments
+///
+/// * `solutions` - The rows, in order
+/// * `encoding` - How to store x and y
+///
+/// # Returns
+///
+/// A `Result` containing a batch with the schema of [`solutions_schema`], or an
+/// `ArrowError`.
+///
+/// # Errors
+///
+/// Returns an `ArrowError` if a string or binary column would exceed the 2 GiB
+/// offset limit of its Arrow type.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_solutions, solutions_to_record_batch, BigIntEncoding, PellSolution};
+/// let solutions: Vec<PellSolution> = pell_solutions(2, 3)
+///     .unwrap()
+///     .into_iter()
+///     .zip(1..)
+///     .map(|((x, y), k)| PellSolution::with_index(2, k, x, y))
+///     .collect();
+///
+/// let batch = solutions_to_record_batch(&solutions, BigIntEncoding::Decimal).unwrap();
+/// assert_eq!(batch.num_rows(), 3);
+/// assert_eq!(batch.schema().field(2).name(), "x");
+/// ```
+pub fn solutions_to_record_batch(solutions: &[PellSolution], encoding: BigIntEncoding) -> Result<RecordBatch, ArrowError> {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values(solutions.iter().map(|s| s.d))),
+        Arc::new(UInt64Array::from_iter(solutions.iter().map(|s| s.k))),
+        encoding.column(solutions.iter().map(|s| &s.x)),
+        encoding.column(solutions.iter().map(|s| &s.y)),
+    ];
+    RecordBatch::try_new(solutions_schema(encoding), columns)
+}
+
+/// Schema of the batches built by [`reports_to_record_batch`]
+///
+/// One column per field of [`DReport`], with the same names; the `Option`
+/// fields are nullable.
+pub fn reports_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("d", DataType::UInt64, false),
+        Field::new("is_valid", DataType::Boolean, false),
+        Field::new("is_prime", DataType::Boolean, false),
+        Field::new("squarefree_part", DataType::UInt64, false),
+        Field::new("period", DataType::UInt64, true),
+        Field::new("negative_pell_solvable", DataType::Boolean, true),
+        Field::new("regulator", DataType::Float64, true),
+        Field::new("x_digits", DataType::UInt64, true),
+        Field::new("y_digits", DataType::UInt64, true),
+        Field::new("class_number", DataType::UInt64, true),
+    ]))
+}
+
+/// Build an Arrow record batch with one row per D report
+///
+/// # Errors
+///
+/// Never fails in practice; the `Result` is that of `RecordBatch::try_new`.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{analyze_d, reports_to_record_batch, DReport};
+/// let reports: Vec<DReport> = (2..=100).map(analyze_d).collect();
+/// let batch = reports_to_record_batch(&reports).unwrap();
+/// assert_eq!(batch.num_rows(), 99);
+/// assert_eq!(batch.column_by_name("period").unwrap().null_count(), 9); // the squares
+/// ```
+pub fn reports_to_record_batch(reports: &[DReport]) -> Result<RecordBatch, ArrowError> {
+    let u64s = |field: fn(&DReport) -> u64| -> ArrayRef { Arc::new(UInt64Array::from_iter_values(reports.iter().map(field))) };
+    let optional_u64s = |field: fn(&DReport) -> Option<u64>| -> ArrayRef { Arc::new(UInt64Array::from_iter(reports.iter().map(field))) };
+    let bools = |field: fn(&DReport) -> bool| -> ArrayRef { Arc::new(BooleanArray::from_iter(reports.iter().map(|r| Some(field(r))))) };
+
+    let columns = vec![
+        u64s(|r| r.d),
+        bools(|r| r.is_valid),
+        bools(|r| r.is_prime),
+        u64s(|r| r.squarefree_part),
+        optional_u64s(|r| r.period),
+        Arc::new(BooleanArray::from_iter(reports.iter().map(|r| r.negative_pell_solvable))),
+        Arc::new(Float64Array::from_iter(reports.iter().map(|r| r.regulator))),
+        optional_u64s(|r| r.x_digits),
+        optional_u64s(|r| r.y_digits),
+        optional_u64s(|r| r.class_number),
+    ];
+    RecordBatch::try_new(reports_schema(), columns)
+}
+
+/// Schema of the batches built by [`regulator_rows_to_record_batch`]
+///
+/// One column per field of [`RegulatorRow`], with the same names; only
+/// `class_number` is nullable.
+pub fn regulator_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("d", DataType::UInt64, false),
+        Field::new("regulator", DataType::Float64, false),
+        Field::new("period", DataType::UInt64, false),
+        Field::new("class_number", DataType::UInt64, true),
+    ]))
+}
+
+/// Build an Arrow record batch from the rows of a regulator table
+///
+/// # Errors
+///
+/// Never fails in practice; the `Result` is that of `RecordBatch::try_new`.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{regulator_rows_to_record_batch, regulator_table};
+/// let batch = regulator_rows_to_record_batch(&regulator_table(2..=100)).unwrap();
+/// assert_eq!(batch.num_rows(), 90);
+/// ```
+pub fn regulator_rows_to_record_batch(rows: &[RegulatorRow]) -> Result<RecordBatch, ArrowError> {
+    let u64s = |field: fn(&RegulatorRow) -> u64| -> ArrayRef { Arc::new(UInt64Array::from_iter_values(rows.iter().map(field))) };
+
+    let columns = vec![
+        u64s(|r| r.d),
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(|r| r.regulator))),
+        u64s(|r| r.period),
+        Arc::new(UInt64Array::from_iter(rows.iter().map(|r| r.class_number))),
+    ];
+    RecordBatch::try_new(regulator_schema(), columns)
+}
+
+/// Write record batches to `writer` as one Parquet file
+///
+/// All batches must share the schema of the first; an empty slice writes
+/// nothing. Columns are stored uncompressed, which keeps the feature free of
+/// compression libraries.
+///
+/// # Errors
+///
+/// Returns a `ParquetError` if the batches have different schemas or writing
+/// to `writer` fails.
+#[cfg(feature = "parquet")]
+pub fn write_parquet<W: Write + Send>(batches: &[RecordBatch], writer: W) -> Result<(), parquet::errors::ParquetError> {
+    let Some(first) = batches.first() else {
+        return Ok(());
+    };
+    let mut writer = parquet::arrow::ArrowWriter::try_new(writer, first.schema(), None)?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.close()?;
+    Ok(())
+}
+
+/// Write solutions to `writer` as a Parquet file
+///
+/// Shorthand for [`solutions_to_record_batch`] followed by [`write_parquet`].
+///
+/// # Errors
+///
+/// Returns a `ParquetError` if the batch cannot be built or written.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_min_solution, write_solutions_parquet, BigIntEncoding, PellSolution};
+/// let (x, y) = pell_min_solution(991).unwrap();
+/// let mut file = Vec::new();
+/// write_solutions_parquet(&[PellSolution::new(991, x, y)], BigIntEncoding::Binary, &mut file).unwrap();
+/// assert_eq!(&file[..4], b"PAR1");
+/// ```
+#[cfg(feature = "parquet")]
+pub fn write_solutions_parquet<W: Write + Send>(
+    solutions: &[PellSolution],
+    encoding: BigIntEncoding,
+    writer: W,
+) -> Result<(), parquet::errors::ParquetError> {
+    write_parquet(&[solutions_to_record_batch(solutions, encoding)?], writer)
+}
+
+/// Write D reports to `writer` as a Parquet file
+///
+/// Shorthand for [`reports_to_record_batch`] followed by [`write_parquet`].
+///
+/// # Errors
+///
+/// Returns a `ParquetError` if the batch cannot be built or written.
+#[cfg(feature = "parquet")]
+pub fn write_reports_parquet<W: Write + Send>(reports: &[DReport], writer: W) -> Result<(), parquet::errors::ParquetError> {
+    write_parquet(&[reports_to_record_batch(reports)?], writer)
+}
+
+/// Write a regulator table to `writer` as a Parquet file
+///
+/// Shorthand for [`regulator_rows_to_record_batch`] followed by [`write_parquet`].
+///
+/// # Errors
+///
+/// Returns a `ParquetError` if the batch cannot be built or written.
+#[cfg(feature = "parquet")]
+pub fn write_regulator_parquet<W: Write + Send>(rows: &[RegulatorRow], writer: W) -> Result<(), parquet::errors::ParquetError> {
+    write_parquet(&[regulator_rows_to_record_batch(rows)?], writer)
+}
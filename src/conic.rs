@@ -0,0 +1,203 @@
+//! The points of the conic x² - D·y² = 1 and their group law
+//!
+//! Identifying (x, y) with x + y√D, the points of the conic multiply like
+//! numbers of norm 1 and form a group: the identity is (1, 0), the inverse
+//! of (x, y) is its conjugate (x, -y), and the product of two points is
+//! again on the conic. The group is written additively here, so the k-th
+//! solution is k times the fundamental one, which is what
+//! [`pell_solution_k`](crate::pell_solution_k) computes.
+//!
+//! # Examples
+//!
+//! ```
+//! # use pell991::ConicPoint;
+//! let p = ConicPoint::fundamental(2).unwrap(); // (3, 2)
+//! let q = &p + &p;
+//! assert_eq!(q.to_string(), "(17, 12)");
+//! assert_eq!(&q - &p, p);
+//! assert_eq!(&p * 3, &q + &p);
+//! assert!((&p + &-&p).is_identity());
+//! ```
+
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+
+use crate::error::PellError;
+use crate::solver::{pell_min_solution, verify_pell_solution};
+
+/// A point (x, y) on the conic x² - D·y² = 1, with its D
+///
+/// Points can only be built on the conic, so every result of the group
+/// operations is on it too. Adding or subtracting points of different D
+/// panics.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConicPoint {
+    d: u64,
+    x: BigInt,
+    y: BigInt,
+}
+
+impl ConicPoint {
+    /// The point (x, y) of x² - D·y² = 1, or `None` if it is not on the conic
+    ///
+    /// Any D is accepted. For D = 0 the conic is the pair of lines x = ±1, so every
+    /// (±1, y) is a point; for any other perfect square, D = 1 included, the only
+    /// points are (±1, 0).
+    pub fn new(d: u64, x: BigInt, y: BigInt) -> Option<Self> {
+        verify_pell_solution(d, &x, &y).then_some(ConicPoint { d, x, y })
+    }
+
+    /// The identity (1, 0) of the group of x² - D·y² = 1
+    pub fn identity(d: u64) -> Self {
+        ConicPoint { d, x: BigInt::one(), y: BigInt::zero() }
+    }
+
+    /// The fundamental solution (x₁, y₁), which generates all solutions with x > 0
+    ///
+    /// # Errors
+    ///
+    /// Returns `PellError::InvalidD` if `d` ≤ 1.
+    /// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+    pub fn fundamental(d: u64) -> Result<Self, PellError> {
+        let (x, y) = pell_min_solution(d)?;
+        Ok(ConicPoint { d, x, y })
+    }
+
+    /// The coefficient D of the conic
+    pub fn d(&self) -> u64 {
+        self.d
+    }
+
+    /// The x-coordinate
+    pub fn x(&self) -> &BigInt {
+        &self.x
+    }
+
+    /// The y-coordinate
+    pub fn y(&self) -> &BigInt {
+        &self.y
+    }
+
+    /// Whether this is the identity (1, 0)
+    pub fn is_identity(&self) -> bool {
+        self.x.is_one() && self.y.is_zero()
+    }
+
+    /// The point k·P, where negative k gives multiples of the inverse
+    ///
+    /// Uses binary exponentiation, like [`pell_solution_k`](crate::pell_solution_k)
+    /// does for the fundamental solution.
+    pub fn multiple(&self, k: i64) -> Self {
+        let mut result = ConicPoint::identity(self.d);
+        let mut base = if k < 0 { -self } else { self.clone() };
+        let mut exp = k.unsigned_abs();
+        while exp > 0 {
+            if exp % 2 == 1 {
+                result = &result + &base;
+            }
+            exp /= 2;
+            if exp > 0 {
+                base = &base + &base;
+            }
+        }
+        result
+    }
+}
+
+/// Writes the point as `(x, y)`
+impl fmt::Display for ConicPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl From<ConicPoint> for (BigInt, BigInt) {
+    fn from(point: ConicPoint) -> Self {
+        (point.x, point.y)
+    }
+}
+
+/// The group law (x + y√D)(u + v√D)
+///
+/// # Panics
+///
+/// Panics if the points belong to different D.
+impl Add for &ConicPoint {
+    type Output = ConicPoint;
+
+    fn add(self, other: &ConicPoint) -> ConicPoint {
+        assert_eq!(self.d, other.d, "points of different conics cannot be added");
+        let d = self.d;
+        ConicPoint {
+            d,
+            x: &self.x * &other.x + d * &self.y * &other.y,
+            y: &self.x * &other.y + &self.y * &other.x,
+        }
+    }
+}
+
+impl Add for ConicPoint {
+    type Output = ConicPoint;
+
+    fn add(self, other: ConicPoint) -> ConicPoint {
+        &self + &other
+    }
+}
+
+/// The inverse (x, -y), the conjugate x - y√D
+impl Neg for &ConicPoint {
+    type Output = ConicPoint;
+
+    fn neg(self) -> ConicPoint {
+        ConicPoint { d: self.d, x: self.x.clone(), y: -&self.y }
+    }
+}
+
+impl Neg for ConicPoint {
+    type Output = ConicPoint;
+
+    fn neg(self) -> ConicPoint {
+        ConicPoint { d: self.d, x: self.x, y: -self.y }
+    }
+}
+
+/// P - Q = P + (-Q)
+///
+/// # Panics
+///
+/// Panics if the points belong to different D.
+impl Sub for &ConicPoint {
+    type Output = ConicPoint;
+
+    fn sub(self, other: &ConicPoint) -> ConicPoint {
+        self + &-other
+    }
+}
+
+impl Sub for ConicPoint {
+    type Output = ConicPoint;
+
+    fn sub(self, other: ConicPoint) -> ConicPoint {
+        &self - &other
+    }
+}
+
+/// Scalar multiplication, see [`ConicPoint::multiple`]
+impl Mul<i64> for &ConicPoint {
+    type Output = ConicPoint;
+
+    fn mul(self, k: i64) -> ConicPoint {
+        self.multiple(k)
+    }
+}
+
+impl Mul<i64> for ConicPoint {
+    type Output = ConicPoint;
+
+    fn mul(self, k: i64) -> ConicPoint {
+        self.multiple(k)
+    }
+}
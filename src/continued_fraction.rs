@@ -0,0 +1,662 @@
+//! The continued fraction expansion of √D
+//!
+//! For non-square D the expansion is periodic: √D = [a₀; a₁, …, aᵣ₋₁, 2a₀, a₁, …],
+//! and the convergents at the ends of the periods are the solutions of the
+//! Pell equation.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::mem;
+use core::ops::RangeInclusive;
+
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use crate::error::PellError;
+use crate::solver::validate_d;
+use crate::utils::{is_valid_pell_d, isqrt_u64};
+
+/// The periodic continued fraction √D = [a₀; (a₁, …, aᵣ)]
+///
+/// The last term of the period is always 2a₀. `Display` writes the expansion
+/// with the period in parentheses.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::continued_fraction;
+/// let cf = continued_fraction(13).unwrap();
+/// assert_eq!(cf.a0, 3);
+/// assert_eq!(cf.period, vec![1, 1, 1, 1, 6]);
+/// assert_eq!(cf.to_string(), "[3; (1, 1, 1, 1, 6)]");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ContinuedFraction {
+    /// The integer part ⌊√D⌋
+    pub a0: u64,
+    /// The repeating partial quotients a₁, …, aᵣ
+    pub period: Vec<u64>,
+}
+
+impl ContinuedFraction {
+    /// The partial quotients a₀, a₁, a₂, … without end
+    pub fn terms(&self) -> impl Iterator<Item = u64> + '_ {
+        core::iter::once(self.a0).chain(self.period.iter().copied().cycle())
+    }
+
+    /// The convergents pₙ/qₙ of the expansion, starting with p₀/q₀ = a₀/1
+    ///
+    /// An empty `period` stands for the rational a₀, whose only convergent is a₀/1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::continued_fraction;
+    /// let convergents: Vec<String> = continued_fraction(2)
+    ///     .unwrap()
+    ///     .convergents()
+    ///     .take(4)
+    ///     .map(|(p, q)| format!("{p}/{q}"))
+    ///     .collect();
+    /// assert_eq!(convergents, ["1/1", "3/2", "7/5", "17/12"]);
+    /// ```
+    pub fn convergents(&self) -> Convergents<'_> {
+        Convergents {
+            cf: self,
+            n: 0,
+            p: (BigInt::one(), BigInt::zero()),
+            q: (BigInt::zero(), BigInt::one()),
+        }
+    }
+
+    /// The convergent pₙ/qₙ, in O(r + log n) big-number products for a period of length r
+    ///
+    /// Writes the expansion as the continuant product
+    /// [[a₀, 1], [1, 0]]·[[a₁, 1], [1, 0]]·…·[[aₙ, 1], [1, 0]] = [[pₙ, pₙ₋₁], [qₙ, qₙ₋₁]]
+    /// and raises the product over one period to the number of whole periods
+    /// by repeated squaring, instead of stepping through n terms like
+    /// [`convergents`](Self::convergents).
+    ///
+    /// An empty `period` stands for the rational a₀, so every convergent is a₀/1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::continued_fraction;
+    /// let cf = continued_fraction(2).unwrap();
+    /// assert_eq!(cf.convergent(3), (17.into(), 12.into()));
+    /// assert_eq!(cf.convergent(3), cf.convergents().nth(3).unwrap());
+    /// ```
+    pub fn convergent(&self, n: u64) -> (BigInt, BigInt) {
+        let partial = |a: u64| [BigInt::from(a), BigInt::one(), BigInt::one(), BigInt::zero()];
+        let mut result = partial(self.a0);
+        if n > 0 && !self.period.is_empty() {
+            let r = self.period.len() as u64;
+            let (whole, rest) = ((n - 1) / r, (n - 1) % r + 1);
+            let mut block =
+                self.period.iter().map(|&a| partial(a)).reduce(|m, a| mat_mul(&m, &a)).expect("r ≥ 1");
+            let mut exp = whole;
+            while exp > 0 {
+                if exp % 2 == 1 {
+                    result = mat_mul(&result, &block);
+                }
+                exp /= 2;
+                if exp > 0 {
+                    block = mat_mul(&block, &block);
+                }
+            }
+            for &a in &self.period[..rest as usize] {
+                result = mat_mul(&result, &partial(a));
+            }
+        }
+        let [p, _, q, _] = result;
+        (p, q)
+    }
+
+    /// The expansion in LaTeX bracket notation, with the period under `\overline`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::continued_fraction;
+    /// let cf = continued_fraction(13).unwrap();
+    /// assert_eq!(cf.to_latex(), r"[3; \overline{1, 1, 1, 1, 6}]");
+    /// ```
+    pub fn to_latex(&self) -> String {
+        format!("[{}; \\overline{{{}}}]", self.a0, self.period_terms())
+    }
+
+    /// The expansion as nested LaTeX `\cfrac`s, one period deep and then `\dotsb`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::continued_fraction;
+    /// let cf = continued_fraction(3).unwrap();
+    /// assert_eq!(cf.to_latex_cfrac(), r"1 + \cfrac{1}{1 + \cfrac{1}{2 + \dotsb}}");
+    /// ```
+    pub fn to_latex_cfrac(&self) -> String {
+        let mut latex = self.a0.to_string();
+        for a in &self.period {
+            latex.push_str(&format!(" + \\cfrac{{1}}{{{a}"));
+        }
+        latex.push_str(" + \\dotsb");
+        latex.push_str(&"}".repeat(self.period.len()));
+        latex
+    }
+
+    /// The expansion in bracket notation with the period overlined by
+    /// combining characters (U+0305), for plain-text slides and messages
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::continued_fraction;
+    /// let cf = continued_fraction(7).unwrap();
+    /// assert_eq!(cf.to_unicode(), "[2; 1\u{305},\u{305} \u{305}1\u{305},\u{305} \u{305}1\u{305},\u{305} \u{305}4\u{305}]");
+    /// ```
+    pub fn to_unicode(&self) -> String {
+        let overlined: String = self.period_terms().chars().flat_map(|c| [c, '\u{305}']).collect();
+        format!("[{}; {overlined}]", self.a0)
+    }
+
+    /// The terms of the period, separated by commas
+    fn period_terms(&self) -> String {
+        self.period.iter().map(u64::to_string).collect::<Vec<_>>().join(", ")
+    }
+}
+
+impl fmt::Display for ContinuedFraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}; (", self.a0)?;
+        for (i, a) in self.period.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{a}")?;
+        }
+        f.write_str(")]")
+    }
+}
+
+/// Iterator over the convergents of a [`ContinuedFraction`], see
+/// [`ContinuedFraction::convergents`]
+#[derive(Debug, Clone)]
+pub struct Convergents<'a> {
+    cf: &'a ContinuedFraction,
+    /// Index of the next convergent
+    n: usize,
+    /// (pₙ₋₁, pₙ₋₂)
+    p: (BigInt, BigInt),
+    /// (qₙ₋₁, qₙ₋₂)
+    q: (BigInt, BigInt),
+}
+
+impl Iterator for Convergents<'_> {
+    type Item = (BigInt, BigInt);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let a = match self.n {
+            0 => self.cf.a0,
+            _ if self.cf.period.is_empty() => return None,
+            n => self.cf.period[(n - 1) % self.cf.period.len()],
+        };
+        self.n += 1;
+
+        let p = &self.p.0 * a + &self.p.1;
+        let q = &self.q.0 * a + &self.q.1;
+        self.p.1 = mem::replace(&mut self.p.0, p.clone());
+        self.q.1 = mem::replace(&mut self.q.0, q.clone());
+        Some((p, q))
+    }
+
+    /// The convergents never end, except for a rational a₀
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.cf.period.is_empty() {
+            let remaining = usize::from(self.n == 0);
+            return (remaining, Some(remaining));
+        }
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for Convergents<'_> {}
+
+/// Expand √D into its periodic continued fraction
+///
+/// # Arguments
+///
+/// * `d` - The value whose square root to expand (must be > 1 and non-square)
+///
+/// # Returns
+///
+/// A `Result` containing the expansion, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Performance
+///
+/// The period has O(√D·log D) terms, each computed with machine arithmetic, so
+/// this is much cheaper than solving the Pell equation, but for D near `u64::MAX`
+/// the period itself can take gigabytes.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::continued_fraction;
+/// let cf = continued_fraction(991).unwrap();
+/// assert_eq!(cf.period.len(), 60);
+/// assert_eq!(cf.period.last(), Some(&62));
+/// ```
+pub fn continued_fraction(d: u64) -> Result<ContinuedFraction, PellError> {
+    validate_d(d)?;
+
+    let a0 = isqrt_u64(d);
+    let (mut m, mut q, mut a) = (0i128, 1i128, a0 as i128);
+    let mut period = Vec::new();
+    while a != 2 * a0 as i128 {
+        m = q * a - m;
+        q = (d as i128 - m * m) / q;
+        a = (a0 as i128 + m) / q;
+        period.push(a as u64);
+    }
+
+    Ok(ContinuedFraction { a0, period })
+}
+
+/// Length of the period of the continued fraction of √D
+///
+/// The same expansion as [`continued_fraction`], but only counting the terms,
+/// so it runs in constant memory for any D.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::period_length;
+/// assert_eq!(period_length(991), Ok(60));
+/// assert_eq!(period_length(2), Ok(1));
+/// ```
+pub fn period_length(d: u64) -> Result<u64, PellError> {
+    validate_d(d)?;
+
+    // m ≤ a₀ and q ≤ 2a₀ throughout, so nothing here exceeds 2·D
+    let a0 = isqrt_u64(d);
+    let (mut m, mut q, mut a) = (0u64, 1u64, a0);
+    let mut length = 0;
+    while a != 2 * a0 {
+        m = q * a - m;
+        q = (d - m * m) / q;
+        a = (a0 + m) / q;
+        length += 1;
+    }
+    Ok(length)
+}
+
+/// The product of the 2×2 matrices [m₀, m₁; m₂, m₃] and [a₀, a₁; a₂, a₃]
+fn mat_mul(m: &[BigInt; 4], a: &[BigInt; 4]) -> [BigInt; 4] {
+    [
+        &m[0] * &a[0] + &m[1] * &a[2],
+        &m[0] * &a[1] + &m[1] * &a[3],
+        &m[2] * &a[0] + &m[3] * &a[2],
+        &m[2] * &a[1] + &m[3] * &a[3],
+    ]
+}
+
+/// The n-th convergent pₙ/qₙ of √D, without stepping through the ones before
+///
+/// Expands one period of √D and calls [`ContinuedFraction::convergent`],
+/// so the cost is the period plus O(log n) products of numbers the size of
+/// pₙ: a deep convergent such as n = 10⁶ takes about twenty squarings
+/// rather than a million steps.
+///
+/// # Arguments
+///
+/// * `d` - The value whose square root to expand (must be > 1 and non-square)
+/// * `n` - The index of the convergent, where p₀/q₀ = a₀/1
+///
+/// # Returns
+///
+/// A `Result` containing `(pₙ, qₙ)`, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::nth_convergent;
+/// // The period of √13 has odd length 5: p₄/q₄ solves x² - 13y² = -1
+/// // and p₉/q₉ is the fundamental solution
+/// let (p, q) = nth_convergent(13, 4).unwrap();
+/// assert_eq!(&p * &p - 13 * &q * &q, (-1).into());
+/// assert_eq!(nth_convergent(13, 9).unwrap(), (649.into(), 180.into()));
+/// ```
+pub fn nth_convergent(d: u64, n: u64) -> Result<(BigInt, BigInt), PellError> {
+    Ok(continued_fraction(d)?.convergent(n))
+}
+
+/// One convergent pₖ/qₖ of √D and how close it is
+///
+/// With the `serde` feature q serializes as a decimal string, like the
+/// coordinates of [`PellSolution`](crate::PellSolution).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ConvergenceRow {
+    /// Index k of the convergent, from 0
+    pub k: u64,
+    /// The denominator qₖ
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_bigint"))]
+    #[cfg_attr(feature = "schemars", schemars(with = "alloc::string::String", pattern(r"^-?[0-9]+$")))]
+    pub q: BigInt,
+    /// The error scaled by the square of the denominator, |√D - pₖ/qₖ|·qₖ²
+    pub scaled_error: f64,
+}
+
+/// The scaled errors of the first `n` convergents of √D
+///
+/// Every convergent satisfies |√D - p/q| < 1/q², so `scaled_error` stays
+/// below 1; it equals |p² - D·q²|/(√D + p/q), which is how it is computed,
+/// without rational arithmetic on the growing p and q. Along the period it
+/// cycles through |p² - D·q²|/(2√D), and at the solutions of the Pell
+/// equation it is about 1/(2√D).
+///
+/// # Arguments
+///
+/// * `d` - The value whose square root to expand (must be > 1 and non-square)
+/// * `n` - Number of convergents, k = 0, …, n - 1
+///
+/// # Returns
+///
+/// A `Result` containing one `ConvergenceRow` per convergent, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::continued_fraction::convergence_series;
+/// // 1/1, 3/2, 7/5, 17/12, …: |p² - 2q²| = 1, so the error tends to 1/(2√2)
+/// let series = convergence_series(2, 20).unwrap();
+/// assert_eq!(series[3].q.to_string(), "12");
+/// let limit = 1.0 / (2.0 * 2f64.sqrt());
+/// assert!((series[19].scaled_error - limit).abs() < 1e-12);
+/// ```
+pub fn convergence_series(d: u64, n: usize) -> Result<Vec<ConvergenceRow>, PellError> {
+    let cf = continued_fraction(d)?;
+    let big_d = BigInt::from(d);
+    Ok((0..)
+        .zip(cf.convergents().take(n))
+        .map(|(k, (p, q))| {
+            let residual = &p * &p - &big_d * &q * &q;
+            let scaled_error = scaled_error(d, &p, &q, &residual);
+            ConvergenceRow { k, q, scaled_error }
+        })
+        .collect())
+}
+
+/// |√D - p/q|·q², computed as |p² - D·q²|/(√D + p/q) from the residual p² - D·q²
+fn scaled_error(d: u64, p: &BigInt, q: &BigInt, residual: &BigInt) -> f64 {
+    // Shift both to about 64 bits of q, so that p/q stays in range of f64
+    let shift = q.bits().saturating_sub(64);
+    let ratio = (p >> shift).to_f64().unwrap_or(f64::MAX) / (q >> shift).to_f64().unwrap_or(f64::MAX);
+    residual.abs().to_f64().unwrap_or(f64::MAX) / ((d as f64).sqrt() + ratio)
+}
+
+/// How well one convergent pₖ/qₖ approximates √D
+///
+/// Every convergent satisfies |√D - p/q| < 1/q². The sharper bounds hold for
+/// some convergents only: of two consecutive ones at least one satisfies
+/// Legendre's 1/(2q²), and of three consecutive ones at least one satisfies
+/// Hurwitz's 1/(√5·q²).
+///
+/// With the `serde` feature p and q serialize as decimal strings.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ApproximationRow {
+    /// Index k of the convergent, from 0
+    pub k: u64,
+    /// The numerator pₖ
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_bigint"))]
+    #[cfg_attr(feature = "schemars", schemars(with = "alloc::string::String", pattern(r"^-?[0-9]+$")))]
+    pub p: BigInt,
+    /// The denominator qₖ
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_bigint"))]
+    #[cfg_attr(feature = "schemars", schemars(with = "alloc::string::String", pattern(r"^-?[0-9]+$")))]
+    pub q: BigInt,
+    /// pₖ² - D·qₖ²
+    pub residual: i64,
+    /// |√D - pₖ/qₖ|·qₖ², as in [`ConvergenceRow`]
+    pub scaled_error: f64,
+    /// Whether |√D - pₖ/qₖ| < 1/(2qₖ²)
+    pub legendre: bool,
+    /// Whether |√D - pₖ/qₖ| < 1/(√5·qₖ²)
+    pub hurwitz: bool,
+    /// Whether (pₖ, qₖ) solves x² - D·y² = 1
+    pub pell_solution: bool,
+}
+
+/// Writes the row on one line, such as `k=3: 17/12, p² - D·q² = 1, Legendre yes, Hurwitz yes, Pell solution`
+impl fmt::Display for ApproximationRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let yes_no = |holds| if holds { "yes" } else { "no" };
+        write!(
+            f,
+            "k={}: {}/{}, p² - D·q² = {}, Legendre {}, Hurwitz {}",
+            self.k,
+            self.p,
+            self.q,
+            self.residual,
+            yes_no(self.legendre),
+            yes_no(self.hurwitz)
+        )?;
+        if self.pell_solution {
+            f.write_str(", Pell solution")?;
+        }
+        Ok(())
+    }
+}
+
+/// Report how well each of the first `n` convergents of √D approximates it
+///
+/// The bounds are decided exactly in integers: with r = p² - D·q², the error
+/// |√D - p/q|·q² equals |r|/(√D + p/q), and both comparisons reduce to
+/// squaring away √D.
+///
+/// # Arguments
+///
+/// * `d` - The value whose square root to expand (must be > 1 and non-square)
+/// * `n` - Number of convergents, k = 0, …, n - 1
+///
+/// # Returns
+///
+/// A `Result` containing one `ApproximationRow` per convergent, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::continued_fraction::approximation_report;
+/// // √7 = [2; (1, 1, 1, 4)]: 2/1, 3/1, 5/2, 8/3, …
+/// for row in approximation_report(7, 4).unwrap() {
+///     println!("{row}");
+/// }
+/// // k=0: 2/1, p² - D·q² = -3, Legendre no, Hurwitz no
+/// // k=1: 3/1, p² - D·q² = 2, Legendre yes, Hurwitz yes
+/// // k=2: 5/2, p² - D·q² = -3, Legendre no, Hurwitz no
+/// // k=3: 8/3, p² - D·q² = 1, Legendre yes, Hurwitz yes, Pell solution
+/// ```
+pub fn approximation_report(d: u64, n: usize) -> Result<Vec<ApproximationRow>, PellError> {
+    let cf = continued_fraction(d)?;
+    let big_d = BigInt::from(d);
+    Ok((0..)
+        .zip(cf.convergents().take(n))
+        .map(|(k, (p, q))| {
+            let residual = &p * &p - &big_d * &q * &q;
+            let r = residual.abs();
+            // |r|/(√D + p/q) < 1/2 ⟺ 2|r|q - p < q√D
+            let lhs: BigInt = 2 * &r * &q - &p;
+            let legendre = !lhs.is_positive() || &lhs * &lhs < &big_d * &q * &q;
+            // |r|/(√D + p/q) < 1/√5 ⟺ √5|r|q - p < q√D ⟺ 5r²q² + r < 2√5|r|pq, where both sides are positive
+            let lhs: BigInt = 5 * &r * &r * &q * &q + &residual;
+            let hurwitz = &lhs * &lhs < 20 * &r * &r * &p * &p * &q * &q;
+            ApproximationRow {
+                k,
+                scaled_error: scaled_error(d, &p, &q, &residual),
+                residual: residual.to_i64().expect("|p² - D·q²| < 2√D + 1"),
+                legendre,
+                hurwitz,
+                pell_solution: residual.is_one(),
+                p,
+                q,
+            }
+        })
+        .collect())
+}
+
+/// The symmetries of the period of √D
+///
+/// Writing the complete quotients as (mₙ + √D)/qₙ, the period a₁, …, aᵣ of √D
+/// always ends in aᵣ = 2a₀, the terms before it form a palindrome, and the
+/// denominators satisfy qₙ = qᵣ₋ₙ. Every field is therefore `true` for every
+/// valid D; a `false` one means the expansion is wrong. For even r the middle
+/// denominator qᵣ/₂ divides 2D, and for odd r the two middle ones are equal.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::continued_fraction::cf_structure;
+/// let structure = cf_structure(61).unwrap();
+/// assert_eq!(structure.period_length, 11);
+/// assert!(structure.holds());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CfStructure {
+    /// The value D
+    pub d: u64,
+    /// Length r of the period
+    pub period_length: u64,
+    /// Whether the last term of the period is 2a₀
+    pub ends_with_twice_a0: bool,
+    /// Whether a₁, …, aᵣ₋₁ reads the same backwards
+    pub palindromic: bool,
+    /// Whether qₙ = qᵣ₋ₙ for 0 ≤ n ≤ r
+    pub denominators_symmetric: bool,
+}
+
+impl CfStructure {
+    /// Whether every symmetry holds, as it must for a correct expansion
+    pub fn holds(&self) -> bool {
+        self.ends_with_twice_a0 && self.palindromic && self.denominators_symmetric
+    }
+}
+
+/// Check the symmetries of the period of √D
+///
+/// The expansion is redone here with its complete quotients rather than
+/// taken from [`continued_fraction`], which stops as soon as it meets 2a₀.
+///
+/// # Arguments
+///
+/// * `d` - The value whose square root to expand (must be > 1 and non-square)
+///
+/// # Returns
+///
+/// A `Result` containing the `CfStructure`, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+pub fn cf_structure(d: u64) -> Result<CfStructure, PellError> {
+    validate_d(d)?;
+
+    // The period is the terms up to the first return of the denominator to 1
+    let a0 = isqrt_u64(d);
+    let (mut m, mut q, mut a) = (0u64, 1u64, a0);
+    let (mut terms, mut denominators) = (Vec::new(), alloc::vec![1]);
+    loop {
+        m = q * a - m;
+        q = (d - m * m) / q;
+        a = (a0 + m) / q;
+        terms.push(a);
+        denominators.push(q);
+        if q == 1 {
+            break;
+        }
+    }
+
+    let (&last, inner) = terms.split_last().expect("the period has at least one term");
+    Ok(CfStructure {
+        d,
+        period_length: terms.len() as u64,
+        ends_with_twice_a0: last == 2 * a0,
+        palindromic: inner.iter().eq(inner.iter().rev()),
+        denominators_symmetric: denominators.iter().eq(denominators.iter().rev()),
+    })
+}
+
+/// Whether the period of √D without its last term is a palindrome
+///
+/// Always `true` for a valid D; see [`cf_structure`] for the other symmetries.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::continued_fraction::cf_period_is_palindromic;
+/// // √61 = [7; (1, 4, 3, 1, 2, 2, 1, 3, 4, 1, 14)]
+/// assert_eq!(cf_period_is_palindromic(61), Ok(true));
+/// ```
+pub fn cf_period_is_palindromic(d: u64) -> Result<bool, PellError> {
+    cf_structure(d).map(|structure| structure.palindromic)
+}
+
+/// The valid D in `range` whose expansion breaks one of the symmetries of [`CfStructure`]
+///
+/// Empty unless the expansion is wrong, so a scan over a large range doubles
+/// as a self-check of the arithmetic.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::continued_fraction::cf_structure_exceptions;
+/// assert!(cf_structure_exceptions(2..=10_000).is_empty());
+/// ```
+pub fn cf_structure_exceptions(range: RangeInclusive<u64>) -> Vec<CfStructure> {
+    range
+        .filter(|&d| is_valid_pell_d(d))
+        .filter_map(|d| cf_structure(d).ok())
+        .filter(|structure| !structure.holds())
+        .collect()
+}
@@ -0,0 +1,226 @@
+//! Persistent, file-backed cache of fundamental solutions
+//!
+//! Long sweeps over many D values can keep their hard-won fundamental solutions
+//! in a [`DiskCache`], so a restarted process picks up where the previous one
+//! stopped instead of recomputing them.
+//!
+//! The file is plain text and append-only: a header line followed by one
+//! `D period x y` line per solution. A crash mid-write leaves at most one
+//! unterminated trailing line, which is discarded when the cache is next opened.
+//! Every entry read back is checked to be the fundamental solution with the
+//! right period, so a corrupted or hand-edited file is reported instead of
+//! answering with wrong solutions.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use num_bigint::BigInt;
+use crate::solver::{is_fundamental, pell_min_solution_with_period};
+
+/// First line of every cache file, identifying the format version
+const HEADER: &str = "# pell991 solution cache v1";
+
+/// A fundamental solution together with the period of the continued fraction of √D
+type Entry = ((BigInt, BigInt), u64);
+
+/// A cache of fundamental solutions persisted to an append-only file
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::DiskCache;
+/// let path = std::env::temp_dir().join(format!("pell991-doc-{}.cache", std::process::id()));
+///
+/// let mut cache = DiskCache::open(&path).unwrap();
+/// let ((x, y), period) = cache.get_or_solve(991).unwrap();
+/// drop(cache);
+///
+/// // A new process (or a new handle) finds the solution on disk
+/// let reopened = DiskCache::open(&path).unwrap();
+/// assert_eq!(reopened.get(991), Some(&((x, y), period)));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct DiskCache {
+    path: PathBuf,
+    entries: HashMap<u64, Entry>,
+    file: File,
+}
+
+impl DiskCache {
+    /// Open the cache file at `path`, creating it if it does not exist
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file cannot be read or created, or an error of
+    /// kind `InvalidData` if it is not a cache file or contains a corrupt line.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let entries = if path.exists() { load(&path)? } else { HashMap::new() };
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        // Drop a line left incomplete by a crash, so appends start on a fresh line
+        let contents = fs::read(&path)?;
+        let complete = contents.iter().rposition(|&byte| byte == b'\n').map_or(0, |i| i + 1);
+        if complete < contents.len() {
+            file.set_len(complete as u64)?;
+        }
+        if complete == 0 {
+            writeln!(file, "{HEADER}")?;
+        }
+
+        Ok(DiskCache { path, entries, file })
+    }
+
+    /// Path of the backing file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of D values in the cache
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache holds no solutions
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Look up the fundamental solution and continued fraction period for `d`
+    pub fn get(&self, d: u64) -> Option<&Entry> {
+        self.entries.get(&d)
+    }
+
+    /// Iterate over the cached `(D, ((x, y), period))` pairs in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &Entry)> {
+        self.entries.iter().map(|(&d, entry)| (d, entry))
+    }
+
+    /// Record the fundamental solution and period for `d`, appending it to the file
+    ///
+    /// Values already in the cache are not written again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file cannot be written, or an error of kind
+    /// `InvalidInput` if `(x, y)` is not the fundamental solution for `d` or
+    /// `period` is not the period of the continued fraction of √D.
+    pub fn insert(&mut self, d: u64, solution: (BigInt, BigInt), period: u64) -> io::Result<()> {
+        if self.entries.contains_key(&d) {
+            return Ok(());
+        }
+        if !is_fundamental(d, &solution, period) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "({}, {}) with period {period} is not the fundamental solution for D = {d}",
+                    solution.0, solution.1
+                ),
+            ));
+        }
+
+        writeln!(self.file, "{d} {period} {} {}", solution.0, solution.1)?;
+        self.file.flush()?;
+        self.entries.insert(d, (solution, period));
+        Ok(())
+    }
+
+    /// Return the cached solution for `d`, solving and persisting it on a miss
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidInput` wrapping the `PellError` if `d` is
+    /// not a valid Pell D, or an I/O error if the file cannot be written.
+    pub fn get_or_solve(&mut self, d: u64) -> io::Result<Entry> {
+        if let Some(entry) = self.entries.get(&d) {
+            return Ok(entry.clone());
+        }
+        let (solution, period) = pell_min_solution_with_period(d)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        self.insert(d, solution.clone(), period)?;
+        Ok((solution, period))
+    }
+
+    /// Write all cached solutions to `path` as a fresh cache file, sorted by D
+    ///
+    /// Saving to the cache's own path compacts it. The file is written to a
+    /// temporary sibling first and then renamed, so an interrupted save never
+    /// leaves a truncated cache behind.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file cannot be written.
+    pub fn save<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+
+        let mut keys: Vec<u64> = self.entries.keys().copied().collect();
+        keys.sort_unstable();
+
+        let mut writer = BufWriter::new(File::create(&temp)?);
+        writeln!(writer, "{HEADER}")?;
+        for d in keys {
+            let ((x, y), period) = &self.entries[&d];
+            writeln!(writer, "{d} {period} {x} {y}")?;
+        }
+        writer.into_inner().map_err(|err| err.into_error())?.sync_all()?;
+        fs::rename(&temp, path)?;
+
+        // The old handle points at the replaced file when compacting in place
+        if path == self.path {
+            self.file = OpenOptions::new().append(true).open(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read every complete, valid entry of a cache file
+fn load(path: &Path) -> io::Result<HashMap<u64, Entry>> {
+    let mut contents = String::new();
+    File::open(path)?.read_to_string(&mut contents)?;
+
+    let mut lines: Vec<&str> = contents.split('\n').collect();
+    // The piece after the last newline is either empty or an interrupted write
+    lines.pop();
+
+    let mut entries = HashMap::new();
+    let mut lines = lines.into_iter().enumerate();
+    match lines.next() {
+        Some((_, HEADER)) | None => {}
+        Some(_) => return Err(invalid_data(format!("{} is not a pell991 cache file", path.display()))),
+    }
+
+    for (index, line) in lines {
+        if line.is_empty() {
+            continue;
+        }
+        let (d, entry) = parse_line(line)
+            .ok_or_else(|| invalid_data(format!("corrupt entry on line {}: '{line}'", index + 1)))?;
+        entries.insert(d, entry);
+    }
+
+    Ok(entries)
+}
+
+/// Parse and verify one `D period x y` line
+fn parse_line(line: &str) -> Option<(u64, Entry)> {
+    let mut fields = line.split(' ');
+    let d = fields.next()?.parse().ok()?;
+    let period = fields.next()?.parse().ok()?;
+    let x: BigInt = fields.next()?.parse().ok()?;
+    let y: BigInt = fields.next()?.parse().ok()?;
+    let solution = (x, y);
+    if fields.next().is_some() || !is_fundamental(d, &solution, period) {
+        return None;
+    }
+    Some((d, (solution, period)))
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -1,10 +1,20 @@
 //! Error types for Pell equation solving
 
-use std::fmt;
+use alloc::string::String;
+use core::fmt;
+use core::time::Duration;
+
+use crate::negative::NegativeObstruction;
 use crate::utils::isqrt_u64;
 
 /// Errors that can occur when solving Pell equations
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// New variants may be added in minor releases, so matches outside this crate
+/// need a wildcard arm.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum PellError {
     /// D must be greater than 1
     InvalidD(u64),
@@ -12,6 +22,38 @@ pub enum PellError {
     PerfectSquare(u64),
     /// k must be greater than 0
     InvalidK(u64),
+    /// N must be non-zero in x² - D·y² = N
+    InvalidN(i64),
+    /// The set of primes for Størmer's method is unusable
+    InvalidPrimeSet(String),
+    /// A solution string could not be parsed
+    Parse(String),
+    /// A binary-encoded solution could not be decoded
+    Decode(String),
+    /// A value does not fit in the requested machine integer type
+    Overflow {
+        /// Width in bits of the integer type that was too small
+        bits: u32,
+    },
+    /// The solver gave up after the configured number of steps
+    LimitExceeded {
+        /// The step limit that was reached
+        steps: u64,
+    },
+    /// The solver gave up after the configured wall-clock time
+    Timeout {
+        /// The time limit that was reached
+        limit: Duration,
+    },
+    /// The negative Pell equation x² - D·y² = -1 has no solution
+    NegativeUnsolvable {
+        /// The D whose negative equation was solved
+        d: u64,
+        /// Why there is no solution
+        reason: NegativeObstruction,
+    },
+    /// A pair given as a solution does not have x, y > 0 and x² - D·y² = 1
+    InvalidSolution(u64),
 }
 
 impl fmt::Display for PellError {
@@ -20,8 +62,17 @@ impl fmt::Display for PellError {
             PellError::InvalidD(d) => write!(f, "D must be > 1, got {d}"),
             PellError::PerfectSquare(d) => write!(f, "D must be non-square, got {d} which is {}²", isqrt_u64(*d)),
             PellError::InvalidK(k) => write!(f, "k must be > 0, got {k}"),
+            PellError::InvalidN(n) => write!(f, "N must be non-zero, got {n}"),
+            PellError::InvalidPrimeSet(msg) => write!(f, "invalid prime set: {msg}"),
+            PellError::Parse(msg) => write!(f, "invalid solution string: {msg}"),
+            PellError::Decode(msg) => write!(f, "invalid binary solution: {msg}"),
+            PellError::Overflow { bits } => write!(f, "value does not fit in a {bits}-bit integer"),
+            PellError::LimitExceeded { steps } => write!(f, "no solution found within {steps} steps"),
+            PellError::Timeout { limit } => write!(f, "no solution found within {limit:?}"),
+            PellError::NegativeUnsolvable { d, reason } => write!(f, "x² - {d}·y² = -1 has no solution: {reason}"),
+            PellError::InvalidSolution(d) => write!(f, "not a positive solution of x² - {d}·y² = 1"),
         }
     }
 }
 
-impl std::error::Error for PellError {}
\ No newline at end of file
+impl core::error::Error for PellError {}
\ No newline at end of file
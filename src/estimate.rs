@@ -0,0 +1,241 @@
+//! Size, memory and cost predictions for Pell solutions that avoid computing them
+
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+use crate::continued_fraction::period_length;
+use crate::error::PellError;
+use crate::solver::pell_min_solution;
+use crate::utils::log10_bigint;
+
+/// Compute log₁₀(x₁ + y₁√D) for the fundamental solution (x₁, y₁)
+///
+/// Since y₁√D = √(x₁² - 1), this depends only on x₁. Small values are evaluated
+/// exactly in floating point; large ones use log₁₀(2x₁), which is accurate to
+/// far better than `f64` precision once x₁ exceeds 2⁵³.
+pub(crate) fn log10_fundamental_unit(x1: &BigInt) -> f64 {
+    match x1.to_f64() {
+        Some(x) if x < 9_007_199_254_740_992.0 => (x + (x * x - 1.0).sqrt()).log10(),
+        _ => log10_bigint(x1) + core::f64::consts::LOG10_2,
+    }
+}
+
+/// Predict log₁₀ of (xₖ, yₖ) from log₁₀(x₁ + y₁√D)
+///
+/// Uses xₖ = (εᵏ + ε⁻ᵏ)/2 and yₖ = (εᵏ - ε⁻ᵏ)/(2√D) with ε = x₁ + y₁√D.
+/// The ε⁻ᵏ terms only matter when εᵏ is small, so they are dropped otherwise.
+pub(crate) fn log10_solution_k(d: u64, log10_unit: f64, k: u64) -> (f64, f64) {
+    let log10_power = k as f64 * log10_unit;
+    let half_log10_d = 0.5 * (d as f64).log10();
+
+    if log10_power < 15.0 {
+        let power = 10f64.powf(log10_power);
+        let x = (power + power.recip()) / 2.0;
+        let y = (power - power.recip()) / (2.0 * (d as f64).sqrt());
+        (x.log10(), y.log10())
+    } else {
+        let log10_x = log10_power - core::f64::consts::LOG10_2;
+        (log10_x, log10_x - half_log10_d)
+    }
+}
+
+/// Predict the number of decimal digits of the k-th solution (xₖ, yₖ)
+///
+/// Computes only the fundamental solution and then uses
+/// log₁₀ xₖ ≈ k·log₁₀(x₁ + y₁√D) - log₁₀ 2, so it answers instantly even for
+/// values of k whose solutions would need gigabytes to store. Use it to decide
+/// whether a computation is feasible before committing to it.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `k` - The index of the solution (k ≥ 1)
+///
+/// # Returns
+///
+/// A `Result` containing the predicted digit counts `(digits of xₖ, digits of yₖ)`,
+/// or a `PellError` if the input is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidK` if `k` is 0, and the errors of
+/// `pell_min_solution` if D is invalid.
+///
+/// # Accuracy
+///
+/// The prediction is exact unless xₖ or yₖ lies within floating-point error of a
+/// power of ten, in which case it may be off by one.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::predicted_digits;
+/// // The fundamental solution for D = 991 has 30 and 29 digits
+/// assert_eq!(predicted_digits(991, 1).unwrap(), (30, 29));
+///
+/// // The millionth solution, without computing it
+/// let (x_digits, _) = predicted_digits(991, 1_000_000).unwrap();
+/// assert!(x_digits > 29_000_000);
+/// ```
+pub fn predicted_digits(d: u64, k: u64) -> Result<(u64, u64), PellError> {
+    if k == 0 {
+        return Err(PellError::InvalidK(k));
+    }
+
+    let (x1, _) = pell_min_solution(d)?;
+    let (log10_x, log10_y) = log10_solution_k(d, log10_fundamental_unit(&x1), k);
+
+    Ok((log10_x.floor() as u64 + 1, log10_y.floor() as u64 + 1))
+}
+
+/// Bytes of a `BigInt` besides its digits (the digit vector and the sign)
+const BIGINT_OVERHEAD: f64 = core::mem::size_of::<BigInt>() as f64;
+
+/// Solution-sized buffers alive at once while computing, besides the results:
+/// the exponentiation's base, accumulator and scratch values, and products
+const WORKING_COPIES: f64 = 8.0;
+
+/// Estimate the peak memory, in bytes, of generating `count` solutions from index `k`
+///
+/// Models `pell_solutions_range(d, k, k + count)`: the returned vector holds all
+/// the solutions, whose sizes grow linearly with the index, and computing them
+/// needs a few more buffers the size of the largest one. Like
+/// [`predicted_digits`], it only solves for the fundamental solution, so
+/// services can reject infeasible requests before running out of memory.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `k` - The index of the first solution (k ≥ 1)
+/// * `count` - Number of consecutive solutions to generate
+///
+/// # Returns
+///
+/// A `Result` containing the estimated peak number of bytes (saturating at
+/// `u64::MAX`), or a `PellError` if the input is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidK` if `k` is 0, and the errors of
+/// `pell_min_solution` if D is invalid.
+///
+/// # Accuracy
+///
+/// The estimate errs on the high side of the actual peak, usually within a
+/// factor of two; allocator overhead is not included.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::estimate_memory;
+/// const LIMIT: u64 = 1 << 30; // 1 GiB
+///
+/// assert!(estimate_memory(991, 1, 1_000).unwrap() < LIMIT);
+/// // A million solutions of D = 991 would need terabytes
+/// assert!(estimate_memory(991, 1, 1_000_000).unwrap() > LIMIT);
+/// ```
+pub fn estimate_memory(d: u64, k: u64, count: u64) -> Result<u64, PellError> {
+    if k == 0 {
+        return Err(PellError::InvalidK(k));
+    }
+    let (x1, _) = pell_min_solution(d)?;
+    Ok(memory_from_fundamental(&x1, k, count))
+}
+
+/// [`estimate_memory`] from an already computed fundamental solution x₁
+pub(crate) fn memory_from_fundamental(x1: &BigInt, k: u64, count: u64) -> u64 {
+    if count == 0 {
+        return 0;
+    }
+
+    // xⱼ and yⱼ each take about j·log₁₀(ε)·log₂(10)/8 bytes
+    let bytes_per_index = log10_fundamental_unit(x1) * core::f64::consts::LOG2_10 / 8.0;
+    let (k, count) = (k as f64, count as f64);
+    let index_sum = count * k + count * (count - 1.0) / 2.0;
+    let stored = 2.0 * index_sum * bytes_per_index + 2.0 * count * BIGINT_OVERHEAD;
+    let working = WORKING_COPIES * ((k + count - 1.0) * bytes_per_index + BIGINT_OVERHEAD);
+
+    (stored + working).ceil().min(u64::MAX as f64) as u64
+}
+
+/// Decimal digits gained per continued fraction step: the denominators of the
+/// convergents of almost every number grow like 10^(n·π²/(12·ln 2·ln 10))
+/// (Lévy's constant), and those of √D follow within a few percent
+const DIGITS_PER_STEP: f64 = 0.515_320_417;
+
+/// Bits in one limb of a `BigInt`
+const LIMB_BITS: f64 = 64.0;
+
+/// The predicted cost of solving x² - D·y² = 1 with the continued fraction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolveCost {
+    /// Length of the period of √D
+    pub period: u64,
+    /// Continued fraction steps to the fundamental solution: the period, or
+    /// twice the period when it is odd
+    pub steps: u64,
+    /// Predicted decimal digits of x₁
+    pub digits: u64,
+    /// Predicted limb multiply-adds of the big-number arithmetic, summed over
+    /// all steps; grows like steps², and is the figure to compare between D
+    pub limb_operations: u64,
+}
+
+/// Predict the work of solving for D before committing to it
+///
+/// The period is computed exactly with machine arithmetic, which takes a
+/// fraction of the time of the solve itself: the solve updates two convergents
+/// per step, each as long as the digits gained so far, so its big-number work
+/// grows with the square of the number of steps. The digits are predicted
+/// with Lévy's constant. Schedulers can order work by `limb_operations` and
+/// scale timeouts with it.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation (must be > 1 and non-square)
+///
+/// # Returns
+///
+/// A `Result` containing the `SolveCost`, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Accuracy
+///
+/// `period` and `steps` are exact. Once the solve takes a few hundred steps,
+/// `digits` is usually within 15% of the truth; for short periods a single
+/// large partial quotient can throw it off further. `limb_operations`
+/// inherits that error.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::estimate_solve_cost;
+/// let cost = estimate_solve_cost(991).unwrap();
+/// assert_eq!((cost.period, cost.steps), (60, 60));
+/// assert!(cost.digits.abs_diff(30) <= 6); // x₁ has 30 digits
+///
+/// // √1000099 has a far longer period, and costs quadratically more
+/// let expensive = estimate_solve_cost(1_000_099).unwrap();
+/// assert!(expensive.limb_operations > 100 * cost.limb_operations);
+/// ```
+pub fn estimate_solve_cost(d: u64) -> Result<SolveCost, PellError> {
+    let period = period_length(d)?;
+    let steps = if period % 2 == 1 { 2 * period } else { period };
+
+    let digits = steps as f64 * DIGITS_PER_STEP;
+    // Step n touches two convergents of about n·DIGITS_PER_STEP digits each
+    let limbs_per_step = DIGITS_PER_STEP * core::f64::consts::LOG2_10 / LIMB_BITS;
+    let limb_operations = steps as f64 * (steps as f64 + 1.0) * limbs_per_step + 2.0 * steps as f64;
+
+    Ok(SolveCost {
+        period,
+        steps,
+        digits: digits.ceil().max(1.0) as u64,
+        limb_operations: limb_operations.ceil().min(u64::MAX as f64) as u64,
+    })
+}
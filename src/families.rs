@@ -0,0 +1,181 @@
+//! Families of D close to a perfect square
+//!
+//! When D sits just above or below a square n², the continued fraction of √D
+//! has a short period and the fundamental solution is a small polynomial
+//! in n: for D = n² + 1 it is (2n² + 1, 2n), for D = n² - 1 it is (n, 1). These
+//! are special cases of the Richaud–Degert type D = n² + r with r | 4n and
+//! -n < r ≤ n, whose fundamental units are all known in closed form.
+//! [`classify_d`] recognizes the families and their parameters.
+//!
+//! # Examples
+//!
+//! ```
+//! # use pell991::families::{classify_d, DFamily};
+//! assert_eq!(classify_d(1025), DFamily::SquarePlusOne { n: 32 }); // also 4·16² + 1
+//! assert_eq!(classify_d(1016), DFamily::RichaudDegert { n: 32, r: -8 });
+//! assert_eq!(classify_d(991), DFamily::Other);
+//! ```
+
+use core::fmt;
+use core::ops::RangeInclusive;
+
+use crate::utils::{is_valid_pell_d, isqrt_u64};
+
+/// The family a D value belongs to, with its parameters
+///
+/// D = 4n² + 1 is the even case of [`DFamily::SquarePlusOne`], and likewise
+/// D = 4n² - 1 of [`DFamily::SquareMinusOne`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DFamily {
+    /// D = n² + 1
+    SquarePlusOne {
+        /// The parameter n ≥ 1
+        n: u64,
+    },
+    /// D = n² - 1
+    SquareMinusOne {
+        /// The parameter n ≥ 2
+        n: u64,
+    },
+    /// D = n² + 2
+    SquarePlusTwo {
+        /// The parameter n ≥ 1
+        n: u64,
+    },
+    /// D = n² - 2
+    SquareMinusTwo {
+        /// The parameter n ≥ 2
+        n: u64,
+    },
+    /// D = n² + 4
+    SquarePlusFour {
+        /// The parameter n ≥ 1
+        n: u64,
+    },
+    /// D = n² - 4
+    SquareMinusFour {
+        /// The parameter n ≥ 3
+        n: u64,
+    },
+    /// Any other D = n² + r with r | 4n and -n < r ≤ n (Richaud–Degert type)
+    RichaudDegert {
+        /// The parameter n
+        n: u64,
+        /// The offset r = D - n²
+        r: i64,
+    },
+    /// None of the above, or not a valid Pell D
+    Other,
+}
+
+impl DFamily {
+    /// The parameter n, or `None` for [`DFamily::Other`]
+    pub fn n(&self) -> Option<u64> {
+        match *self {
+            DFamily::SquarePlusOne { n }
+            | DFamily::SquareMinusOne { n }
+            | DFamily::SquarePlusTwo { n }
+            | DFamily::SquareMinusTwo { n }
+            | DFamily::SquarePlusFour { n }
+            | DFamily::SquareMinusFour { n }
+            | DFamily::RichaudDegert { n, .. } => Some(n),
+            DFamily::Other => None,
+        }
+    }
+
+    /// The offset r = D - n², or `None` for [`DFamily::Other`]
+    pub fn offset(&self) -> Option<i64> {
+        match *self {
+            DFamily::SquarePlusOne { .. } => Some(1),
+            DFamily::SquareMinusOne { .. } => Some(-1),
+            DFamily::SquarePlusTwo { .. } => Some(2),
+            DFamily::SquareMinusTwo { .. } => Some(-2),
+            DFamily::SquarePlusFour { .. } => Some(4),
+            DFamily::SquareMinusFour { .. } => Some(-4),
+            DFamily::RichaudDegert { r, .. } => Some(r),
+            DFamily::Other => None,
+        }
+    }
+}
+
+/// Writes the form of D, such as `31² + 1`, or `other`
+impl fmt::Display for DFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.n(), self.offset()) {
+            (Some(n), Some(r)) if r < 0 => write!(f, "{n}² - {}", r.unsigned_abs()),
+            (Some(n), Some(r)) => write!(f, "{n}² + {r}"),
+            _ => write!(f, "other"),
+        }
+    }
+}
+
+/// Find the family of D
+///
+/// The named families are tried first, from offset ±1 to ±4, then the general
+/// Richaud–Degert type.
+///
+/// # Arguments
+///
+/// * `d` - The value to classify
+///
+/// # Returns
+///
+/// The `DFamily` of D, or `DFamily::Other` if D is in none of them or is not
+/// a valid Pell D.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::families::{classify_d, DFamily};
+/// assert_eq!(classify_d(2), DFamily::SquarePlusOne { n: 1 });
+/// assert_eq!(classify_d(98), DFamily::SquareMinusTwo { n: 10 });
+/// assert_eq!(classify_d(104), DFamily::SquarePlusFour { n: 10 });
+/// assert_eq!(classify_d(105), DFamily::RichaudDegert { n: 10, r: 5 });
+/// assert_eq!(classify_d(100), DFamily::Other);
+/// ```
+pub fn classify_d(d: u64) -> DFamily {
+    if !is_valid_pell_d(d) {
+        return DFamily::Other;
+    }
+
+    // D = n² + r for the nearest squares below and above
+    let below = isqrt_u64(d);
+    let above = below + 1;
+    let r_below = d - below * below;
+    let r_above = (u128::from(above) * u128::from(above) - u128::from(d)) as u64;
+
+    match (r_below, r_above) {
+        (1, _) => return DFamily::SquarePlusOne { n: below },
+        (_, 1) => return DFamily::SquareMinusOne { n: above },
+        (2, _) => return DFamily::SquarePlusTwo { n: below },
+        (_, 2) => return DFamily::SquareMinusTwo { n: above },
+        (4, _) => return DFamily::SquarePlusFour { n: below },
+        (_, 4) => return DFamily::SquareMinusFour { n: above },
+        _ => {}
+    }
+
+    // r_below + r_above = 2n + 1, so at most one of these holds
+    if r_below <= below && (4 * below) % r_below == 0 {
+        return DFamily::RichaudDegert { n: below, r: r_below as i64 };
+    }
+    if r_above < above && (4 * above) % r_above == 0 {
+        return DFamily::RichaudDegert { n: above, r: -(r_above as i64) };
+    }
+    DFamily::Other
+}
+
+/// Classify every D in `range`, keeping those in a family
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::families::{scan_d_families, DFamily};
+/// let plus_one: Vec<u64> = scan_d_families(2..=200)
+///     .filter(|(_, family)| matches!(family, DFamily::SquarePlusOne { .. }))
+///     .map(|(d, _)| d)
+///     .collect();
+/// assert_eq!(plus_one, [2, 5, 10, 17, 26, 37, 50, 65, 82, 101, 122, 145, 170, 197]);
+/// ```
+pub fn scan_d_families(range: RangeInclusive<u64>) -> impl Iterator<Item = (u64, DFamily)> {
+    range.map(|d| (d, classify_d(d))).filter(|&(_, family)| family != DFamily::Other)
+}
@@ -0,0 +1,302 @@
+//! C interface
+//!
+//! A stable `extern "C"` layer over the solver, so C, C++ and anything with a
+//! C FFI can link the crate directly. The declarations are in
+//! `include/pell991.h`, generated by cbindgen from this module (see
+//! `cbindgen.toml`). Build the library with
+//!
+//! ```text
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! (or `staticlib`). Every function returns a [`Pell991Status`] and writes its
+//! results through out-pointers. Panics never unwind into C: they are caught
+//! and reported as [`Pell991Status::Internal`]. Coordinates come either as decimal strings or
+//! as arrays of 64-bit limbs; both are allocated by the library and must be
+//! released with [`pell991_string_free`] and [`pell991_limbs_free`].
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use num_bigint::BigInt;
+
+use crate::error::PellError;
+use crate::solver::{pell_min_solution, pell_solution_k, verify_pell_solution};
+
+/// Outcome of a call into the C interface
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pell991Status {
+    /// The call succeeded and its out-pointers were written
+    Ok = 0,
+    /// D must be greater than 1
+    InvalidD = 1,
+    /// D must not be a perfect square
+    PerfectSquare = 2,
+    /// k must be greater than 0
+    InvalidK = 3,
+    /// A pointer argument was null, or a string was not a decimal integer
+    InvalidArgument = 4,
+    /// Any other solver error
+    Failed = 5,
+    /// The library panicked; this is a bug
+    Internal = 6,
+}
+
+impl From<PellError> for Pell991Status {
+    fn from(err: PellError) -> Self {
+        match err {
+            PellError::InvalidD(_) => Pell991Status::InvalidD,
+            PellError::PerfectSquare(_) => Pell991Status::PerfectSquare,
+            PellError::InvalidK(_) => Pell991Status::InvalidK,
+            _ => Pell991Status::Failed,
+        }
+    }
+}
+
+/// The magnitude of an integer as 64-bit limbs, least significant first
+///
+/// An empty array (`len` 0, `limbs` null) is zero.
+#[repr(C)]
+#[derive(Debug)]
+pub struct Pell991Limbs {
+    /// The limbs, owned by the library
+    pub limbs: *mut u64,
+    /// Number of limbs
+    pub len: usize,
+}
+
+impl Pell991Limbs {
+    const EMPTY: Pell991Limbs = Pell991Limbs { limbs: ptr::null_mut(), len: 0 };
+
+    fn new(n: &BigInt) -> Self {
+        let limbs = n.magnitude().to_u64_digits().into_boxed_slice();
+        if limbs.is_empty() {
+            return Pell991Limbs::EMPTY;
+        }
+        let len = limbs.len();
+        Pell991Limbs { limbs: Box::into_raw(limbs).cast(), len }
+    }
+}
+
+/// Run the body of an exported function, turning a panic into `Internal`
+fn guard(body: impl FnOnce() -> Pell991Status) -> Pell991Status {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(Pell991Status::Internal)
+}
+
+/// Solve the k-th solution, or the fundamental one for `k == 1`
+fn solve(d: u64, k: u64) -> Result<(BigInt, BigInt), PellError> {
+    // Checked before solving, so that k = 0 is reported even for an expensive D
+    if k == 0 {
+        return Err(PellError::InvalidK(k));
+    }
+    let (x1, y1) = pell_min_solution(d)?;
+    if k == 1 {
+        return Ok((x1, y1));
+    }
+    pell_solution_k(d, &x1, &y1, k)
+}
+
+fn decimal_string(n: &BigInt) -> *mut c_char {
+    CString::new(n.to_string()).expect("digits contain no NUL").into_raw()
+}
+
+/// Solve and write the pair as decimal strings, or set both to null on failure
+///
+/// # Safety
+///
+/// `x_out` and `y_out` must be null or valid for writes.
+unsafe fn write_strings(d: u64, k: u64, x_out: *mut *mut c_char, y_out: *mut *mut c_char) -> Pell991Status {
+    if x_out.is_null() || y_out.is_null() {
+        return Pell991Status::InvalidArgument;
+    }
+    // SAFETY: both pointers are non-null and the caller guarantees they are writable
+    unsafe {
+        x_out.write(ptr::null_mut());
+        y_out.write(ptr::null_mut());
+    }
+    match solve(d, k) {
+        Ok((x, y)) => {
+            // SAFETY: as above
+            unsafe {
+                x_out.write(decimal_string(&x));
+                y_out.write(decimal_string(&y));
+            }
+            Pell991Status::Ok
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// Solve and write the pair as limb arrays, or set both to empty on failure
+///
+/// # Safety
+///
+/// `x_out` and `y_out` must be null or valid for writes.
+unsafe fn write_limbs(d: u64, k: u64, x_out: *mut Pell991Limbs, y_out: *mut Pell991Limbs) -> Pell991Status {
+    if x_out.is_null() || y_out.is_null() {
+        return Pell991Status::InvalidArgument;
+    }
+    // SAFETY: both pointers are non-null and the caller guarantees they are writable
+    unsafe {
+        x_out.write(Pell991Limbs::EMPTY);
+        y_out.write(Pell991Limbs::EMPTY);
+    }
+    match solve(d, k) {
+        Ok((x, y)) => {
+            // SAFETY: as above
+            unsafe {
+                x_out.write(Pell991Limbs::new(&x));
+                y_out.write(Pell991Limbs::new(&y));
+            }
+            Pell991Status::Ok
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// Find the fundamental solution of x² - D·y² = 1 as decimal strings
+///
+/// On success `*x_out` and `*y_out` point to NUL-terminated strings to be
+/// released with [`pell991_string_free`]; on failure they are set to null.
+///
+/// # Safety
+///
+/// `x_out` and `y_out` must each be null or valid for writing a pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pell991_min_solution(d: u64, x_out: *mut *mut c_char, y_out: *mut *mut c_char) -> Pell991Status {
+    // SAFETY: forwarded from the caller
+    guard(|| unsafe { write_strings(d, 1, x_out, y_out) })
+}
+
+/// Find the k-th solution of x² - D·y² = 1 as decimal strings
+///
+/// Strings are returned as in [`pell991_min_solution`].
+///
+/// # Safety
+///
+/// `x_out` and `y_out` must each be null or valid for writing a pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pell991_solution_k(
+    d: u64,
+    k: u64,
+    x_out: *mut *mut c_char,
+    y_out: *mut *mut c_char,
+) -> Pell991Status {
+    // SAFETY: forwarded from the caller
+    guard(|| unsafe { write_strings(d, k, x_out, y_out) })
+}
+
+/// Find the fundamental solution of x² - D·y² = 1 as limb arrays
+///
+/// On success `*x_out` and `*y_out` hold arrays to be released with
+/// [`pell991_limbs_free`]; on failure they are set to empty arrays.
+///
+/// # Safety
+///
+/// `x_out` and `y_out` must each be null or valid for writing a `Pell991Limbs`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pell991_min_solution_limbs(
+    d: u64,
+    x_out: *mut Pell991Limbs,
+    y_out: *mut Pell991Limbs,
+) -> Pell991Status {
+    // SAFETY: forwarded from the caller
+    guard(|| unsafe { write_limbs(d, 1, x_out, y_out) })
+}
+
+/// Find the k-th solution of x² - D·y² = 1 as limb arrays
+///
+/// Arrays are returned as in [`pell991_min_solution_limbs`].
+///
+/// # Safety
+///
+/// `x_out` and `y_out` must each be null or valid for writing a `Pell991Limbs`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pell991_solution_k_limbs(
+    d: u64,
+    k: u64,
+    x_out: *mut Pell991Limbs,
+    y_out: *mut Pell991Limbs,
+) -> Pell991Status {
+    // SAFETY: forwarded from the caller
+    guard(|| unsafe { write_limbs(d, k, x_out, y_out) })
+}
+
+/// Check whether the decimal strings `x` and `y` satisfy x² - D·y² = 1
+///
+/// Writes the answer to `*valid_out`.
+///
+/// # Safety
+///
+/// `x` and `y` must be null or NUL-terminated strings, and `valid_out` must be
+/// null or valid for writing a `bool`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pell991_verify(d: u64, x: *const c_char, y: *const c_char, valid_out: *mut bool) -> Pell991Status {
+    if x.is_null() || y.is_null() || valid_out.is_null() {
+        return Pell991Status::InvalidArgument;
+    }
+    guard(|| {
+        let parse = |s: *const c_char| {
+            // SAFETY: non-null, and the caller guarantees NUL termination
+            let s = unsafe { CStr::from_ptr(s) };
+            s.to_str().ok()?.parse::<BigInt>().ok()
+        };
+        let (Some(x), Some(y)) = (parse(x), parse(y)) else {
+            return Pell991Status::InvalidArgument;
+        };
+        // SAFETY: non-null, and the caller guarantees it is writable
+        unsafe { valid_out.write(verify_pell_solution(d, &x, &y)) };
+        Pell991Status::Ok
+    })
+}
+
+/// Release a string returned by this library; null is ignored
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this library that has not been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pell991_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: the string came from `CString::into_raw`
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Release a limb array returned by this library; empty arrays are ignored
+///
+/// # Safety
+///
+/// `limbs` must be empty or an array returned by this library that has not been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn pell991_limbs_free(limbs: Pell991Limbs) {
+    if !limbs.limbs.is_null() {
+        // SAFETY: the array came from `Box::into_raw` of a slice of `len` limbs
+        drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(limbs.limbs, limbs.len)) });
+    }
+}
+
+/// A static, NUL-terminated description of the status code `status`
+///
+/// Takes a plain `int`, since C can pass any value where an enum is expected;
+/// codes that are not a [`Pell991Status`] get "unknown status". The string
+/// must not be freed.
+#[unsafe(no_mangle)]
+pub extern "C" fn pell991_status_message(status: c_int) -> *const c_char {
+    let statuses = [
+        (Pell991Status::Ok, c"ok"),
+        (Pell991Status::InvalidD, c"D must be > 1"),
+        (Pell991Status::PerfectSquare, c"D must not be a perfect square"),
+        (Pell991Status::InvalidK, c"k must be > 0"),
+        (Pell991Status::InvalidArgument, c"null pointer or invalid decimal integer"),
+        (Pell991Status::Failed, c"the solver failed"),
+        (Pell991Status::Internal, c"internal error in the library"),
+    ];
+    let message = statuses
+        .iter()
+        .find(|&&(known, _)| known as c_int == status)
+        .map_or(c"unknown status", |&(_, message)| message);
+    message.as_ptr()
+}
@@ -0,0 +1,230 @@
+//! Reduced ideals of Z[√D] and their cycles
+//!
+//! A primitive ideal [Q, P + √D] of Z[√D] is reduced when 0 < P < √D and
+//! √D - P < Q < √D + P. One step of the continued fraction algorithm maps reduced
+//! ideals to reduced ideals, and the reduced ideals of each ideal class form a
+//! single cycle under that map. The cycle through Z[√D] itself is the period of
+//! the continued fraction of √D.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use num_integer::Integer;
+use crate::error::PellError;
+use crate::solver::validate_d;
+use crate::utils::{is_valid_pell_d, isqrt_u64};
+
+/// A reduced primitive ideal [Q, P + √D], stored as (P, Q)
+type ReducedIdeal = (u64, u64);
+
+/// List all reduced primitive ideals [Q, P + √D] of Z[√D]
+///
+/// Runs in O(D) time, so it is practical for D up to roughly 10⁹.
+fn reduced_ideals(d: u64) -> Vec<ReducedIdeal> {
+    reduced_ideals_until(d, || Ok(())).expect("the check never fails")
+}
+
+/// [`reduced_ideals`], calling `check` once per P and giving up with its error
+fn reduced_ideals_until<F>(d: u64, mut check: F) -> Result<Vec<ReducedIdeal>, PellError>
+where
+    F: FnMut() -> Result<(), PellError>,
+{
+    let root = isqrt_u64(d);
+    let mut ideals = Vec::new();
+
+    for p in 1..=root {
+        check()?;
+        let norm = d - p * p;
+        // √D - P < Q < √D + P  ⇔  root - P < Q ≤ root + P
+        for q in (root - p + 1)..=(root + p) {
+            if norm % q == 0 && q.gcd(&(2 * p)).gcd(&(norm / q)) == 1 {
+                ideals.push((p, q));
+            }
+        }
+    }
+
+    Ok(ideals)
+}
+
+/// One continued fraction step on a reduced ideal
+fn rho(d: u64, root: u64, (p, q): ReducedIdeal) -> ReducedIdeal {
+    let a = (p + root) / q;
+    let p_next = a * q - p;
+    (p_next, (d - p_next * p_next) / q)
+}
+
+/// Compute the class number of the order Z[√D]
+///
+/// This is the number of classes of invertible ideals of Z[√D] modulo principal
+/// ideals, equivalently the number of cycles of reduced ideals. For squarefree
+/// D ≡ 2, 3 (mod 4) it is the class number of the field Q(√D).
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D (must be > 1 and non-square)
+///
+/// # Returns
+///
+/// A `Result` containing the class number, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Performance
+///
+/// Enumerates all reduced ideals in O(D) time.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::class_number;
+/// assert_eq!(class_number(2).unwrap(), 1);
+/// assert_eq!(class_number(10).unwrap(), 2);
+/// assert_eq!(class_number(79).unwrap(), 3);
+/// ```
+pub fn class_number(d: u64) -> Result<u64, PellError> {
+    class_number_until(d, || Ok(()))
+}
+
+/// [`class_number`], calling `check` regularly during the O(D) enumeration and
+/// giving up with its error, so that callers can enforce a deadline
+pub(crate) fn class_number_until<F>(d: u64, check: F) -> Result<u64, PellError>
+where
+    F: FnMut() -> Result<(), PellError>,
+{
+    validate_d(d)?;
+
+    let root = isqrt_u64(d);
+    let ideals = reduced_ideals_until(d, check)?;
+    let mut unvisited: BTreeSet<ReducedIdeal> = ideals.iter().copied().collect();
+    let mut cycles = 0;
+
+    for start in ideals {
+        if !unvisited.remove(&start) {
+            continue;
+        }
+        cycles += 1;
+
+        let mut ideal = rho(d, root, start);
+        while ideal != start {
+            unvisited.remove(&ideal);
+            ideal = rho(d, root, ideal);
+        }
+    }
+
+    Ok(cycles)
+}
+
+/// Compute the caliber of Z[√D], the number of reduced primitive ideals
+///
+/// The reduced ideals fall into one cycle per ideal class, so the caliber is
+/// the sum of the cycle lengths: the period of √D when the class number is 1,
+/// and more otherwise.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D (must be > 1 and non-square)
+///
+/// # Returns
+///
+/// A `Result` containing the caliber, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Performance
+///
+/// Enumerates all reduced ideals in O(D) time, as [`class_number`] does.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{caliber, class_number};
+/// // One class, so the only cycle is the period of √991
+/// assert_eq!(class_number(991).unwrap(), 1);
+/// assert_eq!(caliber(991).unwrap(), 60);
+///
+/// // Two classes, with cycles of 2 and 2 ideals
+/// assert_eq!(caliber(10).unwrap(), 4);
+/// ```
+pub fn caliber(d: u64) -> Result<u64, PellError> {
+    validate_d(d)?;
+    Ok(reduced_ideals(d).len() as u64)
+}
+
+/// How the class numbers of Z[√D] are distributed over a range of D
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClassNumberStats {
+    /// Number of valid D in the range
+    pub total: u64,
+    /// How many D have each class number h, keyed by h
+    pub counts: BTreeMap<u64, u64>,
+    /// The D with class number 1, in increasing order
+    pub class_number_one: Vec<u64>,
+}
+
+impl ClassNumberStats {
+    /// How many D in the range have class number `h`
+    pub fn count(&self, h: u64) -> u64 {
+        self.counts.get(&h).copied().unwrap_or(0)
+    }
+
+    /// The fraction of D in the range with class number 1, or 0 for an empty range
+    pub fn class_number_one_density(&self) -> f64 {
+        if self.total == 0 { 0.0 } else { self.class_number_one.len() as f64 / self.total as f64 }
+    }
+}
+
+/// Tabulate the class numbers of Z[√D] for every valid D in `range`
+///
+/// D ≤ 1 and perfect squares are skipped. With the `rayon` feature the class
+/// numbers are computed concurrently.
+///
+/// # Arguments
+///
+/// * `range` - The D values to tabulate
+///
+/// # Returns
+///
+/// A `ClassNumberStats` with the count of each class number and the D with
+/// class number 1.
+///
+/// # Performance
+///
+/// Each class number takes O(D) time, as in [`class_number`].
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::class_number_stats;
+/// let stats = class_number_stats(2..=20);
+/// assert_eq!(stats.total, 16);
+/// assert_eq!(stats.class_number_one, [2, 3, 5, 6, 7, 8, 11, 12, 13, 14, 17, 18, 19, 20]);
+/// assert_eq!(stats.count(2), 2); // D = 10 and 15
+/// ```
+pub fn class_number_stats(range: RangeInclusive<u64>) -> ClassNumberStats {
+    let class_number = |d| class_number(d).expect("only valid D are tabulated");
+
+    #[cfg(feature = "rayon")]
+    let class_numbers: Vec<(u64, u64)> = {
+        use rayon::prelude::*;
+        range.into_par_iter().filter(|&d| is_valid_pell_d(d)).map(|d| (d, class_number(d))).collect()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let class_numbers: Vec<(u64, u64)> = range.filter(|&d| is_valid_pell_d(d)).map(|d| (d, class_number(d))).collect();
+
+    let mut stats = ClassNumberStats::default();
+    for (d, h) in class_numbers {
+        stats.total += 1;
+        *stats.counts.entry(h).or_default() += 1;
+        if h == 1 {
+            stats.class_number_one.push(d);
+        }
+    }
+    stats
+}
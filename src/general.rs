@@ -0,0 +1,356 @@
+//! The generalized Pell equation x² - D·y² = N
+//!
+//! The solutions of x² - D·y² = N fall into finitely many classes: two
+//! solutions are in the same class when their quotient is a power of the
+//! fundamental unit x₁ + y₁√D, up to sign. Each class is represented by its
+//! fundamental solution, the member with the smallest non-negative y.
+
+use alloc::vec::Vec;
+
+use num_bigint::BigInt;
+use num_traits::{Signed, Zero};
+use crate::error::PellError;
+use crate::negative::pell_negative_solution;
+use crate::solver::{pell_min_solution, validate_d};
+use crate::utils::isqrt_u64;
+
+/// Multiply x + y√D by u + v√D
+fn multiply(d: &BigInt, (x, y): (&BigInt, &BigInt), (u, v): (&BigInt, &BigInt)) -> (BigInt, BigInt) {
+    (x * u + d * y * v, x * v + y * u)
+}
+
+/// Find the fundamental solution of every class of x² - D·y² = N
+///
+/// Uses the Lagrange–Matthews–Mollin algorithm: for every f with f² | N and
+/// m = N/f², each square root z of D modulo |m| leads to at most one class,
+/// found by expanding (z + √D)/|m| as a continued fraction for one period.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D (must be > 1 and non-square)
+/// * `n` - The right-hand side N (must be non-zero)
+///
+/// # Returns
+///
+/// A `Result` containing the fundamental solutions `(x, y)` of all classes,
+/// ordered by y and then x, or a `PellError`. The vector is empty when the
+/// equation has no solutions. Each has y ≥ 0, and x > 0 where the class has
+/// a choice; the conjugate class of `(x, y)` is the one of `(-x, y)`.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+/// Returns `PellError::InvalidN` if `n` is zero.
+///
+/// # Performance
+///
+/// Tries every residue modulo |N/f²| for every square f² dividing N, so the
+/// cost grows linearly with |N| on top of one period of √D per class.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::pell_general_fundamental_solutions;
+/// // 3² - 2·1² = 7, and its conjugate class (-3, 1)
+/// let classes: Vec<(String, String)> = pell_general_fundamental_solutions(2, 7)
+///     .unwrap()
+///     .into_iter()
+///     .map(|(x, y)| (x.to_string(), y.to_string()))
+///     .collect();
+/// assert_eq!(classes, [("-3".to_string(), "1".to_string()), ("3".to_string(), "1".to_string())]);
+///
+/// // x² ≡ 2 (mod 3) is impossible
+/// assert!(pell_general_fundamental_solutions(3, 2).unwrap().is_empty());
+/// ```
+pub fn pell_general_fundamental_solutions(d: u64, n: i64) -> Result<Vec<(BigInt, BigInt)>, PellError> {
+    validate_d(d)?;
+    if n == 0 {
+        return Err(PellError::InvalidN(n));
+    }
+    let (x1, y1) = pell_min_solution(d)?;
+    pell_general_fundamental_solutions_with_fundamental(d, n, &x1, &y1)
+}
+
+/// [`pell_general_fundamental_solutions`] from an already computed fundamental
+/// solution (x₁, y₁) of x² - D·y² = 1
+///
+/// Skips solving the Pell equation again. The pair is trusted as given, like in
+/// [`PellSolutionIterator::with_fundamental`](crate::PellSolutionIterator::with_fundamental).
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+/// Returns `PellError::InvalidN` if `n` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_general_fundamental_solutions, pell_general_fundamental_solutions_with_fundamental};
+/// # use pell991::pell_min_solution;
+/// let (x1, y1) = pell_min_solution(2).unwrap();
+/// assert_eq!(
+///     pell_general_fundamental_solutions_with_fundamental(2, 7, &x1, &y1),
+///     pell_general_fundamental_solutions(2, 7)
+/// );
+/// ```
+pub fn pell_general_fundamental_solutions_with_fundamental(
+    d: u64,
+    n: i64,
+    x1: &BigInt,
+    y1: &BigInt,
+) -> Result<Vec<(BigInt, BigInt)>, PellError> {
+    validate_d(d)?;
+    if n == 0 {
+        return Err(PellError::InvalidN(n));
+    }
+
+    let d_big = BigInt::from(d);
+    // Lazily solved: only needed when a continued fraction lands on -m
+    let mut negative_unit: Option<Option<(BigInt, BigInt)>> = None;
+
+    let mut solutions = Vec::new();
+    let n_abs = n.unsigned_abs();
+    let mut f = 1u64;
+    while f * f <= n_abs {
+        if n_abs % (f * f) == 0 {
+            let m = n as i128 / (f * f) as i128;
+            let modulus = m.abs();
+            // z ranges over (-|m|/2, |m|/2]
+            for z in (-(modulus - 1) / 2)..=(modulus / 2) {
+                if (z * z - d as i128).rem_euclid(modulus) != 0 {
+                    continue;
+                }
+                let Some((r, s, norm)) = reach_unit_denominator(d, z, modulus) else {
+                    continue;
+                };
+                let (r, s) = if norm == m {
+                    (r, s)
+                } else {
+                    let negative_unit = negative_unit.get_or_insert_with(|| pell_negative_solution(d).ok());
+                    match negative_unit {
+                        Some((t, u)) => multiply(&d_big, (&r, &s), (t, u)),
+                        None => continue,
+                    }
+                };
+                let f = BigInt::from(f);
+                solutions.push(class_representative(&d_big, (x1, y1), (f.clone() * r, f * s)));
+            }
+        }
+        f += 1;
+    }
+
+    solutions.sort_by(|(xa, ya), (xb, yb)| ya.cmp(yb).then_with(|| xa.cmp(xb)));
+    solutions.dedup();
+    Ok(solutions)
+}
+
+/// Find the smallest n ≥ 1 making D·n² + c a perfect square m²
+///
+/// This is the question behind the Pell equation, in its own terms: for
+/// c = 1 it asks for the smallest n with D·n² + 1 square, whose answer is
+/// the fundamental solution (m, n) = (x₁, y₁). Since D·n² + c = m² means
+/// m² - D·n² = c:
+///
+/// * c = 1 uses [`pell_min_solution`];
+/// * c = -1 uses [`pell_negative_solution`];
+/// * any other c, such as ±4, uses [`pell_general_fundamental_solutions`] and
+///   takes the smallest positive y over all classes. When c is a perfect
+///   square the class of (√c, 0) contributes n = √c·y₁.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D (must be > 1 and non-square)
+/// * `c` - The constant added to D·n²
+///
+/// # Returns
+///
+/// A `Result` containing `Some((n, m))` with m > 0, `None` if no n ≥ 1 makes
+/// D·n² + c a square (always the case for c = 0), or a `PellError`.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Performance
+///
+/// As for [`pell_general_fundamental_solutions`], the cost grows linearly
+/// with |c| when c ≠ ±1.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::min_n_for_square;
+/// // The puzzle this crate is named after: the smallest n with 991·n² + 1 square
+/// let (n, m) = min_n_for_square(991, 1).unwrap().unwrap();
+/// assert_eq!(n.to_string(), "12055735790331359447442538767");
+/// assert_eq!(m.to_string(), "379516400906811930638014896080");
+///
+/// // 5·1² + 4 = 3², but 3·n² - 1 is never a square
+/// let (n, m) = min_n_for_square(5, 4).unwrap().unwrap();
+/// assert_eq!((n.to_string(), m.to_string()), ("1".to_string(), "3".to_string()));
+/// assert_eq!(min_n_for_square(3, -1), Ok(None));
+/// ```
+pub fn min_n_for_square(d: u64, c: i64) -> Result<Option<(BigInt, BigInt)>, PellError> {
+    match c {
+        0 => validate_d(d).map(|()| None),
+        1 => pell_min_solution(d).map(|(x, y)| Some((y, x))),
+        -1 => match pell_negative_solution(d) {
+            Ok((x, y)) => Ok(Some((y, x))),
+            Err(PellError::NegativeUnsolvable { .. }) => Ok(None),
+            Err(err) => Err(err),
+        },
+        _ => {
+            let classes = pell_general_fundamental_solutions(d, c)?;
+            let (x1, y1) = pell_min_solution(d)?;
+            Ok(classes
+                .into_iter()
+                .map(|(x, y)| {
+                    let x = x.abs();
+                    // The class of (√c, 0) next reaches √c·(x₁, y₁)
+                    if y.is_zero() { (&x * &y1, &x * &x1) } else { (y, x) }
+                })
+                .min())
+        }
+    }
+}
+
+/// Expand (z + √D)/q₀ until a denominator ±1 appears within the first period
+///
+/// Returns `(r, s, r² - D·s²)` from the convergent before that denominator,
+/// where r² - D·s² = ±q₀, or `None` if the period ends first.
+fn reach_unit_denominator(d: u64, z: i128, q0: i128) -> Option<(BigInt, BigInt, i128)> {
+    let a0 = isqrt_u64(d) as i128;
+    let d = d as i128;
+    let (mut p, mut q) = (z, q0);
+    // (Gᵢ₋₁, Gᵢ₋₂) and (Bᵢ₋₁, Bᵢ₋₂), with Gᵢ = q₀·Aᵢ - z·Bᵢ
+    let (mut g, mut b) = ((BigInt::from(q0), BigInt::from(-z)), (BigInt::zero(), BigInt::from(1)));
+    let mut first_reduced: Option<(i128, i128)> = None;
+    let mut odd = false;
+
+    loop {
+        if q.abs() == 1 {
+            let sign = if odd { -1 } else { 1 };
+            return Some((g.0, b.0, sign * q * q0));
+        }
+        // (p + √D)/q is reduced from some point on, and then purely periodic
+        if first_reduced == Some((p, q)) {
+            return None;
+        }
+        if first_reduced.is_none() && q > 0 && p > 0 && p <= a0 && a0 - p < q && q <= p + a0 {
+            first_reduced = Some((p, q));
+        }
+
+        let a = if q > 0 { (p + a0).div_euclid(q) } else { -(p + a0).div_euclid(-q) - 1 };
+        g = (&g.0 * a + &g.1, g.0);
+        b = (&b.0 * a + &b.1, b.0);
+        p = a * q - p;
+        q = (d - p * p) / q;
+        odd = !odd;
+    }
+}
+
+/// The fundamental solution of the class of `(x, y)`: the member with the
+/// smallest |y|, signed so that y ≥ 0 and, between two such members, x > 0
+fn class_representative(d: &BigInt, (x1, y1): (&BigInt, &BigInt), (mut x, mut y): (BigInt, BigInt)) -> (BigInt, BigInt) {
+    let inverse_y1 = -y1;
+    loop {
+        let up = multiply(d, (&x, &y), (x1, y1));
+        let down = multiply(d, (&x, &y), (x1, &inverse_y1));
+        if up.1.abs() < y.abs() {
+            (x, y) = up;
+        } else if down.1.abs() < y.abs() {
+            (x, y) = down;
+        } else {
+            // A neighbour with the same |y| is the same class with the sign of x flipped
+            let tied = [up, down].into_iter().any(|(_, ny)| ny.abs() == y.abs());
+            if y.is_negative() {
+                (x, y) = (-x, -y);
+            }
+            if tied || y.is_zero() {
+                x = x.abs();
+            }
+            return (x, y);
+        }
+    }
+}
+
+/// The first `count` solutions in the class of `(x, y)`
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D (must be > 1 and non-square)
+/// * `x`, `y` - A solution of x² - D·y² = N, usually a fundamental solution
+///   from [`pell_general_fundamental_solutions`]
+/// * `count` - Number of solutions to generate
+///
+/// # Returns
+///
+/// A `Result` containing `(x, y)·(x₁ + y₁√D)ᵏ` for k = 0..count, where
+/// (x₁, y₁) is the fundamental solution of x² - D·y² = 1, or a `PellError`.
+/// Solutions after the first are negated where needed so that y ≥ 0, which
+/// makes them positive.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigInt;
+/// # use pell991::pell_general_class_solutions;
+/// let solutions = pell_general_class_solutions(2, &BigInt::from(3), &BigInt::from(1), 3).unwrap();
+/// let ys: Vec<String> = solutions.iter().map(|(_, y)| y.to_string()).collect();
+/// assert_eq!(ys, ["1", "9", "53"]); // 13² - 2·9² = 7
+/// ```
+pub fn pell_general_class_solutions(d: u64, x: &BigInt, y: &BigInt, count: usize) -> Result<Vec<(BigInt, BigInt)>, PellError> {
+    let (x1, y1) = pell_min_solution(d)?;
+    pell_general_class_solutions_with_fundamental(d, x, y, &x1, &y1, count)
+}
+
+/// [`pell_general_class_solutions`] from an already computed fundamental
+/// solution (x₁, y₁) of x² - D·y² = 1
+///
+/// Skips solving the Pell equation again, which matters when walking several
+/// classes of the same D. The pair is trusted as given.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigInt;
+/// # use pell991::pell_general_class_solutions_with_fundamental;
+/// let (x1, y1) = (BigInt::from(3), BigInt::from(2));
+/// let solutions = pell_general_class_solutions_with_fundamental(2, &BigInt::from(3), &BigInt::from(1), &x1, &y1, 2);
+/// assert_eq!(solutions.unwrap()[1], (BigInt::from(13), BigInt::from(9)));
+/// ```
+pub fn pell_general_class_solutions_with_fundamental(
+    d: u64,
+    x: &BigInt,
+    y: &BigInt,
+    x1: &BigInt,
+    y1: &BigInt,
+    count: usize,
+) -> Result<Vec<(BigInt, BigInt)>, PellError> {
+    validate_d(d)?;
+    let d = BigInt::from(d);
+    let mut solutions = Vec::with_capacity(count);
+    if count > 0 {
+        solutions.push((x.clone(), y.clone()));
+    }
+    let mut current = (x.clone(), y.clone());
+    while solutions.len() < count {
+        current = multiply(&d, (&current.0, &current.1), (x1, y1));
+        let (x, y) = &current;
+        solutions.push(if y.is_negative() { (-x, -y) } else { (x.clone(), y.clone()) });
+    }
+    Ok(solutions)
+}
@@ -0,0 +1,85 @@
+//! The half-period method for Pell equations
+
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::error::PellError;
+use crate::solver::validate_d;
+use crate::utils::isqrt_u64;
+
+/// Solve the Pell equation x² - D·y² = 1 by expanding only half the period of √D.
+///
+/// The period of the continued fraction of √D is a palindrome, and so are the
+/// (Pₙ, Qₙ) of the expansion around its middle. The middle is recognised by
+/// Pₙ₊₁ = Pₙ (even period) or Qₙ₊₁ = Qₙ (odd period), and the convergents
+/// there already determine the fundamental unit, so the expansion stops after
+/// half the steps of [`pell_min_solution`](crate::pell_min_solution).
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation (must be > 1 and non-square)
+///
+/// # Returns
+///
+/// A `Result` containing the minimal solution `(x, y)`, or a `PellError` if the
+/// input is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Algorithm
+///
+/// With pₙ/qₙ the convergents: for an even period stopping at n, the solution
+/// is ((pₙ₋₁² + D·qₙ₋₁²) / Qₙ, 2pₙ₋₁qₙ₋₁ / Qₙ). For an odd period,
+/// (pₙ + qₙ√D)(pₙ₋₁ + qₙ₋₁√D) / Qₙ is the solution of x² - D·y² = -1, and its
+/// square the solution of the +1 equation.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_min_solution, pell_min_solution_half_period};
+/// assert_eq!(pell_min_solution_half_period(991).unwrap(), pell_min_solution(991).unwrap());
+/// assert_eq!(pell_min_solution_half_period(13).unwrap(), pell_min_solution(13).unwrap());
+/// ```
+pub fn pell_min_solution_half_period(d: u64) -> Result<(BigInt, BigInt), PellError> {
+    validate_d(d)?;
+
+    let d_i = d as i128;
+    let a0 = isqrt_u64(d) as i128;
+    let big_d = BigInt::from(d);
+
+    // (Pₙ, Qₙ, aₙ) of the expansion, and the convergents pₙ₋₁/qₙ₋₁ and pₙ/qₙ
+    let (mut m, mut q, mut a) = (0i128, 1i128, a0);
+    let (mut x_prev, mut x) = (BigInt::one(), BigInt::from(a0));
+    let (mut y_prev, mut y) = (BigInt::zero(), BigInt::one());
+
+    loop {
+        let m_next = q * a - m;
+        let q_next = (d_i - m_next * m_next) / q;
+        count!(cf_steps += 1);
+
+        if m_next == m {
+            let x1 = (&x_prev * &x_prev + &big_d * &y_prev * &y_prev) / q;
+            let y1 = ((&x_prev * &y_prev) << 1) / q;
+            count!(bigint_multiplications += 4);
+            return Ok((x1, y1));
+        }
+        if q_next == q {
+            let x_neg = (&x * &x_prev + &big_d * &y * &y_prev) / q;
+            let y_neg = (&x * &y_prev + &y * &x_prev) / q;
+            let x1 = &x_neg * &x_neg + &big_d * &y_neg * &y_neg;
+            let y1 = (&x_neg * &y_neg) << 1;
+            count!(bigint_multiplications += 10);
+            return Ok((x1, y1));
+        }
+
+        (m, q) = (m_next, q_next);
+        a = (a0 + m) / q;
+        let x_next = &x * a + &x_prev;
+        let y_next = &y * a + &y_prev;
+        count!(bigint_multiplications += 2);
+        x_prev = core::mem::replace(&mut x, x_next);
+        y_prev = core::mem::replace(&mut y, y_next);
+    }
+}
@@ -0,0 +1,262 @@
+//! A parallel harness testing the known identities over a range of D
+//!
+//! [`verify_all`] runs a battery of [`Checks`] on every valid D of a range,
+//! spread over a thread pool, and collects whatever does not hold into a
+//! [`VerifyReport`]. With the reference algorithms the report is empty, so the
+//! harness is mostly useful for validating a new [`Solver`] backend, or the
+//! crate itself after a change to its arithmetic.
+//!
+//! # Examples
+//!
+//! ```
+//! # use pell991::harness::{verify_all, Checks};
+//! # use pell991::ChakravalaSolver;
+//! let checks = Checks::all().solutions(Box::new(ChakravalaSolver));
+//! let report = verify_all(2..=2000, &checks, 4);
+//! assert_eq!(report.checked, 1956);
+//! assert!(report.is_clean(), "{:?}", report.anomalies);
+//! ```
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::RangeInclusive;
+
+use num_bigint::BigInt;
+use rayon::prelude::*;
+
+use crate::algorithms::{CfSolver, Solver};
+use crate::continued_fraction::{cf_structure, period_length};
+use crate::negative::{congruence_obstruction, pell_negative_solution};
+use crate::solver::{pell_min_solution, verify_pell_solution};
+use crate::utils::is_valid_pell_d;
+
+/// One check of the battery, naming the source of an [`Anomaly`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Check {
+    /// The solver's answer satisfies x² - D·y² = 1 and is the minimal solution
+    Solution,
+    /// The period of √D ends in 2a₀ and is symmetric, see [`cf_structure`]
+    Palindrome,
+    /// The solvability of x² - D·y² = -1 agrees with its criteria
+    NegativePell,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Check::Solution => "solution",
+            Check::Palindrome => "palindrome",
+            Check::NegativePell => "negative_pell",
+        })
+    }
+}
+
+/// The battery run by [`verify_all`]
+///
+/// Starts empty; add checks with the builder methods, or start from
+/// [`Checks::all`].
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::harness::Checks;
+/// # use pell991::BsgsSolver;
+/// let checks = Checks::new().solutions(Box::new(BsgsSolver::new())).negative_pell();
+/// ```
+#[derive(Default)]
+pub struct Checks {
+    solver: Option<Box<dyn Solver>>,
+    palindrome: bool,
+    negative_pell: bool,
+}
+
+impl fmt::Debug for Checks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Checks")
+            .field("solver", &self.solver.as_ref().map(|solver| solver.name()))
+            .field("palindrome", &self.palindrome)
+            .field("negative_pell", &self.negative_pell)
+            .finish()
+    }
+}
+
+impl Checks {
+    /// A battery without any checks
+    pub fn new() -> Self {
+        Checks::default()
+    }
+
+    /// Every check, with the solutions found by the continued fraction
+    pub fn all() -> Self {
+        Checks::new().solutions(Box::new(CfSolver)).palindrome().negative_pell()
+    }
+
+    /// Check the minimal solutions found by `solver`, replacing any earlier solver
+    ///
+    /// Each answer must satisfy the equation and, unless `solver` is the
+    /// continued fraction itself, equal the answer of `pell_min_solution`.
+    pub fn solutions(mut self, solver: Box<dyn Solver>) -> Self {
+        self.solver = Some(solver);
+        self
+    }
+
+    /// Check the symmetries of the period of √D
+    pub fn palindrome(mut self) -> Self {
+        self.palindrome = true;
+        self
+    }
+
+    /// Check that a congruence obstruction forces an even period, and that
+    /// for an odd period the negative solution exists and squares to the
+    /// minimal solution
+    pub fn negative_pell(mut self) -> Self {
+        self.negative_pell = true;
+        self
+    }
+}
+
+/// A D for which a check failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anomaly {
+    /// The D value
+    pub d: u64,
+    /// The check that failed
+    pub check: Check,
+    /// What went wrong
+    pub details: String,
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "D = {}: {} check failed: {}", self.d, self.check, self.details)
+    }
+}
+
+/// The outcome of [`verify_all`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// How many valid D were checked
+    pub checked: u64,
+    /// The failures, ordered by D and then by check
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl VerifyReport {
+    /// Whether every check passed
+    pub fn is_clean(&self) -> bool {
+        self.anomalies.is_empty()
+    }
+}
+
+/// Run the battery on one D, pushing its failures
+fn check_d(d: u64, checks: &Checks, anomalies: &mut Vec<Anomaly>) {
+    let mut fail = |check, details: String| anomalies.push(Anomaly { d, check, details });
+
+    if let Some(solver) = &checks.solver {
+        match solver.min_solution(d) {
+            Err(err) => fail(Check::Solution, format!("{} failed: {err}", solver.name())),
+            Ok((x, y)) if !verify_pell_solution(d, &x, &y) => {
+                fail(Check::Solution, format!("{} returned ({x}, {y}), which is not a solution", solver.name()));
+            }
+            Ok((x, y)) if solver.name() != CfSolver.name() => {
+                if let Ok((min_x, min_y)) = pell_min_solution(d) {
+                    if (&x, &y) != (&min_x, &min_y) {
+                        let name = solver.name();
+                        let details = format!("{name} returned ({x}, {y}), the minimal solution is ({min_x}, {min_y})");
+                        fail(Check::Solution, details);
+                    }
+                }
+            }
+            Ok(_) => {}
+        }
+    }
+
+    if checks.palindrome {
+        match cf_structure(d) {
+            Ok(structure) if !structure.holds() => fail(Check::Palindrome, format!("{structure:?}")),
+            Ok(_) => {}
+            Err(err) => fail(Check::Palindrome, format!("{err}")),
+        }
+    }
+
+    if checks.negative_pell {
+        if let Some(details) = negative_pell_mismatch(d) {
+            fail(Check::NegativePell, details);
+        }
+    }
+}
+
+/// What contradicts the criteria for x² - D·y² = -1, if anything
+fn negative_pell_mismatch(d: u64) -> Option<String> {
+    let period = match period_length(d) {
+        Ok(period) => period,
+        Err(err) => return Some(format!("{err}")),
+    };
+    if let Some(reason) = congruence_obstruction(d) {
+        return (period % 2 == 1).then(|| format!("odd period {period}, but {reason}"));
+    }
+
+    match (pell_negative_solution(d), period % 2 == 1) {
+        (Ok((x, y)), true) => {
+            let norm = &x * &x - BigInt::from(d) * &y * &y;
+            if norm != BigInt::from(-1) {
+                return Some(format!("({x}, {y}) has norm {norm}, not -1"));
+            }
+            let square = (&x * &x + d * &y * &y, 2 * &x * &y);
+            match pell_min_solution(d) {
+                Ok(min) if min == square => None,
+                Ok((min_x, min_y)) => {
+                    Some(format!("({x}, {y}) squares to ({}, {}), not ({min_x}, {min_y})", square.0, square.1))
+                }
+                Err(err) => Some(format!("{err}")),
+            }
+        }
+        (Ok((x, y)), false) => Some(format!("even period {period}, but ({x}, {y}) was returned")),
+        (Err(err), true) => Some(format!("odd period {period}, but {err}")),
+        (Err(_), false) => None,
+    }
+}
+
+/// Run `checks` on every valid D in `range`, in parallel
+///
+/// D ≤ 1 and perfect squares are skipped. The D values are spread over a
+/// dedicated pool of `threads` threads, so the run does not compete with
+/// other work on rayon's global pool.
+///
+/// # Arguments
+///
+/// * `range` - The D values to check
+/// * `checks` - The battery to run on each D
+/// * `threads` - Size of the thread pool, or 0 for one thread per CPU
+///
+/// # Returns
+///
+/// A `VerifyReport` with the number of D checked and every failure.
+///
+/// # Panics
+///
+/// Panics if the thread pool cannot be created.
+pub fn verify_all(range: RangeInclusive<u64>, checks: &Checks, threads: usize) -> VerifyReport {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().expect("failed to create the thread pool");
+    pool.install(|| {
+        let (checked, anomalies) = range
+            .into_par_iter()
+            .filter(|&d| is_valid_pell_d(d))
+            .map(|d| {
+                let mut anomalies = Vec::new();
+                check_d(d, checks, &mut anomalies);
+                (1, anomalies)
+            })
+            .reduce(
+                || (0, Vec::new()),
+                |(checked, mut anomalies), (more, rest)| {
+                    anomalies.extend(rest);
+                    (checked + more, anomalies)
+                },
+            );
+        VerifyReport { checked, anomalies }
+    })
+}
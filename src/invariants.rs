@@ -0,0 +1,308 @@
+//! Checkers for the mathematical identities the crate relies on
+//!
+//! Each function verifies one family of invariants and reports the first one
+//! that fails, so fuzz targets and downstream property tests can assert
+//! correctness in one call instead of re-deriving the identities:
+//!
+//! * [`check_solution_invariants`]: a run of consecutive solutions
+//! * [`check_cf_invariants`]: the continued fraction of √D
+//! * [`check_group_law`]: multiplication in Z[√D]
+//! * [`check_recurrence`]: an imported run of solutions, reporting the index
+//!   of the first bad one as an [`IdentityViolation`]
+//!
+//! # Examples
+//!
+//! ```
+//! # use pell991::invariants::{check_cf_invariants, check_group_law, check_solution_invariants};
+//! # use pell991::{continued_fraction, pell_solutions};
+//! let solutions = pell_solutions(991, 5).unwrap();
+//! check_solution_invariants(991, &solutions).unwrap();
+//! check_cf_invariants(&continued_fraction(991).unwrap()).unwrap();
+//! check_group_law(991, &solutions[1], &solutions[3]).unwrap();
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+use num_bigint::BigInt;
+use num_traits::{One, Signed, Zero};
+
+use crate::continued_fraction::{continued_fraction, ContinuedFraction};
+use crate::solver::verify_pell_solution;
+use crate::utils::{is_valid_pell_d, isqrt_u64};
+
+/// A failed invariant: which identity broke, and on what values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvariantViolation {
+    /// Short name of the invariant, such as `"norm"` or `"palindrome"`
+    pub invariant: &'static str,
+    /// The values that break it
+    pub details: String,
+}
+
+impl InvariantViolation {
+    fn new(invariant: &'static str, details: String) -> Self {
+        InvariantViolation { invariant, details }
+    }
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invariant `{}` violated: {}", self.invariant, self.details)
+    }
+}
+
+impl core::error::Error for InvariantViolation {}
+
+/// A failed identity in a run of solutions: which one broke, and at which index
+///
+/// Returned by [`check_recurrence`]; `k` is the 1-based index of the first
+/// solution that does not fit, counting from the fundamental one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentityViolation {
+    /// Index k of the offending solution (xₖ, yₖ)
+    pub k: u64,
+    /// Short name of the identity, such as `"norm"` or `"recurrence"`
+    pub identity: &'static str,
+    /// The values that break it
+    pub details: String,
+}
+
+impl fmt::Display for IdentityViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "identity `{}` fails at k = {}: {}", self.identity, self.k, self.details)
+    }
+}
+
+impl core::error::Error for IdentityViolation {}
+
+/// Fail with `invariant` unless `holds`
+fn ensure(holds: bool, invariant: &'static str, details: impl FnOnce() -> String) -> Result<(), InvariantViolation> {
+    if holds { Ok(()) } else { Err(InvariantViolation::new(invariant, details())) }
+}
+
+/// (x + y√D)(u + v√D)
+fn multiply(d: &BigInt, (x, y): (&BigInt, &BigInt), (u, v): (&BigInt, &BigInt)) -> (BigInt, BigInt) {
+    (x * u + d * y * v, x * v + y * u)
+}
+
+/// The norm x² - D·y² of x + y√D
+fn norm(d: &BigInt, (x, y): (&BigInt, &BigInt)) -> BigInt {
+    x * x - d * y * y
+}
+
+/// Check a run of consecutive solutions of x² - D·y² = 1, starting at k = 1
+///
+/// `solutions` is read as (x₁, y₁), (x₂, y₂), … as returned by
+/// [`pell_solutions`](crate::pell_solutions). Checked:
+///
+/// * `valid_d`: D > 1 and D is not a perfect square
+/// * `positive`: every x and y is positive
+/// * `norm`: every pair satisfies x² - D·y² = 1
+/// * `recurrence`: xₖ₊₁ + yₖ₊₁√D = (x₁ + y₁√D)(xₖ + yₖ√D)
+///
+/// Together these make the run strictly increasing. Minimality of (x₁, y₁)
+/// is not checked, since that would mean solving D again.
+///
+/// # Errors
+///
+/// Returns the first `InvariantViolation` found.
+pub fn check_solution_invariants(d: u64, solutions: &[(BigInt, BigInt)]) -> Result<(), InvariantViolation> {
+    ensure(is_valid_pell_d(d), "valid_d", || format!("D = {d} is not a valid Pell D"))?;
+
+    for (k, (x, y)) in (1..).zip(solutions) {
+        ensure(x.is_positive() && y.is_positive(), "positive", || format!("solution {k} is ({x}, {y})"))?;
+        ensure(verify_pell_solution(d, x, y), "norm", || {
+            format!("solution {k} = ({x}, {y}) does not satisfy x² - {d}·y² = 1")
+        })?;
+    }
+
+    let big_d = BigInt::from(d);
+    if let Some((x1, y1)) = solutions.first() {
+        for (k, pair) in (1..).zip(solutions.windows(2)) {
+            let (x, y) = &pair[0];
+            let next = multiply(&big_d, (x1, y1), (x, y));
+            ensure(next == pair[1], "recurrence", || {
+                format!("solution {} is ({}, {}), expected ({}, {})", k + 1, pair[1].0, pair[1].1, next.0, next.1)
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Check the continued fraction expansion of √D
+///
+/// D is not part of a [`ContinuedFraction`], so it is recovered from the
+/// convergent pᵣ₋₁/qᵣ₋₁ closing the first period, which satisfies
+/// p² - D·q² = (-1)ʳ for a period of length r. Checked:
+///
+/// * `nonempty`: the period has at least one term
+/// * `period_end`: the last term of the period is 2a₀
+/// * `bounds`: the other terms lie in 1..=a₀
+/// * `palindrome`: the period without its last term reads the same backwards
+/// * `recovered_d`: (p² - (-1)ʳ)/q² is an integer D with ⌊√D⌋ = a₀
+/// * `expansion`: expanding √D again gives exactly `cf`
+///
+/// # Errors
+///
+/// Returns the first `InvariantViolation` found.
+pub fn check_cf_invariants(cf: &ContinuedFraction) -> Result<(), InvariantViolation> {
+    let period = &cf.period;
+    let Some((&last, inner)) = period.split_last() else {
+        return Err(InvariantViolation::new("nonempty", format!("{cf} has an empty period")));
+    };
+
+    ensure(u128::from(last) == 2 * u128::from(cf.a0), "period_end", || {
+        format!("{cf} ends in {last}, not 2a₀ = {}", 2 * u128::from(cf.a0))
+    })?;
+    if let Some((i, a)) = inner.iter().enumerate().find(|&(_, &a)| a == 0 || a > cf.a0) {
+        return Err(InvariantViolation::new("bounds", format!("term {} of {cf} is {a}, outside 1..={}", i + 1, cf.a0)));
+    }
+    ensure(inner.iter().eq(inner.iter().rev()), "palindrome", || format!("{cf} is not symmetric"))?;
+
+    let (p, q) = cf.convergents().nth(period.len() - 1).expect("convergents never end");
+    let sign = if period.len() % 2 == 0 { BigInt::one() } else { -BigInt::one() };
+    let numerator = &p * &p - sign;
+    let denominator = &q * &q;
+    let d = (!denominator.is_zero() && (&numerator % &denominator).is_zero())
+        .then(|| u64::try_from(numerator / denominator).ok())
+        .flatten();
+    let Some(d) = d else {
+        return Err(InvariantViolation::new("recovered_d", format!("{cf} is not the expansion of √D for any D")));
+    };
+    ensure(isqrt_u64(d) == cf.a0, "recovered_d", || format!("{cf} gives D = {d}, but ⌊√{d}⌋ ≠ {}", cf.a0))?;
+
+    ensure(continued_fraction(d).as_ref() == Ok(cf), "expansion", || format!("{cf} is not the expansion of √{d}"))
+}
+
+/// Check the multiplication of Z[√D] on two elements x + y√D
+///
+/// `a` and `b` can be any pairs; when both are Pell solutions, so is their
+/// product, which is what makes the solutions a group. Checked:
+///
+/// * `norm`: N(ab) = N(a)·N(b), where N(x + y√D) = x² - D·y²
+/// * `commutative`: ab = ba
+/// * `conjugate`: a·ā = N(a), where ā = x - y√D
+/// * `closure`: if N(a) = N(b) = 1, then ab and a·b̄ are Pell solutions
+///
+/// # Errors
+///
+/// Returns the first `InvariantViolation` found.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::invariants::check_group_law;
+/// # use pell991::BigInt;
+/// // (3 + 2√2)(17 + 12√2) = 99 + 70√2
+/// let a = (BigInt::from(3), BigInt::from(2));
+/// let b = (BigInt::from(17), BigInt::from(12));
+/// assert!(check_group_law(2, &a, &b).is_ok());
+/// ```
+pub fn check_group_law(d: u64, a: &(BigInt, BigInt), b: &(BigInt, BigInt)) -> Result<(), InvariantViolation> {
+    let big_d = BigInt::from(d);
+    let (a, b) = ((&a.0, &a.1), (&b.0, &b.1));
+    let product = multiply(&big_d, a, b);
+    let (norm_a, norm_b) = (norm(&big_d, a), norm(&big_d, b));
+
+    let norm_product = norm(&big_d, (&product.0, &product.1));
+    ensure(norm_product == &norm_a * &norm_b, "norm", || {
+        format!("N({} + {}√{d}) = {norm_product}, expected {norm_a}·{norm_b}", product.0, product.1)
+    })?;
+    ensure(multiply(&big_d, b, a) == product, "commutative", || "ab ≠ ba".into())?;
+
+    let conjugate_a = -a.1;
+    let (real, imaginary) = multiply(&big_d, a, (a.0, &conjugate_a));
+    ensure(real == norm_a && imaginary.is_zero(), "conjugate", || {
+        format!("a·ā = {real} + {imaginary}√{d}, expected {norm_a}")
+    })?;
+
+    if norm_a.is_one() && norm_b.is_one() {
+        let conjugate_b = -b.1;
+        let quotient = multiply(&big_d, a, (b.0, &conjugate_b));
+        for (name, (x, y)) in [("ab", &product), ("ab̄", &quotient)] {
+            ensure(verify_pell_solution(d, x, y), "closure", || {
+                format!("{name} = ({x}, {y}) does not satisfy x² - {d}·y² = 1")
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Check an imported run of solutions of x² - D·y² = 1, starting at k = 1
+///
+/// Meant for validating a dataset in one call: the identities are tried
+/// index by index, and the first solution breaking any of them is reported.
+/// With (x₀, y₀) = (1, 0), checked for each k:
+///
+/// * `positive`: xₖ and yₖ are positive
+/// * `norm`: xₖ² - D·yₖ² = 1
+/// * `composition`: x₂ⱼ + y₂ⱼ√D = (xⱼ + yⱼ√D)², for even k = 2j
+/// * `recurrence`: xₖ = 2x₁xₖ₋₁ - xₖ₋₂ and yₖ = 2x₁yₖ₋₁ - yₖ₋₂, for k ≥ 2
+///
+/// As in [`check_solution_invariants`], minimality of (x₁, y₁) is not checked.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `solutions` - The run (x₁, y₁), (x₂, y₂), …
+///
+/// # Errors
+///
+/// Returns an `IdentityViolation` at the first bad index, or at k = 0 if D is
+/// not a valid Pell D.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::invariants::check_recurrence;
+/// # use pell991::{pell_solutions, BigInt};
+/// let mut solutions = pell_solutions(61, 10).unwrap();
+/// assert_eq!(check_recurrence(61, &solutions), Ok(()));
+///
+/// solutions[6].1 += 1;
+/// let violation = check_recurrence(61, &solutions).unwrap_err();
+/// assert_eq!((violation.k, violation.identity), (7, "norm"));
+/// ```
+pub fn check_recurrence(d: u64, solutions: &[(BigInt, BigInt)]) -> Result<(), IdentityViolation> {
+    let fail = |k: u64, identity: &'static str, details: String| Err(IdentityViolation { k, identity, details });
+    if !is_valid_pell_d(d) {
+        return fail(0, "valid_d", format!("D = {d} is not a valid Pell D"));
+    }
+    let Some((x1, _)) = solutions.first() else {
+        return Ok(());
+    };
+
+    let big_d = BigInt::from(d);
+    let identity = (BigInt::one(), BigInt::zero());
+    for (k, (x, y)) in (1u64..).zip(solutions) {
+        if !(x.is_positive() && y.is_positive()) {
+            return fail(k, "positive", format!("solution is ({x}, {y})"));
+        }
+        if !verify_pell_solution(d, x, y) {
+            return fail(k, "norm", format!("({x}, {y}) does not satisfy x² - {d}·y² = 1"));
+        }
+
+        // Once the earlier solutions are right, either identity alone pins
+        // down (xₖ, yₖ); the composition law is tried first at even k
+        let i = (k - 1) as usize;
+        if k % 2 == 0 {
+            let (half_x, half_y) = &solutions[i / 2];
+            let expected = multiply(&big_d, (half_x, half_y), (half_x, half_y));
+            if (x, y) != (&expected.0, &expected.1) {
+                let j = k / 2;
+                return fail(k, "composition", format!("got ({x}, {y}), expected the square of solution {j}"));
+            }
+        }
+        if i >= 1 {
+            let previous = &solutions[i - 1];
+            let before = if i >= 2 { &solutions[i - 2] } else { &identity };
+            let expected = (2 * x1 * &previous.0 - &before.0, 2 * x1 * &previous.1 - &before.1);
+            if (x, y) != (&expected.0, &expected.1) {
+                return fail(k, "recurrence", format!("got ({x}, {y}), expected ({}, {})", expected.0, expected.1));
+            }
+        }
+    }
+    Ok(())
+}
@@ -45,28 +45,225 @@
 //! println!("m = {}", m);  // 379516400906811930638014896080
 //! ```
 //!
+//! ## Features
+//!
+//! - `std` (default): links the standard library. Without it the crate is
+//!   `no_std` and only needs `alloc`, so the solvers run on embedded targets and
+//!   in WASM runtimes; floating point estimates then use `libm`.
+//! - `serde`: `Serialize`/`Deserialize` for [`PellSolution`], [`PellError`],
+//!   [`DReport`], [`ContinuedFraction`] and the position of a
+//!   [`PellSolutionIterator`]. Big integers are written as decimal strings,
+//!   see [`serde_bigint`](crate::serde_bigint).
+//! - `schemars`: `JsonSchema` for the same types (except the iterator), so
+//!   services built on this crate can publish and validate their payloads.
+//! - `binary`: [`encode_solution`](binary::encode_solution) and
+//!   [`decode_solution`](binary::decode_solution), a compact binary form of
+//!   solutions storing x and y as raw little-endian limbs.
+//! - `tracing`: spans and events around the continued fraction loop,
+//!   exponentiation and verification, for services using `tracing`.
+//! - `metrics`: per-thread counters of the work done by the solvers, see
+//!   [`metrics`](crate::metrics).
+//! - `rayon`: [`par_solutions`](parallel::par_solutions) and
+//!   [`pell_solutions_parallel`](parallel::pell_solutions_parallel), parallel
+//!   generation of solution sequences, and
+//!   [`pell_min_solutions_for`](parallel::pell_min_solutions_for), solving many
+//!   D values concurrently.
+//! - `disk-cache`: [`DiskCache`](disk_cache::DiskCache), a file-backed cache of
+//!   fundamental solutions that survives process restarts.
+//! - `ffi`: a C interface in [`ffi`](crate::ffi), declared in
+//!   `include/pell991.h`.
+//! - `uniffi`: Kotlin and Swift bindings generated by uniffi from
+//!   `src/pell991.udl`, see [`uniffi_bindings`](crate::uniffi_bindings);
+//!   `uniffi-bindgen` adds the binary that writes the foreign-language code.
+//! - `python`: a PyO3 extension module, see [`python`](crate::python),
+//!   built with maturin from `pyproject.toml`.
+//! - `arrow`: Arrow record batches of solutions and [`DReport`]s, see
+//!   [`columnar`](crate::columnar); `parquet` adds writers for Parquet files.
+//! - `sqlite`: [`SqliteCache`](sqlite_cache::SqliteCache), a cache of
+//!   fundamental solutions, periods and regulators in an SQLite database, which
+//!   the solvers consult once [`enable_sqlite_cache`](sqlite_cache::enable_sqlite_cache)
+//!   is called.
+//! - `server`: an axum router serving solutions and [`DReport`]s as JSON,
+//!   with timeouts and rate limiting, see [`server`](crate::server).
+//! - `proptest`: `Arbitrary` implementations and strategies for valid D
+//!   values, solution indices, equations and solutions, see
+//!   [`strategies`](crate::strategies).
+//! - `cli`: the `pell` command-line tool (`cargo install pell991 --features cli`).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The generated uniffi scaffolding has a doc comment followed by a blank line
+#![cfg_attr(feature = "uniffi", allow(clippy::empty_line_after_doc_comments))]
+
+extern crate alloc;
 
+/// Add to the work counters of the `metrics` feature; compiles to nothing without it
+macro_rules! count {
+    ($($field:ident += $amount:expr),+ $(,)?) => {
+        #[cfg(feature = "metrics")]
+        $crate::metrics::record(|metrics| {
+            $(metrics.$field += $amount as u64;)+
+        });
+    };
+}
+
+pub mod algorithms;
+pub mod analysis;
+pub mod benford;
+#[cfg(feature = "binary")]
+pub mod binary;
+pub mod bsgs;
+#[cfg(feature = "std")]
+pub mod cache;
+pub mod chakravala;
+#[cfg(feature = "arrow")]
+pub mod columnar;
+pub mod conic;
+pub mod continued_fraction;
+#[cfg(feature = "disk-cache")]
+pub mod disk_cache;
 pub mod error;
+pub mod estimate;
+pub mod families;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod forms;
+pub mod general;
+pub mod half_period;
+#[cfg(feature = "rayon")]
+pub mod harness;
+pub mod invariants;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod negative;
+pub mod options;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod prime_search;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod quotients;
+pub mod random;
+pub mod records;
+#[cfg(feature = "serde")]
+pub mod serde_bigint;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod solution;
 pub mod solver;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_cache;
+pub mod stormer;
+pub mod trace;
+#[cfg(feature = "proptest")]
+pub mod strategies;
+#[cfg(feature = "uniffi")]
+pub mod uniffi_bindings;
 pub mod utils;
 
+pub use algorithms::{
+    all_solvers, solver_by_name, AutoSolver, BsgsSolver, ChakravalaSolver, CfSolver, HalfPeriodSolver, Solver,
+    BENCHMARK_D_VALUES,
+};
+#[cfg(feature = "std")]
+pub use algorithms::{compare_solvers, SolverTiming};
+pub use analysis::{analyze_d, analyze_d_with_fundamental, regulator_table, DReport, RegulatorRow, CLASS_NUMBER_MAX_D};
+pub use benford::{leading_digit_distribution, BenfordStats};
+#[cfg(feature = "std")]
+pub use analysis::{survey, write_regulator_csv, SurveyRow};
+#[cfg(feature = "binary")]
+pub use binary::{decode_solution, encode_solution, SolutionView};
+pub use bsgs::pell_min_solution_bsgs;
+#[cfg(feature = "std")]
+pub use cache::{disable_solution_cache, enable_solution_cache, solution_cache_snapshot, SolutionCache};
+pub use chakravala::pell_min_solution_chakravala;
+#[cfg(feature = "arrow")]
+pub use columnar::{
+    regulator_rows_to_record_batch, regulator_schema, reports_schema, reports_to_record_batch, solutions_schema,
+    solutions_to_record_batch, BigIntEncoding,
+};
+#[cfg(feature = "parquet")]
+pub use columnar::{write_parquet, write_regulator_parquet, write_reports_parquet, write_solutions_parquet};
+pub use conic::ConicPoint;
+pub use continued_fraction::{
+    cf_period_is_palindromic, cf_structure, continued_fraction, nth_convergent, period_length, CfStructure,
+    ContinuedFraction, Convergents,
+};
+#[cfg(feature = "disk-cache")]
+pub use disk_cache::DiskCache;
 pub use error::PellError;
+pub use estimate::{estimate_memory, estimate_solve_cost, predicted_digits, SolveCost};
+pub use families::{classify_d, DFamily};
+pub use forms::{caliber, class_number, class_number_stats, ClassNumberStats};
+pub use general::{
+    min_n_for_square, pell_general_class_solutions, pell_general_class_solutions_with_fundamental,
+    pell_general_fundamental_solutions, pell_general_fundamental_solutions_with_fundamental,
+};
+pub use half_period::pell_min_solution_half_period;
+pub use invariants::{IdentityViolation, InvariantViolation};
+#[cfg(feature = "metrics")]
+pub use metrics::Metrics;
+pub use negative::{
+    negative_pell_density, pell_negative_solution, pell_negative_solution_with_fundamental, NegativeObstruction,
+    NegativePellDensity,
+};
+pub use options::{ProgressEvent, SolveOptions};
+#[cfg(feature = "rayon")]
+pub use parallel::{par_solutions, pell_min_solutions_for, pell_solutions_parallel};
+pub use prime_search::{find_prime_x, find_prime_y};
+pub use quotients::{partial_quotient_distribution, period_quotient_distribution, QuotientDistribution};
+pub use random::{RandomSource, SplitMix64};
+pub use records::{DigitRecord, DigitRecords, PeriodRecord, PeriodRecords};
+pub use solution::{d_for_solution, PellSolution};
+#[cfg(feature = "std")]
+pub use solution::{write_digits, write_solution};
 pub use solver::{
+    count_solutions_below,
+    for_each_solution,
+    last_digits,
     pell_min_solution, 
+    pell_min_solution_limited,
+    pell_min_solution_u128,
     pell_min_solution_unchecked,
+    pell_min_solution_with_options,
+    pell_min_solution_with_period,
     pell_solution_k, 
     pell_solution_k_unchecked,
+    pell_solution_k_x,
+    pell_solution_k_y,
     pell_solutions,
+    pell_solutions_range,
+    search_solutions,
+    solution_near,
+    solutions_below,
+    solve_range,
     verify_pell_solution,
+    verify_pell_solution_mod,
+    Pairs,
     PellSolutionIterator,
+    SearchBound,
+    Solutions,
+    TakeWhileDigits,
 };
+#[cfg(feature = "std")]
+pub use solver::solve_many;
+#[cfg(feature = "sqlite")]
+pub use sqlite_cache::{disable_sqlite_cache, enable_sqlite_cache, CachedSolution, SqliteCache, SqliteCacheError};
+pub use stormer::smooth_consecutive_pairs;
+#[cfg(feature = "uniffi")]
+use uniffi_bindings::{first_solutions, min_solution, nth_solution, verify_solution};
+#[cfg(feature = "uniffi")]
+uniffi::include_scaffolding!("pell991");
 pub use utils::{
     isqrt_u64, 
     is_square_u64, 
     is_valid_pell_d, 
     estimate_period_length, 
     fundamental_discriminant, 
-    is_prime
+    is_prime,
+    squarefree_part,
+    decimal_digits,
+    valid_pell_d_in,
+    DSieve
 };
 
 /// Re-export BigInt for convenience
@@ -1,47 +1,868 @@
-//! Binary executable for the 991 Pell Puzzle solver
-
-use pell991::{pell_min_solution, pell_solution_k, verify_pell_solution, PellError};
-
-fn main() -> Result<(), PellError> {
-    println!("The 991 Pell Puzzle");
-    println!("{}", "=".repeat(25));
-    println!();
-    
-    println!("Solving the mystery: when does √(991·n² + 1) become a perfect integer?");
-    println!();
-    
-    let d = 991_u64;
-    let (m, n) = pell_min_solution(d)?;
-    
-    println!("The magical solution found!");
-    println!("   n = {n}");
-    println!("   m = {m}");
-    println!();
-    
-    // Verify the solution
-    assert!(verify_pell_solution(d, &m, &n));
-    println!("Verified: m² - 991n² = 1");
-    println!();
-    
-    println!("This means √(991·{n}² + 1) = {m}");
-    println!("   After billions of tries, the irrational veil finally lifts!");
-    println!();
-    
-    println!("The infinite staircase of solutions:");
-    
-    // Generate a few more solutions
-    for k in 1..=5 {
-        let (xk, yk) = pell_solution_k(d, &m, &n, k)?;
-        println!("\nSolution {k}:");
-        println!("   x = {xk}");
-        println!("   y = {yk}");
-        if k == 1 {
-            println!("   This is our magical pair!");
-        }
-    }
-    
-    println!();
-    println!("The mathematical beauty of Pell equations revealed!");
-    
+//! The `pell` command-line tool
+//!
+//! Solves and explores Pell equations from the shell, built on the `pell991`
+//! library. Run `pell --help` for the list of subcommands.
+
+use std::env;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use num_bigint::BigInt;
+use pell991::{
+    analyze_d, analyze_d_with_fundamental, continued_fraction, decimal_digits, is_prime, is_valid_pell_d,
+    pell_general_class_solutions_with_fundamental, pell_general_fundamental_solutions_with_fundamental,
+    pell_min_solution_with_options, pell_negative_solution_with_fundamental, pell_solution_k, period_length,
+    smooth_consecutive_pairs, valid_pell_d_in, verify_pell_solution, write_digits, write_solution, DiskCache,
+    PellError, PellSolution, PellSolutionIterator, SolveOptions,
+};
+use rayon::prelude::*;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// Number of D values handed to the thread pool at once by `search`, so that
+/// matches are printed while the scan goes on
+const SEARCH_CHUNK: u64 = 1 << 14;
+
+/// Name of the solution cache file inside `--cache-dir`
+const CACHE_FILE: &str = "solutions.cache";
+
+/// Solve and explore Pell equations x² - D·y² = 1
+///
+/// Defaults for the global options are read from ~/.config/pell/config.toml
+/// (or $XDG_CONFIG_HOME/pell/config.toml) if it exists.
+///
+/// Exit status: 0 on success, 1 if the equation has no solution or a checked
+/// solution is invalid, 2 for usage errors, 3 for invalid input (such as a
+/// square D), 4 if the step limit was reached, and 5 for I/O and other errors.
+#[derive(Debug, Parser)]
+#[command(name = "pell", version, arg_required_else_help = true)]
+struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Options for every subcommand, which can also be set in the config file
+#[derive(Debug, Args)]
+struct GlobalArgs {
+    /// Output format of `solve`, `search` and `records`, and of errors (as JSON on stderr)
+    /// [default: text, or `format` from the config file]
+    #[arg(long, global = true, value_enum)]
+    format: Option<Format>,
+    /// Read defaults from FILE instead of the standard config file
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+    /// Number of worker threads for `search` and `records` [default: one per CPU]
+    #[arg(long, global = true, value_name = "N")]
+    threads: Option<usize>,
+    /// Give up solving after STEPS continued fraction steps
+    #[arg(long, global = true, value_name = "STEPS")]
+    max_steps: Option<u64>,
+    /// Keep fundamental solutions in a cache under DIR, read by every subcommand and
+    /// written by those solving a single D
+    #[arg(long, global = true, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+}
+
+/// Contents of the config file; every key is optional
+///
+/// ```toml
+/// format = "csv"
+/// threads = 4
+/// max-steps = 1000000
+/// cache-dir = "/var/cache/pell"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct Config {
+    format: Option<Format>,
+    threads: Option<usize>,
+    max_steps: Option<u64>,
+    cache_dir: Option<PathBuf>,
+}
+
+/// The global options after merging the command line over the config file
+struct Settings {
+    format: Format,
+    max_steps: Option<u64>,
+    cache: Option<DiskCache>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print the fundamental solution of x² - D·y² = 1, or other solutions
+    Solve(SolveArgs),
+    /// Print the minimal solution of x² - D·y² = -1, or why there is none
+    ///
+    /// Exits with status 1 if the equation has no solution.
+    Negative {
+        /// The coefficient D (greater than 1 and not a perfect square), decimal or 0x-prefixed hex
+        #[arg(value_parser = parse_d)]
+        d: u64,
+    },
+    /// Print the fundamental solution of every class of x² - D·y² = N
+    ///
+    /// Prints one "class i: (x, y)" line per solution. Exits with status 1 if
+    /// the equation has no solutions.
+    General {
+        /// The coefficient D (greater than 1 and not a perfect square), decimal or 0x-prefixed hex
+        #[arg(value_parser = parse_d)]
+        d: u64,
+        /// The right-hand side N (non-zero, may be negative)
+        #[arg(allow_hyphen_values = true)]
+        n: i64,
+        /// Print the first COUNT solutions of each class instead of only the fundamental one
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+    },
+    /// Print the continued fraction of √D, with the period in parentheses
+    Cf {
+        /// The coefficient D (greater than 1 and not a perfect square), decimal or 0x-prefixed hex
+        #[arg(value_parser = parse_d)]
+        d: u64,
+        /// Also list the first N convergents p/q
+        #[arg(long, value_name = "N")]
+        convergents: Option<usize>,
+    },
+    /// Check whether (x, y) solves x² - D·y² = 1
+    ///
+    /// Exits with status 1 if it does not.
+    Verify {
+        /// The coefficient D, decimal or 0x-prefixed hex
+        #[arg(value_parser = parse_d)]
+        d: u64,
+        /// The x-coordinate to check
+        #[arg(allow_hyphen_values = true)]
+        x: BigInt,
+        /// The y-coordinate to check
+        #[arg(allow_hyphen_values = true)]
+        y: BigInt,
+    },
+    /// Print the arithmetic properties of D and its Pell equation
+    Analyze {
+        /// The value to analyze, decimal or 0x-prefixed hex
+        #[arg(value_parser = parse_d)]
+        d: u64,
+    },
+    /// Scan a range of D values in parallel for Pell equations with given properties
+    ///
+    /// Prints "D period digits" for every match, where digits is the number of
+    /// digits of x in the fundamental solution, and a summary on stderr.
+    Search(SearchArgs),
+    /// Print all pairs of consecutive numbers whose prime factors all lie in a set
+    ///
+    /// Prints "n n+1" for each pair, found from Pell equations by Størmer's theorem.
+    Stormer {
+        /// The allowed prime factors, comma-separated
+        #[arg(long, value_delimiter = ',', required = true)]
+        primes: Vec<u64>,
+    },
+    /// Print the D up to a limit whose fundamental solution has the largest x
+    ///
+    /// This is Project Euler problem 66. The equations are solved in parallel
+    /// and the time taken is reported on stderr, so it doubles as a benchmark.
+    Euler66 {
+        /// Largest D to consider
+        #[arg(long, default_value_t = 1000, value_parser = parse_d)]
+        max_d: u64,
+    },
+    /// Print each D that sets a new record period length or solution size
+    ///
+    /// Prints "D value" lines as the scan goes, so long scans show progress.
+    Records {
+        /// Largest D to scan
+        #[arg(long, value_parser = parse_d)]
+        max_d: u64,
+        /// The quantity whose records to track
+        #[arg(long, value_enum, default_value_t = RecordKind::Period)]
+        by: RecordKind,
+    },
+}
+
+/// How results are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Format {
+    /// Human-readable lines
+    Text,
+    /// Comma-separated values with a header row, one row per solution or D
+    Csv,
+    /// One JSON object per solution or D, and errors as a JSON object on stderr
+    Json,
+}
+
+/// Failure categories, each with its own exit status that scripts can rely on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    /// The equation has no solution, or a checked solution is invalid
+    Unsolvable = 1,
+    /// The command line could not be parsed
+    Usage = 2,
+    /// An argument or the config file is invalid
+    InvalidInput = 3,
+    /// The step limit or time limit was reached
+    Limit = 4,
+    /// Reading or writing a file failed, or another unexpected error
+    Internal = 5,
+}
+
+impl From<Failure> for ExitCode {
+    fn from(failure: Failure) -> Self {
+        ExitCode::from(failure as u8)
+    }
+}
+
+/// An error ending the command, reported on stderr
+#[derive(Debug)]
+struct CliError {
+    failure: Failure,
+    /// Stable snake_case identifier of the error, finer than `failure`
+    kind: &'static str,
+    message: String,
+}
+
+impl CliError {
+    fn new(failure: Failure, kind: &'static str, message: impl Into<String>) -> Self {
+        CliError { failure, kind, message: message.into() }
+    }
+
+    /// Print the error to stderr, as a JSON object in the JSON format
+    fn report(&self, format: Format) {
+        match format {
+            Format::Json => eprintln!(
+                "{{\"error\":{{\"kind\":\"{}\",\"exit_code\":{},\"message\":{}}}}}",
+                self.kind,
+                self.failure as u8,
+                json_string(&self.message)
+            ),
+            Format::Text | Format::Csv => eprintln!("error: {self}"),
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<PellError> for CliError {
+    fn from(err: PellError) -> Self {
+        let (failure, kind) = match &err {
+            PellError::InvalidD(_) => (Failure::InvalidInput, "invalid_d"),
+            PellError::PerfectSquare(_) => (Failure::InvalidInput, "perfect_square"),
+            PellError::InvalidK(_) => (Failure::InvalidInput, "invalid_k"),
+            PellError::InvalidN(_) => (Failure::InvalidInput, "invalid_n"),
+            PellError::InvalidPrimeSet(_) => (Failure::InvalidInput, "invalid_prime_set"),
+            PellError::Parse(_) => (Failure::InvalidInput, "parse"),
+            PellError::Decode(_) => (Failure::InvalidInput, "decode"),
+            PellError::Overflow { .. } => (Failure::Limit, "overflow"),
+            PellError::LimitExceeded { .. } => (Failure::Limit, "limit_exceeded"),
+            PellError::Timeout { .. } => (Failure::Limit, "timeout"),
+            PellError::NegativeUnsolvable { .. } => (Failure::Unsolvable, "negative_unsolvable"),
+            PellError::InvalidSolution(_) => (Failure::InvalidInput, "invalid_solution"),
+            _ => (Failure::Internal, "error"),
+        };
+        CliError::new(failure, kind, err.to_string())
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        CliError::new(Failure::Internal, "io", err.to_string())
+    }
+}
+
+/// `value` as a JSON string literal
+fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Write a row of `search` or `records` output in `format`
+fn write_row(out: &mut impl Write, format: Format, columns: &[(&str, u64)]) -> io::Result<()> {
+    for (i, (name, value)) in columns.iter().enumerate() {
+        match (format, i) {
+            (Format::Json, 0) => write!(out, "{{\"{name}\":{value}")?,
+            (Format::Json, _) => write!(out, ",\"{name}\":{value}")?,
+            (Format::Text, 0) | (Format::Csv, 0) => write!(out, "{value}")?,
+            (Format::Text, _) => write!(out, " {value}")?,
+            (Format::Csv, _) => write!(out, ",{value}")?,
+        }
+    }
+    if format == Format::Json {
+        write!(out, "}}")?;
+    }
+    writeln!(out)
+}
+
+/// Write the header row of CSV output; the other formats have none
+fn write_header(out: &mut impl Write, format: Format, columns: &[&str]) -> io::Result<()> {
+    if format == Format::Csv {
+        writeln!(out, "{}", columns.join(","))?;
+    }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// What `records` measures for each D
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum RecordKind {
+    /// Length of the continued fraction period of √D
+    Period,
+    /// Decimal digits of x in the fundamental solution
+    Digits,
+}
+
+#[derive(Debug, Args)]
+struct SolveArgs {
+    /// The coefficient D (greater than 1 and not a perfect square), decimal or 0x-prefixed hex
+    #[arg(value_parser = parse_d)]
+    d: u64,
+    /// Print the k-th solution instead of the fundamental one
+    #[arg(long, conflicts_with = "count")]
+    k: Option<u64>,
+    /// Print the first COUNT solutions, one per line
+    #[arg(long)]
+    count: Option<usize>,
+    /// Stream the solutions to FILE, one "x=…, y=…, d=…, k=…" line each, instead of printing them
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Print only the number of digits and the SHA-256 of the decimal digits of x and y
+    #[arg(long)]
+    summary: bool,
+}
+
+#[derive(Debug, Args)]
+struct SearchArgs {
+    /// Smallest D to scan
+    #[arg(long, default_value_t = 2, value_parser = parse_d)]
+    min_d: u64,
+    /// Largest D to scan
+    #[arg(long, value_parser = parse_d)]
+    max_d: u64,
+    /// Only D whose fundamental x has at least DIGITS digits
+    #[arg(long, value_name = "DIGITS")]
+    min_digits: Option<u64>,
+    /// Only prime D
+    #[arg(long)]
+    prime_d: bool,
+    /// Only D whose continued fraction period is longer than LENGTH
+    #[arg(long, value_name = "LENGTH")]
+    period_over: Option<u64>,
+}
+
+/// A D value found by `search`
+#[derive(Debug, Clone, Copy)]
+struct SearchMatch {
+    d: u64,
+    period: u64,
+    digits: u64,
+}
+
+fn main() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        // Help and version go to stdout as usual; usage errors too unless JSON was asked for
+        Err(err) if !err.use_stderr() || requested_format() != Some(Format::Json) => err.exit(),
+        Err(err) => {
+            let usage = CliError::new(Failure::Usage, "usage", clap_message(&err));
+            usage.report(Format::Json);
+            return usage.failure.into();
+        }
+    };
+    // Errors in the config file itself are reported in the command-line format
+    let mut format = cli.global.format.unwrap_or(Format::Text);
+    let result = settings(cli.global).and_then(|mut settings| {
+        format = settings.format;
+        run(cli.command, &mut settings)
+    });
+    match result {
+        Ok(code) => code,
+        Err(err) => {
+            err.report(format);
+            err.failure.into()
+        }
+    }
+}
+
+/// The `--format` given on a command line that clap rejected, found by hand
+fn requested_format() -> Option<Format> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let value = args.iter().enumerate().find_map(|(i, arg)| match arg.strip_prefix("--format") {
+        Some("") => args.get(i + 1).map(String::as_str),
+        Some(value) => value.strip_prefix('='),
+        None => None,
+    })?;
+    Format::from_str(value, true).ok()
+}
+
+/// The first line of a clap error, without its `error: ` prefix
+fn clap_message(err: &clap::Error) -> String {
+    let rendered = err.to_string();
+    let line = rendered.lines().next().unwrap_or_default();
+    line.strip_prefix("error: ").unwrap_or(line).to_string()
+}
+
+fn run(command: Command, settings: &mut Settings) -> Result<ExitCode, CliError> {
+    match command {
+        Command::Solve(args) => solve(&args, settings),
+        Command::Negative { d } => negative(d, settings),
+        Command::General { d, n, count } => general(d, n, count, settings),
+        Command::Cf { d, convergents } => cf(d, convergents, settings),
+        Command::Verify { d, x, y } => verify(d, &x, &y),
+        Command::Analyze { d } => analyze(d, settings),
+        Command::Search(args) => Ok(search(&args, settings)),
+        Command::Stormer { primes } => Ok(stormer(&primes)?),
+        Command::Euler66 { max_d } => euler66(max_d, settings),
+        Command::Records { max_d, by } => Ok(records(max_d, by, settings)),
+    }
+}
+
+/// The standard config file location, following the XDG base directory convention
+fn default_config_path() -> Option<PathBuf> {
+    let config_home = match env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_home.join("pell").join("config.toml"))
+}
+
+/// Read the config file: the one given with `--config`, which must exist, or
+/// else the standard one, which may be missing
+fn load_config(explicit: Option<&Path>) -> Result<Config, CliError> {
+    let path = match explicit {
+        Some(path) => path.to_path_buf(),
+        None => match default_config_path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Config::default()),
+        },
+    };
+    let text = fs::read_to_string(&path).map_err(|err| {
+        CliError::new(Failure::InvalidInput, "config", format!("cannot read config file {}: {err}", path.display()))
+    })?;
+    toml::from_str(&text).map_err(|err| {
+        CliError::new(Failure::InvalidInput, "config", format!("invalid config file {}: {err}", path.display()))
+    })
+}
+
+/// Merge the command line over the config file and set up the thread pool and cache
+fn settings(args: GlobalArgs) -> Result<Settings, CliError> {
+    let config = load_config(args.config.as_deref())?;
+
+    if let Some(threads) = args.threads.or(config.threads) {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .map_err(|err| CliError::new(Failure::Internal, "thread_pool", err.to_string()))?;
+    }
+    let cache = match args.cache_dir.or(config.cache_dir) {
+        Some(dir) => {
+            let path = dir.join(CACHE_FILE);
+            let open = fs::create_dir_all(&dir).and_then(|()| DiskCache::open(&path));
+            let message = |err| format!("cannot open solution cache {}: {err}", path.display());
+            Some(open.map_err(|err| CliError::new(Failure::Internal, "io", message(err)))?)
+        }
+        None => None,
+    };
+
+    Ok(Settings {
+        format: args.format.or(config.format).unwrap_or(Format::Text),
+        max_steps: args.max_steps.or(config.max_steps),
+        cache,
+    })
+}
+
+impl Settings {
+    /// Solve options carrying the step limit
+    fn options(&self) -> SolveOptions {
+        match self.max_steps {
+            Some(max_steps) => SolveOptions::new().max_steps(max_steps),
+            None => SolveOptions::new(),
+        }
+    }
+}
+
+/// The fundamental solution and period of D, from the cache if there is one, within
+/// the step limit, adding them to the cache if they had to be solved
+///
+/// The subcommands hand the result to the `_with_fundamental` library functions,
+/// so D is solved at most once per run.
+fn fundamental_solution(d: u64, settings: &mut Settings) -> Result<((BigInt, BigInt), u64), CliError> {
+    if let Some(entry) = settings.cache.as_ref().and_then(|cache| cache.get(d)) {
+        return Ok(entry.clone());
+    }
+
+    let (solution, period) = solve_entry(d, &settings.options(), None)?;
+    if let Some(cache) = &mut settings.cache {
+        cache.insert(d, solution.clone(), period)?;
+    }
+    Ok((solution, period))
+}
+
+/// The fundamental solution and period of D, from `cache` if given, within the
+/// limits of `options`; the parallel scans share the cache this way without writing it
+fn solve_entry(
+    d: u64,
+    options: &SolveOptions,
+    cache: Option<&DiskCache>,
+) -> Result<((BigInt, BigInt), u64), PellError> {
+    if let Some(entry) = cache.and_then(|cache| cache.get(d)) {
+        return Ok(entry.clone());
+    }
+    let solution = pell_min_solution_with_options(d, options)?;
+    // The solve took at least a period of steps, so this stays within the limit
+    let period = period_length(d)?;
+    Ok((solution, period))
+}
+
+/// Parse D as a decimal number, or as hexadecimal with a `0x` prefix
+///
+/// The error is a plain message for clap: `PellError::Parse` is about solution strings.
+fn parse_d(value: &str) -> Result<u64, String> {
+    let parsed = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    // clap already quotes the value, so the message only says what was wrong with it
+    parsed.map_err(|err| format!("D must be a decimal or 0x-prefixed hexadecimal 64-bit integer ({err})"))
+}
+
+fn solve(args: &SolveArgs, settings: &mut Settings) -> Result<ExitCode, CliError> {
+    let d = args.d;
+    let ((x1, y1), _) = fundamental_solution(d, settings)?;
+    let solutions: Box<dyn Iterator<Item = PellSolution>> = match (args.count, args.k) {
+        (Some(count), _) => Box::new(
+            PellSolutionIterator::with_fundamental(d, x1, y1)
+                .take(count)
+                .zip(1..)
+                .map(move |((x, y), k)| PellSolution::with_index(d, k, x, y)),
+        ),
+        (None, Some(k)) => {
+            let (x, y) = pell_solution_k(d, &x1, &y1, k)?;
+            Box::new(iter::once(PellSolution::with_index(d, k, x, y)))
+        }
+        (None, None) => Box::new(iter::once(PellSolution::with_index(d, 1, x1, y1))),
+    };
+
+    let mut file = match &args.output {
+        Some(path) => {
+            let file = File::create(path).map_err(|err| {
+                CliError::new(Failure::Internal, "io", format!("cannot create {}: {err}", path.display()))
+            })?;
+            Some(BufWriter::new(file))
+        }
+        None => None,
+    };
+    let format = settings.format;
+    let print = !args.summary && file.is_none();
+    if print && format == Format::Csv {
+        println!("d,k,x,y");
+    }
+
+    for solution in solutions {
+        if let Some(file) = &mut file {
+            write_solution(&solution, &mut *file, 10)?;
+            writeln!(file)?;
+        }
+        let k = solution.k.unwrap_or(1);
+        if args.summary {
+            for (name, value) in [("x", &solution.x), ("y", &solution.y)] {
+                let mut digest = DigitDigest::default();
+                write_digits(value, &mut digest, 10)?;
+                println!("k={k} {name} digits={} sha256={:x}", digest.digits, digest.hasher.finalize());
+            }
+        } else if print {
+            let (x, y) = (&solution.x, &solution.y);
+            match (format, args.count) {
+                (Format::Text, Some(_)) => println!("{solution}"),
+                (Format::Text, None) => println!("x = {x}\ny = {y}"),
+                (Format::Csv, _) => println!("{d},{k},{x},{y}"),
+                // x and y are strings, since JSON readers often cap numbers at 64 bits
+                (Format::Json, _) => println!("{{\"d\":{d},\"k\":{k},\"x\":\"{x}\",\"y\":\"{y}\"}}"),
+            }
+        }
+    }
+    if let Some(file) = &mut file {
+        file.flush()?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Counts and hashes the digits streamed into it by `solve --summary`
+#[derive(Default)]
+struct DigitDigest {
+    digits: u64,
+    hasher: Sha256,
+}
+
+impl Write for DigitDigest {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.digits += buf.iter().filter(|byte| byte.is_ascii_digit()).count() as u64;
+        self.hasher.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn negative(d: u64, settings: &mut Settings) -> Result<ExitCode, CliError> {
+    let ((x1, y1), period) = fundamental_solution(d, settings)?;
+    let (x, y) = pell_negative_solution_with_fundamental(d, &x1, &y1, period)?;
+    println!("x = {x}");
+    println!("y = {y}");
+    Ok(ExitCode::SUCCESS)
+}
+
+fn general(d: u64, n: i64, count: usize, settings: &mut Settings) -> Result<ExitCode, CliError> {
+    let ((x1, y1), _) = fundamental_solution(d, settings)?;
+    let classes = pell_general_fundamental_solutions_with_fundamental(d, n, &x1, &y1)?;
+    if classes.is_empty() {
+        let message = format!("x² - {d}·y² = {n} has no solutions");
+        return Err(CliError::new(Failure::Unsolvable, "no_solutions", message));
+    }
+    for (class, (x, y)) in classes.iter().enumerate() {
+        for (x, y) in pell_general_class_solutions_with_fundamental(d, x, y, &x1, &y1, count)? {
+            println!("class {}: ({x}, {y})", class + 1);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn cf(d: u64, convergents: Option<usize>, settings: &mut Settings) -> Result<ExitCode, CliError> {
+    // The expansion is no longer than the solve, so a solve within the limit bounds it
+    if settings.max_steps.is_some() {
+        fundamental_solution(d, settings)?;
+    }
+    let expansion = continued_fraction(d)?;
+    println!("√{d} = {expansion}");
+    if let Some(count) = convergents {
+        for (n, (p, q)) in expansion.convergents().take(count).enumerate() {
+            println!("{n}: {p}/{q}");
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn verify(d: u64, x: &BigInt, y: &BigInt) -> Result<ExitCode, CliError> {
+    if !verify_pell_solution(d, x, y) {
+        let message = format!("invalid: (x, y) = ({x}, {y}) does not solve x² - {d}·y² = 1");
+        return Err(CliError::new(Failure::Unsolvable, "invalid_solution", message));
+    }
+    println!("valid: (x, y) = ({x}, {y}) solves x² - {d}·y² = 1");
+    Ok(ExitCode::SUCCESS)
+}
+
+fn analyze(d: u64, settings: &mut Settings) -> Result<ExitCode, CliError> {
+    // Invalid D values still get a report, without the Pell-specific rows
+    let report = if is_valid_pell_d(d) {
+        let ((x1, y1), period) = fundamental_solution(d, settings)?;
+        analyze_d_with_fundamental(d, &x1, &y1, period)
+    } else {
+        analyze_d(d)
+    };
+    let show = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+    let rows = [
+        ("D", report.d.to_string()),
+        ("valid Pell D", report.is_valid.to_string()),
+        ("prime", report.is_prime.to_string()),
+        ("squarefree part", report.squarefree_part.to_string()),
+        ("period", show(report.period.map(|period| period.to_string()))),
+        ("x² - D·y² = -1 solvable", show(report.negative_pell_solvable.map(|solvable| solvable.to_string()))),
+        ("regulator", show(report.regulator.map(|regulator| format!("{regulator:.6}")))),
+        ("digits of x", show(report.x_digits.map(|digits| digits.to_string()))),
+        ("digits of y", show(report.y_digits.map(|digits| digits.to_string()))),
+        ("class number", show(report.class_number.map(|h| h.to_string()))),
+    ];
+    for (label, value) in rows {
+        println!("{:<25}{value}", format!("{label}:"));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn search(args: &SearchArgs, settings: &Settings) -> ExitCode {
+    let format = settings.format;
+    let options = settings.options();
+    let limited = AtomicU64::new(0);
+    let end = args.max_d.saturating_add(1);
+    let mut scanned = 0u64;
+    let mut matched = 0u64;
+    let mut longest: Option<SearchMatch> = None;
+    let mut largest: Option<SearchMatch> = None;
+
+    let mut out = io::stdout().lock();
+    if write_header(&mut out, format, &["d", "period", "digits"]).is_err() {
+        return ExitCode::SUCCESS;
+    }
+    let mut chunk_start = args.min_d;
+    'scan: while chunk_start < end {
+        let chunk_end = chunk_start.saturating_add(SEARCH_CHUNK).min(end);
+        let candidates: Vec<u64> = valid_pell_d_in(chunk_start..chunk_end)
+            .filter(|&d| !args.prime_d || is_prime(d))
+            .collect();
+        scanned += candidates.len() as u64;
+
+        let matches: Vec<SearchMatch> = candidates
+            .into_par_iter()
+            .filter_map(|d| search_d(d, args, &options, settings.cache.as_ref(), &limited))
+            .collect();
+        for found in matches {
+            let columns = [("d", found.d), ("period", found.period), ("digits", found.digits)];
+            // A closed pipe (as with `| head`) means nobody wants more matches
+            if write_row(&mut out, format, &columns).is_err() {
+                break 'scan;
+            }
+            matched += 1;
+            if longest.is_none_or(|longest| found.period > longest.period) {
+                longest = Some(found);
+            }
+            if largest.is_none_or(|largest| found.digits > largest.digits) {
+                largest = Some(found);
+            }
+        }
+        chunk_start = chunk_end;
+    }
+
+    eprintln!("scanned {scanned} D values, {matched} matched");
+    let limited = limited.into_inner();
+    if limited > 0 {
+        eprintln!("skipped {limited} D values at the step limit");
+    }
+    if let (Some(longest), Some(largest)) = (longest, largest) {
+        eprintln!("longest period: {} (D = {})", longest.period, longest.d);
+        eprintln!("most digits: {} (D = {})", largest.digits, largest.d);
+    }
+    ExitCode::SUCCESS
+}
+
+/// Check one D against the filters of `args`, cheapest first, counting it in
+/// `limited` if solving it reaches the limits of `options`
+fn search_d(
+    d: u64,
+    args: &SearchArgs,
+    options: &SolveOptions,
+    cache: Option<&DiskCache>,
+    limited: &AtomicU64,
+) -> Option<SearchMatch> {
+    // The period alone needs no big integers, so it can rule D out before solving
+    if let Some(over) = args.period_over {
+        if period_length(d).ok()? <= over {
+            return None;
+        }
+    }
+
+    let ((x, _), period) = match solve_entry(d, options, cache) {
+        Ok(entry) => entry,
+        Err(_) => {
+            limited.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+    };
+    let digits = decimal_digits(&x);
+    if args.min_digits.is_some_and(|min_digits| digits < min_digits) {
+        return None;
+    }
+    Some(SearchMatch { d, period, digits })
+}
+
+fn stormer(primes: &[u64]) -> Result<ExitCode, PellError> {
+    for (n, next) in smooth_consecutive_pairs(primes)? {
+        println!("{n} {next}");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn euler66(max_d: u64, settings: &Settings) -> Result<ExitCode, CliError> {
+    let started = Instant::now();
+    let solved = AtomicU64::new(0);
+    let options = settings.options();
+    // A D skipped at the step limit could hold the answer, so reaching the limit fails the command
+    let largest = (2..=max_d)
+        .into_par_iter()
+        .filter(|&d| is_valid_pell_d(d))
+        .map(|d| {
+            solved.fetch_add(1, Ordering::Relaxed);
+            solve_entry(d, &options, settings.cache.as_ref()).map(|((x, y), _)| (d, x, y))
+        })
+        // Ties go to the smaller D, whatever order the threads finish in
+        .try_reduce_with(|a, b| Ok(if b.1.cmp(&a.1).then(a.0.cmp(&b.0)).is_gt() { b } else { a }));
+    let (d, x, y) = largest.ok_or(PellError::InvalidD(max_d))??;
+
+    println!("D = {d}");
+    println!("x = {x}");
+    println!("y = {y}");
+    eprintln!("solved {} equations in {:.3?}", solved.into_inner(), started.elapsed());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn records(max_d: u64, by: RecordKind, settings: &Settings) -> ExitCode {
+    let format = settings.format;
+    let options = settings.options();
+    let limited = AtomicU64::new(0);
+    let measure = |d: u64| -> u64 {
+        match by {
+            RecordKind::Period => period_length(d).unwrap_or(0),
+            RecordKind::Digits => match solve_entry(d, &options, settings.cache.as_ref()) {
+                Ok(((x, _), _)) => decimal_digits(&x),
+                Err(_) => {
+                    limited.fetch_add(1, Ordering::Relaxed);
+                    0
+                }
+            },
+        }
+    };
+
+    let end = max_d.saturating_add(1);
+    let mut record = 0;
+    let mut out = io::stdout().lock();
+    let column = match by {
+        RecordKind::Period => "period",
+        RecordKind::Digits => "digits",
+    };
+    if write_header(&mut out, format, &["d", column]).is_err() {
+        return ExitCode::SUCCESS;
+    }
+    let mut chunk_start = 2;
+    while chunk_start < end {
+        let chunk_end = chunk_start.saturating_add(SEARCH_CHUNK).min(end);
+        let candidates: Vec<u64> = valid_pell_d_in(chunk_start..chunk_end).collect();
+        let values: Vec<(u64, u64)> = candidates.into_par_iter().map(|d| (d, measure(d))).collect();
+
+        for (d, value) in values {
+            if value > record {
+                record = value;
+                // A closed pipe (as with `| head`) means nobody wants more records
+                if write_row(&mut out, format, &[("d", d), (column, value)]).and_then(|()| out.flush()).is_err() {
+                    return ExitCode::SUCCESS;
+                }
+            }
+        }
+        chunk_start = chunk_end;
+    }
+    let limited = limited.into_inner();
+    if limited > 0 {
+        eprintln!("skipped {limited} D values at the step limit");
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,89 @@
+//! Work counters for the solving algorithms
+//!
+//! With the `metrics` feature enabled, the solvers count the work they do in
+//! per-thread counters: continued fraction (and infrastructure) steps, BigInt
+//! multiplications, recurrence steps, exponentiations and verifications.
+//! Wall time depends on the machine; these counts do not, which makes them
+//! suitable for comparing algorithms and tuning pipelines.
+//!
+//! Heap allocations are not counted. Applications that need them can install a
+//! counting `#[global_allocator]` and read it around [`measure`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use pell991::{metrics, pell_min_solution};
+//! let (_, work) = metrics::measure(|| pell_min_solution(991).unwrap());
+//! assert_eq!(work.cf_steps, 59);
+//! assert!(work.bigint_multiplications > 0);
+//! ```
+
+use std::cell::Cell;
+
+/// Counts of the work performed by the solvers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Metrics {
+    /// Continued fraction steps, including steps through the infrastructure in BSGS
+    pub cf_steps: u64,
+    /// Multiplications with at least one BigInt operand
+    pub bigint_multiplications: u64,
+    /// Solutions advanced by one index with the recurrence relation
+    pub recurrence_steps: u64,
+    /// Fast exponentiations computing a k-th solution
+    pub exponentiations: u64,
+    /// Checks of x² - D·y² = 1
+    pub verifications: u64,
+}
+
+impl Metrics {
+    /// The work done between an `earlier` snapshot and this one
+    ///
+    /// Counters that went down in between, because of a [`reset`], count as zero.
+    pub fn since(&self, earlier: &Metrics) -> Metrics {
+        Metrics {
+            cf_steps: self.cf_steps.saturating_sub(earlier.cf_steps),
+            bigint_multiplications: self.bigint_multiplications.saturating_sub(earlier.bigint_multiplications),
+            recurrence_steps: self.recurrence_steps.saturating_sub(earlier.recurrence_steps),
+            exponentiations: self.exponentiations.saturating_sub(earlier.exponentiations),
+            verifications: self.verifications.saturating_sub(earlier.verifications),
+        }
+    }
+}
+
+thread_local! {
+    static COUNTERS: Cell<Metrics> = const { Cell::new(Metrics {
+        cf_steps: 0,
+        bigint_multiplications: 0,
+        recurrence_steps: 0,
+        exponentiations: 0,
+        verifications: 0,
+    }) };
+}
+
+/// The counters of the current thread since it started (or since [`reset`])
+pub fn snapshot() -> Metrics {
+    COUNTERS.with(Cell::get)
+}
+
+/// Zero the counters of the current thread
+pub fn reset() {
+    COUNTERS.with(|counters| counters.set(Metrics::default()));
+}
+
+/// Run `f` and return its result together with the work it did on this thread
+///
+/// Work done on other threads (for example by parallel helpers) is not included.
+pub fn measure<R, F: FnOnce() -> R>(f: F) -> (R, Metrics) {
+    let before = snapshot();
+    let result = f();
+    (result, snapshot().since(&before))
+}
+
+/// Update the counters of the current thread
+pub(crate) fn record<F: FnOnce(&mut Metrics)>(update: F) {
+    COUNTERS.with(|counters| {
+        let mut metrics = counters.get();
+        update(&mut metrics);
+        counters.set(metrics);
+    });
+}
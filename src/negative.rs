@@ -0,0 +1,249 @@
+//! The negative Pell equation x² - D·y² = -1
+//!
+//! Unlike the Pell equation, the negative one is only solvable for some D:
+//! exactly those whose continued fraction √D has an odd period. Its minimal
+//! solution is then the convergent just before the end of the first period.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::RangeInclusive;
+
+use num_bigint::BigInt;
+use crate::continued_fraction::{continued_fraction, period_length};
+use crate::error::PellError;
+use crate::solver::validate_d;
+use crate::utils::squarefree_part;
+
+/// Trial divisors used to look for a prime factor p ≡ 3 (mod 4) of D before
+/// falling back to the period, which explains most unsolvable D cheaply
+const SMALL_FACTOR_LIMIT: u64 = 1000;
+
+/// The reason x² - D·y² = -1 has no solution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum NegativeObstruction {
+    /// 4 divides D, so x² ≡ -1 (mod 4) would be needed
+    DivisibleBy4,
+    /// D has a prime factor p ≡ 3 (mod 4), so x² ≡ -1 (mod p) would be needed
+    PrimeFactor(u64),
+    /// The continued fraction of √D has this even period length
+    EvenPeriod(u64),
+}
+
+impl fmt::Display for NegativeObstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NegativeObstruction::DivisibleBy4 => write!(f, "D is divisible by 4, and -1 is not a square mod 4"),
+            NegativeObstruction::PrimeFactor(p) => {
+                write!(f, "D has the prime factor {p} ≡ 3 (mod 4), and -1 is not a square mod {p}")
+            }
+            NegativeObstruction::EvenPeriod(period) => {
+                write!(f, "the continued fraction of √D has even period {period}")
+            }
+        }
+    }
+}
+
+/// Solve the negative Pell equation x² - D·y² = -1
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D (must be > 1 and non-square)
+///
+/// # Returns
+///
+/// A `Result` containing the minimal positive solution `(x, y)`, or a `PellError`.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+/// Returns `PellError::NegativeUnsolvable` with the reason if there is no solution.
+///
+/// # Algorithm
+///
+/// Congruence obstructions (4 | D, or a small prime factor p ≡ 3 mod 4) are
+/// checked first. Otherwise √D is expanded: for an odd period r the convergent
+/// p_{r-1}/q_{r-1} is the solution, and for an even period there is none.
+/// Squaring x + y√D gives the fundamental solution of x² - D·y² = 1.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_negative_solution, NegativeObstruction, PellError};
+/// let (x, y) = pell_negative_solution(13).unwrap();
+/// assert_eq!((x.to_string(), y.to_string()), ("18".to_string(), "5".to_string())); // 324 - 325 = -1
+///
+/// assert_eq!(
+///     pell_negative_solution(991),
+///     Err(PellError::NegativeUnsolvable { d: 991, reason: NegativeObstruction::PrimeFactor(991) })
+/// );
+/// ```
+pub fn pell_negative_solution(d: u64) -> Result<(BigInt, BigInt), PellError> {
+    validate_d(d)?;
+    if let Some(reason) = congruence_obstruction(d) {
+        return Err(PellError::NegativeUnsolvable { d, reason });
+    }
+
+    let cf = continued_fraction(d)?;
+    let period = cf.period.len();
+    if period % 2 == 0 {
+        let reason = NegativeObstruction::EvenPeriod(period as u64);
+        return Err(PellError::NegativeUnsolvable { d, reason });
+    }
+    Ok(cf.convergents().nth(period - 1).expect("convergents never end"))
+}
+
+/// [`pell_negative_solution`] from the already computed fundamental solution
+/// (x₁, y₁) of x² - D·y² = 1 and the period of √D
+///
+/// Skips expanding √D again. For an odd period x₁ + y₁√D = (x + y√D)², so the
+/// solution follows from x₁ = 2x² + 1 and y₁ = 2xy with one integer square
+/// root. The pair and period are trusted as given.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+/// Returns `PellError::NegativeUnsolvable` with the reason if there is no
+/// solution, the same reason as [`pell_negative_solution`] gives.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigInt;
+/// # use pell991::pell_negative_solution_with_fundamental;
+/// let solution = pell_negative_solution_with_fundamental(13, &BigInt::from(649), &BigInt::from(180), 5);
+/// assert_eq!(solution, Ok((BigInt::from(18), BigInt::from(5))));
+/// ```
+pub fn pell_negative_solution_with_fundamental(
+    d: u64,
+    x1: &BigInt,
+    y1: &BigInt,
+    period: u64,
+) -> Result<(BigInt, BigInt), PellError> {
+    validate_d(d)?;
+    if let Some(reason) = congruence_obstruction(d) {
+        return Err(PellError::NegativeUnsolvable { d, reason });
+    }
+    if period % 2 == 0 {
+        let reason = NegativeObstruction::EvenPeriod(period);
+        return Err(PellError::NegativeUnsolvable { d, reason });
+    }
+
+    let x = ((x1 - 1u32) >> 1u32).sqrt();
+    let y = y1 / (&x << 1u32);
+    Ok((x, y))
+}
+
+/// A cheap proof that x² ≡ -1 has no solution modulo some divisor of D
+pub(crate) fn congruence_obstruction(d: u64) -> Option<NegativeObstruction> {
+    if d % 4 == 0 {
+        return Some(NegativeObstruction::DivisibleBy4);
+    }
+
+    let mut remaining = d;
+    let mut p = 3;
+    while p <= SMALL_FACTOR_LIMIT && p * p <= remaining {
+        if remaining % p == 0 {
+            if p % 4 == 3 {
+                return Some(NegativeObstruction::PrimeFactor(p));
+            }
+            while remaining % p == 0 {
+                remaining /= p;
+            }
+        }
+        p += 2;
+    }
+    // Once no trial divisor up to √remaining is left, the cofactor is prime
+    let cofactor_is_prime = remaining > 1 && p * p > remaining;
+    (cofactor_is_prime && remaining % 4 == 3).then_some(NegativeObstruction::PrimeFactor(remaining))
+}
+
+/// Squarefree D in one residue class, and how many of them admit x² - D·y² = -1
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResidueClassCount {
+    /// How many squarefree D > 1 fall in the class
+    pub squarefree: u64,
+    /// How many of those have a solution of x² - D·y² = -1
+    pub solvable: u64,
+}
+
+/// How often x² - D·y² = -1 is solvable for the squarefree D of a range
+///
+/// Since -1 must be a square modulo every odd prime factor of D, the residue
+/// of D modulo 8 already rules out the classes 3, 6 and 7; the interesting
+/// counts are those of the classes 1, 2 and 5.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NegativePellDensity {
+    /// How many squarefree D > 1 the range contains
+    pub squarefree: u64,
+    /// How many of those have a solution of x² - D·y² = -1
+    pub solvable: u64,
+    /// The same counts for each residue of D modulo 8, indexed by the residue
+    pub by_residue_mod_8: [ResidueClassCount; 8],
+}
+
+impl NegativePellDensity {
+    /// The fraction of the squarefree D with a solution, or 0 if there are none
+    pub fn density(&self) -> f64 {
+        if self.squarefree == 0 { 0.0 } else { self.solvable as f64 / self.squarefree as f64 }
+    }
+}
+
+/// Whether x² - D·y² = -1 has a solution, without solving it
+///
+/// Tries the congruence obstructions, then the parity of the period, which is
+/// computed in constant memory.
+fn negative_solvable(d: u64) -> bool {
+    congruence_obstruction(d).is_none() && period_length(d).is_ok_and(|period| period % 2 == 1)
+}
+
+/// Count the squarefree D in `range` for which x² - D·y² = -1 is solvable
+///
+/// D ≤ 1 and D with a square factor are skipped. With the `rayon` feature
+/// the D values are checked concurrently.
+///
+/// # Arguments
+///
+/// * `range` - The D values to examine
+///
+/// # Returns
+///
+/// A `NegativePellDensity` with the overall counts and their breakdown by D
+/// modulo 8.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::negative_pell_density;
+/// // Squarefree 2..=30: 2, 5, 10, 13, 17, 26 and 29 are solvable
+/// let density = negative_pell_density(2..=30);
+/// assert_eq!((density.solvable, density.squarefree), (7, 18));
+/// assert_eq!(density.by_residue_mod_8[5].solvable, 3); // 5, 13 and 29
+/// assert_eq!(density.by_residue_mod_8[3].solvable, 0);
+/// ```
+pub fn negative_pell_density(range: RangeInclusive<u64>) -> NegativePellDensity {
+    let is_candidate = |&d: &u64| d > 1 && squarefree_part(d) == d;
+
+    #[cfg(feature = "rayon")]
+    let checked: Vec<(u64, bool)> = {
+        use rayon::prelude::*;
+        range.into_par_iter().filter(is_candidate).map(|d| (d, negative_solvable(d))).collect()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let checked: Vec<(u64, bool)> = range.filter(is_candidate).map(|d| (d, negative_solvable(d))).collect();
+
+    let mut density = NegativePellDensity::default();
+    for (d, solvable) in checked {
+        let class = &mut density.by_residue_mod_8[(d % 8) as usize];
+        density.squarefree += 1;
+        class.squarefree += 1;
+        if solvable {
+            density.solvable += 1;
+            class.solvable += 1;
+        }
+    }
+    density
+}
@@ -0,0 +1,129 @@
+//! Options controlling and observing a solve
+
+use alloc::sync::Arc;
+use core::fmt;
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+/// Progress of a running continued fraction solve, passed to the
+/// [`on_progress`](SolveOptions::on_progress) callback after every step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressEvent {
+    /// The D being solved
+    pub d: u64,
+    /// Continued fraction steps completed so far
+    pub steps: u64,
+    /// Bit length of the numerator of the current convergent, which becomes x
+    pub convergent_bits: u64,
+    /// The period of the continued fraction of √D, once the end of the first
+    /// period has been reached (only happens before the solution for odd periods)
+    pub period: Option<u64>,
+    /// Estimated fraction of the solve completed, in [0, 1]
+    ///
+    /// Based on a heuristic period length until the period is known, after
+    /// which it is exact.
+    pub estimated_fraction: f64,
+}
+
+/// Callback receiving progress events
+type ProgressCallback = Arc<dyn Fn(ProgressEvent) + Send + Sync>;
+
+/// Limits and hooks for [`pell_min_solution_with_options`](crate::pell_min_solution_with_options)
+///
+/// The default options place no limits, matching `pell_min_solution`.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_min_solution_with_options, PellError, SolveOptions};
+/// let options = SolveOptions::new().max_steps(10);
+///
+/// // √13 has period 5, so its solution is found within 10 steps...
+/// assert!(pell_min_solution_with_options(13, &options).is_ok());
+///
+/// // ...but √991 needs 60
+/// assert_eq!(
+///     pell_min_solution_with_options(991, &options),
+///     Err(PellError::LimitExceeded { steps: 10 })
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct SolveOptions {
+    pub(crate) max_steps: Option<u64>,
+    #[cfg(feature = "std")]
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) on_progress: Option<ProgressCallback>,
+}
+
+impl fmt::Debug for SolveOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("SolveOptions");
+        debug.field("max_steps", &self.max_steps);
+        #[cfg(feature = "std")]
+        debug.field("timeout", &self.timeout);
+        debug
+            .field("on_progress", &self.on_progress.as_ref().map(|_| "<callback>"))
+            .finish()
+    }
+}
+
+impl SolveOptions {
+    /// Options without any limits
+    pub fn new() -> Self {
+        SolveOptions::default()
+    }
+
+    /// Give up with `PellError::LimitExceeded` after `max_steps` continued fraction steps
+    ///
+    /// The minimal solution of D is found after one or two periods of the
+    /// continued fraction of √D, and the period length grows like O(√D·log D).
+    pub fn max_steps(mut self, max_steps: u64) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Give up with `PellError::Timeout` once the solve has run for `timeout`
+    ///
+    /// The clock is checked between continued fraction steps, so a solve stops
+    /// within one step of the deadline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// # use pell991::{pell_min_solution_with_options, SolveOptions};
+    /// let options = SolveOptions::new().timeout(Duration::from_secs(5));
+    /// assert!(pell_min_solution_with_options(991, &options).is_ok());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Call `callback` with a [`ProgressEvent`] after every continued fraction step
+    ///
+    /// The callback runs on the solving thread, so it should be cheap; throttle
+    /// expensive reporting (such as redrawing a progress bar) inside it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    /// use std::sync::Arc;
+    /// # use pell991::{pell_min_solution_with_options, SolveOptions};
+    /// let last_step = Arc::new(AtomicU64::new(0));
+    /// let seen = Arc::clone(&last_step);
+    /// let options = SolveOptions::new().on_progress(move |event| seen.store(event.steps, Ordering::Relaxed));
+    ///
+    /// pell_min_solution_with_options(991, &options).unwrap();
+    /// assert_eq!(last_step.load(Ordering::Relaxed), 59);
+    /// ```
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(ProgressEvent) + Send + Sync + 'static,
+    {
+        self.on_progress = Some(Arc::new(callback));
+        self
+    }
+}
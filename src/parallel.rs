@@ -0,0 +1,134 @@
+//! Parallel solving and solution generation with rayon
+
+use core::ops::Range;
+
+use num_bigint::BigInt;
+use rayon::prelude::*;
+use crate::error::PellError;
+use crate::solver::{pell_min_solution, PellSolutionIterator};
+use crate::utils::is_square_u64;
+
+/// A D value together with the outcome of solving its Pell equation
+type Solved = (u64, Result<(BigInt, BigInt), PellError>);
+
+/// Generate the solutions with indices in `range` (end exclusive) in parallel
+///
+/// The range is split into chunks, one or a few per worker thread. Each chunk is
+/// seeded with a single fast exponentiation and then advanced with the
+/// recurrence, so the total work stays close to that of sequential generation.
+/// Collecting the returned iterator preserves the order of the indices.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `range` - Indices k of the solutions to generate (`range.start` ≥ 1)
+///
+/// # Returns
+///
+/// A `Result` containing a parallel iterator over the solutions (xₖ, yₖ), or a
+/// `PellError` if the input is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidK` if `range.start` is 0, and the errors of
+/// `pell_min_solution` if D is invalid.
+/// Returns `PellError::Overflow` if a chunk of the range does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use rayon::prelude::*;
+/// # use pell991::{par_solutions, pell_solutions_range};
+/// let solutions: Vec<_> = par_solutions(991, 1..200).unwrap().collect();
+/// assert_eq!(solutions, pell_solutions_range(991, 1, 200).unwrap());
+/// ```
+pub fn par_solutions(
+    d: u64,
+    range: Range<u64>,
+) -> Result<impl ParallelIterator<Item = (BigInt, BigInt)>, PellError> {
+    if range.start == 0 {
+        return Err(PellError::InvalidK(range.start));
+    }
+    let (x1, y1) = pell_min_solution(d)?;
+
+    let len = range.end.saturating_sub(range.start);
+    // A few chunks per thread balance the load without many extra exponentiations
+    let chunks = (rayon::current_num_threads() as u64 * 4).clamp(1, len.max(1));
+    let chunk_len = usize::try_from(len.div_ceil(chunks).max(1))
+        .map_err(|_| PellError::Overflow { bits: usize::BITS })?;
+
+    let starts: Vec<u64> = (range.start..range.end).step_by(chunk_len).collect();
+    let end = range.end;
+
+    Ok(starts.into_par_iter().flat_map_iter(move |start| {
+        let mut iter = PellSolutionIterator::with_fundamental(d, x1.clone(), y1.clone());
+        iter.skip_to(start).expect("chunk starts are at least 1");
+        // The last chunk may be shorter, and the rest of a huge range may not fit in a usize
+        iter.take(usize::try_from(end - start).map_or(chunk_len, |rest| rest.min(chunk_len)))
+    }))
+}
+
+/// Generate the first `count` solutions in parallel
+///
+/// The parallel counterpart of `pell_solutions`, producing the same vector.
+/// It pays off for counts in the thousands, where the chunks' seeding
+/// exponentiations are small next to the recurrence steps they replace.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `count` - Number of solutions to generate (starting from k=1)
+///
+/// # Returns
+///
+/// A `Result` containing a vector of solution tuples, or a `PellError` if the input is invalid.
+///
+/// # Errors
+///
+/// Returns the errors of `pell_min_solution` if D is invalid.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_solutions, pell_solutions_parallel};
+/// let solutions = pell_solutions_parallel(991, 300).unwrap();
+/// assert_eq!(solutions, pell_solutions(991, 300).unwrap());
+/// ```
+pub fn pell_solutions_parallel(d: u64, count: usize) -> Result<Vec<(BigInt, BigInt)>, PellError> {
+    let mut solutions = Vec::with_capacity(count);
+    solutions.par_extend(par_solutions(d, 1..count as u64 + 1)?);
+    Ok(solutions)
+}
+
+/// Solve the Pell equation for many D values concurrently, skipping perfect squares
+///
+/// The parallel counterpart of `solve_range`. Every D is a separate task for
+/// rayon's work-stealing scheduler, so a few D values with very long periods
+/// keep their threads busy while the other threads finish the rest. The results
+/// are returned in the order of `ds`.
+///
+/// # Arguments
+///
+/// * `ds` - The D values to solve, such as `2..=n` or a `Vec<u64>`
+///
+/// # Returns
+///
+/// A vector of `(d, Result<(x, y), PellError>)` with the minimal solution for each non-square D
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_min_solutions_for, solve_range};
+/// let parallel = pell_min_solutions_for(2..=500);
+/// let sequential: Vec<_> = solve_range(2..=500).collect();
+/// assert_eq!(parallel, sequential);
+/// ```
+pub fn pell_min_solutions_for<I>(ds: I) -> Vec<Solved>
+where
+    I: IntoParallelIterator<Item = u64>,
+{
+    ds.into_par_iter()
+        .filter(|&d| !is_square_u64(d))
+        .map(|d| (d, pell_min_solution(d)))
+        .collect()
+}
@@ -0,0 +1,105 @@
+//! Searches for Pell solutions with a prime coordinate
+//!
+//! The coordinates of the solutions divide one another: xⱼ | xₖ whenever
+//! k/j is odd, so xₖ can only be prime when k is a power of two. The search
+//! jumps straight from xₖ to x₂ₖ = 2xₖ² - 1, so a scan up to index k costs
+//! log₂ k squarings and primality tests rather than k steps.
+//!
+//! For y the structure is even more rigid. Writing x₁ + y₁√D raised to the
+//! k-th power with Chebyshev polynomials gives yₖ = y₁·Uₖ₋₁(x₁), and
+//! U₂ₘ = (Uₘ - Uₘ₋₁)(Uₘ + Uₘ₋₁) while U₂ₘ₊₁ is even, so yₖ is composite for
+//! every k > 1: only y₁ can be prime.
+//!
+//! Primality is decided by [`is_probable_prime_big`] with a fixed seed, so
+//! results are reproducible; a reported index is wrong with probability
+//! below 4⁻²⁰ per coordinate.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::error::PellError;
+use crate::random::{is_probable_prime_big, SplitMix64};
+use crate::solver::pell_min_solution;
+
+/// Miller–Rabin rounds per candidate
+const ROUNDS: u32 = 20;
+
+/// Seed of the bases, fixed so that every run reports the same indices
+const SEED: u64 = 0x5045_4C4C;
+
+/// Find the indices k ≤ `max_k` at which xₖ is (probably) prime
+///
+/// Only k = 1, 2, 4, 8, … can qualify, see the [module documentation](self).
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `max_k` - The last index to consider
+///
+/// # Returns
+///
+/// A `Result` containing the indices in increasing order, or a `PellError`.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::prime_search::find_prime_x;
+/// // x₁ = 3, x₂ = 17, x₄ = 577 and x₈ = 665857 are prime, x₁₆ = 886731088897 is not
+/// assert_eq!(find_prime_x(2, 20).unwrap(), [1, 2, 4, 8]);
+/// ```
+pub fn find_prime_x(d: u64, max_k: u64) -> Result<Vec<u64>, PellError> {
+    let (mut x, _) = pell_min_solution(d)?;
+    let mut rng = SplitMix64::new(SEED);
+    let mut indices = Vec::new();
+    let mut k = 1;
+    while k <= max_k {
+        if is_probable_prime_big(&x, ROUNDS, &mut rng) {
+            indices.push(k);
+        }
+        if k > max_k / 2 {
+            break;
+        }
+        // x₂ₖ = 2xₖ² - 1
+        x = ((&x * &x) << 1) - 1;
+        count!(bigint_multiplications += 1);
+        k *= 2;
+    }
+    Ok(indices)
+}
+
+/// Find the indices k ≤ `max_k` at which yₖ is (probably) prime
+///
+/// Only k = 1 can qualify, see the [module documentation](self), so this
+/// tests y₁ alone and returns `[1]` or nothing.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `max_k` - The last index to consider
+///
+/// # Returns
+///
+/// A `Result` containing the indices in increasing order, or a `PellError`.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::prime_search::find_prime_y;
+/// assert_eq!(find_prime_y(7, 100).unwrap(), [1]); // y₁ = 3
+/// assert!(find_prime_y(991, 100).unwrap().is_empty());
+/// ```
+pub fn find_prime_y(d: u64, max_k: u64) -> Result<Vec<u64>, PellError> {
+    let (_, y1) = pell_min_solution(d)?;
+    let prime = max_k >= 1 && is_probable_prime_big(&y1, ROUNDS, &mut SplitMix64::new(SEED));
+    Ok(if prime { vec![1] } else { Vec::new() })
+}
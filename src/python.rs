@@ -0,0 +1,88 @@
+//! Python bindings
+//!
+//! A PyO3 extension module named `pell991`, built with maturin from
+//! `pyproject.toml`:
+//!
+//! ```text
+//! maturin develop --release
+//! ```
+//!
+//! Solutions are returned as native Python `int`s. The conversion copies the
+//! little-endian bytes of each `BigInt` straight into a `PyLong`
+//! (`_PyLong_FromByteArray`, or `PyLong_FromNativeBytes` on Python 3.13+), which
+//! is linear in the size of the number. Going through decimal strings instead
+//! costs a base conversion on both sides, which CPython does in quadratic time
+//! before 3.12 and which dominates for million-digit solutions.
+//! The results are plain `int`s, so `gmpy2.mpz(x)` takes them without parsing.
+//!
+//! The solvers run with the GIL released.
+
+use num_bigint::BigInt;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::continued_fraction::continued_fraction as expand;
+use crate::error::PellError;
+use crate::solver::{pell_min_solution, pell_solution_k, pell_solutions, verify_pell_solution};
+
+impl From<PellError> for PyErr {
+    fn from(err: PellError) -> Self {
+        match err {
+            PellError::LimitExceeded { .. } | PellError::Timeout { .. } => PyRuntimeError::new_err(err.to_string()),
+            _ => PyValueError::new_err(err.to_string()),
+        }
+    }
+}
+
+/// The fundamental solution (x, y) of x² - D·y² = 1
+#[pyfunction]
+fn min_solution(py: Python<'_>, d: u64) -> PyResult<(BigInt, BigInt)> {
+    Ok(py.detach(|| pell_min_solution(d))?)
+}
+
+/// The k-th solution (x, y) of x² - D·y² = 1, counting the fundamental one as k = 1
+#[pyfunction]
+fn solution_k(py: Python<'_>, d: u64, k: u64) -> PyResult<(BigInt, BigInt)> {
+    let solution = py.detach(|| {
+        let (x1, y1) = pell_min_solution(d)?;
+        pell_solution_k(d, &x1, &y1, k)
+    });
+    Ok(solution?)
+}
+
+/// The first `count` solutions of x² - D·y² = 1
+#[pyfunction]
+fn solutions(py: Python<'_>, d: u64, count: usize) -> PyResult<Vec<(BigInt, BigInt)>> {
+    Ok(py.detach(|| pell_solutions(d, count))?)
+}
+
+/// Whether (x, y) satisfies x² - D·y² = 1
+#[pyfunction]
+fn verify(py: Python<'_>, d: u64, x: BigInt, y: BigInt) -> bool {
+    py.detach(|| verify_pell_solution(d, &x, &y))
+}
+
+/// The continued fraction of √D as (a0, period)
+#[pyfunction]
+fn continued_fraction(d: u64) -> PyResult<(u64, Vec<u64>)> {
+    let cf = expand(d)?;
+    Ok((cf.a0, cf.period))
+}
+
+/// Initialize the `pell991` Python module
+///
+/// Registered as the extension module's entry point; embedders can also call it
+/// on a module of their own.
+///
+/// # Errors
+///
+/// Returns any error from adding the functions to `m`.
+#[pymodule]
+pub fn pell991(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(min_solution, m)?)?;
+    m.add_function(wrap_pyfunction!(solution_k, m)?)?;
+    m.add_function(wrap_pyfunction!(solutions, m)?)?;
+    m.add_function(wrap_pyfunction!(verify, m)?)?;
+    m.add_function(wrap_pyfunction!(continued_fraction, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,207 @@
+//! The distribution of the partial quotients of √D
+//!
+//! For almost every real number the partial quotient k occurs with the
+//! Gauss–Kuzmin frequency log₂(1 + 1/(k(k + 2))): about 41.5% ones, 17% twos,
+//! 9.3% threes, and so on. Quadratic irrationals are not "almost every"
+//! number, but the periods of √D follow the law closely on average. The
+//! functions here count the quotients of one period or of a range of D and
+//! compare the counts against the law.
+//!
+//! # Examples
+//!
+//! ```
+//! # use pell991::quotients::partial_quotient_distribution;
+//! let distribution = partial_quotient_distribution(2..=10_000, 10);
+//! let ones = &distribution.buckets[0];
+//! assert_eq!(ones.quotient, 1);
+//! assert!((ones.observed - ones.expected).abs() < 0.02);
+//! assert!(distribution.total_variation < 0.05);
+//! ```
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use crate::error::PellError;
+use crate::solver::validate_d;
+use crate::utils::{is_valid_pell_d, isqrt_u64};
+
+/// Observed and expected frequency of one partial quotient
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotientBucket {
+    /// The partial quotient k
+    pub quotient: u64,
+    /// How many times k occurred
+    pub count: u64,
+    /// `count` as a fraction of all quotients counted
+    pub observed: f64,
+    /// The Gauss–Kuzmin probability of k
+    pub expected: f64,
+}
+
+/// The partial quotients counted, compared against the Gauss–Kuzmin law
+///
+/// The closing term 2a₀ of each period is left out: it is fixed by D rather
+/// than distributed like the others.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotientDistribution {
+    /// Number of partial quotients counted
+    pub total: u64,
+    /// One bucket per quotient 1, 2, …, up to the requested maximum
+    pub buckets: Vec<QuotientBucket>,
+    /// Quotients above the last bucket, with their combined probability
+    pub tail: QuotientBucket,
+    /// Pearson's χ² statistic of the counts, buckets and tail together
+    pub chi_squared: f64,
+    /// Total variation distance ½·Σ|observed - expected| from the law
+    pub total_variation: f64,
+}
+
+/// The Gauss–Kuzmin probability log₂(1 + 1/(k(k + 2))) of the partial quotient k
+///
+/// # Panics
+///
+/// Panics if `k` is 0.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::quotients::gauss_kuzmin_probability;
+/// assert!((gauss_kuzmin_probability(1) - 0.415).abs() < 0.001);
+/// ```
+pub fn gauss_kuzmin_probability(k: u64) -> f64 {
+    assert!(k > 0, "partial quotients are positive");
+    let k = k as f64;
+    (1.0 / (k * (k + 2.0))).ln_1p() / core::f64::consts::LN_2
+}
+
+/// Run `on_quotient` on the terms a₁, …, aᵣ₋₁ of the period of √D
+fn for_each_quotient(d: u64, mut on_quotient: impl FnMut(u64)) {
+    let a0 = isqrt_u64(d);
+    let (mut m, mut q) = (0u64, 1u64);
+    let mut a = a0;
+    loop {
+        m = q * a - m;
+        q = (d - m * m) / q;
+        a = (a0 + m) / q;
+        if a == 2 * a0 {
+            return;
+        }
+        on_quotient(a);
+    }
+}
+
+/// Tallies of partial quotients, turned into a `QuotientDistribution` at the end
+struct Tally {
+    counts: Vec<u64>,
+    tail: u64,
+}
+
+impl Tally {
+    fn new(max_quotient: u64) -> Self {
+        assert!(max_quotient > 0, "max_quotient must be > 0");
+        let buckets = usize::try_from(max_quotient).expect("max_quotient fits in memory");
+        Tally { counts: vec![0; buckets], tail: 0 }
+    }
+
+    fn add(&mut self, a: u64) {
+        match usize::try_from(a - 1).ok().and_then(|i| self.counts.get_mut(i)) {
+            Some(count) => *count += 1,
+            None => self.tail += 1,
+        }
+    }
+
+    fn finish(self) -> QuotientDistribution {
+        let total = self.counts.iter().sum::<u64>() + self.tail;
+        let bucket = |quotient, count, expected| QuotientBucket {
+            quotient,
+            count,
+            observed: if total == 0 { 0.0 } else { count as f64 / total as f64 },
+            expected,
+        };
+
+        let buckets: Vec<QuotientBucket> =
+            (1..).zip(self.counts).map(|(k, count)| bucket(k, count, gauss_kuzmin_probability(k))).collect();
+        // The probabilities of 1..=K telescope to log₂(2(K + 1)/(K + 2))
+        let last = buckets.len() as f64;
+        let tail_quotient = buckets.len() as u64 + 1;
+        let tail = bucket(tail_quotient, self.tail, ((last + 2.0) / (last + 1.0)).log2());
+
+        let (mut chi_squared, mut total_variation) = (0.0, 0.0);
+        for b in buckets.iter().chain([&tail]) {
+            let expected_count = b.expected * total as f64;
+            if expected_count > 0.0 {
+                chi_squared += (b.count as f64 - expected_count).powi(2) / expected_count;
+            }
+            total_variation += (b.observed - b.expected).abs() / 2.0;
+        }
+        if total == 0 {
+            total_variation = 0.0;
+        }
+
+        QuotientDistribution { total, buckets, tail, chi_squared, total_variation }
+    }
+}
+
+/// The distribution of the partial quotients in the period of √D
+///
+/// The period is expanded with machine arithmetic and never stored, so this
+/// works for periods too long to keep in memory.
+///
+/// # Arguments
+///
+/// * `d` - The value whose square root to expand (must be > 1 and non-square)
+/// * `max_quotient` - Quotients 1..=max_quotient get their own bucket, larger ones share the tail
+///
+/// # Returns
+///
+/// A `Result` containing the `QuotientDistribution`, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Panics
+///
+/// Panics if `max_quotient` is 0.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::quotients::period_quotient_distribution;
+/// // √7 = [2; (1, 1, 1, 4)]: three ones, and the closing 4 is left out
+/// let distribution = period_quotient_distribution(7, 3).unwrap();
+/// assert_eq!(distribution.total, 3);
+/// assert_eq!(distribution.buckets[0].count, 3);
+/// ```
+pub fn period_quotient_distribution(d: u64, max_quotient: u64) -> Result<QuotientDistribution, PellError> {
+    validate_d(d)?;
+    let mut tally = Tally::new(max_quotient);
+    for_each_quotient(d, |a| tally.add(a));
+    Ok(tally.finish())
+}
+
+/// The distribution of the partial quotients in the periods of √D for every valid D in `range`
+///
+/// D ≤ 1 and perfect squares in the range are skipped; a range without a
+/// valid D gives a distribution with `total` 0 and both statistics 0.
+///
+/// # Arguments
+///
+/// * `range` - The D values whose expansions to count
+/// * `max_quotient` - Quotients 1..=max_quotient get their own bucket, larger ones share the tail
+///
+/// # Panics
+///
+/// Panics if `max_quotient` is 0.
+pub fn partial_quotient_distribution(range: RangeInclusive<u64>, max_quotient: u64) -> QuotientDistribution {
+    let mut tally = Tally::new(max_quotient);
+    for d in range.filter(|&d| is_valid_pell_d(d)) {
+        for_each_quotient(d, |a| tally.add(a));
+    }
+    tally.finish()
+}
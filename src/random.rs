@@ -0,0 +1,299 @@
+//! Injectable randomness for the probabilistic algorithms
+//!
+//! Randomized routines take a [`RandomSource`] instead of reaching for a global
+//! generator, so a run can be reproduced exactly by reusing its seed, and a
+//! failing test reports a seed that replays the failure. [`SplitMix64`] is a
+//! small seedable generator for that purpose; any other generator can be
+//! plugged in by implementing the trait.
+//!
+//! # Examples
+//!
+//! ```
+//! # use pell991::random::{random_moduli, SplitMix64};
+//! let first = random_moduli(3, &mut SplitMix64::new(42));
+//! let again = random_moduli(3, &mut SplitMix64::new(42));
+//! assert_eq!(first, again);
+//! ```
+
+use alloc::vec::Vec;
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, ToPrimitive};
+
+use crate::solver::verify_pell_solution_mod;
+
+/// A source of uniformly distributed 64-bit values
+pub trait RandomSource {
+    /// The next random value
+    fn next_u64(&mut self) -> u64;
+
+    /// A uniformly distributed value in `0..bound`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is 0.
+    fn next_below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "bound must be > 0");
+        // Reject the lowest 2⁶⁴ mod bound values, which would bias the remainder
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let value = self.next_u64();
+            if value >= threshold {
+                return value % bound;
+            }
+        }
+    }
+}
+
+impl<R: RandomSource + ?Sized> RandomSource for &mut R {
+    fn next_u64(&mut self) -> u64 {
+        (**self).next_u64()
+    }
+}
+
+/// The SplitMix64 generator: fast, seedable, and good enough for choosing
+/// witnesses and moduli, but not cryptographically secure
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::random::{RandomSource, SplitMix64};
+/// let mut rng = SplitMix64::new(0);
+/// assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// A generator whose output is determined by `seed`
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    /// A generator seeded from the clock and the process's hash keys
+    ///
+    /// Differs between runs; read the seed back with [`SplitMix64::seed`]
+    /// right after creation to make a run reproducible.
+    #[cfg(feature = "std")]
+    pub fn from_entropy() -> Self {
+        use std::hash::{BuildHasher, Hasher};
+
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        if let Ok(elapsed) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            hasher.write_u128(elapsed.as_nanos());
+        }
+        SplitMix64::new(hasher.finish())
+    }
+
+    /// The current state, which seeds a generator continuing with the same output
+    pub fn seed(&self) -> u64 {
+        self.state
+    }
+}
+
+impl RandomSource for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+fn mul_mod(a: u64, b: u64, n: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(n)) as u64
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, n: u64) -> u64 {
+    let mut result = 1 % n;
+    while exp > 0 {
+        if exp % 2 == 1 {
+            result = mul_mod(result, base, n);
+        }
+        base = mul_mod(base, base, n);
+        exp /= 2;
+    }
+    result
+}
+
+/// Miller–Rabin test of n with `rounds` random bases drawn from `rng`
+///
+/// Unlike the trial division of [`is_prime`](crate::is_prime), this is fast
+/// for any 64-bit n. Primes always pass; a composite passes each round with
+/// probability at most 1/4, so 20 rounds leave less than one chance in 10¹².
+///
+/// # Arguments
+///
+/// * `n` - The number to test
+/// * `rounds` - Number of random bases to try
+/// * `rng` - Source of the bases
+///
+/// # Returns
+///
+/// `false` if n is certainly composite (or < 2), `true` if it passed every round
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::random::{is_probable_prime, SplitMix64};
+/// let mut rng = SplitMix64::new(7);
+/// assert!(is_probable_prime(18_446_744_073_709_551_557, 20, &mut rng));
+/// assert!(!is_probable_prime(561, 20, &mut rng)); // a Carmichael number
+/// ```
+pub fn is_probable_prime<R: RandomSource + ?Sized>(n: u64, rounds: u32, rng: &mut R) -> bool {
+    const SMALL_PRIMES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for p in SMALL_PRIMES {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> s;
+    'rounds: for _ in 0..rounds {
+        let base = 2 + rng.next_below(n - 3);
+        let mut x = pow_mod(base, odd, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'rounds;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// A uniformly distributed value in `0..bound`, by rejection on the bit length of bound
+fn random_below<R: RandomSource + ?Sized>(bound: &BigUint, rng: &mut R) -> BigUint {
+    let bits = bound.bits();
+    let words = bits.div_ceil(64);
+    loop {
+        let bytes: Vec<u8> = (0..words).flat_map(|_| rng.next_u64().to_le_bytes()).collect();
+        let candidate = BigUint::from_bytes_le(&bytes) >> (words * 64 - bits);
+        if candidate < *bound {
+            return candidate;
+        }
+    }
+}
+
+/// Miller–Rabin test of a big integer n with `rounds` random bases drawn from `rng`
+///
+/// The arbitrary-precision form of [`is_probable_prime`], with the same
+/// guarantees: primes always pass, and a composite passes each round with
+/// probability at most 1/4. Values that fit in a `u64` are handed to
+/// [`is_probable_prime`].
+///
+/// # Arguments
+///
+/// * `n` - The number to test
+/// * `rounds` - Number of random bases to try
+/// * `rng` - Source of the bases
+///
+/// # Returns
+///
+/// `false` if n is certainly composite (or < 2), `true` if it passed every round
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::random::{is_probable_prime_big, SplitMix64};
+/// # use pell991::BigInt;
+/// let mersenne_127 = (BigInt::from(1) << 127) - 1;
+/// let mut rng = SplitMix64::new(7);
+/// assert!(is_probable_prime_big(&mersenne_127, 20, &mut rng));
+/// assert!(!is_probable_prime_big(&(&mersenne_127 * &mersenne_127), 20, &mut rng));
+/// ```
+pub fn is_probable_prime_big<R: RandomSource + ?Sized>(n: &BigInt, rounds: u32, rng: &mut R) -> bool {
+    if n.sign() != Sign::Plus {
+        return false;
+    }
+    if let Some(small) = n.to_u64() {
+        return is_probable_prime(small, rounds, rng);
+    }
+    let n = n.magnitude();
+    const SMALL_PRIMES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if SMALL_PRIMES.iter().any(|&p| (n % p).to_u32() == Some(0)) {
+        return false;
+    }
+
+    let one = BigUint::one();
+    let n_minus_1 = n - &one;
+    let s = n_minus_1.trailing_zeros().expect("n - 1 > 0");
+    let odd = &n_minus_1 >> s;
+    let base_bound = n - 3u32;
+    'rounds: for _ in 0..rounds {
+        let base = random_below(&base_bound, rng) + 2u32;
+        let mut x = base.modpow(&odd, n);
+        if x == one || x == n_minus_1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = &x * &x % n;
+            if x == n_minus_1 {
+                continue 'rounds;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Draw `count` distinct random primes between 2⁶³ and 2⁶⁴
+///
+/// Suitable as the moduli of [`verify_pell_solution_mod`]: a wrong pair
+/// passes a random 64-bit prime only if the prime divides x² - D·y² - 1.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::random::{random_moduli, SplitMix64};
+/// let moduli = random_moduli(4, &mut SplitMix64::new(1));
+/// assert_eq!(moduli.len(), 4);
+/// assert!(moduli.iter().all(|&p| p >= 1 << 63));
+/// ```
+pub fn random_moduli<R: RandomSource + ?Sized>(count: usize, rng: &mut R) -> Vec<u64> {
+    let mut moduli = Vec::with_capacity(count);
+    while moduli.len() < count {
+        let candidate = rng.next_u64() | (1 << 63) | 1;
+        if is_probable_prime(candidate, 20, rng) && !moduli.contains(&candidate) {
+            moduli.push(candidate);
+        }
+    }
+    moduli
+}
+
+/// Check x² - D·y² = 1 modulo `rounds` random 64-bit primes drawn from `rng`
+///
+/// The randomized form of [`verify_pell_solution_mod`]: fixed moduli can be
+/// targeted by a crafted pair, random ones cannot, and reusing the seed of
+/// `rng` replays the exact same check.
+///
+/// # Returns
+///
+/// `false` if (x, y) is certainly not a solution, `true` if it passed every round
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::random::{verify_pell_solution_random, SplitMix64};
+/// # use pell991::{pell_min_solution, pell_solution_k};
+/// let (x1, y1) = pell_min_solution(991).unwrap();
+/// let (x, y) = pell_solution_k(991, &x1, &y1, 1000).unwrap();
+///
+/// let mut rng = SplitMix64::new(2024);
+/// assert!(verify_pell_solution_random(991, &x, &y, 3, &mut rng));
+/// assert!(!verify_pell_solution_random(991, &(x + 1), &y, 3, &mut rng));
+/// ```
+pub fn verify_pell_solution_random<R: RandomSource + ?Sized>(d: u64, x: &BigInt, y: &BigInt, rounds: usize, rng: &mut R) -> bool {
+    verify_pell_solution_mod(d, x, y, &random_moduli(rounds, rng))
+}
@@ -0,0 +1,215 @@
+//! Record-setting D values
+//!
+//! The D whose continued fraction period of √D is longer than that of every
+//! smaller D form the sequence 2, 3, 7, 13, 19, 31, 43, 46, 94, ….
+//! [`PeriodRecords`] walks D upward and yields each one with its period, and
+//! can pick a long scan back up from a saved high-water mark.
+//!
+//! [`DigitRecords`] does the same for the number of decimal digits of x in
+//! the fundamental solution, whose champions are 2, 10, 13, 29, 46, 61, 109,
+//! 181, 277, 409, 421, 541, 661, ….
+//!
+//! # Examples
+//!
+//! ```
+//! # use pell991::records::PeriodRecords;
+//! let records: Vec<(u64, u64)> = PeriodRecords::new()
+//!     .take_while(|record| record.d <= 100)
+//!     .map(|record| (record.d, record.period))
+//!     .collect();
+//! assert_eq!(records, [(2, 1), (3, 2), (7, 4), (13, 5), (19, 6), (31, 8), (43, 10), (46, 12), (94, 16)]);
+//! ```
+
+use core::iter::FusedIterator;
+
+use num_bigint::BigInt;
+
+use crate::continued_fraction::period_length;
+use crate::solver::pell_min_solution;
+use crate::utils::decimal_digits;
+
+/// A D whose period is longer than that of every smaller D
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PeriodRecord {
+    /// The coefficient D
+    pub d: u64,
+    /// Length of the period of the continued fraction of √D
+    pub period: u64,
+}
+
+/// Iterator over the period-length records, in increasing D
+///
+/// Each record is found by expanding every √D since the previous one, so the
+/// gaps grow with D. To split a scan across runs, save [`next_d`](Self::next_d)
+/// and [`record`](Self::record) and continue with [`PeriodRecords::resume`].
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::records::PeriodRecords;
+/// let mut scan = PeriodRecords::new();
+/// let last = scan.by_ref().take_while(|record| record.d < 100).last().unwrap();
+/// assert_eq!((last.d, last.period), (94, 16));
+///
+/// // Later, from the saved high-water mark
+/// let mut resumed = PeriodRecords::resume(last.d + 1, last.period);
+/// assert_eq!(resumed.next().map(|record| record.d), Some(139));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeriodRecords {
+    /// The next D to expand, or `None` once every u64 has been
+    next_d: Option<u64>,
+    /// The longest period seen so far
+    record: u64,
+}
+
+impl PeriodRecords {
+    /// A scan from D = 2
+    pub fn new() -> Self {
+        PeriodRecords::resume(2, 0)
+    }
+
+    /// A scan continuing at `next_d`, yielding only periods longer than `record`
+    ///
+    /// # Arguments
+    ///
+    /// * `next_d` - The first D to expand
+    /// * `record` - The longest period among the D below `next_d`
+    pub fn resume(next_d: u64, record: u64) -> Self {
+        PeriodRecords {
+            next_d: Some(next_d),
+            record,
+        }
+    }
+
+    /// The next D the scan will expand, or `None` if it has passed `u64::MAX`
+    pub fn next_d(&self) -> Option<u64> {
+        self.next_d
+    }
+
+    /// The longest period found so far, or passed to [`PeriodRecords::resume`]
+    pub fn record(&self) -> u64 {
+        self.record
+    }
+}
+
+impl Default for PeriodRecords {
+    fn default() -> Self {
+        PeriodRecords::new()
+    }
+}
+
+impl Iterator for PeriodRecords {
+    type Item = PeriodRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(d) = self.next_d {
+            self.next_d = d.checked_add(1);
+            // D ≤ 1 and perfect squares have no period
+            let Ok(period) = period_length(d) else {
+                continue;
+            };
+            if period > self.record {
+                self.record = period;
+                return Some(PeriodRecord { d, period });
+            }
+        }
+        None
+    }
+}
+
+impl FusedIterator for PeriodRecords {}
+
+/// A D whose fundamental solution has more digits than that of every smaller D
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DigitRecord {
+    /// The coefficient D
+    pub d: u64,
+    /// Number of decimal digits of `x`
+    pub digits: u64,
+    /// x of the fundamental solution
+    pub x: BigInt,
+    /// y of the fundamental solution
+    pub y: BigInt,
+}
+
+/// Iterator over the records in the size of the fundamental solution, in increasing D
+///
+/// A record is a D whose x₁ has more decimal digits than the x₁ of every
+/// smaller D. Every D since the previous record is solved, so this costs as
+/// much as solving them all; the records are yielded as soon as they are
+/// found, ready to be streamed elsewhere. Scans are split across runs as with
+/// [`PeriodRecords`].
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::records::DigitRecords;
+/// let champions: Vec<(u64, u64)> = DigitRecords::new()
+///     .take_while(|record| record.d <= 700)
+///     .map(|record| (record.d, record.digits))
+///     .collect();
+/// assert_eq!(champions.last(), Some(&(661, 38)));
+///
+/// let mut resumed = DigitRecords::resume(662, 38);
+/// assert_eq!(resumed.next().map(|record| (record.d, record.digits)), Some((1021, 48)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitRecords {
+    /// The next D to solve, or `None` once every u64 has been
+    next_d: Option<u64>,
+    /// The most digits seen so far
+    record: u64,
+}
+
+impl DigitRecords {
+    /// A scan from D = 2
+    pub fn new() -> Self {
+        DigitRecords::resume(2, 0)
+    }
+
+    /// A scan continuing at `next_d`, yielding only solutions with more than `record` digits
+    ///
+    /// # Arguments
+    ///
+    /// * `next_d` - The first D to solve
+    /// * `record` - The most digits among the solutions for the D below `next_d`
+    pub fn resume(next_d: u64, record: u64) -> Self {
+        DigitRecords { next_d: Some(next_d), record }
+    }
+
+    /// The next D the scan will solve, or `None` if it has passed `u64::MAX`
+    pub fn next_d(&self) -> Option<u64> {
+        self.next_d
+    }
+
+    /// The most digits found so far, or passed to [`DigitRecords::resume`]
+    pub fn record(&self) -> u64 {
+        self.record
+    }
+}
+
+impl Default for DigitRecords {
+    fn default() -> Self {
+        DigitRecords::new()
+    }
+}
+
+impl Iterator for DigitRecords {
+    type Item = DigitRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(d) = self.next_d {
+            self.next_d = d.checked_add(1);
+            let Ok((x, y)) = pell_min_solution(d) else { continue };
+            let digits = decimal_digits(&x);
+            if digits > self.record {
+                self.record = digits;
+                return Some(DigitRecord { d, digits, x, y });
+            }
+        }
+        None
+    }
+}
+
+impl FusedIterator for DigitRecords {}
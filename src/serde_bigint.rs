@@ -0,0 +1,91 @@
+//! Serde support for `BigInt` as a decimal string
+//!
+//! num-bigint serializes a `BigInt` as its sign and a list of 32-bit digits,
+//! which is compact but unreadable and awkward for other languages to consume.
+//! The types of this crate use this module instead, so `x` and `y` appear as
+//! `"379516400906811930638014896080"` in JSON. Human-readable formats also
+//! accept plain integers, for hand-written input; binary formats such as
+//! bincode, which cannot say what they hold, always read a string.
+//!
+//! Use it on your own fields with `#[serde(with = "pell991::serde_bigint")]`.
+//!
+//! # Examples
+//!
+//! ```
+//! use num_bigint::BigInt;
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Row {
+//!     d: u64,
+//!     #[serde(with = "pell991::serde_bigint")]
+//!     x: BigInt,
+//! }
+//!
+//! let json = serde_json::to_string(&Row { d: 2, x: BigInt::from(3) }).unwrap();
+//! assert_eq!(json, r#"{"d":2,"x":"3"}"#);
+//!
+//! let row: Row = serde_json::from_str(r#"{"d":2,"x":17}"#).unwrap();
+//! assert_eq!(row.x, BigInt::from(17));
+//! ```
+
+use core::fmt;
+use core::str::FromStr;
+
+use num_bigint::BigInt;
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+
+/// Serialize `n` as a decimal string
+///
+/// # Errors
+///
+/// Returns any error from the serializer.
+pub fn serialize<S: Serializer>(n: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(n)
+}
+
+/// Deserialize a `BigInt` from a decimal string, or an integer in
+/// human-readable formats
+///
+/// # Errors
+///
+/// Returns an error if the input is neither an integer nor a string of
+/// decimal digits with an optional sign.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(DecimalVisitor)
+    } else {
+        // Non-self-describing formats only support asking for the type that was written
+        deserializer.deserialize_str(DecimalVisitor)
+    }
+}
+
+struct DecimalVisitor;
+
+impl Visitor<'_> for DecimalVisitor {
+    type Value = BigInt;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an integer or a string of decimal digits")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<BigInt, E> {
+        BigInt::from_str(v).map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<BigInt, E> {
+        Ok(BigInt::from(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<BigInt, E> {
+        Ok(BigInt::from(v))
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<BigInt, E> {
+        Ok(BigInt::from(v))
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<BigInt, E> {
+        Ok(BigInt::from(v))
+    }
+}
@@ -0,0 +1,389 @@
+//! An embeddable HTTP service
+//!
+//! [`router`] builds an axum [`Router`] answering
+//!
+//! | Route | Response |
+//! |-------|----------|
+//! | `GET /solve/{d}` | the fundamental [`PellSolution`] |
+//! | `GET /solutions/{d}?count=n` | the first n solutions (10 by default) |
+//! | `GET /analyze/{d}` | the [`DReport`] of D |
+//!
+//! Responses are JSON, with x and y as decimal strings as in the `serde`
+//! feature. Errors are JSON objects with a machine-readable `error` kind and a
+//! human-readable `message`:
+//!
+//! ```text
+//! 400 {"error":"perfect_square","message":"D must be non-square, got 4 which is 2²"}
+//! ```
+//!
+//! Solves run on tokio's blocking thread pool under the timeout and step limit
+//! of the [`ServerConfig`]; the timeout covers the whole `/analyze` report,
+//! whose class number is only computed for small D. `/solutions` requests
+//! whose answer would exceed the memory budget are rejected before any
+//! solution past the first is computed, and requests beyond the rate limit
+//! are turned away with `429 Too Many Requests`. The router can be served on its own:
+//!
+//! ```no_run
+//! # async fn run() -> std::io::Result<()> {
+//! use pell991::server::{router, ServerConfig};
+//!
+//! let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await?;
+//! axum::serve(listener, router(ServerConfig::default())).await
+//! # }
+//! ```
+//!
+//! or nested into a larger application with `Router::nest`.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::rejection::{PathRejection, QueryRejection};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{analyze_d_with_options, DReport, CLASS_NUMBER_MAX_D};
+use crate::error::PellError;
+use crate::estimate::memory_from_fundamental;
+use crate::options::SolveOptions;
+use crate::solution::PellSolution;
+use crate::solver::{pell_min_solution_with_options, PellSolutionIterator};
+
+/// Number of solutions returned by `/solutions/{d}` without a `count`
+const DEFAULT_COUNT: usize = 10;
+
+/// Limits applied by the service built by [`router`]
+///
+/// The default allows 10 seconds per solve, at most 1000 solutions and
+/// 64 MiB of them per request, class numbers for D up to
+/// [`CLASS_NUMBER_MAX_D`], and 10 requests per second with bursts of up to 20.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// # use pell991::server::ServerConfig;
+/// let config = ServerConfig::new()
+///     .timeout(Duration::from_secs(2))
+///     .max_steps(1_000_000)
+///     .max_count(100)
+///     .max_response_bytes(1 << 20)
+///     .class_number_max_d(1_000_000)
+///     .rate_limit(50, 100);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    timeout: Option<Duration>,
+    max_steps: Option<u64>,
+    max_count: usize,
+    max_response_bytes: u64,
+    class_number_max_d: u64,
+    rate_limit: Option<(u32, u32)>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            timeout: Some(Duration::from_secs(10)),
+            max_steps: None,
+            max_count: 1000,
+            max_response_bytes: 64 << 20,
+            class_number_max_d: CLASS_NUMBER_MAX_D,
+            rate_limit: Some((10, 20)),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// The default limits
+    pub fn new() -> Self {
+        ServerConfig::default()
+    }
+
+    /// Give up on a solve after `timeout`, answering `503 Service Unavailable`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Let solves run for as long as they take
+    pub fn no_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Give up on a solve after `max_steps` continued fraction steps, answering
+    /// `422 Unprocessable Entity`
+    pub fn max_steps(mut self, max_steps: u64) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Reject `/solutions/{d}` requests for more than `max_count` solutions
+    pub fn max_count(mut self, max_count: usize) -> Self {
+        self.max_count = max_count;
+        self
+    }
+
+    /// Reject `/solutions/{d}` requests whose solutions would take more than
+    /// `bytes` of memory, as predicted by [`estimate_memory`](crate::estimate_memory)
+    pub fn max_response_bytes(mut self, bytes: u64) -> Self {
+        self.max_response_bytes = bytes;
+        self
+    }
+
+    /// Compute the class number in `/analyze/{d}` only for D ≤ `max_d`, as its
+    /// O(D) cost is not bounded by the step limit; 0 leaves it out entirely
+    pub fn class_number_max_d(mut self, max_d: u64) -> Self {
+        self.class_number_max_d = max_d;
+        self
+    }
+
+    /// Accept `per_second` requests per second on average, and up to `burst`
+    /// at once
+    ///
+    /// The limit is shared by all clients of the router; put a per-client
+    /// limiter in front of it for public deployments.
+    pub fn rate_limit(mut self, per_second: u32, burst: u32) -> Self {
+        self.rate_limit = Some((per_second, burst));
+        self
+    }
+
+    /// Accept every request
+    pub fn no_rate_limit(mut self) -> Self {
+        self.rate_limit = None;
+        self
+    }
+
+    fn solve_options(&self) -> SolveOptions {
+        let mut options = SolveOptions::new();
+        if let Some(timeout) = self.timeout {
+            options = options.timeout(timeout);
+        }
+        if let Some(max_steps) = self.max_steps {
+            options = options.max_steps(max_steps);
+        }
+        options
+    }
+}
+
+/// Build the router of the service
+///
+/// # Arguments
+///
+/// * `config` - Limits on solves, response sizes and request rate
+///
+/// # Returns
+///
+/// A `Router` with the routes listed in the [module documentation](self).
+///
+/// # Examples
+///
+/// ```
+/// use axum::body::{to_bytes, Body};
+/// use axum::http::Request;
+/// use tower::ServiceExt;
+/// # use pell991::server::{router, ServerConfig};
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let response = router(ServerConfig::default())
+///     .oneshot(Request::get("/solve/991").body(Body::empty()).unwrap())
+///     .await
+///     .unwrap();
+/// assert_eq!(response.status(), 200);
+///
+/// let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+/// assert!(body.starts_with(br#"{"d":991,"x":"379516400906811930638014896080""#));
+/// # });
+/// ```
+pub fn router(config: ServerConfig) -> Router {
+    let state = AppState {
+        options: config.solve_options(),
+        max_count: config.max_count,
+        max_response_bytes: config.max_response_bytes,
+        class_number_max_d: config.class_number_max_d,
+    };
+    let router = Router::new()
+        .route("/solve/{d}", get(solve))
+        .route("/solutions/{d}", get(solutions))
+        .route("/analyze/{d}", get(analyze))
+        .with_state(state);
+
+    match config.rate_limit {
+        Some((per_second, burst)) => {
+            let bucket = Arc::new(Mutex::new(TokenBucket::new(per_second, burst)));
+            router.layer(middleware::from_fn_with_state(bucket, rate_limit))
+        }
+        None => router,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AppState {
+    options: SolveOptions,
+    max_count: usize,
+    max_response_bytes: u64,
+    class_number_max_d: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SolutionsQuery {
+    count: Option<usize>,
+}
+
+async fn solve(State(state): State<AppState>, d: Result<Path<u64>, PathRejection>) -> Result<Json<PellSolution>, ApiError> {
+    let Path(d) = d?;
+    let (x, y) = blocking(move || pell_min_solution_with_options(d, &state.options)).await?;
+    Ok(Json(PellSolution::with_index(d, 1, x, y)))
+}
+
+async fn solutions(
+    State(state): State<AppState>,
+    d: Result<Path<u64>, PathRejection>,
+    query: Result<Query<SolutionsQuery>, QueryRejection>,
+) -> Result<Json<Vec<PellSolution>>, ApiError> {
+    let Path(d) = d?;
+    let Query(query) = query?;
+    let count = query.count.unwrap_or(DEFAULT_COUNT);
+    if count > state.max_count {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "count_too_large",
+            format!("count must be at most {}, got {count}", state.max_count),
+        ));
+    }
+
+    let solutions = blocking(move || {
+        let (x1, y1) = pell_min_solution_with_options(d, &state.options)?;
+        let bytes = memory_from_fundamental(&x1, 1, count as u64);
+        if bytes > state.max_response_bytes {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "response_too_large",
+                format!("{count} solutions of D = {d} need about {bytes} bytes, more than {}", state.max_response_bytes),
+            ));
+        }
+        Ok(PellSolutionIterator::with_fundamental(d, x1, y1).into_solutions().take(count).collect())
+    })
+    .await?;
+    Ok(Json(solutions))
+}
+
+async fn analyze(State(state): State<AppState>, d: Result<Path<u64>, PathRejection>) -> Result<Json<DReport>, ApiError> {
+    let Path(d) = d?;
+    let report = blocking(move || analyze_d_with_options(d, &state.options, state.class_number_max_d)).await?;
+    Ok(Json(report))
+}
+
+/// Run a solve on the blocking thread pool, keeping the async workers free
+async fn blocking<T, E, F>(f: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    E: Into<ApiError> + Send + 'static,
+    F: FnOnce() -> Result<T, E> + Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result.map_err(Into::into),
+        Err(err) => Err(ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "internal", err.to_string())),
+    }
+}
+
+/// A token bucket holding up to `burst` tokens, refilled at `per_second`
+#[derive(Debug)]
+struct TokenBucket {
+    per_second: f64,
+    burst: f64,
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_second: u32, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        TokenBucket { per_second: f64::from(per_second), burst, tokens: burst, refilled: Instant::now() }
+    }
+
+    /// Take a token, or return how long until one is available
+    fn try_acquire(&mut self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.burst);
+        self.refilled = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else if self.per_second > 0.0 {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.per_second))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+}
+
+async fn rate_limit(State(bucket): State<Arc<Mutex<TokenBucket>>>, request: Request, next: Next) -> Response {
+    let acquired = bucket.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).try_acquire();
+    match acquired {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            let retry_after = wait.as_secs_f64().ceil().min(f64::from(u32::MAX)) as u32;
+            let mut response =
+                ApiError::new(StatusCode::TOO_MANY_REQUESTS, "rate_limited", "too many requests".to_string()).into_response();
+            response.headers_mut().insert(header::RETRY_AFTER, retry_after.into());
+            response
+        }
+    }
+}
+
+/// An error response: a status code and a JSON body
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    body: ErrorBody,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, error: &'static str, message: String) -> Self {
+        ApiError { status, body: ErrorBody { error, message } }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+impl From<PellError> for ApiError {
+    fn from(err: PellError) -> Self {
+        let (status, error) = match &err {
+            PellError::InvalidD(_) => (StatusCode::BAD_REQUEST, "invalid_d"),
+            PellError::PerfectSquare(_) => (StatusCode::BAD_REQUEST, "perfect_square"),
+            PellError::LimitExceeded { .. } => (StatusCode::UNPROCESSABLE_ENTITY, "limit_exceeded"),
+            PellError::Timeout { .. } => (StatusCode::SERVICE_UNAVAILABLE, "timeout"),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "internal"),
+        };
+        ApiError::new(status, error, err.to_string())
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> Self {
+        ApiError::new(rejection.status(), "invalid_request", rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiError::new(rejection.status(), "invalid_request", rejection.body_text())
+    }
+}
@@ -0,0 +1,519 @@
+//! Solution type for Pell equations
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+use num_bigint::{BigInt, BigUint};
+#[cfg(feature = "std")]
+use num_bigint::Sign;
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use crate::error::PellError;
+use crate::solver::verify_pell_solution;
+
+/// A solution (x, y) to the Pell equation x² - D·y² = 1, together with its D
+/// and, when known, its index k
+///
+/// The `Display` implementation writes the solution as `x=…, y=…, d=…` (plus
+/// `, k=…` when the index is known), which `FromStr` reads back, so solutions
+/// can be round-tripped through text.
+///
+/// With the `serde` feature x and y serialize as decimal strings, see
+/// [`serde_bigint`](crate::serde_bigint).
+///
+/// Solutions are ordered by D first and then by magnitude of x and y, so for
+/// a fixed D sorting matches the solution index k. Since k is determined by
+/// D, x and y, it is ignored by comparisons and hashing.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::PellSolution;
+/// let solution: PellSolution = "x=3, y=2, d=2".parse().unwrap();
+/// assert!(solution.verify());
+/// assert_eq!(solution.to_string(), "x=3, y=2, d=2");
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PellSolution {
+    /// The coefficient D in the Pell equation
+    pub d: u64,
+    /// The x-coordinate of the solution
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_bigint"))]
+    #[cfg_attr(feature = "schemars", schemars(with = "alloc::string::String", pattern(r"^-?[0-9]+$")))]
+    pub x: BigInt,
+    /// The y-coordinate of the solution
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_bigint"))]
+    #[cfg_attr(feature = "schemars", schemars(with = "alloc::string::String", pattern(r"^-?[0-9]+$")))]
+    pub y: BigInt,
+    /// The index k of the solution (xₖ + yₖ√D = (x₁ + y₁√D)ᵏ), if known
+    pub k: Option<u64>,
+}
+
+impl PellSolution {
+    /// Create a new solution for the given D, with unknown index
+    ///
+    /// The pair is not verified; use [`PellSolution::verify`] for that.
+    pub fn new(d: u64, x: BigInt, y: BigInt) -> Self {
+        PellSolution { d, x, y, k: None }
+    }
+
+    /// Create the k-th solution for the given D
+    ///
+    /// Neither the pair nor the index is verified.
+    pub fn with_index(d: u64, k: u64, x: BigInt, y: BigInt) -> Self {
+        PellSolution { d, x, y, k: Some(k) }
+    }
+
+    /// Check that (x, y) satisfies x² - D·y² = 1
+    pub fn verify(&self) -> bool {
+        verify_pell_solution(self.d, &self.x, &self.y)
+    }
+
+    /// The 64-bit limbs of |x| and |y|, least significant first
+    ///
+    /// num-bigint does not lend out its digit storage, so the limbs are copied,
+    /// but there is no base conversion: this is a linear-time move of the raw
+    /// number, suited to FFI and binary serialization. Zero has no limbs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::PellSolution;
+    /// let solution: PellSolution = "x=17, y=12, d=2".parse().unwrap();
+    /// assert_eq!(solution.to_limbs(), (vec![17], vec![12]));
+    /// ```
+    pub fn to_limbs(&self) -> (Vec<u64>, Vec<u64>) {
+        (self.x.magnitude().to_u64_digits(), self.y.magnitude().to_u64_digits())
+    }
+
+    /// Build a solution from the 64-bit limbs of x and y, least significant
+    /// first, with unknown index
+    ///
+    /// The inverse of [`PellSolution::to_limbs`] for solutions with x, y ≥ 0.
+    /// Trailing zero limbs are allowed. The pair is not verified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::{pell_min_solution, PellSolution};
+    /// let (x, y) = pell_min_solution(991).unwrap();
+    /// let solution = PellSolution::new(991, x, y);
+    ///
+    /// let (x_limbs, y_limbs) = solution.to_limbs();
+    /// assert_eq!(PellSolution::from_limbs(991, &x_limbs, &y_limbs), solution);
+    /// ```
+    pub fn from_limbs(d: u64, x: &[u64], y: &[u64]) -> Self {
+        let from_limbs = |limbs: &[u64]| {
+            let digits = limbs.iter().flat_map(|&limb| [limb as u32, (limb >> 32) as u32]).collect();
+            BigInt::from(BigUint::new(digits))
+        };
+        PellSolution::new(d, from_limbs(x), from_limbs(y))
+    }
+
+    /// The equation x² - D·y² = 1 with the values filled in, as LaTeX
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::PellSolution;
+    /// let solution: PellSolution = "649 180".parse().unwrap();
+    /// assert_eq!(solution.to_latex(), r"649^{2} - 13 \cdot 180^{2} = 1");
+    /// ```
+    pub fn to_latex(&self) -> String {
+        let square = |n: &BigInt| if n.is_negative() { format!("({n})^{{2}}") } else { format!("{n}^{{2}}") };
+        format!("{} - {} \\cdot {} = 1", square(&self.x), self.d, square(&self.y))
+    }
+
+    /// The equation x² - D·y² = 1 with the values filled in, in Unicode
+    ///
+    /// Uses the minus sign U+2212 and the middle dot, for plain-text slides and messages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::PellSolution;
+    /// let solution: PellSolution = "649 180".parse().unwrap();
+    /// assert_eq!(solution.to_unicode(), "649² − 13·180² = 1");
+    /// ```
+    pub fn to_unicode(&self) -> String {
+        let square = |n: &BigInt| if n.is_negative() { format!("(−{})²", n.magnitude()) } else { format!("{n}²") };
+        format!("{} − {}·{} = 1", square(&self.x), self.d, square(&self.y))
+    }
+}
+
+impl PartialEq for PellSolution {
+    fn eq(&self, other: &Self) -> bool {
+        (self.d, &self.x, &self.y) == (other.d, &other.x, &other.y)
+    }
+}
+
+impl Eq for PellSolution {}
+
+impl Hash for PellSolution {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.d, &self.x, &self.y).hash(state);
+    }
+}
+
+impl PartialOrd for PellSolution {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PellSolution {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.d, &self.x, &self.y).cmp(&(other.d, &other.x, &other.y))
+    }
+}
+
+impl fmt::Display for PellSolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "x={}, y={}, d={}", self.x, self.y, self.d)?;
+        if let Some(k) = self.k {
+            write!(f, ", k={k}")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<PellSolution> for (BigInt, BigInt) {
+    fn from(solution: PellSolution) -> Self {
+        (solution.x, solution.y)
+    }
+}
+
+/// Convert to machine integers, for code that works with small solutions
+///
+/// # Errors
+///
+/// Returns `PellError::Overflow` if x or y is negative or does not fit in a `u128`.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_min_solution, pell_solution_k, PellError, PellSolution};
+/// let solution: PellSolution = "x=17, y=12, d=2".parse().unwrap();
+/// assert_eq!(<(u128, u128)>::try_from(&solution), Ok((17, 12)));
+///
+/// // The second solution for D = 991 has 60 digits
+/// let (x1, y1) = pell_min_solution(991).unwrap();
+/// let (x2, y2) = pell_solution_k(991, &x1, &y1, 2).unwrap();
+/// let huge = PellSolution::with_index(991, 2, x2, y2);
+/// assert_eq!(<(u128, u128)>::try_from(&huge), Err(PellError::Overflow { bits: 128 }));
+/// ```
+impl TryFrom<&PellSolution> for (u128, u128) {
+    type Error = PellError;
+
+    fn try_from(solution: &PellSolution) -> Result<Self, Self::Error> {
+        let overflow = || PellError::Overflow { bits: 128 };
+        let x = solution.x.to_u128().ok_or_else(overflow)?;
+        let y = solution.y.to_u128().ok_or_else(overflow)?;
+        Ok((x, y))
+    }
+}
+
+/// Build a solution from `(d, x, y)`, with unknown index
+impl From<(u64, BigInt, BigInt)> for PellSolution {
+    fn from((d, x, y): (u64, BigInt, BigInt)) -> Self {
+        PellSolution::new(d, x, y)
+    }
+}
+
+/// Build a solution from `(d, x, y)` in machine integers, with unknown index
+impl From<(u64, u128, u128)> for PellSolution {
+    fn from((d, x, y): (u64, u128, u128)) -> Self {
+        PellSolution::new(d, BigInt::from(x), BigInt::from(y))
+    }
+}
+
+/// Build a solution from `(x, y)`, recovering D as (x² - 1) / y² like the plain
+/// form of `FromStr`
+///
+/// # Errors
+///
+/// Returns `PellError::Parse` if y = 0 or (x² - 1) / y² is not an integer
+/// greater than 1 that fits in a `u64`.
+impl TryFrom<(BigInt, BigInt)> for PellSolution {
+    type Error = PellError;
+
+    fn try_from((x, y): (BigInt, BigInt)) -> Result<Self, Self::Error> {
+        let d = infer_d(&x, &y)?;
+        Ok(PellSolution::new(d, x, y))
+    }
+}
+
+impl FromStr for PellSolution {
+    type Err = PellError;
+
+    /// Parse a solution from text
+    ///
+    /// Two forms are accepted:
+    ///
+    /// * `x=…, y=…, d=…` — keys in any order, separated by commas. If `d` is
+    ///   omitted it is recovered from the pair as in the plain form. An optional
+    ///   `k=…` gives the solution index. Each key may appear only once.
+    /// * `x y` — two whitespace-separated integers. D is recovered as
+    ///   (x² - 1) / y², which must be an exact integer greater than 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::PellSolution;
+    /// let solution: PellSolution = "17 12".parse().unwrap();
+    /// assert_eq!(solution.d, 2);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.contains('=') {
+            parse_keyed(s)
+        } else {
+            parse_plain(s)
+        }
+    }
+}
+
+fn parse_keyed(s: &str) -> Result<PellSolution, PellError> {
+    let mut x = None;
+    let mut y = None;
+    let mut d = None;
+    let mut k = None;
+
+    for field in s.split(',') {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or_else(|| PellError::Parse(format!("expected key=value, got '{}'", field.trim())))?;
+        let value = value.trim();
+        match key.trim() {
+            "x" => set_once(&mut x, "x", parse_bigint(value)?)?,
+            "y" => set_once(&mut y, "y", parse_bigint(value)?)?,
+            "d" => {
+                let parsed = value
+                    .parse::<u64>()
+                    .map_err(|_| PellError::Parse(format!("invalid D value '{value}'")))?;
+                set_once(&mut d, "d", parsed)?;
+            }
+            "k" => {
+                let parsed = value
+                    .parse::<u64>()
+                    .map_err(|_| PellError::Parse(format!("invalid k value '{value}'")))?;
+                set_once(&mut k, "k", parsed)?;
+            }
+            other => return Err(PellError::Parse(format!("unknown key '{other}'"))),
+        }
+    }
+
+    let x = x.ok_or_else(|| PellError::Parse("missing x".to_string()))?;
+    let y = y.ok_or_else(|| PellError::Parse("missing y".to_string()))?;
+    let d = match d {
+        Some(d) => d,
+        None => infer_d(&x, &y)?,
+    };
+
+    Ok(PellSolution { d, x, y, k })
+}
+
+/// Store the value of a key, rejecting a key given twice
+fn set_once<T>(slot: &mut Option<T>, key: &str, value: T) -> Result<(), PellError> {
+    match slot.replace(value) {
+        Some(_) => Err(PellError::Parse(format!("duplicate key '{key}'"))),
+        None => Ok(()),
+    }
+}
+
+fn parse_plain(s: &str) -> Result<PellSolution, PellError> {
+    let mut parts = s.split_whitespace();
+    let (Some(x), Some(y), None) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(PellError::Parse(format!("expected 'x y', got '{s}'")));
+    };
+
+    let x = parse_bigint(x)?;
+    let y = parse_bigint(y)?;
+    let d = infer_d(&x, &y)?;
+
+    Ok(PellSolution::new(d, x, y))
+}
+
+fn parse_bigint(value: &str) -> Result<BigInt, PellError> {
+    value
+        .parse::<BigInt>()
+        .map_err(|_| PellError::Parse(format!("invalid integer '{value}'")))
+}
+
+/// Find the D for which (x, y) solves x² - D·y² = 1
+///
+/// Computes (x² - 1)/y² and keeps it if it is an exact, positive integer.
+/// Such a D is never a perfect square: x² - n²y² = (x - ny)(x + ny) = 1 has
+/// no solution with y ≠ 0. Unlike the parsers of [`PellSolution`], D may
+/// exceed `u64`.
+///
+/// # Arguments
+///
+/// * `x` - The candidate x
+/// * `y` - The candidate y
+///
+/// # Returns
+///
+/// `Some(D)`, or `None` if y = 0 or (x² - 1)/y² is not a positive integer.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{d_for_solution, BigInt};
+/// let x: BigInt = "379516400906811930638014896080".parse().unwrap();
+/// let y: BigInt = "12055735790331359447442538767".parse().unwrap();
+/// assert_eq!(d_for_solution(&x, &y), Some(BigInt::from(991)));
+///
+/// assert_eq!(d_for_solution(&BigInt::from(18), &BigInt::from(5)), None); // 18² - 13·5² = -1
+/// ```
+pub fn d_for_solution(x: &BigInt, y: &BigInt) -> Option<BigInt> {
+    if y.is_zero() {
+        return None;
+    }
+    let (d, rem) = (x * x - BigInt::one()).div_rem(&(y * y));
+    (rem.is_zero() && d.is_positive()).then_some(d)
+}
+
+/// Recover D = (x² - 1) / y² from a candidate solution
+fn infer_d(x: &BigInt, y: &BigInt) -> Result<u64, PellError> {
+    if y.is_zero() {
+        return Err(PellError::Parse("cannot infer D when y = 0".to_string()));
+    }
+
+    let (d, rem) = (x * x - BigInt::one()).div_rem(&(y * y));
+    if !rem.is_zero() {
+        return Err(PellError::Parse(format!("(x² - 1) is not divisible by y² for x={x}, y={y}")));
+    }
+
+    d.to_u64()
+        .filter(|&d| d > 1)
+        .ok_or_else(|| PellError::Parse(format!("inferred D = {d} is not a valid u64 greater than 1")))
+}
+
+/// Number of digits converted at once when streaming a solution
+#[cfg(feature = "std")]
+const BLOCK_DIGITS: usize = 512;
+
+/// Write `solution` to `w` in the `Display` format, with x and y in base `radix`
+///
+/// The digits are produced block by block and written as they are ready, so a
+/// solution with millions of digits never has to exist as one `String`. D and k
+/// are written in decimal; with radix 10 the output equals `solution.to_string()`.
+///
+/// # Arguments
+///
+/// * `solution` - The solution to write
+/// * `w` - The destination, which should be buffered (for example a `BufWriter`)
+/// * `radix` - Base of the digits of x and y, from 2 to 36
+///
+/// # Errors
+///
+/// Returns any error from writing to `w`.
+///
+/// # Panics
+///
+/// Panics if `radix` is not in 2..=36.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{write_solution, PellSolution};
+/// let solution: PellSolution = "x=17, y=12, d=2".parse().unwrap();
+/// let mut out = Vec::new();
+/// write_solution(&solution, &mut out, 16).unwrap();
+/// assert_eq!(out, b"x=11, y=c, d=2");
+/// ```
+#[cfg(feature = "std")]
+pub fn write_solution<W: std::io::Write>(solution: &PellSolution, mut w: W, radix: u32) -> std::io::Result<()> {
+    assert!((2..=36).contains(&radix), "radix must be in 2..=36, got {radix}");
+
+    w.write_all(b"x=")?;
+    write_digits(&solution.x, &mut w, radix)?;
+    w.write_all(b", y=")?;
+    write_digits(&solution.y, &mut w, radix)?;
+    write!(w, ", d={}", solution.d)?;
+    if let Some(k) = solution.k {
+        write!(w, ", k={k}")?;
+    }
+    Ok(())
+}
+
+/// Write the digits of `n` in base `radix`, converting in blocks
+///
+/// The streaming counterpart of `n.to_str_radix(radix)`, used by
+/// [`write_solution`] for each coordinate. Writing into a hasher or a counter
+/// fingerprints a huge number without ever holding all of its digits.
+///
+/// # Errors
+///
+/// Returns any error from writing to `w`.
+///
+/// # Panics
+///
+/// Panics if `radix` is not in 2..=36.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigInt;
+/// # use pell991::write_digits;
+/// let mut out = Vec::new();
+/// write_digits(&BigInt::from(-255), &mut out, 16).unwrap();
+/// assert_eq!(out, b"-ff");
+/// ```
+#[cfg(feature = "std")]
+pub fn write_digits<W: std::io::Write>(n: &BigInt, mut w: W, radix: u32) -> std::io::Result<()> {
+    assert!((2..=36).contains(&radix), "radix must be in 2..=36, got {radix}");
+    if n.sign() == Sign::Minus {
+        w.write_all(b"-")?;
+    }
+    let magnitude = n.magnitude();
+
+    // powers[i] = radix^(BLOCK_DIGITS·2ⁱ), up to the largest not exceeding n
+    let mut powers = alloc::vec![BigUint::from(radix).pow(BLOCK_DIGITS as u32)];
+    while let Some(last) = powers.last() {
+        let next = last * last;
+        if &next > magnitude {
+            break;
+        }
+        powers.push(next);
+    }
+    write_magnitude(&mut w, magnitude, radix, &powers, 0)
+}
+
+/// Write `n` using the powers in `powers`, zero-padded to `width` digits (0 for none)
+///
+/// Splits n by the largest power into a high and a low half, the low half
+/// always having exactly as many digits as that power has zeros.
+#[cfg(feature = "std")]
+fn write_magnitude<W: std::io::Write>(
+    w: &mut W,
+    n: &BigUint,
+    radix: u32,
+    powers: &[BigUint],
+    width: usize,
+) -> std::io::Result<()> {
+    let Some((power, lower)) = powers.split_last() else {
+        let digits = n.to_str_radix(radix);
+        for _ in digits.len()..width {
+            w.write_all(b"0")?;
+        }
+        return w.write_all(digits.as_bytes());
+    };
+
+    if width == 0 && n < power {
+        return write_magnitude(w, n, radix, lower, 0);
+    }
+    let low_width = BLOCK_DIGITS << lower.len();
+    let (high, low) = n.div_rem(power);
+    write_magnitude(w, &high, radix, lower, width.saturating_sub(low_width))?;
+    write_magnitude(w, &low, radix, lower, low_width)
+}
@@ -1,9 +1,67 @@
 //! Core Pell equation solving algorithms
 
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::mem;
+
 use num_bigint::BigInt;
-use num_traits::{One, Zero};
+use num_traits::{One, Signed, ToPrimitive, Zero};
 use crate::error::PellError;
-use crate::utils::{isqrt_u64, is_square_u64};
+use crate::estimate::log10_fundamental_unit;
+use crate::solution::PellSolution;
+use crate::options::{ProgressEvent, SolveOptions};
+use crate::utils::{estimate_period_length, isqrt_u64, is_square_u64, log10_bigint};
+
+/// Check that D is usable in a Pell equation: D > 1 and not a perfect square
+pub(crate) fn validate_d(d: u64) -> Result<(), PellError> {
+    if d <= 1 {
+        return Err(PellError::InvalidD(d));
+    }
+    if is_square_u64(d) {
+        return Err(PellError::PerfectSquare(d));
+    }
+    Ok(())
+}
+
+/// Check that (x, y) is a positive solution of x² - D·y² = 1, as the unit
+/// formulas that skip D or divide by y need
+pub(crate) fn validate_solution(d: u64, x: &BigInt, y: &BigInt) -> Result<(), PellError> {
+    if !x.is_positive() || !y.is_positive() || !verify_pell_solution(d, x, y) {
+        return Err(PellError::InvalidSolution(d));
+    }
+    Ok(())
+}
+
+/// Whether `(x, y)` is the fundamental solution for `d` and `period` the period of √D
+///
+/// Besides x² - D·y² = 1 with x, y > 0, the size of x must match the fundamental
+/// solution predicted by the continued fraction of √D; every later solution is
+/// at least 3.5 times larger. The prediction follows the ratios
+/// pₖ/pₖ₋₁ = aₖ + pₖ₋₂/pₖ₋₁ of the convergents in floating point, so the check
+/// is one pass over the expansion with no BigInt arithmetic. The persistent
+/// caches use it to reject damaged or foreign entries.
+#[cfg(any(feature = "disk-cache", feature = "sqlite"))]
+pub(crate) fn is_fundamental(d: u64, (x, y): &(BigInt, BigInt), period: u64) -> bool {
+    if validate_solution(d, x, y).is_err() {
+        return false;
+    }
+    let Ok(cf) = crate::continued_fraction::continued_fraction(d) else {
+        return false;
+    };
+    if cf.period.len() as u64 != period {
+        return false;
+    }
+
+    // x₁ = pᵣ₋₁ for an even period r, and p₂ᵣ₋₁ for an odd one
+    let last = if period % 2 == 0 { period - 1 } else { 2 * period - 1 };
+    let mut ratio = cf.a0 as f64;
+    let mut log10_p = ratio.log10();
+    for &a in cf.period.iter().cycle().take(last as usize) {
+        ratio = a as f64 + 1.0 / ratio;
+        log10_p += ratio.log10();
+    }
+    (log10_bigint(x) - log10_p).abs() < 0.25
+}
 
 /// Solve the Pell equation x² - D·y² = 1 for non-square D > 1.
 ///
@@ -44,13 +102,128 @@ use crate::utils::{isqrt_u64, is_square_u64};
 /// // Verification: 3² - 2·2² = 9 - 8 = 1 ✓
 /// ```
 pub fn pell_min_solution(d_constant: u64) -> Result<(BigInt, BigInt), PellError> {
-    if d_constant <= 1 {
-        return Err(PellError::InvalidD(d_constant));
+    pell_min_solution_with_period(d_constant).map(|(solution, _)| solution)
+}
+
+/// Solve the Pell equation and also return the period length of the continued fraction of √D.
+///
+/// The period falls out of the continued fraction expansion used to find the
+/// minimal solution, so this costs no more than `pell_min_solution`.
+///
+/// # Arguments
+///
+/// * `d_constant` - The coefficient D in the Pell equation (must be > 1 and non-square)
+///
+/// # Returns
+///
+/// A `Result` containing `((x, y), period)` where `(x, y)` is the minimal solution
+/// and `period` is the length of the periodic part of the continued fraction of √D,
+/// or a `PellError` if the input is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d_constant` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d_constant` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigInt;
+/// # use pell991::pell_min_solution_with_period;
+///
+/// // √7 = [2; 1, 1, 1, 4], so the period is 4
+/// let ((x, y), period) = pell_min_solution_with_period(7).unwrap();
+/// assert_eq!((x, y), (BigInt::from(8), BigInt::from(3)));
+/// assert_eq!(period, 4);
+/// ```
+pub fn pell_min_solution_with_period(d_constant: u64) -> Result<((BigInt, BigInt), u64), PellError> {
+    solve_cached(d_constant, &SolveOptions::default())
+}
+
+/// Solve the Pell equation, giving up after `max_steps` continued fraction steps
+///
+/// Useful for D values from untrusted input, whose solutions can take a very
+/// long time to find. Shorthand for `pell_min_solution_with_options` with
+/// `SolveOptions::new().max_steps(max_steps)`.
+///
+/// # Arguments
+///
+/// * `d_constant` - The coefficient D in the Pell equation (must be > 1 and non-square)
+/// * `max_steps` - Maximum number of continued fraction terms to expand
+///
+/// # Returns
+///
+/// A `Result` containing the minimal solution `(x, y)`, or a `PellError`.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d_constant` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d_constant` is a perfect square.
+/// Returns `PellError::LimitExceeded` if no solution is found within `max_steps` steps.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_min_solution_limited, PellError};
+/// assert!(pell_min_solution_limited(991, 100).is_ok());
+/// assert_eq!(pell_min_solution_limited(991, 50), Err(PellError::LimitExceeded { steps: 50 }));
+/// ```
+pub fn pell_min_solution_limited(d_constant: u64, max_steps: u64) -> Result<(BigInt, BigInt), PellError> {
+    pell_min_solution_with_options(d_constant, &SolveOptions::new().max_steps(max_steps))
+}
+
+/// Solve the Pell equation under the limits given by `options`
+///
+/// # Arguments
+///
+/// * `d_constant` - The coefficient D in the Pell equation (must be > 1 and non-square)
+/// * `options` - Limits on the solve (see [`SolveOptions`])
+///
+/// # Returns
+///
+/// A `Result` containing the minimal solution `(x, y)`, or a `PellError`.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d_constant` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d_constant` is a perfect square.
+/// Returns `PellError::LimitExceeded` if the step limit is reached.
+/// Returns `PellError::Timeout` if the time limit is reached.
+pub fn pell_min_solution_with_options(d_constant: u64, options: &SolveOptions) -> Result<(BigInt, BigInt), PellError> {
+    solve_cached(d_constant, options).map(|(solution, _)| solution)
+}
+
+/// Validate D, then answer from the process-wide caches or solve and cache
+pub(crate) fn solve_cached(d_constant: u64, options: &SolveOptions) -> Result<((BigInt, BigInt), u64), PellError> {
+    validate_d(d_constant)?;
+
+    #[cfg(feature = "std")]
+    if let Some(cached) = crate::cache::global_get(d_constant) {
+        return Ok(cached);
     }
-    if is_square_u64(d_constant) {
-        return Err(PellError::PerfectSquare(d_constant));
+
+    #[cfg(feature = "sqlite")]
+    if let Some((solution, period)) = crate::sqlite_cache::global_get(d_constant) {
+        crate::cache::global_insert(d_constant, &solution, period);
+        return Ok((solution, period));
     }
 
+    let (solution, period) = solve_continued_fraction(d_constant, options)?;
+
+    #[cfg(feature = "std")]
+    crate::cache::global_insert(d_constant, &solution, period);
+    #[cfg(feature = "sqlite")]
+    crate::sqlite_cache::global_insert(d_constant, &solution, period);
+
+    Ok((solution, period))
+}
+
+/// Expand √D until the first convergent solving the Pell equation (D already validated)
+#[cfg_attr(feature = "tracing", tracing::instrument(name = "continued_fraction", level = "debug", skip_all, fields(d = d_constant)))]
+fn solve_continued_fraction(d_constant: u64, options: &SolveOptions) -> Result<((BigInt, BigInt), u64), PellError> {
+    #[cfg(feature = "std")]
+    let started = std::time::Instant::now();
+
     let a0 = isqrt_u64(d_constant);
     let mut m: i128 = 0;
     let mut d: i128 = 1;
@@ -67,25 +240,157 @@ pub fn pell_min_solution(d_constant: u64) -> Result<(BigInt, BigInt), PellError>
 
     let big_d = BigInt::from(d_constant);
 
+    // Index of the current convergent p/q, and the period once aₙ = 2a₀ is seen.
+    // For odd periods the solution only appears after the second pass, so the
+    // period is recorded on the way; for even periods it is n + 1 at the solution.
+    let mut n: u64 = 0;
+    let mut period: Option<u64> = None;
+
+    // Heuristic number of steps for progress reporting, replaced once the period is seen
+    let estimated_steps = estimate_period_length(d_constant).unwrap_or(1).max(1);
+
     loop {
-        let lhs = &p * &p - &big_d * &q * &q;
-        if lhs.is_one() {
-            return Ok((p, q));
+        // Advance the (m, d, a) state of the expansion to term n + 1. It is
+        // cheap machine arithmetic and tells where the solutions are:
+        // pₙ² - D·qₙ² = (-1)ⁿ⁺¹·dₙ₊₁, and dₙ₊₁ = 1 exactly at the end of a
+        // period, so only convergents with odd n and dₙ₊₁ = 1 can solve the
+        // equation. Those few candidates get the full BigInt check.
+        let m_next = d * a - m;
+        let d_next = ((d_constant as i128) - m_next * m_next) / d;
+        let a_next = ((a0 as i128) + m_next) / d_next;
+
+        if d_next == 1 && n % 2 == 1 {
+            let lhs = &p * &p - &big_d * &q * &q;
+            count!(bigint_multiplications += 3);
+            if lhs.is_one() {
+                let period = period.unwrap_or(n + 1);
+                #[cfg(feature = "tracing")]
+                tracing::debug!(steps = n, period, x_bits = p.bits(), y_bits = q.bits(), "solution found");
+                return Ok(((p, q), period));
+            }
+        }
+        if options.max_steps.is_some_and(|max_steps| n >= max_steps) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(steps = n, "step limit reached");
+            return Err(PellError::LimitExceeded { steps: n });
         }
+        #[cfg(feature = "std")]
+        if let Some(limit) = options.timeout {
+            if started.elapsed() >= limit {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(steps = n, ?limit, "timed out");
+                return Err(PellError::Timeout { limit });
+            }
+        }
+
+        m = m_next;
+        d = d_next;
+        a = a_next;
+        n += 1;
 
-        m = d * a - m;
-        d = ((d_constant as i128) - m * m) / d;
-        a = ((a0 as i128) + m) / d;
+        if period.is_none() && d == 1 {
+            period = Some(n);
+            #[cfg(feature = "tracing")]
+            tracing::debug!(period = n, "end of first period");
+        }
 
         let a_big = BigInt::from(a);
 
         let p_next = &a_big * &p + &p_prev1;
         let q_next = &a_big * &q + &q_prev1;
+        count!(cf_steps += 1, bigint_multiplications += 2);
 
         p_prev1 = p;
         q_prev1 = q;
         p = p_next;
         q = q_next;
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(step = n, partial_quotient = a as u64, convergent_bits = p.bits(), "step");
+
+        if let Some(callback) = &options.on_progress {
+            // A period seen before the solution is odd, and the solution ends the second pass
+            let estimated_fraction = match period {
+                Some(period) => (n as f64 / (2 * period - 1) as f64).min(1.0),
+                None => (n as f64 / estimated_steps as f64).min(0.99),
+            };
+            callback(ProgressEvent {
+                d: d_constant,
+                steps: n,
+                convergent_bits: p.bits(),
+                period,
+                estimated_fraction,
+            });
+        }
+    }
+}
+
+/// Solve the Pell equation x² - D·y² = 1 in machine integers only
+///
+/// Runs the same continued fraction expansion as `pell_min_solution`, with the
+/// convergents held in `u128`, so it neither allocates nor touches `BigInt`.
+/// This suits targets that cannot afford a heap, or D values known to have
+/// small solutions. The process-wide caches are not consulted.
+///
+/// # Arguments
+///
+/// * `d_constant` - The coefficient D in the Pell equation (must be > 1 and non-square)
+///
+/// # Returns
+///
+/// A `Result` containing the minimal solution `(x, y)`, or a `PellError`.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d_constant` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d_constant` is a perfect square.
+/// Returns `PellError::Overflow { bits: 128 }` if x does not fit in a `u128`.
+///
+/// # Performance
+///
+/// Convergents grow at least like the Fibonacci numbers, so a solution either
+/// appears or overflows within about 190 steps.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_min_solution_u128, PellError};
+/// let (x, y) = pell_min_solution_u128(991).unwrap();
+/// assert_eq!(x, 379516400906811930638014896080);
+/// assert_eq!(y, 12055735790331359447442538767);
+///
+/// // x has 212 digits for D = 9949
+/// assert_eq!(pell_min_solution_u128(9949), Err(PellError::Overflow { bits: 128 }));
+/// ```
+pub fn pell_min_solution_u128(d_constant: u64) -> Result<(u128, u128), PellError> {
+    validate_d(d_constant)?;
+    let overflow = || PellError::Overflow { bits: 128 };
+
+    // 0 ≤ m ≤ a₀ and 1 ≤ d ≤ 2a₀ throughout, so the state needs no signs
+    let big_d = u128::from(d_constant);
+    let a0 = u128::from(isqrt_u64(d_constant));
+    let (mut m, mut d, mut a) = (0u128, 1u128, a0);
+    let (mut p, mut p_prev) = (a0, 1u128);
+    let (mut q, mut q_prev) = (1u128, 0u128);
+
+    let mut n: u64 = 0;
+    loop {
+        let m_next = d * a - m;
+        let d_next = (big_d - m_next * m_next) / d;
+        let a_next = (a0 + m_next) / d_next;
+
+        // pₙ² - D·qₙ² = (-1)ⁿ⁺¹·dₙ₊₁, so this is a solution without squaring p
+        if d_next == 1 && n % 2 == 1 {
+            return Ok((p, q));
+        }
+
+        (m, d, a) = (m_next, d_next, a_next);
+        n += 1;
+
+        let p_next = a.checked_mul(p).and_then(|ap| ap.checked_add(p_prev)).ok_or_else(overflow)?;
+        let q_next = a.checked_mul(q).and_then(|aq| aq.checked_add(q_prev)).ok_or_else(overflow)?;
+        (p_prev, p) = (p, p_next);
+        (q_prev, q) = (q, q_next);
     }
 }
 
@@ -140,6 +445,7 @@ pub fn pell_min_solution_unchecked(d_constant: u64) -> (BigInt, BigInt) {
 /// assert_eq!(x2, BigInt::from(17));
 /// assert_eq!(y2, BigInt::from(12));
 /// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(name = "solution_k", level = "debug", skip_all, fields(d = d_constant, k, x1_bits = x1.bits())))]
 pub fn pell_solution_k(d_constant: u64, x1: &BigInt, y1: &BigInt, k: u64) -> Result<(BigInt, BigInt), PellError> {
     if k == 0 {
         return Err(PellError::InvalidK(k));
@@ -154,24 +460,49 @@ pub fn pell_solution_k(d_constant: u64, x1: &BigInt, y1: &BigInt, k: u64) -> Res
     let mut base_x = x1.clone();
     let mut base_y = y1.clone();
 
+    // Scratch products, so each step only allocates for the multiplications themselves
+    let mut cross = BigInt::zero();
+    let mut scaled = BigInt::zero();
+
     let mut exp = k;
-    let big_d = BigInt::from(d_constant);
+    count!(exponentiations += 1);
 
     while exp > 0 {
         if exp % 2 == 1 {
-            let new_x = &x * &base_x + &big_d * &y * &base_y;
-            let new_y = &x * &base_y + &y * &base_x;
-            x = new_x;
-            y = new_y;
+            // (x + y√D)(bx + by√D) = (x·bx + D·y·by) + (x·by + y·bx)√D
+            cross.clone_from(&x);
+            cross *= &base_y;
+            scaled.clone_from(&y);
+            scaled *= &base_y;
+            scaled *= d_constant;
+            x *= &base_x;
+            x += &scaled;
+            y *= &base_x;
+            y += &cross;
+            count!(bigint_multiplications += 5);
         }
-        let new_x = &base_x * &base_x + &big_d * &base_y * &base_y;
-        let new_y = BigInt::from(2u32) * &base_x * &base_y;
-        base_x = new_x;
-        base_y = new_y;
 
         exp /= 2;
+        if exp == 0 {
+            // The base is not needed again, so skip the (most expensive) last squaring
+            break;
+        }
+
+        // (bx + by√D)² = (bx² + D·by²) + 2·bx·by√D
+        scaled.clone_from(&base_y);
+        scaled *= &base_y;
+        scaled *= d_constant;
+        base_y *= &base_x;
+        base_y <<= 1;
+        cross.clone_from(&base_x);
+        base_x *= &cross;
+        base_x += &scaled;
+        count!(bigint_multiplications += 4);
     }
 
+    #[cfg(feature = "tracing")]
+    tracing::debug!(x_bits = x.bits(), y_bits = y.bits(), "exponentiation done");
+
     Ok((x, y))
 }
 
@@ -187,6 +518,191 @@ pub fn pell_solution_k_unchecked(d_constant: u64, x1: &BigInt, y1: &BigInt, k: u
     pell_solution_k(d_constant, x1, y1, k).unwrap()
 }
 
+/// Compute only xₖ, the x-coordinate of the k-th Pell solution
+///
+/// Cheaper than `pell_solution_k` when y is not needed, such as for NSW numbers
+/// or other sequences read off one coordinate: two multiplications per bit of
+/// k instead of up to nine.
+///
+/// # Arguments
+///
+/// * `d_constant` - The coefficient D in the Pell equation
+/// * `x1` - The x-coordinate of the minimal solution
+/// * `y1` - The y-coordinate of the minimal solution
+/// * `k` - The index of the desired solution (k ≥ 1)
+///
+/// The ladder itself reads only x₁; D and y₁ are there to check that (x₁, y₁)
+/// is a solution, since any other x₁ yields numbers that solve nothing.
+///
+/// # Returns
+///
+/// A `Result` containing xₖ, or a `PellError` if k or the solution is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidK` if `k` is 0.
+/// Returns `PellError::InvalidSolution` unless x₁, y₁ > 0 and x₁² - D·y₁² = 1.
+///
+/// # Algorithm
+///
+/// The x-coordinates satisfy x₂ₙ = 2xₙ² - 1 and x₂ₙ₊₁ = 2xₙxₙ₊₁ - x₁, which
+/// involve neither D nor y. A ladder over the bits of k keeps the pair
+/// (xₙ, xₙ₊₁) and doubles n at every bit.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_min_solution, pell_solution_k, pell_solution_k_x};
+/// let (x1, y1) = pell_min_solution(991).unwrap();
+/// let x = pell_solution_k_x(991, &x1, &y1, 1000).unwrap();
+/// assert_eq!(x, pell_solution_k(991, &x1, &y1, 1000).unwrap().0);
+/// ```
+pub fn pell_solution_k_x(d_constant: u64, x1: &BigInt, y1: &BigInt, k: u64) -> Result<BigInt, PellError> {
+    if k == 0 {
+        return Err(PellError::InvalidK(k));
+    }
+    validate_solution(d_constant, x1, y1)?;
+    Ok(x_ladder(x1, k).0)
+}
+
+/// Compute only yₖ, the y-coordinate of the k-th Pell solution
+///
+/// Cheaper than `pell_solution_k` when x is not needed: the ladder of
+/// [`pell_solution_k_x`] yields xₖ and xₖ₊₁, and yₖ = (xₖ₊₁ - x₁·xₖ) / (D·y₁)
+/// follows with one more multiplication and an exact division.
+///
+/// # Arguments
+///
+/// * `d_constant` - The coefficient D in the Pell equation
+/// * `x1` - The x-coordinate of the minimal solution
+/// * `y1` - The y-coordinate of the minimal solution
+/// * `k` - The index of the desired solution (k ≥ 1)
+///
+/// # Returns
+///
+/// A `Result` containing yₖ, or a `PellError` if k or the solution is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidK` if `k` is 0.
+/// Returns `PellError::InvalidSolution` unless x₁, y₁ > 0 and x₁² - D·y₁² = 1.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{pell_min_solution, pell_solution_k, pell_solution_k_y};
+/// let (x1, y1) = pell_min_solution(991).unwrap();
+/// let y = pell_solution_k_y(991, &x1, &y1, 1000).unwrap();
+/// assert_eq!(y, pell_solution_k(991, &x1, &y1, 1000).unwrap().1);
+/// ```
+pub fn pell_solution_k_y(d_constant: u64, x1: &BigInt, y1: &BigInt, k: u64) -> Result<BigInt, PellError> {
+    if k == 0 {
+        return Err(PellError::InvalidK(k));
+    }
+    // The division below needs y₁ ≠ 0, and is only exact for a true solution
+    validate_solution(d_constant, x1, y1)?;
+    if k == 1 {
+        return Ok(y1.clone());
+    }
+    let (x_k, mut y_k) = x_ladder(x1, k);
+    // xₖ₊₁ = x₁·xₖ + D·y₁·yₖ
+    y_k -= x1 * &x_k;
+    y_k /= y1 * d_constant;
+    count!(bigint_multiplications += 2);
+    Ok(y_k)
+}
+
+/// The x-coordinates (xₖ, xₖ₊₁) of the k-th and (k+1)-th solutions
+fn x_ladder(x1: &BigInt, k: u64) -> (BigInt, BigInt) {
+    count!(exponentiations += 1);
+    let mut low = BigInt::one();
+    let mut high = x1.clone();
+
+    // Invariant: (low, high) = (xₙ, xₙ₊₁), where n is the bits of k seen so far
+    for bit in (0..u64::BITS - k.leading_zeros()).rev() {
+        let mut cross = &low * &high;
+        cross <<= 1;
+        cross -= x1;
+        if (k >> bit) & 1 == 1 {
+            high = &high * &high;
+            high <<= 1;
+            high -= 1u32;
+            low = cross;
+        } else {
+            low = &low * &low;
+            low <<= 1;
+            low -= 1u32;
+            high = cross;
+        }
+        count!(bigint_multiplications += 2);
+    }
+
+    (low, high)
+}
+
+/// The last `t` decimal digits of the k-th solution, xₖ and yₖ modulo 10ᵗ
+///
+/// Raises the matrix [[x₁, D·y₁], [y₁, x₁]] of the fundamental solution to
+/// the k-th power with every product reduced modulo 10ᵗ, so the numbers never
+/// grow past 2t digits. This prints the tail of solutions far too large to
+/// compute, such as k = 10¹⁸, in O(log k) small multiplications.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `k` - The index of the solution (k ≥ 1)
+/// * `t` - Number of trailing digits
+///
+/// # Returns
+///
+/// A `Result` containing `(xₖ mod 10ᵗ, yₖ mod 10ᵗ)`, each in `0..10ᵗ`, or a
+/// `PellError` if the input is invalid. Leading zeros of the digits are
+/// dropped, as with any number; pad to `t` places when printing.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidK` if `k` is 0, and the errors of
+/// `pell_min_solution` if D is invalid.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{last_digits, BigInt};
+/// // x₁₀ of D = 2 is 22619537
+/// assert_eq!(last_digits(2, 10, 4).unwrap().0, BigInt::from(9537));
+///
+/// // The tail of the 10¹⁸-th solution of D = 991
+/// let (x, _) = last_digits(991, 1_000_000_000_000_000_000, 50).unwrap();
+/// println!("x = …{x:0>50}");
+/// ```
+pub fn last_digits(d: u64, k: u64, t: u32) -> Result<(BigInt, BigInt), PellError> {
+    if k == 0 {
+        return Err(PellError::InvalidK(k));
+    }
+    let (x1, y1) = pell_min_solution(d)?;
+    let modulus = BigInt::from(10u32).pow(t);
+
+    let (mut x, mut y) = (BigInt::one() % &modulus, BigInt::zero());
+    let (mut base_x, mut base_y) = (x1 % &modulus, y1 % &modulus);
+    let mut exp = k;
+    while exp > 0 {
+        if exp % 2 == 1 {
+            (x, y) = (
+                (&x * &base_x + d * &y * &base_y) % &modulus,
+                (&x * &base_y + &y * &base_x) % &modulus,
+            );
+        }
+        exp /= 2;
+        if exp > 0 {
+            (base_x, base_y) = (
+                (&base_x * &base_x + d * &base_y * &base_y) % &modulus,
+                (2 * &base_x * &base_y) % &modulus,
+            );
+        }
+    }
+    Ok((x, y))
+}
+
 /// Verify that a given (x, y) pair is a solution to the Pell equation x² - D·y² = 1
 ///
 /// # Arguments
@@ -211,7 +727,65 @@ pub fn pell_solution_k_unchecked(d_constant: u64, x1: &BigInt, y1: &BigInt, k: u
 pub fn verify_pell_solution(d: u64, x: &BigInt, y: &BigInt) -> bool {
     let lhs = x * x;
     let rhs = BigInt::from(d) * y * y + BigInt::one();
-    lhs == rhs
+    let valid = lhs == rhs;
+    count!(verifications += 1, bigint_multiplications += 3);
+
+    #[cfg(feature = "tracing")]
+    tracing::trace!(d, x_bits = x.bits(), y_bits = y.bits(), valid, "verified solution");
+
+    valid
+}
+
+/// Check x² - D·y² = 1 modulo each of `moduli`, a fast probabilistic verification
+///
+/// Reducing x and y modulo a 64-bit number costs time linear in their size,
+/// while the exact check of `verify_pell_solution` squares them. For solutions
+/// with millions of digits this takes milliseconds instead of minutes.
+///
+/// A failure is definite: (x, y) is not a solution. A pass is not a proof, but
+/// if x² - D·y² ≠ 1 then only the few primes dividing the difference let it
+/// through, so a handful of randomly chosen 64-bit primes make a wrong pair
+/// passing practically impossible. With no moduli the check always passes.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `x` - The x-coordinate to verify
+/// * `y` - The y-coordinate to verify
+/// * `moduli` - The moduli to check, ideally distinct random 64-bit primes
+///
+/// # Returns
+///
+/// `false` if the equation fails modulo any of `moduli`, `true` otherwise
+///
+/// # Panics
+///
+/// Panics if any modulus is 0.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigInt;
+/// # use pell991::{pell_min_solution, pell_solution_k, verify_pell_solution_mod};
+/// const PRIMES: [u64; 3] = [18_446_744_073_709_551_557, 18_446_744_073_709_551_533, 18_446_744_073_709_551_521];
+///
+/// let (x1, y1) = pell_min_solution(991).unwrap();
+/// let (x, y) = pell_solution_k(991, &x1, &y1, 10_000).unwrap();
+/// assert!(verify_pell_solution_mod(991, &x, &y, &PRIMES));
+/// assert!(!verify_pell_solution_mod(991, &(x + 1), &y, &PRIMES));
+/// ```
+pub fn verify_pell_solution_mod(d: u64, x: &BigInt, y: &BigInt, moduli: &[u64]) -> bool {
+    count!(verifications += 1);
+
+    moduli.iter().all(|&modulus| {
+        // Squares do not depend on the sign, so the magnitudes suffice
+        let reduce = |n: &BigInt| (n.magnitude() % modulus).to_u64().expect("residue is below a u64 modulus") as u128;
+        let m = modulus as u128;
+        let (x, y) = (reduce(x), reduce(y));
+        let lhs = x * x % m;
+        let rhs = ((d as u128 % m) * y % m * y + 1) % m;
+        lhs == rhs
+    })
 }
 
 /// Generate multiple Pell solutions efficiently using iterative approach
@@ -219,6 +793,14 @@ pub fn verify_pell_solution(d: u64, x: &BigInt, y: &BigInt) -> bool {
 /// This is more efficient than calling `pell_solution_k` repeatedly as it
 /// uses the recurrence relation directly without binary exponentiation.
 ///
+/// # Complexity
+///
+/// After the fundamental solution, each further solution follows from the two
+/// before it by the second-order recurrence xₖ₊₁ = 2x₁·xₖ - xₖ₋₁ (and likewise
+/// for y): two BigInt multiplications per solution. Calling `pell_solution_k`
+/// for every k instead costs O(log k) multiplications per solution, on top of
+/// rebuilding everything below k each time.
+///
 /// # Arguments
 ///
 /// * `d` - The coefficient D in the Pell equation
@@ -240,33 +822,488 @@ pub fn pell_solutions(d: u64, count: usize) -> Result<Vec<(BigInt, BigInt)>, Pel
     if count == 0 {
         return Ok(Vec::new());
     }
-    
-    let (x1, y1) = pell_min_solution(d)?;
-    let mut solutions = Vec::with_capacity(count);
-    
-    // Add the first solution
-    solutions.push((x1.clone(), y1.clone()));
-    
-    if count == 1 {
-        return Ok(solutions);
-    }
-    
-    // Use iterative approach for better performance
-    // (x_k, y_k) = (x1 * x_{k-1} + d * y1 * y_{k-1}, x1 * y_{k-1} + y1 * x_{k-1})
-    let mut x_prev = x1.clone();
-    let mut y_prev = y1.clone();
-    let big_d = BigInt::from(d);
-    
-    for _ in 2..=count {
-        let x_next = &x1 * &x_prev + &big_d * &y1 * &y_prev;
-        let y_next = &x1 * &y_prev + &y1 * &x_prev;
-        
-        solutions.push((x_next.clone(), y_next.clone()));
-        x_prev = x_next;
-        y_prev = y_next;
+    let fundamental = Fundamental::solve(d)?;
+    let first = (fundamental.x1.clone(), fundamental.y1.clone());
+    Ok(consecutive_solutions(&fundamental, first, count))
+}
+
+/// Generate the solutions with indices `k_start..k_end` (end exclusive)
+///
+/// The first requested solution is reached with a single fast exponentiation,
+/// after which the remaining ones follow from the recurrence relation. This avoids
+/// regenerating all solutions below `k_start`.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `k_start` - Index of the first solution to generate (k_start ≥ 1)
+/// * `k_end` - Index one past the last solution to generate
+///
+/// # Returns
+///
+/// A `Result` containing the solutions (xₖ, yₖ) for k in `k_start..k_end`, which is
+/// empty when `k_end ≤ k_start`, or a `PellError` if the input is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidK` if `k_start` is 0, and the errors of
+/// `pell_min_solution` if D is invalid.
+/// Returns `PellError::Overflow` if the number of solutions does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigInt;
+/// # use pell991::pell_solutions_range;
+///
+/// let solutions = pell_solutions_range(2, 3, 5).unwrap();
+/// assert_eq!(solutions, vec![
+///     (BigInt::from(99), BigInt::from(70)),
+///     (BigInt::from(577), BigInt::from(408)),
+/// ]);
+/// ```
+pub fn pell_solutions_range(d: u64, k_start: u64, k_end: u64) -> Result<Vec<(BigInt, BigInt)>, PellError> {
+    if k_start == 0 {
+        return Err(PellError::InvalidK(k_start));
+    }
+
+    let fundamental = Fundamental::solve(d)?;
+    if k_end <= k_start {
+        return Ok(Vec::new());
+    }
+    let count = usize::try_from(k_end - k_start).map_err(|_| PellError::Overflow { bits: usize::BITS })?;
+
+    let first = pell_solution_k(d, &fundamental.x1, &fundamental.y1, k_start)?;
+    Ok(consecutive_solutions(&fundamental, first, count))
+}
+
+/// Collect `count` (≥ 1) consecutive solutions, starting from `first`
+///
+/// The second follows with the first-order recurrence, the rest with the
+/// second-order one from the two solutions already in the vector.
+fn consecutive_solutions(fundamental: &Fundamental, first: (BigInt, BigInt), count: usize) -> Vec<(BigInt, BigInt)> {
+    let mut solutions = Vec::with_capacity(count);
+    solutions.push(first);
+    if count > 1 {
+        let second = fundamental.successor(&solutions[0].0, &solutions[0].1);
+        solutions.push(second);
+    }
+    for k in 2..count {
+        let next = fundamental.successor_of_pair(&solutions[k - 2], &solutions[k - 1]);
+        solutions.push(next);
+    }
+    solutions
+}
+
+/// Visit the first `count` Pell solutions without collecting or cloning them
+///
+/// The callback receives `(k, &xₖ, &yₖ)` for k = 1..=count. Only the last two
+/// solutions are kept and their buffers are updated in place between calls, so
+/// pipelines that only inspect or hash each solution avoid the memory of
+/// `pell_solutions` and the owned pairs handed out by `PellSolutionIterator`.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `count` - Number of solutions to visit (starting from k=1)
+/// * `f` - Callback invoked with the index and references to each solution
+///
+/// # Returns
+///
+/// `Ok(())` once all solutions were visited, or a `PellError` if D is invalid.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::for_each_solution;
+/// let mut digits = Vec::new();
+/// for_each_solution(2, 4, |_k, x, _y| digits.push(x.to_string().len())).unwrap();
+/// assert_eq!(digits, vec![1, 2, 2, 3]); // 3, 17, 99, 577
+/// ```
+pub fn for_each_solution<F>(d: u64, count: usize, mut f: F) -> Result<(), PellError>
+where
+    F: FnMut(u64, &BigInt, &BigInt),
+{
+    if count == 0 {
+        return Ok(());
+    }
+
+    let fundamental = Fundamental::solve(d)?;
+    // (x₀, y₀) = (1, 0) precedes the fundamental solution
+    let mut previous = (BigInt::one(), BigInt::zero());
+    let mut current = (fundamental.x1.clone(), fundamental.y1.clone());
+
+    for k in 1..=count as u64 {
+        f(k, &current.0, &current.1);
+        if k == count as u64 {
+            break;
+        }
+        fundamental.advance_pair(&mut previous, &mut current);
+    }
+
+    Ok(())
+}
+
+/// How far [`search_solutions`] walks the solution sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchBound {
+    /// The solutions with index k ≤ the bound
+    MaxIndex(u64),
+    /// The solutions whose x has at most this many decimal digits
+    MaxDigits(u32),
+}
+
+/// Find the solutions within `bound` whose (x, y) satisfies `predicate`
+///
+/// The solutions are walked in order from k = 1 and handed to `predicate` by
+/// reference; only the matches are cloned. Iteration stops at the first
+/// solution outside the bound, which is exact since x grows with k.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `bound` - The last index, or the most digits of x, to look at
+/// * `predicate` - Called with each (xₖ, yₖ); true keeps the solution
+///
+/// # Returns
+///
+/// A `Result` containing the matching solutions as `PellSolution`s with their
+/// indices, in increasing k, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{search_solutions, SearchBound};
+/// // x ending in 7 among the first 20 solutions for D = 2
+/// let matches = search_solutions(2, SearchBound::MaxIndex(20), |x, _| x % 10u32 == 7u32.into()).unwrap();
+/// let indices: Vec<u64> = matches.iter().filter_map(|s| s.k).collect();
+/// assert_eq!(indices, [2, 4, 8, 10, 14, 16, 20]);
+///
+/// // Palindromic x with at most 10 digits
+/// let palindromes = search_solutions(2, SearchBound::MaxDigits(10), |x, _| {
+///     let digits = x.to_string();
+///     digits.chars().eq(digits.chars().rev())
+/// })
+/// .unwrap();
+/// assert_eq!(palindromes.iter().map(|s| s.x.to_string()).collect::<Vec<_>>(), ["3", "99"]);
+/// ```
+pub fn search_solutions<F>(d: u64, bound: SearchBound, mut predicate: F) -> Result<Vec<PellSolution>, PellError>
+where
+    F: FnMut(&BigInt, &BigInt) -> bool,
+{
+    let mut iter = PellSolutionIterator::new(d)?;
+    // 10^digits, computed once; unused for an index bound
+    let limit = match bound {
+        SearchBound::MaxDigits(digits) => BigInt::from(10u32).pow(digits),
+        SearchBound::MaxIndex(_) => BigInt::zero(),
+    };
+
+    let mut matches = Vec::new();
+    loop {
+        let k = iter.current_k();
+        let (x, y) = iter.current();
+        let beyond = match bound {
+            SearchBound::MaxIndex(max_k) => k > max_k,
+            SearchBound::MaxDigits(_) => *x >= limit,
+        };
+        if beyond {
+            return Ok(matches);
+        }
+        if predicate(x, y) {
+            matches.push(PellSolution::with_index(d, k, x.clone(), y.clone()));
+        }
+        iter.advance();
+    }
+}
+
+/// Count the solutions (xₖ, yₖ), k ≥ 1, with xₖ < `x_bound`
+///
+/// Since xₖ ≈ εᵏ/2 for the fundamental unit ε = x₁ + y₁√D, the count is
+/// about log(2·bound)/log ε. The guess from logarithms is then corrected by
+/// computing xₖ exactly at the boundary, so the answer is exact and costs a
+/// few O(log k) exponentiations however large the bound.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `x_bound` - The exclusive upper bound on x
+///
+/// # Returns
+///
+/// A `Result` containing the number of solutions, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{count_solutions_below, BigInt};
+/// // How many solutions of D = 991 have x < 10¹⁰⁰?
+/// assert_eq!(count_solutions_below(991, &BigInt::from(10).pow(100)).unwrap(), 3);
+/// // x = 3, 17, 99, 577 are below 577 + 1
+/// assert_eq!(count_solutions_below(2, &BigInt::from(578)).unwrap(), 4);
+/// ```
+pub fn count_solutions_below(d: u64, x_bound: &BigInt) -> Result<u64, PellError> {
+    let (x1, y1) = pell_min_solution(d)?;
+    if *x_bound <= x1 {
+        return Ok(0);
+    }
+
+    let log10_unit = log10_fundamental_unit(&x1);
+    let guess = (log10_bigint(x_bound) + core::f64::consts::LOG10_2) / log10_unit;
+    // The guess is positive, so truncating floors it; x₁ < bound, so k = 1 is always counted
+    let mut k = (guess as u64).max(1);
+    let x_k = |k| pell_solution_k_x(d, &x1, &y1, k).expect("k ≥ 1");
+    while k > 1 && x_k(k) >= *x_bound {
+        k -= 1;
+    }
+    while x_k(k + 1) < *x_bound {
+        k += 1;
+    }
+    Ok(k)
+}
+
+/// The solutions (xₖ, yₖ), k ≥ 1, with xₖ < `x_bound`, in increasing order
+///
+/// Counts them with [`count_solutions_below`] and then generates them with the
+/// recurrence, like [`pell_solutions`].
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `x_bound` - The exclusive upper bound on x
+///
+/// # Returns
+///
+/// A `Result` containing the solutions, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{solutions_below, BigInt};
+/// let xs: Vec<BigInt> = solutions_below(2, &BigInt::from(1000)).unwrap().into_iter().map(|(x, _)| x).collect();
+/// assert_eq!(xs, [3, 17, 99, 577].map(BigInt::from));
+/// ```
+pub fn solutions_below(d: u64, x_bound: &BigInt) -> Result<Vec<(BigInt, BigInt)>, PellError> {
+    let count = count_solutions_below(d, x_bound)?;
+    pell_solutions(d, usize::try_from(count).expect("the solutions fit in memory"))
+}
+
+/// The solution whose x is closest to `target`
+///
+/// [`count_solutions_below`] locates the target between two consecutive
+/// solutions, xₖ < target ≤ xₖ₊₁, from logarithms; the two neighbors are
+/// then compared exactly. A target exactly halfway picks the smaller k, and
+/// any target up to x₁ picks k = 1.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation
+/// * `target` - The value to approach
+///
+/// # Returns
+///
+/// A `Result` containing the nearest solution as a `PellSolution` with its
+/// index k, or a `PellError` if D is invalid.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{solution_near, BigInt};
+/// // x = 99 and x = 577 for D = 2: 300 is closer to 99, 400 to 577
+/// assert_eq!(solution_near(2, &BigInt::from(300)).unwrap().k, Some(3));
+/// assert_eq!(solution_near(2, &BigInt::from(400)).unwrap().x, BigInt::from(577));
+///
+/// // The solution of D = 991 nearest to 10¹⁰⁰⁰
+/// let near = solution_near(991, &BigInt::from(10).pow(1000)).unwrap();
+/// assert_eq!(near.k, Some(33));
+/// ```
+pub fn solution_near(d: u64, target: &BigInt) -> Result<PellSolution, PellError> {
+    let below = count_solutions_below(d, target)?;
+    let (x1, y1) = pell_min_solution(d)?;
+    let k = match below {
+        0 => 1,
+        k => {
+            let lower = pell_solution_k_x(d, &x1, &y1, k)?;
+            let upper = pell_solution_k_x(d, &x1, &y1, k + 1)?;
+            if target - &lower <= &upper - target { k } else { k + 1 }
+        }
+    };
+    let (x, y) = pell_solution_k(d, &x1, &y1, k)?;
+    Ok(PellSolution::with_index(d, k, x, y))
+}
+
+/// The fundamental solution (x₁, y₁) of one D, shared by every code path that
+/// walks the solution sequence
+#[derive(Debug, Clone)]
+struct Fundamental {
+    x1: BigInt,
+    y1: BigInt,
+    /// D·y₁, precomputed for the recurrence
+    d_y1: BigInt,
+    /// 2x₁, precomputed for the second-order recurrence
+    two_x1: BigInt,
+}
+
+impl Fundamental {
+    fn new(d: u64, x1: BigInt, y1: BigInt) -> Self {
+        let d_y1 = BigInt::from(d) * &y1;
+        let two_x1 = &x1 << 1;
+        Fundamental { x1, y1, d_y1, two_x1 }
+    }
+
+    /// Solve for the fundamental solution of D
+    fn solve(d: u64) -> Result<Self, PellError> {
+        let (x1, y1) = pell_min_solution(d)?;
+        Ok(Fundamental::new(d, x1, y1))
+    }
+
+    /// Step (xₖ, yₖ) to (xₖ₊₁, yₖ₊₁) in place
+    ///
+    /// (x, y) ← (x₁·x + D·y₁·y, x₁·y + y₁·x), reusing the existing buffers.
+    fn advance(&self, x: &mut BigInt, y: &mut BigInt) {
+        let x_term = &self.d_y1 * &*y;
+        let y_term = &self.y1 * &*x;
+        *x *= &self.x1;
+        *x += x_term;
+        *y *= &self.x1;
+        *y += y_term;
+        count!(recurrence_steps += 1, bigint_multiplications += 4);
+    }
+
+    /// The solution after (xₖ, yₖ), in fresh buffers
+    ///
+    /// For callers that hand the current pair out by value: replacing it with
+    /// the result moves it out instead of cloning it.
+    fn successor(&self, x: &BigInt, y: &BigInt) -> (BigInt, BigInt) {
+        let mut x_next = &self.x1 * x;
+        x_next += &self.d_y1 * y;
+        let mut y_next = &self.x1 * y;
+        y_next += &self.y1 * x;
+        count!(recurrence_steps += 1, bigint_multiplications += 4);
+        (x_next, y_next)
+    }
+
+    /// The solution after `current`, given also the solution before it
+    ///
+    /// Uses the second-order recurrence xₖ₊₁ = 2x₁·xₖ - xₖ₋₁ (and likewise for
+    /// y), which costs two multiplications instead of the four of `successor`.
+    fn successor_of_pair(&self, previous: &(BigInt, BigInt), current: &(BigInt, BigInt)) -> (BigInt, BigInt) {
+        let mut x_next = &self.two_x1 * &current.0;
+        x_next -= &previous.0;
+        let mut y_next = &self.two_x1 * &current.1;
+        y_next -= &previous.1;
+        count!(recurrence_steps += 1, bigint_multiplications += 2);
+        (x_next, y_next)
+    }
+
+    /// Step (xₖ₋₁, yₖ₋₁), (xₖ, yₖ) to (xₖ, yₖ), (xₖ₊₁, yₖ₊₁) in place
+    ///
+    /// The second-order recurrence of `successor_of_pair`, written into the
+    /// buffers of the older solution, which are then swapped to the front.
+    fn advance_pair(&self, previous: &mut (BigInt, BigInt), current: &mut (BigInt, BigInt)) {
+        // xₖ₋₁ - 2x₁·xₖ = -xₖ₊₁, negated without reallocating
+        previous.0 -= &self.two_x1 * &current.0;
+        previous.0 = -mem::take(&mut previous.0);
+        previous.1 -= &self.two_x1 * &current.1;
+        previous.1 = -mem::take(&mut previous.1);
+        count!(recurrence_steps += 1, bigint_multiplications += 2);
+        mem::swap(previous, current);
+    }
+}
+
+/// Lazily solve the Pell equation for every D in a range, skipping perfect squares
+///
+/// Yields `(d, result)` pairs in the order of `ds`, solving each D only when its
+/// item is requested. D values that are perfect squares (including 0 and 1)
+/// have no Pell equation and are skipped.
+///
+/// # Arguments
+///
+/// * `ds` - The D values to solve, typically a range such as `2..=n`
+///
+/// # Returns
+///
+/// An iterator of `(d, Result<(x, y), PellError>)` with the minimal solution for each D
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::solve_range;
+/// let ds: Vec<u64> = solve_range(1..=10).map(|(d, _)| d).collect();
+/// assert_eq!(ds, vec![2, 3, 5, 6, 7, 8, 10]);
+///
+/// let largest = solve_range(2..=100)
+///     .filter_map(|(d, result)| result.ok().map(|(x, _)| (x, d)))
+///     .max()
+///     .unwrap();
+/// assert_eq!(largest.1, 61);
+/// ```
+pub fn solve_range<I>(ds: I) -> impl Iterator<Item = (u64, Result<(BigInt, BigInt), PellError>)>
+where
+    I: IntoIterator<Item = u64>,
+{
+    ds.into_iter()
+        .filter(|&d| !is_square_u64(d))
+        .map(|d| (d, pell_min_solution(d)))
+}
+
+/// Solve the Pell equation for a set of D values, keyed by D
+///
+/// Unlike `solve_range`, every D gets an entry: perfect squares and other
+/// invalid values map to their `PellError`. Repeated D values are solved once.
+/// With the `rayon` feature the distinct D values are solved concurrently.
+///
+/// # Arguments
+///
+/// * `ds` - The D values to solve, in any order
+///
+/// # Returns
+///
+/// A map from each D in `ds` to its minimal solution `(x, y)` or error
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{solve_many, PellError};
+/// let results = solve_many(&[991, 2, 9, 2]);
+/// assert_eq!(results.len(), 3);
+/// assert!(results[&991].is_ok());
+/// assert_eq!(results[&9], Err(PellError::PerfectSquare(9)));
+/// ```
+#[cfg(feature = "std")]
+pub fn solve_many(ds: &[u64]) -> std::collections::HashMap<u64, Result<(BigInt, BigInt), PellError>> {
+    let mut distinct = ds.to_vec();
+    distinct.sort_unstable();
+    distinct.dedup();
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        distinct.into_par_iter().map(|d| (d, pell_min_solution(d))).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        distinct.into_iter().map(|d| (d, pell_min_solution(d))).collect()
     }
-    
-    Ok(solutions)
 }
 
 /// Iterator for generating Pell equation solutions on-demand
@@ -282,13 +1319,19 @@ pub fn pell_solutions(d: u64, count: usize) -> Result<Vec<(BigInt, BigInt)>, Pel
 /// let first_three: Vec<_> = iter.take(3).collect();
 /// assert_eq!(first_three.len(), 3);
 /// ```
+///
+/// With the `serde` feature the iterator serializes as its position — D, the
+/// current index k and the current pair — so iteration can be resumed in another
+/// process. Deserializing solves for the fundamental solution again and rejects
+/// a pair that is not the k-th solution.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "IteratorState", try_from = "IteratorState"))]
 pub struct PellSolutionIterator {
     d: u64,
-    x1: BigInt,
-    y1: BigInt,
+    fundamental: Fundamental,
     current_x: BigInt,
     current_y: BigInt,
-    big_d: BigInt,
     k: u64,
 }
 
@@ -311,17 +1354,83 @@ impl PellSolutionIterator {
     /// ```
     pub fn new(d: u64) -> Result<Self, PellError> {
         let (x1, y1) = pell_min_solution(d)?;
-        let big_d = BigInt::from(d);
-        
-        Ok(PellSolutionIterator {
+        Ok(Self::with_fundamental(d, x1, y1))
+    }
+
+    /// Create an iterator whose first item is the k-th solution
+    ///
+    /// The starting solution is computed once by fast exponentiation, after which
+    /// iteration continues with the recurrence, so paging through solutions does
+    /// not have to restart at k = 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `d` - The coefficient D in the Pell equation
+    /// * `k` - Index of the first solution to yield (k ≥ 1)
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the iterator, or a `PellError` if D or k is invalid.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PellError::InvalidK` if `k` is 0, and the errors of
+    /// `pell_min_solution` if D is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint::BigInt;
+    /// # use pell991::PellSolutionIterator;
+    /// let mut iter = PellSolutionIterator::start_at(2, 3).unwrap();
+    /// assert_eq!(iter.current_k(), 3);
+    /// assert_eq!(iter.next(), Some((BigInt::from(99), BigInt::from(70))));
+    /// assert_eq!(iter.next(), Some((BigInt::from(577), BigInt::from(408))));
+    /// ```
+    pub fn start_at(d: u64, k: u64) -> Result<Self, PellError> {
+        if k == 0 {
+            return Err(PellError::InvalidK(k));
+        }
+        let mut iter = Self::new(d)?;
+        iter.skip_to(k)?;
+        Ok(iter)
+    }
+
+    /// Create an iterator from an already computed fundamental solution
+    ///
+    /// Skips solving the Pell equation again when the caller already has (x₁, y₁),
+    /// for example from `pell_min_solution`, a [`SolutionCache`](crate::SolutionCache)
+    /// or a saved result. Construction costs one multiplication.
+    ///
+    /// The pair is trusted as given, in every build: if it is not the minimal
+    /// solution for D, the iterator yields every power of it instead, which are
+    /// still solutions as long as the pair is one. Check pairs from untrusted
+    /// sources with [`verify_pell_solution`] first.
+    ///
+    /// # Arguments
+    ///
+    /// * `d` - The coefficient D in the Pell equation
+    /// * `x1` - The x-coordinate of the minimal solution
+    /// * `y1` - The y-coordinate of the minimal solution
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint::BigInt;
+    /// # use pell991::{pell_min_solution, PellSolutionIterator};
+    /// let (x1, y1) = pell_min_solution(2).unwrap();
+    /// let mut iter = PellSolutionIterator::with_fundamental(2, x1, y1);
+    /// iter.next();
+    /// assert_eq!(iter.next(), Some((BigInt::from(17), BigInt::from(12))));
+    /// ```
+    pub fn with_fundamental(d: u64, x1: BigInt, y1: BigInt) -> Self {
+        PellSolutionIterator {
             d,
             current_x: x1.clone(),
             current_y: y1.clone(),
-            x1,
-            y1,
-            big_d,
+            fundamental: Fundamental::new(d, x1, y1),
             k: 1,
-        })
+        }
     }
     
     /// Get the current k value (1-indexed)
@@ -334,29 +1443,285 @@ impl PellSolutionIterator {
         self.d
     }
     
+    /// Borrow the solution that the next call to `next()` would return
+    ///
+    /// Together with [`advance`](Self::advance) this forms a lending iteration
+    /// mode: read-only consumers can inspect each solution without cloning
+    /// two large BigInts per step.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::PellSolutionIterator;
+    /// let mut iter = PellSolutionIterator::new(991).unwrap();
+    /// let mut total_bits = 0;
+    /// for _ in 0..100 {
+    ///     let (x, y) = iter.current();
+    ///     total_bits += x.bits() + y.bits();
+    ///     iter.advance();
+    /// }
+    /// assert_eq!(iter.current_k(), 101);
+    /// # assert!(total_bits > 0);
+    /// ```
+    pub fn current(&self) -> (&BigInt, &BigInt) {
+        (&self.current_x, &self.current_y)
+    }
+
+    /// Move to the next solution without returning the current one
+    pub fn advance(&mut self) {
+        self.fundamental.advance(&mut self.current_x, &mut self.current_y);
+        self.k += 1;
+    }
+
     /// Reset the iterator to the beginning
     pub fn reset(&mut self) {
-        self.current_x = self.x1.clone();
-        self.current_y = self.y1.clone();
+        self.current_x = self.fundamental.x1.clone();
+        self.current_y = self.fundamental.y1.clone();
         self.k = 1;
     }
+
+    /// Turn this iterator into one yielding [`PellSolution`] values
+    ///
+    /// Each item carries D and its index k alongside (x, y), so collected
+    /// solutions keep their context.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::PellSolutionIterator;
+    /// let solutions: Vec<_> = PellSolutionIterator::new(2).unwrap().into_solutions().take(3).collect();
+    /// assert_eq!(solutions[2].to_string(), "x=99, y=70, d=2, k=3");
+    /// assert!(solutions.iter().all(|s| s.verify()));
+    /// ```
+    pub fn into_solutions(self) -> Solutions {
+        Solutions { inner: self }
+    }
+
+    /// Yield solutions while x has at most `max_digits` decimal digits
+    ///
+    /// Since xₖ > yₖ and both grow with k, iteration stops for good at the first
+    /// solution over the budget. The bound 10^max_digits is computed once, so each
+    /// step costs a single comparison instead of a decimal conversion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pell991::PellSolutionIterator;
+    /// // 3, 17, 99, 577, 3363: four solutions have x below 1000
+    /// let small: Vec<_> = PellSolutionIterator::new(2).unwrap().take_while_digits(3).collect();
+    /// assert_eq!(small.len(), 4);
+    /// ```
+    pub fn take_while_digits(self, max_digits: u32) -> TakeWhileDigits {
+        TakeWhileDigits {
+            inner: self,
+            bound: Some(BigInt::from(10u32).pow(max_digits)),
+        }
+    }
+
+    /// Yield consecutive solutions ((xₖ, yₖ), (xₖ₊₁, yₖ₊₁)) as overlapping pairs
+    ///
+    /// Each solution is computed once and appears in two pairs, which suits
+    /// growth-ratio and identity checks between neighbours.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint::BigInt;
+    /// # use pell991::PellSolutionIterator;
+    /// let mut pairs = PellSolutionIterator::new(2).unwrap().pairs();
+    /// let ((x1, _), (x2, _)) = pairs.next().unwrap();
+    /// assert_eq!((x1, x2), (BigInt::from(3), BigInt::from(17)));
+    ///
+    /// // Neighbours satisfy xₖ₊₁ = x₁·xₖ + D·y₁·yₖ
+    /// for ((x, y), (next_x, _)) in pairs.take(5) {
+    ///     assert_eq!(next_x, 3 * &x + 2 * 2 * &y);
+    /// }
+    /// ```
+    pub fn pairs(self) -> Pairs {
+        Pairs { inner: self, previous: None }
+    }
+
+    /// Reposition the iterator so that the next item is the k-th solution
+    ///
+    /// Takes O(log k) multiplications regardless of the current position, so
+    /// iteration can resume from a saved index. Moving backwards is allowed.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - Index of the next solution to yield (k ≥ 1)
+    ///
+    /// # Errors
+    ///
+    /// Returns `PellError::InvalidK` if `k` is 0; the iterator is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num_bigint::BigInt;
+    /// # use pell991::PellSolutionIterator;
+    /// let mut iter = PellSolutionIterator::new(2).unwrap();
+    /// iter.skip_to(4).unwrap();
+    /// assert_eq!(iter.next(), Some((BigInt::from(577), BigInt::from(408))));
+    ///
+    /// iter.skip_to(2).unwrap();
+    /// assert_eq!(iter.next(), Some((BigInt::from(17), BigInt::from(12))));
+    /// ```
+    pub fn skip_to(&mut self, k: u64) -> Result<(), PellError> {
+        let (x, y) = pell_solution_k(self.d, &self.fundamental.x1, &self.fundamental.y1, k)?;
+        self.current_x = x;
+        self.current_y = y;
+        self.k = k;
+        Ok(())
+    }
 }
 
 impl Iterator for PellSolutionIterator {
     type Item = (BigInt, BigInt);
     
     fn next(&mut self) -> Option<Self::Item> {
-        let result = (self.current_x.clone(), self.current_y.clone());
-        
-        // Compute next solution using recurrence relation
-        // (x_{k+1}, y_{k+1}) = (x1 * x_k + d * y1 * y_k, x1 * y_k + y1 * x_k)
-        let next_x = &self.x1 * &self.current_x + &self.big_d * &self.y1 * &self.current_y;
-        let next_y = &self.x1 * &self.current_y + &self.y1 * &self.current_x;
-        
-        self.current_x = next_x;
-        self.current_y = next_y;
+        let (x_next, y_next) = self.fundamental.successor(&self.current_x, &self.current_y);
         self.k += 1;
-        
-        Some(result)
+        Some((mem::replace(&mut self.current_x, x_next), mem::replace(&mut self.current_y, y_next)))
     }
-}
\ No newline at end of file
+
+    /// The sequence of solutions is infinite
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for PellSolutionIterator {}
+
+/// Serialized position of a [`PellSolutionIterator`]
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct IteratorState {
+    d: u64,
+    k: u64,
+    #[serde(with = "crate::serde_bigint")]
+    x: BigInt,
+    #[serde(with = "crate::serde_bigint")]
+    y: BigInt,
+}
+
+#[cfg(feature = "serde")]
+impl From<PellSolutionIterator> for IteratorState {
+    fn from(iter: PellSolutionIterator) -> Self {
+        IteratorState { d: iter.d, k: iter.k, x: iter.current_x, y: iter.current_y }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<IteratorState> for PellSolutionIterator {
+    type Error = PellError;
+
+    fn try_from(state: IteratorState) -> Result<Self, Self::Error> {
+        if state.k == 0 {
+            return Err(PellError::InvalidK(state.k));
+        }
+        let mut iter = PellSolutionIterator::new(state.d)?;
+        // Positive solutions are the powers of the fundamental one and each xₖ is
+        // at least 3.5 times the previous, so the size of x pins down its index
+        let log10_unit = log10_fundamental_unit(&iter.fundamental.x1);
+        let (log10_x, _) = crate::estimate::log10_solution_k(state.d, log10_unit, state.k);
+        let misplaced = (log10_bigint(&state.x) - log10_x).abs() >= 0.25;
+        if misplaced || validate_solution(state.d, &state.x, &state.y).is_err() {
+            return Err(PellError::Parse(alloc::format!(
+                "({}, {}) is not solution k = {} for D = {}",
+                state.x, state.y, state.k, state.d
+            )));
+        }
+        iter.current_x = state.x;
+        iter.current_y = state.y;
+        iter.k = state.k;
+        Ok(iter)
+    }
+}
+
+/// Iterator over the solutions within a digit budget, created by
+/// [`PellSolutionIterator::take_while_digits`]
+#[derive(Debug, Clone)]
+pub struct TakeWhileDigits {
+    inner: PellSolutionIterator,
+    /// 10^max_digits, or `None` once the budget has been exceeded
+    bound: Option<BigInt>,
+}
+
+impl Iterator for TakeWhileDigits {
+    type Item = (BigInt, BigInt);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bound = self.bound.as_ref()?;
+        if self.inner.current_x >= *bound {
+            self.bound = None;
+            return None;
+        }
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.bound {
+            Some(_) => (0, None),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl FusedIterator for TakeWhileDigits {}
+
+/// Iterator over consecutive solution pairs, created by [`PellSolutionIterator::pairs`]
+#[derive(Debug, Clone)]
+pub struct Pairs {
+    inner: PellSolutionIterator,
+    previous: Option<(BigInt, BigInt)>,
+}
+
+impl Iterator for Pairs {
+    type Item = ((BigInt, BigInt), (BigInt, BigInt));
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let previous = match self.previous.take() {
+            Some(previous) => previous,
+            None => self.inner.next()?,
+        };
+        let current = self.inner.next()?;
+        self.previous = Some(current.clone());
+        Some((previous, current))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl FusedIterator for Pairs {}
+
+/// Iterator over [`PellSolution`] values, created by
+/// [`PellSolutionIterator::into_solutions`]
+#[derive(Debug, Clone)]
+pub struct Solutions {
+    inner: PellSolutionIterator,
+}
+
+impl Solutions {
+    /// The underlying iterator, for repositioning with `skip_to` or `reset`
+    pub fn inner_mut(&mut self) -> &mut PellSolutionIterator {
+        &mut self.inner
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = PellSolution;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let k = self.inner.k;
+        let (x, y) = self.inner.next()?;
+        Some(PellSolution::with_index(self.inner.d, k, x, y))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl FusedIterator for Solutions {}
\ No newline at end of file
@@ -0,0 +1,282 @@
+//! SQLite-backed cache of fundamental solutions
+//!
+//! A [`SqliteCache`] keeps one row per D with the period of √D, the fundamental
+//! solution and the regulator, in a database file that can be shared between
+//! runs and copied between machines. Unlike the append-only file of the
+//! `disk-cache` feature, the table can also be queried directly:
+//!
+//! ```text
+//! sqlite3 solutions.db "SELECT d, period, regulator FROM solutions ORDER BY regulator DESC LIMIT 10"
+//! ```
+//!
+//! Installed with [`enable_sqlite_cache`], the database is consulted by
+//! `pell_min_solution` and the other continued fraction solvers, after the
+//! in-memory cache of [`enable_solution_cache`](crate::enable_solution_cache).
+
+use std::fmt;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use num_bigint::BigInt;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::analysis::regulator;
+use crate::error::PellError;
+use crate::solver::{is_fundamental, pell_min_solution_with_period};
+
+/// A fundamental solution together with the period of the continued fraction of √D
+type Entry = ((BigInt, BigInt), u64);
+
+/// The process-wide database, `None` until enabled
+static GLOBAL_DATABASE: Mutex<Option<SqliteCache>> = Mutex::new(None);
+
+/// How long to wait for another process holding a write lock on the database
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// D is stored as the bits of an `i64`, since SQLite integers are signed; D
+/// values above `i64::MAX` appear negative in the table
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS solutions (
+    d INTEGER PRIMARY KEY,
+    period INTEGER NOT NULL,
+    x TEXT NOT NULL,
+    y TEXT NOT NULL,
+    regulator REAL NOT NULL
+)";
+
+/// One row of the cache
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedSolution {
+    /// The coefficient D
+    pub d: u64,
+    /// Length of the period of the continued fraction of √D
+    pub period: u64,
+    /// x of the fundamental solution
+    pub x: BigInt,
+    /// y of the fundamental solution
+    pub y: BigInt,
+    /// The regulator ln ε of Z[√D], as in [`DReport`](crate::DReport)
+    pub regulator: f64,
+}
+
+/// Errors from [`SqliteCache::get_or_solve`]
+#[derive(Debug)]
+pub enum SqliteCacheError {
+    /// The database could not be read or written
+    Sqlite(rusqlite::Error),
+    /// D could not be solved
+    Pell(PellError),
+}
+
+impl fmt::Display for SqliteCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqliteCacheError::Sqlite(err) => write!(f, "solution database error: {err}"),
+            SqliteCacheError::Pell(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SqliteCacheError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SqliteCacheError::Sqlite(err) => Some(err),
+            SqliteCacheError::Pell(err) => Some(err),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for SqliteCacheError {
+    fn from(err: rusqlite::Error) -> Self {
+        SqliteCacheError::Sqlite(err)
+    }
+}
+
+impl From<PellError> for SqliteCacheError {
+    fn from(err: PellError) -> Self {
+        SqliteCacheError::Pell(err)
+    }
+}
+
+/// A cache of fundamental solutions in an SQLite database
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::SqliteCache;
+/// let path = std::env::temp_dir().join(format!("pell991-doc-{}.db", std::process::id()));
+///
+/// let cache = SqliteCache::open(&path).unwrap();
+/// let row = cache.get_or_solve(991).unwrap();
+/// assert_eq!(row.period, 60);
+/// drop(cache);
+///
+/// // Another run (or machine, given the file) finds the row
+/// let reopened = SqliteCache::open(&path).unwrap();
+/// assert_eq!(reopened.get(991).unwrap(), Some(row));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SqliteCache {
+    connection: Connection,
+}
+
+impl SqliteCache {
+    /// Open the database at `path`, creating it and the `solutions` table if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns a `rusqlite::Error` if the file cannot be opened or is not an
+    /// SQLite database.
+    pub fn open<P: AsRef<Path>>(path: P) -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Open a private in-memory database, mostly useful for tests
+    ///
+    /// # Errors
+    ///
+    /// Returns a `rusqlite::Error` if SQLite cannot allocate the database.
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> rusqlite::Result<Self> {
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        connection.execute(SCHEMA, [])?;
+        Ok(SqliteCache { connection })
+    }
+
+    /// Number of D values in the database
+    ///
+    /// # Errors
+    ///
+    /// Returns a `rusqlite::Error` if the query fails.
+    pub fn len(&self) -> rusqlite::Result<u64> {
+        self.connection
+            .query_row("SELECT COUNT(*) FROM solutions", [], |row| row.get::<_, i64>(0))
+            .map(|count| count as u64)
+    }
+
+    /// Whether the database holds no solutions
+    ///
+    /// # Errors
+    ///
+    /// Returns a `rusqlite::Error` if the query fails.
+    pub fn is_empty(&self) -> rusqlite::Result<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Look up the row for `d`
+    ///
+    /// A row whose pair is not the fundamental solution for its D, or whose
+    /// period is not that of √D, say from a damaged, hand-edited or foreign
+    /// database, is treated as missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `rusqlite::Error` if the query fails.
+    pub fn get(&self, d: u64) -> rusqlite::Result<Option<CachedSolution>> {
+        let row = self
+            .connection
+            .query_row(
+                "SELECT period, x, y, regulator FROM solutions WHERE d = ?1",
+                params![d as i64],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get(3)?)),
+            )
+            .optional()?;
+
+        Ok(row.and_then(|(period, x, y, regulator)| {
+            let solution: (BigInt, BigInt) = (x.parse().ok()?, y.parse().ok()?);
+            let period = u64::try_from(period).ok()?;
+            if !is_fundamental(d, &solution, period) {
+                return None;
+            }
+            let (x, y) = solution;
+            Some(CachedSolution { d, period, x, y, regulator })
+        }))
+    }
+
+    /// Store the fundamental solution and period for `d`, replacing any row for it
+    ///
+    /// The regulator is computed from the solution.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `rusqlite::Error` if the row cannot be written.
+    pub fn insert(&self, d: u64, solution: &(BigInt, BigInt), period: u64) -> rusqlite::Result<()> {
+        let (x, y) = solution;
+        self.connection.execute(
+            "INSERT OR REPLACE INTO solutions (d, period, x, y, regulator) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![d as i64, period as i64, x.to_string(), y.to_string(), regulator(x, period)],
+        )?;
+        Ok(())
+    }
+
+    /// Return the row for `d`, solving and storing it on a miss
+    ///
+    /// # Errors
+    ///
+    /// Returns `SqliteCacheError::Pell` if `d` is not a valid Pell D, or
+    /// `SqliteCacheError::Sqlite` if the database cannot be read or written.
+    pub fn get_or_solve(&self, d: u64) -> Result<CachedSolution, SqliteCacheError> {
+        if let Some(row) = self.get(d)? {
+            return Ok(row);
+        }
+        let (solution, period) = pell_min_solution_with_period(d)?;
+        self.insert(d, &solution, period)?;
+        let (x, y) = solution;
+        Ok(CachedSolution { d, period, regulator: regulator(&x, period), x, y })
+    }
+}
+
+/// Consult the database at `path` from the solvers, creating it if needed
+///
+/// Replaces any previously enabled database. Database errors during solving
+/// are ignored: a failed lookup solves as if the cache were empty, and a
+/// failed write only loses the chance to reuse the solution.
+///
+/// # Errors
+///
+/// Returns a `rusqlite::Error` if the database cannot be opened.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::{disable_sqlite_cache, enable_sqlite_cache, pell_min_solution};
+/// let path = std::env::temp_dir().join(format!("pell991-doc-global-{}.db", std::process::id()));
+/// enable_sqlite_cache(&path).unwrap();
+/// pell_min_solution(991).unwrap(); // solved and stored
+/// pell_min_solution(991).unwrap(); // read back from the database
+/// disable_sqlite_cache();
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn enable_sqlite_cache<P: AsRef<Path>>(path: P) -> rusqlite::Result<()> {
+    let cache = SqliteCache::open(path)?;
+    *lock_global() = Some(cache);
+    Ok(())
+}
+
+/// Stop consulting the process-wide database and close it
+pub fn disable_sqlite_cache() {
+    *lock_global() = None;
+}
+
+/// Look up `d` in the process-wide database, if enabled
+pub(crate) fn global_get(d: u64) -> Option<Entry> {
+    let row = lock_global().as_ref()?.get(d).ok()??;
+    Some(((row.x, row.y), row.period))
+}
+
+/// Store a freshly computed solution in the process-wide database, if enabled
+pub(crate) fn global_insert(d: u64, solution: &(BigInt, BigInt), period: u64) {
+    if let Some(cache) = lock_global().as_ref() {
+        // Best effort: the solution is returned either way
+        let _ = cache.insert(d, solution, period);
+    }
+}
+
+/// Lock the process-wide database, recovering from a poisoned lock
+fn lock_global() -> MutexGuard<'static, Option<SqliteCache>> {
+    GLOBAL_DATABASE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,125 @@
+//! Consecutive smooth numbers by Størmer's theorem
+//!
+//! For a finite set of primes P there are only finitely many pairs (n, n + 1)
+//! of consecutive P-smooth numbers (numbers with no prime factor outside P).
+//! Størmer reduced finding them all to Pell equations, in the form refined by
+//! Lehmer: each pair comes from one of the first few solutions of
+//! x² - 2q·y² = 1, with q a squarefree product of primes from P.
+
+use alloc::format;
+use alloc::vec::Vec;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use crate::error::PellError;
+use crate::solver::pell_solutions;
+use crate::utils::is_prime;
+
+/// Find all pairs of consecutive P-smooth numbers
+///
+/// # Arguments
+///
+/// * `primes` - The prime set P, in any order; duplicates are ignored
+///
+/// # Returns
+///
+/// A `Result` containing every pair `(n, n + 1)` with both members P-smooth,
+/// in increasing order, or a `PellError`.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidPrimeSet` if a value in `primes` is not prime, or
+/// if the product of all of them (times 2) does not fit in a `u64`.
+///
+/// # Algorithm
+///
+/// For a pair, x = 2n + 1 solves x² - 2q·y² = 1 where q is the squarefree part
+/// of 2n(n + 1), so q is a product of primes from P and y is P-smooth. By
+/// Lehmer's refinement of Størmer's theorem, such a solution is among the first
+/// max(3, (p + 1)/2) solutions of that Pell equation, where p is the largest
+/// prime of P. So the first solutions for each of the 2^|P| values of q are
+/// checked for smoothness.
+///
+/// # Performance
+///
+/// Solves 2^|P| Pell equations with D up to twice the product of P, so large
+/// prime sets get expensive quickly: the first ten primes mean 1024 equations
+/// with D in the billions.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::smooth_consecutive_pairs;
+/// let pairs: Vec<String> = smooth_consecutive_pairs(&[2, 3])
+///     .unwrap()
+///     .iter()
+///     .map(|(n, m)| format!("{n},{m}"))
+///     .collect();
+/// assert_eq!(pairs, ["1,2", "2,3", "3,4", "8,9"]);
+///
+/// // 4374 = 2·3⁷ and 4375 = 5⁴·7 are the largest 7-smooth pair
+/// let pairs = smooth_consecutive_pairs(&[2, 3, 5, 7]).unwrap();
+/// assert_eq!(pairs.len(), 23);
+/// assert_eq!(pairs.last().unwrap().0.to_string(), "4374");
+/// ```
+pub fn smooth_consecutive_pairs(primes: &[u64]) -> Result<Vec<(BigInt, BigInt)>, PellError> {
+    let mut primes = primes.to_vec();
+    primes.sort_unstable();
+    primes.dedup();
+    if let Some(&p) = primes.iter().find(|&&p| !is_prime(p)) {
+        return Err(PellError::InvalidPrimeSet(format!("{p} is not prime")));
+    }
+    primes
+        .iter()
+        .try_fold(2u64, |product, &p| product.checked_mul(p))
+        .ok_or_else(|| PellError::InvalidPrimeSet(format!("the product of {primes:?} overflows u64")))?;
+    // Every pair has an even member
+    if !primes.contains(&2) {
+        return Ok(Vec::new());
+    }
+
+    let largest = *primes.last().expect("the set contains 2");
+    let solutions_per_q = 3.max((largest as usize).div_ceil(2));
+
+    let mut pairs = Vec::new();
+    for subset in 0u64..(1 << primes.len()) {
+        let q: u64 = primes
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| subset & (1 << bit) != 0)
+            .map(|(_, &p)| p)
+            .product();
+        // 2q is a square only for q = 2
+        if q == 2 {
+            continue;
+        }
+        for (x, _) in pell_solutions(2 * q, solutions_per_q)? {
+            let n: BigInt = (x - 1u32) / 2u32;
+            let next = &n + 1u32;
+            if is_smooth(&n, &primes) && is_smooth(&next, &primes) {
+                pairs.push((n, next));
+            }
+        }
+    }
+
+    pairs.sort();
+    pairs.dedup();
+    Ok(pairs)
+}
+
+/// Whether `n` > 0 has no prime factor outside `primes`
+fn is_smooth(n: &BigInt, primes: &[u64]) -> bool {
+    let mut n = n.clone();
+    for &p in primes {
+        let p = BigInt::from(p);
+        loop {
+            let (quotient, remainder) = n.div_rem(&p);
+            if !remainder.is_zero() {
+                break;
+            }
+            n = quotient;
+        }
+    }
+    n.is_one()
+}
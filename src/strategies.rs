@@ -0,0 +1,177 @@
+//! proptest strategies for Pell equations
+//!
+//! Generators for property tests of code built on this crate: valid D values,
+//! solution indices, equations x² - D·y² = N, and genuine solutions. The
+//! newtypes implement [`Arbitrary`], so they can be requested with `any`:
+//!
+//! ```
+//! use proptest::prelude::*;
+//! # use pell991::strategies::{SolutionIndex, ValidD};
+//! # use pell991::{pell_min_solution, pell_solution_k, verify_pell_solution};
+//! proptest!(|(ValidD(d) in any::<ValidD>(), SolutionIndex(k) in any::<SolutionIndex>())| {
+//!     let (x1, y1) = pell_min_solution(d).unwrap();
+//!     let (x, y) = pell_solution_k(d, &x1, &y1, k).unwrap();
+//!     prop_assert!(verify_pell_solution(d, &x, &y));
+//! });
+//! ```
+//!
+//! All strategies shrink towards small values: D towards 2, k towards 1 and
+//! N towards 1.
+
+use core::fmt;
+use core::ops::RangeInclusive;
+
+use proptest::prelude::*;
+
+use crate::solution::PellSolution;
+use crate::solver::{pell_min_solution, pell_solution_k};
+use crate::utils::isqrt_u64;
+
+/// Largest D generated by `any::<ValidD>()`
+pub const DEFAULT_MAX_D: u64 = 10_000;
+
+/// Largest k generated by `any::<SolutionIndex>()`
+pub const DEFAULT_MAX_K: u64 = 64;
+
+/// Largest |N| generated by `any::<PellEquation>()`
+pub const DEFAULT_MAX_N: i64 = 100;
+
+/// A D > 1 that is not a perfect square
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ValidD(pub u64);
+
+/// A solution index k ≥ 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SolutionIndex(pub u64);
+
+/// The generalized Pell equation x² - D·y² = N, with D valid and N non-zero
+///
+/// N = 1 is the Pell equation itself and N = -1 the negative Pell equation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PellEquation {
+    /// The coefficient D
+    pub d: u64,
+    /// The right-hand side N
+    pub n: i64,
+}
+
+impl fmt::Display for PellEquation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "x² - {}·y² = {}", self.d, self.n)
+    }
+}
+
+/// The n-th non-square positive integer, counting 2 as the first
+///
+/// Equals n + round(√n), so every non-square is reached without rejections.
+fn nth_non_square(n: u64) -> u64 {
+    let root = isqrt_u64(n);
+    n + root + u64::from(n > root * root + root)
+}
+
+/// Strategy for valid D values in `range`
+///
+/// Bounds below 2 are raised to 2; perfect squares in the range are never
+/// generated.
+///
+/// # Panics
+///
+/// Panics if `range` contains no valid D.
+///
+/// # Examples
+///
+/// ```
+/// use proptest::prelude::*;
+/// # use pell991::strategies::valid_d_in;
+/// # use pell991::is_valid_pell_d;
+/// proptest!(|(d in valid_d_in(1_000_000..=2_000_000))| {
+///     prop_assert!(is_valid_pell_d(d));
+///     prop_assert!((1_000_000..=2_000_000).contains(&d));
+/// });
+/// ```
+pub fn valid_d_in(range: RangeInclusive<u64>) -> impl Strategy<Value = u64> {
+    // Number of non-squares in 2..=d, the inverse of `nth_non_square`
+    let non_squares_up_to = |d: u64| d - isqrt_u64(d);
+    let (low, high) = ((*range.start()).max(2), *range.end());
+    assert!(
+        low <= high && non_squares_up_to(high) > non_squares_up_to(low - 1),
+        "no valid D in {range:?}"
+    );
+    (non_squares_up_to(low - 1) + 1..=non_squares_up_to(high)).prop_map(nth_non_square)
+}
+
+/// Strategy for solution indices k in `1..=max_k`
+///
+/// # Panics
+///
+/// Panics if `max_k` is 0.
+pub fn solution_index_up_to(max_k: u64) -> impl Strategy<Value = u64> {
+    assert!(max_k >= 1, "k must be > 0");
+    1..=max_k
+}
+
+/// Strategy for genuine solutions: the k-th solution of a valid D
+///
+/// # Arguments
+///
+/// * `d` - Strategy for D; every value must be a valid D
+/// * `k` - Strategy for the index k ≥ 1
+///
+/// # Examples
+///
+/// ```
+/// use proptest::prelude::*;
+/// # use pell991::strategies::{solution, solution_index_up_to, valid_d_in};
+/// proptest!(|(s in solution(valid_d_in(2..=100), solution_index_up_to(5)))| {
+///     prop_assert!(s.verify());
+/// });
+/// ```
+pub fn solution(d: impl Strategy<Value = u64>, k: impl Strategy<Value = u64>) -> impl Strategy<Value = PellSolution> {
+    (d, k).prop_map(|(d, k)| {
+        let (x1, y1) = pell_min_solution(d).expect("the strategy generates valid D values");
+        let (x, y) = pell_solution_k(d, &x1, &y1, k).expect("the strategy generates k > 0");
+        PellSolution::with_index(d, k, x, y)
+    })
+}
+
+impl Arbitrary for ValidD {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        valid_d_in(2..=DEFAULT_MAX_D).prop_map(ValidD).boxed()
+    }
+}
+
+impl Arbitrary for SolutionIndex {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        solution_index_up_to(DEFAULT_MAX_K).prop_map(SolutionIndex).boxed()
+    }
+}
+
+impl Arbitrary for PellEquation {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        // 1..=2·max maps onto 1, -1, 2, -2, …, so shrinking heads for N = 1
+        let n = (1..=2 * DEFAULT_MAX_N).prop_map(|i| if i % 2 == 1 { (i + 1) / 2 } else { -i / 2 });
+        (valid_d_in(2..=DEFAULT_MAX_D), n).prop_map(|(d, n)| PellEquation { d, n }).boxed()
+    }
+}
+
+/// The k-th solution of a valid D up to [`DEFAULT_MAX_D`], with k up to 8
+///
+/// k is kept smaller than for [`SolutionIndex`] so the coordinates stay at a
+/// few thousand digits.
+impl Arbitrary for PellSolution {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        solution(valid_d_in(2..=DEFAULT_MAX_D), solution_index_up_to(8)).boxed()
+    }
+}
@@ -0,0 +1,267 @@
+//! Step-by-step traces of the algorithms, for teaching
+//!
+//! The solvers keep only what they need to reach the answer. The functions
+//! here run the same algorithms but report every intermediate value as a
+//! [`TraceEvent`], either to a callback or collected into a `Vec`:
+//!
+//! * the continued fraction expansion of √D, one event per term, with the
+//!   complete quotient (m + √D)/d, the partial quotient a, the convergent p/q
+//!   and its residual p² - D·q², until the residual is 1;
+//! * binary exponentiation of the fundamental solution, one event per
+//!   squaring of the base and per multiplication into the result.
+//!
+//! Every convergent is kept, so traces are meant for D with short periods.
+//!
+//! # Examples
+//!
+//! ```
+//! # use pell991::trace::trace_min_solution;
+//! // √7 = [2; (1, 1, 1, 4)]
+//! for event in trace_min_solution(7).unwrap() {
+//!     println!("{event}");
+//! }
+//! // n=0: (m + √D)/d = (0 + √D)/1, a=2, p/q = 2/1, p² - D·q² = -3
+//! // n=1: (m + √D)/d = (2 + √D)/3, a=1, p/q = 3/1, p² - D·q² = 2
+//! // n=2: (m + √D)/d = (1 + √D)/2, a=1, p/q = 5/2, p² - D·q² = -3
+//! // n=3: (m + √D)/d = (1 + √D)/3, a=1, p/q = 8/3, p² - D·q² = 1
+//! // solution x=8, y=3 after 3 steps, period 4
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use num_bigint::BigInt;
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::error::PellError;
+use crate::solver::validate_d;
+use crate::utils::isqrt_u64;
+
+/// One step of a traced algorithm
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// Term n of the continued fraction of √D and the convergent it completes
+    CfStep {
+        /// Index n of the term
+        n: u64,
+        /// m in the complete quotient (m + √D)/d
+        m: u64,
+        /// d in the complete quotient (m + √D)/d
+        d: u64,
+        /// The partial quotient a = ⌊(m + √D)/d⌋
+        a: u64,
+        /// Numerator of the convergent pₙ/qₙ
+        p: BigInt,
+        /// Denominator of the convergent pₙ/qₙ
+        q: BigInt,
+        /// pₙ² - D·qₙ², which is ±1 or small, and 1 at the solution
+        residual: i64,
+    },
+    /// The continued fraction reached the fundamental solution
+    Solution {
+        /// x of the fundamental solution, the last p
+        x: BigInt,
+        /// y of the fundamental solution, the last q
+        y: BigInt,
+        /// Index of the convergent that solves the equation
+        steps: u64,
+        /// Length of the period of the continued fraction of √D
+        period: u64,
+    },
+    /// The exponentiation base was squared, and is now (x₁ + y₁√D)^power
+    Square {
+        /// Exponent of the fundamental solution in the new base
+        power: u64,
+        /// x of the new base
+        x: BigInt,
+        /// y of the new base
+        y: BigInt,
+    },
+    /// The base was multiplied into the result, which is now (x₁ + y₁√D)^power
+    Multiply {
+        /// Exponent of the fundamental solution in the new result
+        power: u64,
+        /// x of the new result
+        x: BigInt,
+        /// y of the new result
+        y: BigInt,
+    },
+}
+
+/// Writes the event on one line, as in the module example
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceEvent::CfStep { n, m, d, a, p, q, residual } => {
+                write!(f, "n={n}: (m + √D)/d = ({m} + √D)/{d}, a={a}, p/q = {p}/{q}, p² - D·q² = {residual}")
+            }
+            TraceEvent::Solution { x, y, steps, period } => {
+                write!(f, "solution x={x}, y={y} after {steps} steps, period {period}")
+            }
+            TraceEvent::Square { power, x, y } => write!(f, "square: base = (x₁ + y₁√D)^{power} = {x} + {y}√D"),
+            TraceEvent::Multiply { power, x, y } => write!(f, "multiply: result = (x₁ + y₁√D)^{power} = {x} + {y}√D"),
+        }
+    }
+}
+
+/// Solve x² - D·y² = 1 with continued fractions, reporting every step to `on_event`
+///
+/// Calls `on_event` with a [`TraceEvent::CfStep`] for each term,
+/// starting from n = 0, and finally with a [`TraceEvent::Solution`].
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation (must be > 1 and non-square)
+/// * `on_event` - Receives the events in order
+///
+/// # Returns
+///
+/// A `Result` containing the fundamental solution `(x, y)`, the same as
+/// `pell_min_solution`, or a `PellError`.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+pub fn trace_min_solution_with<F>(d: u64, mut on_event: F) -> Result<(BigInt, BigInt), PellError>
+where
+    F: FnMut(TraceEvent),
+{
+    validate_d(d)?;
+
+    let big_d = BigInt::from(d);
+    let a0 = isqrt_u64(d);
+    let (mut m, mut denominator, mut a) = (0u64, 1u64, a0);
+    let (mut p, mut p_prev) = (BigInt::from(a0), BigInt::one());
+    let (mut q, mut q_prev) = (BigInt::one(), BigInt::zero());
+    let mut period = None;
+
+    for n in 0.. {
+        let residual = &p * &p - &big_d * &q * &q;
+        on_event(TraceEvent::CfStep {
+            n,
+            m,
+            d: denominator,
+            a,
+            p: p.clone(),
+            q: q.clone(),
+            residual: residual.to_i64().expect("|p² - D·q²| < 2√D"),
+        });
+        if residual.is_one() {
+            let period = period.unwrap_or(n + 1);
+            on_event(TraceEvent::Solution { x: p.clone(), y: q.clone(), steps: n, period });
+            return Ok((p, q));
+        }
+
+        // The next complete quotient, in 128 bits as in the solver
+        let (m_wide, d_wide, a0_wide) = (i128::from(m), i128::from(denominator), i128::from(a0));
+        let m_next = d_wide * i128::from(a) - m_wide;
+        let d_next = (i128::from(d) - m_next * m_next) / d_wide;
+        (m, denominator, a) = (m_next as u64, d_next as u64, ((a0_wide + m_next) / d_next) as u64);
+        if denominator == 1 && period.is_none() {
+            period = Some(n + 1);
+        }
+
+        let p_next = &p * a + &p_prev;
+        let q_next = &q * a + &q_prev;
+        p_prev = core::mem::replace(&mut p, p_next);
+        q_prev = core::mem::replace(&mut q, q_next);
+    }
+    unreachable!("the expansion reaches a solution")
+}
+
+/// Trace the continued fraction solve of x² - D·y² = 1
+///
+/// The events of [`trace_min_solution_with`], collected; the last one is the
+/// [`TraceEvent::Solution`].
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+pub fn trace_min_solution(d: u64) -> Result<Vec<TraceEvent>, PellError> {
+    let mut events = Vec::new();
+    trace_min_solution_with(d, |event| events.push(event))?;
+    Ok(events)
+}
+
+/// Find the k-th solution, reporting the continued fraction steps and then
+/// every squaring and multiplication of the binary exponentiation to `on_event`
+///
+/// The exponentiation follows `pell_solution_k`: the bits of k are read from
+/// the lowest, the base (x₁ + y₁√D)^(2ⁱ) is multiplied into the result for
+/// each set bit, and squared before moving to the next bit.
+///
+/// # Arguments
+///
+/// * `d` - The coefficient D in the Pell equation (must be > 1 and non-square)
+/// * `k` - The index of the solution (must be > 0)
+/// * `on_event` - Receives the events in order
+///
+/// # Returns
+///
+/// A `Result` containing the k-th solution `(xₖ, yₖ)`, or a `PellError`.
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+/// Returns `PellError::InvalidK` if `k` is 0.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::trace::{trace_solution_k_with, TraceEvent};
+/// // 5 = 101₂: multiply by the base, square twice, multiply
+/// let mut steps = Vec::new();
+/// trace_solution_k_with(2, 5, |event| match event {
+///     TraceEvent::Square { power, .. } => steps.push(format!("square to {power}")),
+///     TraceEvent::Multiply { power, .. } => steps.push(format!("multiply to {power}")),
+///     _ => {}
+/// })
+/// .unwrap();
+/// assert_eq!(steps, ["multiply to 1", "square to 2", "square to 4", "multiply to 5"]);
+/// ```
+pub fn trace_solution_k_with<F>(d: u64, k: u64, mut on_event: F) -> Result<(BigInt, BigInt), PellError>
+where
+    F: FnMut(TraceEvent),
+{
+    validate_d(d)?;
+    if k == 0 {
+        return Err(PellError::InvalidK(k));
+    }
+    let (mut base_x, mut base_y) = trace_min_solution_with(d, &mut on_event)?;
+
+    let (mut x, mut y) = (BigInt::one(), BigInt::zero());
+    let (mut power, mut base_power, mut exp) = (0, 1, k);
+    loop {
+        if exp % 2 == 1 {
+            (x, y) = (&x * &base_x + d * &y * &base_y, &x * &base_y + &y * &base_x);
+            power += base_power;
+            on_event(TraceEvent::Multiply { power, x: x.clone(), y: y.clone() });
+        }
+        exp /= 2;
+        if exp == 0 {
+            return Ok((x, y));
+        }
+        (base_x, base_y) = (&base_x * &base_x + d * &base_y * &base_y, 2 * &base_x * &base_y);
+        base_power *= 2;
+        on_event(TraceEvent::Square { power: base_power, x: base_x.clone(), y: base_y.clone() });
+    }
+}
+
+/// Trace the computation of the k-th solution
+///
+/// The events of [`trace_solution_k_with`], collected; the last one is the
+/// [`TraceEvent::Multiply`] producing (xₖ, yₖ).
+///
+/// # Errors
+///
+/// Returns `PellError::InvalidD` if `d` ≤ 1.
+/// Returns `PellError::PerfectSquare` if `d` is a perfect square.
+/// Returns `PellError::InvalidK` if `k` is 0.
+pub fn trace_solution_k(d: u64, k: u64) -> Result<Vec<TraceEvent>, PellError> {
+    let mut events = Vec::new();
+    trace_solution_k_with(d, k, |event| events.push(event))?;
+    Ok(events)
+}
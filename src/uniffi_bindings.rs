@@ -0,0 +1,64 @@
+//! Kotlin and Swift bindings
+//!
+//! The interface is defined in `src/pell991.udl`; the build script turns it
+//! into the scaffolding included here. Build the library as a `cdylib` and
+//! generate the bindings with the `uniffi-bindgen` binary:
+//!
+//! ```text
+//! cargo rustc --release --features uniffi --crate-type cdylib
+//! cargo run --features uniffi-bindgen --bin uniffi-bindgen -- \
+//!     generate src/pell991.udl --language kotlin --out-dir bindings
+//! ```
+//!
+//! The scaffolding has to live in the crate root, so it is included from
+//! `lib.rs`; this module holds the namespace functions that have no counterpart
+//! elsewhere in the crate, and the conversion of `BigInt` to decimal strings.
+
+use core::str::FromStr;
+
+use num_bigint::BigInt;
+
+use crate::error::PellError;
+use crate::solution::PellSolution;
+use crate::solver::{pell_min_solution, pell_solution_k, pell_solutions};
+use crate::UniffiCustomTypeConverter;
+
+impl UniffiCustomTypeConverter for BigInt {
+    type Builtin = String;
+
+    fn into_custom(val: Self::Builtin) -> uniffi::Result<Self> {
+        Ok(BigInt::from_str(&val)?)
+    }
+
+    fn from_custom(obj: Self) -> Self::Builtin {
+        obj.to_string()
+    }
+}
+
+/// The fundamental solution, with index 1
+pub fn min_solution(d: u64) -> Result<PellSolution, PellError> {
+    let (x, y) = pell_min_solution(d)?;
+    Ok(PellSolution::with_index(d, 1, x, y))
+}
+
+/// The k-th solution
+pub fn nth_solution(d: u64, k: u64) -> Result<PellSolution, PellError> {
+    let (x1, y1) = pell_min_solution(d)?;
+    let (x, y) = pell_solution_k(d, &x1, &y1, k)?;
+    Ok(PellSolution::with_index(d, k, x, y))
+}
+
+/// The solutions for k = 1..=count
+pub fn first_solutions(d: u64, count: u32) -> Result<Vec<PellSolution>, PellError> {
+    let solutions = pell_solutions(d, count as usize)?;
+    Ok(solutions
+        .into_iter()
+        .zip(1..)
+        .map(|((x, y), k)| PellSolution::with_index(d, k, x, y))
+        .collect())
+}
+
+/// Whether the solution satisfies its equation
+pub fn verify_solution(solution: &PellSolution) -> bool {
+    solution.verify()
+}
@@ -1,9 +1,19 @@
 //! Utility functions for mathematical operations
 
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use num_bigint::{BigInt, BigUint};
+use num_traits::ToPrimitive;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
 /// Compute the integer square root of a u64: ⌊√n⌋
 ///
 /// Returns the largest integer x such that x² ≤ n.
-/// Uses Newton's method with proper bounds checking.
+/// Uses integer-only Newton's method, so it is exact for every u64 and can be
+/// evaluated at compile time.
 ///
 /// # Arguments
 ///
@@ -19,53 +29,29 @@
 /// # use pell991::isqrt_u64;
 /// assert_eq!(isqrt_u64(15), 3); // √15 ≈ 3.87, so ⌊√15⌋ = 3
 /// assert_eq!(isqrt_u64(16), 4); // √16 = 4
+///
+/// // Usable in constant contexts
+/// const ROOT: u64 = isqrt_u64(991);
+/// assert_eq!(ROOT, 31);
 /// ```
-pub fn isqrt_u64(n: u64) -> u64 {
-    if n == 0 {
-        return 0;
-    }
-    if n <= 3 {
-        return 1;
-    }
-    if n <= 8 {
-        return 2;
-    }
-    if n <= 15 {
-        return 3;
-    }
-    
-    // Use Newton's method starting with a good initial guess
-    let mut x = (n as f64).sqrt() as u64;
-    
-    // Ensure initial guess is reasonable
-    if x == 0 {
-        x = 1;
+pub const fn isqrt_u64(n: u64) -> u64 {
+    if n < 2 {
+        return n;
     }
-    
+
+    // Start from a power of two that is at least √n, so Newton's method
+    // decreases monotonically to ⌊√n⌋
+    let bits = u64::BITS - n.leading_zeros();
+    let mut x = 1u64 << bits.div_ceil(2);
+
     // Newton's method: x_{n+1} = (x_n + n/x_n) / 2
-    for _ in 0..64 { // At most 64 iterations should be enough for u64
+    loop {
         let x_new = (x + n / x) / 2;
         if x_new >= x {
-            break;
+            return x;
         }
         x = x_new;
     }
-    
-    // Ensure we have the correct floor value
-    while x * x > n {
-        x -= 1;
-    }
-    
-    // Check if we can go one higher
-    if let Some(next) = x.checked_add(1) {
-        if let Some(next_sq) = next.checked_mul(next) {
-            if next_sq <= n {
-                x = next;
-            }
-        }
-    }
-    
-    x
 }
 
 /// Check if a number is a perfect square.
@@ -84,8 +70,11 @@ pub fn isqrt_u64(n: u64) -> u64 {
 /// # use pell991::is_square_u64;
 /// assert!(is_square_u64(16));  // 16 = 4²
 /// assert!(!is_square_u64(15)); // 15 is not a perfect square
+///
+/// // Compile-time validation
+/// const _: () = assert!(!is_square_u64(991));
 /// ```
-pub fn is_square_u64(n: u64) -> bool {
+pub const fn is_square_u64(n: u64) -> bool {
     let r = isqrt_u64(n);
     r * r == n
 }
@@ -111,10 +100,125 @@ pub fn is_square_u64(n: u64) -> bool {
 /// assert!(!is_valid_pell_d(1));  // Invalid: 1 is not > 1
 /// assert!(!is_valid_pell_d(4));  // Invalid: 4 is a perfect square
 /// ```
-pub fn is_valid_pell_d(d: u64) -> bool {
+pub const fn is_valid_pell_d(d: u64) -> bool {
     d > 1 && !is_square_u64(d)
 }
 
+/// The least perfect square ≥ n, with its root, or `None` if it exceeds `u64::MAX`
+fn next_square(n: u64) -> Option<(u64, u64)> {
+    let root = isqrt_u64(n);
+    let root = if root * root < n { root + 1 } else { root };
+    root.checked_mul(root).map(|square| (root, square))
+}
+
+/// Iterate over the valid Pell D values in `range`, in increasing order
+///
+/// Instead of testing every value with [`is_valid_pell_d`], the walk tracks the
+/// next perfect square and skips it, so each value costs one comparison.
+///
+/// # Arguments
+///
+/// * `range` - The candidate D values (end exclusive)
+///
+/// # Returns
+///
+/// An iterator over the non-square D > 1 in `range`
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::valid_pell_d_in;
+/// let ds: Vec<u64> = valid_pell_d_in(0..12).collect();
+/// assert_eq!(ds, vec![2, 3, 5, 6, 7, 8, 10, 11]);
+/// ```
+pub fn valid_pell_d_in(range: Range<u64>) -> impl Iterator<Item = u64> {
+    let start = range.start.max(2);
+    let mut next = next_square(start);
+    (start..range.end).filter(move |&d| match next {
+        Some((root, square)) if d == square => {
+            next = (root + 1).checked_mul(root + 1).map(|square| (root + 1, square));
+            false
+        }
+        _ => true,
+    })
+}
+
+/// Validity of every D in a range, computed once for repeated membership checks
+///
+/// Marks the perfect squares (and 0 and 1) of the range in a bit set, so batch
+/// code validating many candidates answers each with a lookup instead of a
+/// square root. Uses one bit per value in the range.
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::DSieve;
+/// let sieve = DSieve::new(0..1000);
+/// assert!(sieve.is_valid(991));
+/// assert!(!sieve.is_valid(961)); // 31²
+/// assert_eq!(sieve.count(), 1000 - 32); // all but the squares 0, 1, 4, …, 961
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DSieve {
+    range: Range<u64>,
+    /// One bit per D in the range, set for invalid values
+    invalid: Vec<u64>,
+}
+
+impl DSieve {
+    /// Sieve the D values in `range` (end exclusive)
+    pub fn new(range: Range<u64>) -> Self {
+        let len = range.end.saturating_sub(range.start);
+        let mut invalid = vec![0u64; len.div_ceil(64) as usize];
+        let mut mark = |d: u64| {
+            let offset = d - range.start;
+            invalid[(offset / 64) as usize] |= 1 << (offset % 64);
+        };
+
+        for d in range.start..range.end.min(2) {
+            mark(d);
+        }
+        let mut next = next_square(range.start.max(2));
+        while let Some((root, square)) = next {
+            if square >= range.end {
+                break;
+            }
+            mark(square);
+            next = (root + 1).checked_mul(root + 1).map(|square| (root + 1, square));
+        }
+
+        DSieve { range, invalid }
+    }
+
+    /// The sieved range
+    pub fn range(&self) -> Range<u64> {
+        self.range.clone()
+    }
+
+    /// Whether `d` is a valid Pell D
+    ///
+    /// Values outside the sieved range are checked directly.
+    pub fn is_valid(&self, d: u64) -> bool {
+        if !self.range.contains(&d) {
+            return is_valid_pell_d(d);
+        }
+        let offset = d - self.range.start;
+        self.invalid[(offset / 64) as usize] & (1 << (offset % 64)) == 0
+    }
+
+    /// Number of valid D values in the range
+    pub fn count(&self) -> usize {
+        let len = self.range.end.saturating_sub(self.range.start);
+        let invalid: u64 = self.invalid.iter().map(|word| word.count_ones() as u64).sum();
+        (len - invalid) as usize
+    }
+
+    /// Iterate over the valid D values in the range, in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.range.clone().filter(|&d| self.is_valid(d))
+    }
+}
+
 /// Estimate the period length of the continued fraction expansion of √D
 ///
 /// This gives a rough estimate of how long it might take to find the minimal solution.
@@ -210,4 +314,123 @@ pub fn is_prime(n: u64) -> bool {
     }
     
     true
-}
\ No newline at end of file
+}
+
+/// Compute the squarefree part of n: the unique squarefree s with n = s·f²
+///
+/// Removes small prime factors by trial division up to the cube root of what
+/// remains; the leftover cofactor then has at most two prime factors and is
+/// squarefree unless it is a perfect square.
+///
+/// # Arguments
+///
+/// * `n` - The number to analyze
+///
+/// # Returns
+///
+/// The squarefree part of n (0 for n = 0)
+///
+/// # Examples
+///
+/// ```
+/// # use pell991::squarefree_part;
+/// assert_eq!(squarefree_part(12), 3);  // 12 = 3·2²
+/// assert_eq!(squarefree_part(991), 991);
+/// assert_eq!(squarefree_part(49), 1);
+/// ```
+pub fn squarefree_part(n: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+
+    let mut remaining = n;
+    let mut part = 1;
+    let mut p: u64 = 2;
+
+    while (p as u128) * (p as u128) * (p as u128) <= remaining as u128 {
+        if remaining % p == 0 {
+            let mut exponent = 0;
+            while remaining % p == 0 {
+                remaining /= p;
+                exponent += 1;
+            }
+            if exponent % 2 == 1 {
+                part *= p;
+            }
+        }
+        p += if p == 2 { 1 } else { 2 };
+    }
+
+    // The cofactor is 1, a prime, a product of two distinct primes, or a prime square
+    if !is_square_u64(remaining) {
+        part *= remaining;
+    }
+
+    part
+}
+
+/// Count the decimal digits of a BigInt without converting it to a string
+///
+/// The count is derived from the bit length, which pins it down to one of two
+/// values, and a single comparison against a power of ten picks the right one.
+/// This is much cheaper than `to_string().len()` for numbers with millions of digits.
+/// The sign is ignored, and zero has one digit.
+///
+/// # Arguments
+///
+/// * `n` - The number to measure
+///
+/// # Returns
+///
+/// The number of decimal digits in |n|
+///
+/// # Examples
+///
+/// ```
+/// use num_bigint::BigInt;
+/// # use pell991::decimal_digits;
+/// assert_eq!(decimal_digits(&BigInt::from(0)), 1);
+/// assert_eq!(decimal_digits(&BigInt::from(999)), 3);
+/// assert_eq!(decimal_digits(&BigInt::from(-1000)), 4);
+/// ```
+pub fn decimal_digits(n: &BigInt) -> u64 {
+    let bits = n.bits();
+    if bits == 0 {
+        return 1;
+    }
+
+    // 2^(bits-1) ≤ |n| < 2^bits, so |n| has ⌊(bits-1)·log₁₀2⌋ + 1 digits or one more
+    let mut digits = ((bits - 1) as f64 * core::f64::consts::LOG10_2) as u64 + 1;
+
+    let magnitude = n.magnitude();
+    let ten = BigUint::from(10u32);
+    let mut lower = ten.pow((digits - 1) as u32);
+
+    // Correct the estimate against the exact power of ten; at most one step in practice
+    while digits > 1 && *magnitude < lower {
+        digits -= 1;
+        lower /= &ten;
+    }
+    let mut upper = lower * &ten;
+    while *magnitude >= upper {
+        digits += 1;
+        upper *= &ten;
+    }
+
+    digits
+}
+
+/// Approximate log₁₀|n| for a BigInt of any size
+///
+/// Uses the leading 64 bits of the magnitude, so the result carries full `f64`
+/// precision regardless of how many digits n has. Returns negative infinity for zero.
+pub(crate) fn log10_bigint(n: &BigInt) -> f64 {
+    let bits = n.bits();
+    if bits <= 64 {
+        return n.magnitude().to_f64().unwrap_or(0.0).log10();
+    }
+
+    let shift = bits - 64;
+    let leading = (n.magnitude() >> shift).to_f64().unwrap_or(0.0);
+    leading.log10() + shift as f64 * core::f64::consts::LOG10_2
+}
@@ -0,0 +1,111 @@
+//! Tests for the interchangeable solver algorithms
+
+use pell991::{
+    all_solvers, is_valid_pell_d, pell_min_solution, pell_min_solution_bsgs,
+    pell_min_solution_chakravala, pell_min_solution_half_period, solver_by_name, AutoSolver, BsgsSolver,
+    ChakravalaSolver, CfSolver, HalfPeriodSolver, compare_solvers, period_length, PellError, Solver, BENCHMARK_D_VALUES,
+};
+
+#[test]
+fn test_chakravala_matches_cf() {
+    for d in (2..3000).filter(|&d| is_valid_pell_d(d)) {
+        assert_eq!(pell_min_solution_chakravala(d).unwrap(), pell_min_solution(d).unwrap(),
+            "Chakravala mismatch for D = {}", d);
+    }
+}
+
+#[test]
+fn test_half_period_matches_cf() {
+    // Both parities of the period, including period 1 (D = a² + 1) and 2 (D = a² ± 2)
+    for d in (2..3000).filter(|&d| is_valid_pell_d(d)) {
+        assert_eq!(pell_min_solution_half_period(d).unwrap(), pell_min_solution(d).unwrap(),
+            "Half-period mismatch for D = {}", d);
+    }
+    for d in BENCHMARK_D_VALUES {
+        assert_eq!(pell_min_solution_half_period(d).unwrap(), pell_min_solution(d).unwrap(),
+            "Half-period mismatch for D = {}", d);
+    }
+}
+
+#[test]
+fn test_bsgs_matches_cf() {
+    // Every period here fits in the baby steps (at least 240 of them)
+    for d in (2..3000).filter(|&d| is_valid_pell_d(d)) {
+        assert_eq!(pell_min_solution_bsgs(d).unwrap(), pell_min_solution(d).unwrap(),
+            "BSGS mismatch for D = {}", d);
+    }
+
+    // Periods over 4000, ten times the 416 baby steps for these D, need giant steps
+    for d in [2_519_911, 2_810_014, 2_847_079] {
+        assert!(period_length(d).unwrap() > 4000);
+        assert_eq!(pell_min_solution_bsgs(d).unwrap(), pell_min_solution(d).unwrap(),
+            "BSGS mismatch for D = {}", d);
+    }
+}
+
+#[test]
+fn test_bsgs_giant_steps_on_long_periods() {
+    // Few baby steps force many giant steps on long periods
+    let solver = BsgsSolver::with_baby_steps(1);
+    for d in [991, 1_000_003, 4_000_037, 9_999_991, 123_456_791] {
+        assert_eq!(solver.min_solution(d).unwrap(), pell_min_solution(d).unwrap(),
+            "BSGS mismatch for D = {}", d);
+    }
+}
+
+#[test]
+fn test_solvers_by_name() {
+    for name in ["cf", "half-period", "chakravala", "bsgs"] {
+        let solver = solver_by_name(name).unwrap();
+        assert_eq!(solver.name(), name);
+        assert_eq!(solver.min_solution(991).unwrap(), pell_min_solution(991).unwrap());
+    }
+    assert_eq!(solver_by_name("auto").unwrap().name(), "auto");
+    assert!(solver_by_name("unknown").is_none());
+
+    let names: Vec<_> = all_solvers().iter().map(|solver| solver.name()).collect();
+    assert_eq!(names, ["cf", "half-period", "chakravala", "bsgs"]);
+}
+
+#[test]
+fn test_solver_errors() {
+    let solvers: [&dyn Solver; 5] = [&CfSolver, &HalfPeriodSolver, &ChakravalaSolver, &BsgsSolver::new(), &AutoSolver];
+    for solver in solvers {
+        assert_eq!(solver.min_solution(0), Err(PellError::InvalidD(0)));
+        assert_eq!(solver.min_solution(1), Err(PellError::InvalidD(1)));
+        assert_eq!(solver.min_solution(49), Err(PellError::PerfectSquare(49)));
+    }
+}
+
+#[test]
+fn test_auto_solver_choice() {
+    // Short periods go to the half-period method, long ones to BSGS
+    for d in [2, 13, 991, 100_003, 123_456_791, 2_000_000_011] {
+        assert_eq!(AutoSolver.choose(d).name(), "half-period", "D = {}", d);
+    }
+    for d in [2_500_000_001, 10_000_000_019] {
+        assert_eq!(AutoSolver.choose(d).name(), "bsgs", "D = {}", d);
+    }
+    // Invalid D falls through to a solver that reports the error
+    assert_eq!(AutoSolver.choose(1).name(), "half-period");
+
+    for d in [2, 991, 1_000_003, 123_456_791, 2_500_000_001] {
+        assert_eq!(AutoSolver.min_solution(d).unwrap(), pell_min_solution(d).unwrap(),
+            "Auto mismatch for D = {}", d);
+    }
+}
+
+#[test]
+fn test_compare_solvers() {
+    let timings = compare_solvers(&[13, 991]).unwrap();
+    let runs: Vec<_> = timings.iter().map(|timing| (timing.d, timing.solver)).collect();
+    let names = ["cf", "half-period", "chakravala", "bsgs"];
+    let expected: Vec<_> = [13, 991].into_iter().flat_map(|d| names.map(|name| (d, name))).collect();
+    assert_eq!(runs, expected);
+
+    assert_eq!(compare_solvers(&[991, 25]), Err(PellError::PerfectSquare(25)));
+    assert!(compare_solvers(&[]).unwrap().is_empty());
+
+    // The standard set only holds D values every solver accepts
+    assert!(BENCHMARK_D_VALUES.iter().all(|&d| is_valid_pell_d(d)));
+}
@@ -0,0 +1,221 @@
+//! Tests for D value analysis and class numbers
+
+use pell991::{
+    analyze_d, analyze_d_with_fundamental, caliber, class_number, class_number_stats, pell_min_solution,
+    regulator_table, survey, write_regulator_csv,
+    PellError, SolveOptions, CLASS_NUMBER_MAX_D,
+};
+
+#[test]
+fn test_class_numbers_of_fields() {
+    // Squarefree D ≡ 2, 3 (mod 4): Z[√D] is the full ring of integers of Q(√D)
+    let known = [
+        (2, 1), (3, 1), (6, 1), (7, 1), (10, 2), (11, 1), (14, 1), (15, 2),
+        (26, 2), (30, 2), (34, 2), (35, 2), (42, 2), (79, 3), (82, 4), (223, 3),
+        (226, 8), (991, 1),
+    ];
+    for (d, h) in known {
+        assert_eq!(class_number(d).unwrap(), h, "Wrong class number for D = {}", d);
+    }
+}
+
+#[test]
+fn test_class_numbers_of_non_maximal_orders() {
+    // Z[√D] has conductor 2 in Q(√D) for D ≡ 1 (mod 4), or larger when D has square factors
+    let known = [(5, 1), (13, 1), (17, 1), (37, 3), (65, 2), (8, 1), (12, 1)];
+    for (d, h) in known {
+        assert_eq!(class_number(d).unwrap(), h, "Wrong class number for D = {}", d);
+    }
+}
+
+#[test]
+fn test_class_number_errors() {
+    assert_eq!(class_number(1), Err(PellError::InvalidD(1)));
+    assert_eq!(class_number(100), Err(PellError::PerfectSquare(100)));
+}
+
+#[test]
+fn test_caliber() {
+    for d in (2..=400).filter(|&d| class_number(d).is_ok()) {
+        let period = analyze_d(d).period.unwrap();
+        let h = class_number(d).unwrap();
+        let kappa = caliber(d).unwrap();
+        if h == 1 {
+            assert_eq!(kappa, period, "with one class the only cycle is the period, D = {}", d);
+        } else {
+            assert!(kappa > period, "every class adds a cycle, D = {}", d);
+        }
+        assert!(kappa >= h, "each class has at least one reduced ideal, D = {}", d);
+    }
+    assert_eq!(caliber(1), Err(PellError::InvalidD(1)));
+    assert_eq!(caliber(36), Err(PellError::PerfectSquare(36)));
+}
+
+#[test]
+fn test_class_number_stats_match_class_number() {
+    let stats = class_number_stats(2..=500);
+    let valid: Vec<u64> = (2..=500).filter(|&d| class_number(d).is_ok()).collect();
+    assert_eq!(stats.total, valid.len() as u64, "perfect squares are skipped");
+    assert_eq!(stats.counts.values().sum::<u64>(), stats.total);
+
+    for (&h, &count) in &stats.counts {
+        let expected = valid.iter().filter(|&&d| class_number(d).unwrap() == h).count() as u64;
+        assert_eq!(count, expected, "Wrong count for h = {}", h);
+    }
+    let ones: Vec<u64> = valid.iter().copied().filter(|&d| class_number(d).unwrap() == 1).collect();
+    assert_eq!(stats.class_number_one, ones, "class number one D are listed in order");
+    assert_eq!(stats.count(1), ones.len() as u64);
+    assert_eq!(stats.count(1000), 0);
+    assert_eq!(stats.class_number_one_density(), ones.len() as f64 / valid.len() as f64);
+}
+
+#[test]
+fn test_class_number_stats_empty_range() {
+    let stats = class_number_stats(0..=1);
+    assert_eq!(stats.total, 0);
+    assert!(stats.counts.is_empty());
+    assert_eq!(stats.class_number_one_density(), 0.0);
+    assert_eq!(class_number_stats(49..=49).total, 0, "49 is a perfect square");
+}
+
+#[test]
+fn test_analyze_valid_d() {
+    let report = analyze_d(61);
+    assert_eq!(report.d, 61);
+    assert!(report.is_valid);
+    assert!(report.is_prime);
+    assert_eq!(report.squarefree_part, 61);
+    assert_eq!(report.period, Some(11));
+    assert_eq!(report.negative_pell_solvable, Some(true));
+    assert_eq!(report.x_digits, Some(10)); // 1766319049
+    assert_eq!(report.y_digits, Some(9));  // 226153980
+    assert_eq!(report.class_number, Some(1));
+
+    // Odd period: the fundamental unit is 29718 + 3805√61, of norm -1
+    let regulator = report.regulator.unwrap();
+    assert!((regulator - (29718.0f64 + 3805.0 * 61f64.sqrt()).ln()).abs() < 1e-9);
+}
+
+#[test]
+fn test_analyze_even_period() {
+    let report = analyze_d(12);
+    assert!(!report.is_prime);
+    assert_eq!(report.squarefree_part, 3);
+    assert_eq!(report.period, Some(2));
+    assert_eq!(report.negative_pell_solvable, Some(false));
+
+    // Even period: the fundamental unit is the fundamental solution 7 + 2√12
+    let (x, y) = pell_min_solution(12).unwrap();
+    assert_eq!((x.to_string(), y.to_string()), ("7".to_string(), "2".to_string()));
+    assert!((report.regulator.unwrap() - (7.0f64 + 2.0 * 12f64.sqrt()).ln()).abs() < 1e-9);
+}
+
+#[test]
+fn test_analyze_invalid_d() {
+    for d in [0, 1, 4, 49] {
+        let report = analyze_d(d);
+        assert!(!report.is_valid);
+        assert_eq!(report.period, None);
+        assert_eq!(report.negative_pell_solvable, None);
+        assert_eq!(report.regulator, None);
+        assert_eq!(report.x_digits, None);
+        assert_eq!(report.class_number, None);
+    }
+    assert_eq!(analyze_d(49).squarefree_part, 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_report_serialization() {
+    let report = analyze_d(991);
+    let json = serde_json::to_string(&report).unwrap();
+    assert!(json.contains("\"period\":60"));
+
+    let parsed: pell991::DReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, report);
+}
+
+#[test]
+fn test_regulator_table_matches_reports() {
+    let table = regulator_table(90..=130);
+    let valid: Vec<u64> = (90..=130).filter(|&d| analyze_d(d).is_valid).collect();
+    assert_eq!(table.iter().map(|row| row.d).collect::<Vec<_>>(), valid, "rows are the valid D in order");
+
+    for row in &table {
+        let report = analyze_d(row.d);
+        assert_eq!(Some(row.regulator), report.regulator, "regulator of D = {}", row.d);
+        assert_eq!(Some(row.period), report.period, "period of D = {}", row.d);
+        assert_eq!(row.class_number, report.class_number, "class number of D = {}", row.d);
+    }
+    assert!(regulator_table(0..=1).is_empty());
+}
+
+#[test]
+fn test_class_number_limit() {
+    // 10¹⁰ + 1 = (10⁵)² + 1 solves instantly, but its class number would take minutes
+    let d = 10_000_000_001;
+    let report = analyze_d(d);
+    assert_eq!(report.period, Some(1), "the Pell equation is still solved");
+    assert_eq!(report.class_number, None, "no class number above CLASS_NUMBER_MAX_D");
+
+    let table = regulator_table(d..=d);
+    assert_eq!(table[0].class_number, None, "the table skips it too");
+    let mut out = Vec::new();
+    write_regulator_csv(&table, &mut out).unwrap();
+    assert!(String::from_utf8(out).unwrap().ends_with(",1,\n"), "a missing class number is an empty field");
+
+    let at_limit = CLASS_NUMBER_MAX_D - 1; // 10⁷ is a perfect square
+    assert_eq!(analyze_d(at_limit).class_number, class_number(at_limit).ok(), "computed up to the limit");
+}
+
+#[test]
+fn test_regulator_csv() {
+    let table = regulator_table(2..=30);
+    let mut out = Vec::new();
+    write_regulator_csv(&table, &mut out).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("d,regulator,period,class_number"));
+    for (line, row) in lines.zip(&table) {
+        let fields: Vec<&str> = line.split(',').collect();
+        assert_eq!(fields[0], row.d.to_string());
+        assert_eq!(fields[1].parse::<f64>().unwrap(), row.regulator, "the regulator survives the round trip");
+        assert_eq!(fields[2..], [row.period.to_string(), row.class_number.unwrap().to_string()]);
+    }
+    assert_eq!(csv.lines().count(), table.len() + 1);
+}
+
+#[test]
+fn test_survey_matches_reports() {
+    let rows = survey(2..=300, &SolveOptions::new()).unwrap();
+    let valid: Vec<u64> = (2..=300).filter(|&d| analyze_d(d).is_valid).collect();
+    assert_eq!(rows.iter().map(|row| row.d).collect::<Vec<_>>(), valid, "one row per valid D, in order");
+
+    for row in &rows {
+        let report = analyze_d(row.d);
+        assert_eq!(row.is_prime, report.is_prime, "primality of D = {}", row.d);
+        assert_eq!(row.squarefree_part, report.squarefree_part, "squarefree part of D = {}", row.d);
+        assert_eq!(Some(row.period), report.period, "period of D = {}", row.d);
+        assert_eq!((Some(row.x_digits), Some(row.y_digits)), (report.x_digits, report.y_digits), "digits of D = {}", row.d);
+        assert_eq!(Some(row.regulator), report.regulator, "regulator of D = {}", row.d);
+    }
+}
+
+#[test]
+fn test_survey_limits() {
+    assert_eq!(survey(990..=992, &SolveOptions::new().max_steps(10)), Err(PellError::LimitExceeded { steps: 10 }));
+    assert!(survey(2..=4, &SolveOptions::new().max_steps(10)).is_ok(), "short periods fit the limit");
+    assert!(survey(16..=16, &SolveOptions::new()).unwrap().is_empty());
+}
+
+#[test]
+fn test_analyze_with_fundamental_matches_solving() {
+    for d in [0, 1, 2, 13, 16, 61, 991, 1_000_003] {
+        let report = match pell991::pell_min_solution_with_period(d) {
+            Ok(((x1, y1), period)) => analyze_d_with_fundamental(d, &x1, &y1, period),
+            Err(_) => analyze_d_with_fundamental(d, &0.into(), &0.into(), 0),
+        };
+        assert_eq!(report, analyze_d(d), "D = {d}");
+    }
+}
@@ -0,0 +1,53 @@
+//! Tests for the leading digits of the solutions
+
+use pell991::benford::benford_probability;
+use pell991::{leading_digit_distribution, pell_solutions, PellError};
+
+#[test]
+fn test_digits_match_the_expanded_solutions() {
+    for d in [2, 3, 7, 13, 61, 991] {
+        let n = 400;
+        let mut expected = [0u64; 9];
+        for (x, _) in pell_solutions(d, n).unwrap() {
+            let first = x.to_string().as_bytes()[0] - b'0';
+            expected[usize::from(first - 1)] += 1;
+        }
+        let stats = leading_digit_distribution(d, n as u64).unwrap();
+        let counts: Vec<u64> = stats.buckets.iter().map(|bucket| bucket.count).collect();
+        assert_eq!(counts, expected, "Leading digits of x₁..x₄₀₀ for D = {}", d);
+    }
+}
+
+#[test]
+fn test_small_solutions() {
+    // x = 3, 17, 99, 577, 3363, 19601, 114243, 665857, 3880899, 22619537
+    let stats = leading_digit_distribution(2, 10).unwrap();
+    let counts: Vec<u64> = stats.buckets.iter().map(|bucket| bucket.count).collect();
+    assert_eq!(counts, [3, 1, 3, 0, 1, 1, 0, 0, 1]);
+    assert_eq!(stats.total, 10);
+    assert!((stats.buckets[0].observed - 0.3).abs() < 1e-12);
+}
+
+#[test]
+fn test_follows_benford() {
+    let total: f64 = (1..=9).map(benford_probability).sum();
+    assert!((total - 1.0).abs() < 1e-12, "the probabilities sum to 1");
+
+    for d in [5, 94, 1_000_003] {
+        let stats = leading_digit_distribution(d, 50_000).unwrap();
+        assert!(stats.total_variation < 0.01, "D = {}: {}", d, stats.total_variation);
+        for bucket in &stats.buckets {
+            assert_eq!(bucket.expected, benford_probability(bucket.digit));
+        }
+        // χ² with 8 degrees of freedom exceeds 26.1 with probability 0.1%
+        assert!(stats.chi_squared < 26.1, "D = {}: χ² = {}", d, stats.chi_squared);
+    }
+}
+
+#[test]
+fn test_empty_and_invalid() {
+    let stats = leading_digit_distribution(3, 0).unwrap();
+    assert_eq!((stats.total, stats.chi_squared, stats.total_variation), (0, 0.0, 0.0));
+    assert_eq!(leading_digit_distribution(1, 10).unwrap_err(), PellError::InvalidD(1));
+    assert_eq!(leading_digit_distribution(81, 10).unwrap_err(), PellError::PerfectSquare(81));
+}
@@ -0,0 +1,81 @@
+//! Tests for the compact binary encoding
+
+#![cfg(feature = "binary")]
+
+use pell991::{decode_solution, encode_solution, pell_min_solution, BigInt, PellError, PellSolution, SolutionView};
+
+fn solution_991() -> PellSolution {
+    let (x, y) = pell_min_solution(991).unwrap();
+    PellSolution::with_index(991, 1, x, y)
+}
+
+#[test]
+fn test_round_trip() {
+    let solutions = [
+        solution_991(),
+        PellSolution::new(2, BigInt::from(3), BigInt::from(2)),
+        PellSolution::new(2, BigInt::from(-17), BigInt::from(-12)),
+        PellSolution::with_index(5, 0, BigInt::from(1), BigInt::from(0)),
+    ];
+    for solution in solutions {
+        let decoded = decode_solution(&encode_solution(&solution)).unwrap();
+        assert_eq!(decoded, solution, "{} should round-trip", solution);
+        assert_eq!(decoded.k, solution.k, "k of {} should round-trip", solution);
+    }
+}
+
+#[test]
+fn test_layout() {
+    let solution: PellSolution = "x=-17, y=12, d=2, k=2".parse().unwrap();
+    let bytes = encode_solution(&solution);
+
+    assert_eq!(bytes.len(), 56, "40-byte header plus one limb each");
+    assert_eq!(&bytes[..8], b"PELL\x01\x03\x00\x00", "magic, version and flags");
+    assert_eq!(bytes[8..16], 2u64.to_le_bytes(), "D");
+    assert_eq!(bytes[16..24], 2u64.to_le_bytes(), "k");
+    assert_eq!(bytes[24..32], 1u64.to_le_bytes(), "limbs of x");
+    assert_eq!(bytes[40..48], 17u64.to_le_bytes(), "|x|");
+    assert_eq!(bytes[48..56], 12u64.to_le_bytes(), "|y|");
+}
+
+#[test]
+fn test_view_borrows_limbs() {
+    let solution = solution_991();
+    let bytes = encode_solution(&solution);
+    let view = SolutionView::new(&bytes).unwrap();
+
+    assert_eq!((view.d(), view.k()), (991, Some(1)));
+    assert_eq!(view.x_limbs().len(), 16, "x has 30 digits, so two limbs");
+    assert_eq!(BigInt::from_bytes_le(num_bigint::Sign::Plus, view.y_limbs()), solution.y);
+    assert_eq!(view.to_solution(), solution);
+}
+
+#[test]
+fn test_rejects_malformed_input() {
+    let bytes = encode_solution(&solution_991());
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] = b'X';
+    let mut bad_version = bytes.clone();
+    bad_version[4] = 2;
+    let mut bad_flags = bytes.clone();
+    bad_flags[5] |= 0x80;
+    let mut huge_count = bytes.clone();
+    huge_count[24..32].copy_from_slice(&u64::MAX.to_le_bytes());
+
+    let cases: [(&str, &[u8]); 6] = [
+        ("empty", &[]),
+        ("truncated", &bytes[..bytes.len() - 1]),
+        ("bad magic", &bad_magic),
+        ("bad version", &bad_version),
+        ("bad flags", &bad_flags),
+        ("huge limb count", &huge_count),
+    ];
+    for (name, input) in cases {
+        assert!(
+            matches!(decode_solution(input), Err(PellError::Decode(_))),
+            "{} input should be rejected",
+            name
+        );
+    }
+}
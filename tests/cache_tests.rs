@@ -0,0 +1,106 @@
+//! Tests for the fundamental solution cache
+
+#![cfg(feature = "std")]
+
+use pell991::{
+    disable_solution_cache, enable_solution_cache, pell_min_solution, pell_min_solution_with_period,
+    pell_solutions, solution_cache_snapshot, PellError, PellSolutionIterator, SolutionCache,
+};
+
+#[test]
+fn test_cache_hits_and_misses() {
+    let mut cache = SolutionCache::new(4);
+    assert!(cache.is_empty());
+    assert_eq!(cache.get(991), None);
+
+    let solved = cache.get_or_solve(991).unwrap();
+    assert_eq!(solved, pell_min_solution(991).unwrap());
+    assert!(cache.contains(991));
+
+    let (cached, period) = cache.get_or_solve_with_period(991).unwrap();
+    assert_eq!(cached, solved);
+    assert_eq!(period, 60);
+    assert_eq!((cache.hits(), cache.misses()), (1, 2));
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert_eq!((cache.hits(), cache.misses()), (0, 0));
+}
+
+#[test]
+fn test_cache_evicts_least_recently_used() {
+    let mut cache = SolutionCache::new(2);
+    cache.get_or_solve(2).unwrap();
+    cache.get_or_solve(3).unwrap();
+
+    // Touch D = 2 so that D = 3 becomes the least recently used
+    assert!(cache.get(2).is_some());
+    cache.get_or_solve(5).unwrap();
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(2), "Recently used D = 2 should be kept");
+    assert!(!cache.contains(3), "Least recently used D = 3 should be evicted");
+    assert!(cache.contains(5));
+}
+
+#[test]
+fn test_cache_reinsert_refreshes_recency() {
+    let mut cache = SolutionCache::new(3);
+    for d in [2, 3, 5] {
+        cache.get_or_solve(d).unwrap();
+    }
+
+    // Re-inserting D = 2 makes D = 3 the oldest without growing the cache
+    cache.insert(2, pell_min_solution(2).unwrap(), 1);
+    assert_eq!(cache.len(), 3);
+    cache.get_or_solve(6).unwrap();
+    cache.get_or_solve(7).unwrap();
+
+    assert!(cache.contains(2), "Re-inserted D = 2 should be kept");
+    assert!(!cache.contains(3) && !cache.contains(5), "The two oldest D values should be evicted");
+    assert_eq!(cache.len(), 3);
+}
+
+#[test]
+fn test_cache_zero_capacity_and_errors() {
+    let mut cache = SolutionCache::new(0);
+    assert!(cache.get_or_solve(2).is_ok());
+    assert!(cache.is_empty());
+
+    let mut cache = SolutionCache::new(2);
+    assert_eq!(cache.get_or_solve(1), Err(PellError::InvalidD(1)));
+    assert_eq!(cache.get_or_solve(16), Err(PellError::PerfectSquare(16)));
+    assert!(cache.is_empty(), "Errors should not be cached");
+}
+
+// The process-wide cache is shared by all tests in this binary, so everything
+// touching it lives in one test
+#[test]
+fn test_global_cache() {
+    assert!(solution_cache_snapshot().is_none());
+
+    enable_solution_cache(8);
+    let expected = pell_min_solution_with_period(991).unwrap();
+    assert_eq!(pell_min_solution_with_period(991).unwrap(), expected);
+    assert_eq!(pell_solutions(991, 3).unwrap()[0], expected.0);
+    let mut iter = PellSolutionIterator::new(991).unwrap();
+    assert_eq!(iter.next().unwrap(), expected.0);
+
+    let snapshot = solution_cache_snapshot().unwrap();
+    assert_eq!(snapshot.len(), 1);
+    assert_eq!((snapshot.hits(), snapshot.misses()), (3, 1));
+
+    // Invalid D never reaches the cache
+    assert!(pell_min_solution(49).is_err());
+    assert_eq!(solution_cache_snapshot().unwrap().misses(), 1);
+
+    // A zero-capacity cache is never consulted
+    enable_solution_cache(0);
+    assert_eq!(pell_min_solution_with_period(991).unwrap(), expected);
+    let snapshot = solution_cache_snapshot().unwrap();
+    assert_eq!((snapshot.len(), snapshot.hits(), snapshot.misses()), (0, 0, 0));
+
+    disable_solution_cache();
+    assert!(solution_cache_snapshot().is_none());
+    assert_eq!(pell_min_solution_with_period(991).unwrap(), expected);
+}
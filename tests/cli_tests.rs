@@ -0,0 +1,469 @@
+//! Tests for the `pell` command-line tool
+
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use pell991::PellSolution;
+
+/// Run the `pell` binary with `args`, ignoring any config file of the user
+fn pell(args: &[&str]) -> Output {
+    pell_with_config_home(args, &scratch_dir("no-config"))
+}
+
+/// Run the `pell` binary with `args` and `config_home` as $XDG_CONFIG_HOME
+fn pell_with_config_home(args: &[&str], config_home: &PathBuf) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_pell"))
+        .args(args)
+        .env("XDG_CONFIG_HOME", config_home)
+        .output()
+        .expect("failed to run pell")
+}
+
+/// A fresh, empty directory for one test
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("pell-cli-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn test_solve() {
+    let output = pell(&["solve", "991"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "x = 379516400906811930638014896080\ny = 12055735790331359447442538767\n"
+    );
+}
+
+#[test]
+fn test_solve_k_and_count() {
+    let output = pell(&["solve", "2", "--k", "5"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "x = 3363\ny = 2378\n");
+
+    let output = pell(&["solve", "2", "--count", "3"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "x=3, y=2, d=2, k=1\nx=17, y=12, d=2, k=2\nx=99, y=70, d=2, k=3\n");
+
+    let output = pell(&["solve", "2", "--k", "2", "--count", "3"]);
+    assert_eq!(output.status.code(), Some(2), "--k and --count are exclusive");
+}
+
+#[test]
+fn test_solve_hex_d() {
+    assert_eq!(stdout(&pell(&["solve", "0x3DF"])), stdout(&pell(&["solve", "991"])));
+    assert_eq!(stdout(&pell(&["solve", "0Xd"])), "x = 649\ny = 180\n");
+
+    let output = pell(&["solve", "0xZZ"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("invalid value '0xZZ' for '<D>': D must be a decimal or 0x-prefixed"),
+        "Unexpected error: {}", stderr(&output));
+}
+
+#[test]
+fn test_solve_reports_library_errors() {
+    let output = pell(&["solve", "16"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stdout(&output).is_empty());
+    assert_eq!(stderr(&output), "error: D must be non-square, got 16 which is 4²\n");
+
+    let output = pell(&["solve", "2", "--k", "0"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).starts_with("error: "));
+}
+
+#[test]
+fn test_negative() {
+    let output = pell(&["negative", "13"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "x = 18\ny = 5\n");
+
+    let output = pell(&["negative", "34"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).is_empty());
+    assert_eq!(
+        stderr(&output),
+        "error: x² - 34·y² = -1 has no solution: the continued fraction of √D has even period 4\n"
+    );
+}
+
+#[test]
+fn test_general() {
+    let output = pell(&["general", "2", "7"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "class 1: (-3, 1)\nclass 2: (3, 1)\n");
+
+    let output = pell(&["general", "2", "-1", "--count", "3"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "class 1: (1, 1)\nclass 1: (7, 5)\nclass 1: (41, 29)\n");
+
+    let output = pell(&["general", "3", "2"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).is_empty());
+    assert_eq!(stderr(&output), "error: x² - 3·y² = 2 has no solutions\n");
+
+    let output = pell(&["general", "2", "0"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stderr(&output), "error: N must be non-zero, got 0\n");
+}
+
+#[test]
+fn test_cf() {
+    let output = pell(&["cf", "13"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "√13 = [3; (1, 1, 1, 1, 6)]\n");
+
+    let output = pell(&["cf", "2", "--convergents", "4"]);
+    assert_eq!(stdout(&output), "√2 = [1; (2)]\n0: 1/1\n1: 3/2\n2: 7/5\n3: 17/12\n");
+
+    let output = pell(&["cf", "991"]);
+    assert!(stdout(&output).starts_with("√991 = [31; (2, 12, 10, "));
+
+    let output = pell(&["cf", "25"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).starts_with("error: "));
+}
+
+#[test]
+fn test_verify() {
+    let output = pell(&["verify", "2", "17", "12"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("valid"));
+
+    let output = pell(&["verify", "2", "17", "-12"]);
+    assert!(output.status.success(), "Signs should not matter");
+
+    let output = pell(&["verify", "2", "18", "12"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).is_empty());
+    assert!(stderr(&output).starts_with("error: invalid"), "stderr: {}", stderr(&output));
+}
+
+#[test]
+fn test_analyze() {
+    let output = pell(&["analyze", "991"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.contains("period:                  60\n"), "Unexpected report:\n{}", text);
+    assert!(text.contains("class number:            1\n"), "Unexpected report:\n{}", text);
+}
+
+#[test]
+fn test_argument_validation() {
+    // Missing and malformed arguments are usage errors (status 2) with help text
+    let output = pell(&[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Usage: pell [OPTIONS] <COMMAND>"));
+
+    let output = pell(&["solve", "abc"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("invalid value 'abc' for '<D>': D must be"), "stderr: {}", stderr(&output));
+    assert!(!stderr(&output).contains("solution string"), "D is not a solution string: {}", stderr(&output));
+
+    let output = pell(&["verify", "2", "3"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_search_filters() {
+    let output = pell(&["search", "--min-d", "10", "--max-d", "30", "--period-over", "4"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "13 5 3\n19 6 3\n21 6 2\n22 6 3\n29 5 4\n");
+    assert!(stderr(&output).contains("scanned 19 D values, 5 matched"), "Unexpected summary: {}", stderr(&output));
+
+    let output = pell(&["search", "--max-d", "1000", "--prime-d", "--min-digits", "30"]);
+    let ds: Vec<u64> = stdout(&output).lines().map(|line| line.split(' ').next().unwrap().parse().unwrap()).collect();
+    assert!(ds.contains(&991));
+    assert!(ds.windows(2).all(|pair| pair[0] < pair[1]), "Matches should be in order of D");
+    assert!(stderr(&output).contains("most digits: 38 (D = 661)"), "Unexpected summary: {}", stderr(&output));
+}
+
+#[test]
+fn test_search_without_matches() {
+    let output = pell(&["search", "--min-d", "50", "--max-d", "10"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).is_empty());
+    assert_eq!(stderr(&output), "scanned 0 D values, 0 matched\n");
+
+    assert_eq!(pell(&["search"]).status.code(), Some(2), "--max-d is required");
+}
+
+#[test]
+fn test_records() {
+    let output = pell(&["records", "--max-d", "200"]);
+    assert!(output.status.success());
+    let ds: Vec<u64> = stdout(&output).lines().map(|line| line.split(' ').next().unwrap().parse().unwrap()).collect();
+    assert_eq!(ds, [2, 3, 7, 13, 19, 31, 43, 46, 94, 139, 151, 166]);
+
+    let output = pell(&["records", "--max-d", "1000", "--by", "digits"]);
+    let text = stdout(&output);
+    assert!(text.starts_with("2 1\n10 2\n13 3\n"), "Unexpected records:\n{}", text);
+    assert!(text.ends_with("421 34\n541 37\n661 38\n"), "Unexpected records:\n{}", text);
+
+    assert_eq!(pell(&["records", "--max-d", "10", "--by", "size"]).status.code(), Some(2));
+}
+
+#[test]
+fn test_csv_format() {
+    let output = pell(&["solve", "2", "--count", "3", "--format", "csv"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "d,k,x,y\n2,1,3,2\n2,2,17,12\n2,3,99,70\n");
+
+    let output = pell(&["solve", "2", "--k", "5", "--format", "csv"]);
+    assert_eq!(stdout(&output), "d,k,x,y\n2,5,3363,2378\n");
+
+    let output = pell(&["search", "--min-d", "10", "--max-d", "30", "--period-over", "4", "--format", "csv"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "d,period,digits\n13,5,3\n19,6,3\n21,6,2\n22,6,3\n29,5,4\n");
+
+    let output = pell(&["records", "--max-d", "20", "--by", "digits", "--format", "csv"]);
+    assert_eq!(stdout(&output), "d,digits\n2,1\n10,2\n13,3\n");
+
+    assert_eq!(pell(&["solve", "2", "--format", "yaml"]).status.code(), Some(2));
+}
+
+#[test]
+fn test_config_file() {
+    let dir = scratch_dir("config");
+    let config = dir.join("pell.toml");
+    fs::write(&config, "format = \"csv\"\nmax-steps = 10\nthreads = 2\n").unwrap();
+    let config = config.to_str().unwrap();
+
+    let output = pell(&["--config", config, "solve", "2", "--count", "2"]);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert_eq!(stdout(&output), "d,k,x,y\n2,1,3,2\n2,2,17,12\n");
+
+    // The command line wins over the config file
+    let output = pell(&["solve", "2", "--count", "2", "--format", "text", "--config", config]);
+    assert_eq!(stdout(&output), "x=3, y=2, d=2, k=1\nx=17, y=12, d=2, k=2\n");
+
+    let output = pell(&["--config", config, "solve", "991"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(stderr(&output), "error: no solution found within 10 steps\n");
+    let output = pell(&["--config", config, "solve", "991", "--max-steps", "1000", "--format", "text"]);
+    assert!(stdout(&output).starts_with("x = 379516400906811930638014896080\n"));
+}
+
+#[test]
+fn test_default_config_location() {
+    let config_home = scratch_dir("config-home");
+    fs::create_dir_all(config_home.join("pell")).unwrap();
+    fs::write(config_home.join("pell").join("config.toml"), "format = \"csv\"\n").unwrap();
+
+    let output = pell_with_config_home(&["records", "--max-d", "20", "--by", "digits"], &config_home);
+    assert_eq!(stdout(&output), "d,digits\n2,1\n10,2\n13,3\n");
+}
+
+#[test]
+fn test_config_errors() {
+    let dir = scratch_dir("config-errors");
+    let config = dir.join("pell.toml");
+    fs::write(&config, "colour = \"blue\"\n").unwrap();
+
+    let output = pell(&["--config", config.to_str().unwrap(), "solve", "2"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).starts_with("error: invalid config file"), "stderr: {}", stderr(&output));
+    assert!(stderr(&output).contains("colour"), "stderr: {}", stderr(&output));
+
+    let missing = dir.join("missing.toml");
+    let output = pell(&["--config", missing.to_str().unwrap(), "solve", "2"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stderr(&output).starts_with("error: cannot read config file"), "stderr: {}", stderr(&output));
+}
+
+#[test]
+fn test_cache_dir() {
+    let dir = scratch_dir("cache");
+    let cache_dir = dir.join("cache");
+    let cache_arg = cache_dir.to_str().unwrap();
+
+    let output = pell(&["solve", "991", "--cache-dir", cache_arg]);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    let cached = fs::read_to_string(cache_dir.join("solutions.cache")).unwrap();
+    assert!(cached.contains("\n991 60 379516400906811930638014896080 12055735790331359447442538767\n"), "cache: {}", cached);
+
+    // A cached solution needs no solving, so even a tiny step limit succeeds
+    let output = pell(&["solve", "991", "--k", "2", "--cache-dir", cache_arg, "--max-steps", "1"]);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+
+    // The other subcommands read the cache too, and the parallel scans leave it as it is
+    let output = pell(&["euler66", "--max-d", "991", "--cache-dir", cache_arg, "--max-steps", "1"]);
+    assert_eq!(output.status.code(), Some(4), "Every other D is still solved under the limit");
+    let output = pell(&["negative", "991", "--cache-dir", cache_arg, "--max-steps", "1"]);
+    assert_eq!(output.status.code(), Some(1), "stderr: {}", stderr(&output));
+    assert_eq!(fs::read_to_string(cache_dir.join("solutions.cache")).unwrap(), cached);
+}
+
+#[test]
+fn test_max_steps_every_subcommand() {
+    for args in [
+        &["negative", "991"][..],
+        &["general", "991", "1"],
+        &["cf", "991"],
+        &["analyze", "991"],
+        &["euler66", "--max-d", "100"],
+    ] {
+        let output = pell(&[args, &["--max-steps", "20"][..]].concat());
+        assert_eq!(output.status.code(), Some(4), "Exit status for {:?}", args);
+        assert_eq!(stderr(&output), "error: no solution found within 20 steps\n");
+    }
+
+    // The scans skip the D values they cannot solve, and say how many
+    let output = pell(&["search", "--max-d", "100", "--max-steps", "20"]);
+    assert!(output.status.success());
+    assert!(stderr(&output).contains("skipped 2 D values at the step limit"), "stderr: {}", stderr(&output));
+    let output = pell(&["records", "--max-d", "100", "--by", "digits", "--max-steps", "20"]);
+    assert!(output.status.success());
+    assert!(stderr(&output).contains("skipped 2 D values at the step limit"), "stderr: {}", stderr(&output));
+}
+
+#[test]
+fn test_output_file_and_summary() {
+    let dir = scratch_dir("output");
+    let path = dir.join("solutions.txt");
+    let path_arg = path.to_str().unwrap();
+
+    let output = pell(&["solve", "991", "--count", "3", "--output", path_arg]);
+    assert!(output.status.success(), "stderr: {}", stderr(&output));
+    assert!(stdout(&output).is_empty());
+    let lines: Vec<PellSolution> = fs::read_to_string(&path).unwrap().lines().map(|line| line.parse().unwrap()).collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[2].k, Some(3));
+    assert!(lines.iter().all(|solution| solution.verify()));
+
+    // sha256("3") and sha256("2")
+    let output = pell(&["solve", "2", "--summary", "--output", path_arg]);
+    assert_eq!(
+        stdout(&output),
+        "k=1 x digits=1 sha256=4e07408562bedb8b60ce05c1decfe3ad16b72230967de01f640b7e4729b49fce\n\
+         k=1 y digits=1 sha256=d4735e3a265e16eee03f59718b9b5d03019c07d8b6c51f90da3a666eec13ab35\n"
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), "x=3, y=2, d=2, k=1\n");
+
+    let output = pell(&["solve", "991", "--k", "10", "--summary"]);
+    let text = stdout(&output);
+    assert!(text.starts_with("k=10 x digits=299 sha256="), "Unexpected summary: {}", text);
+    assert_eq!(text.lines().count(), 2);
+
+    let output = pell(&["solve", "2", "--output", dir.join("missing").join("file").to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(5));
+    assert!(stderr(&output).starts_with("error: cannot create"), "stderr: {}", stderr(&output));
+}
+
+#[test]
+fn test_stormer() {
+    let output = pell(&["stormer", "--primes", "2,3"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "1 2\n2 3\n3 4\n8 9\n");
+
+    let output = pell(&["stormer", "--primes", "7,5,3,2"]);
+    assert_eq!(stdout(&output).lines().count(), 23);
+    assert!(stdout(&output).ends_with("4374 4375\n"));
+
+    let output = pell(&["stormer", "--primes", "2,4"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stderr(&output), "error: invalid prime set: 4 is not prime\n");
+    assert_eq!(pell(&["stormer"]).status.code(), Some(2));
+}
+
+#[test]
+fn test_euler66() {
+    let output = pell(&["euler66", "--max-d", "1000"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "D = 661\nx = 16421658242965910275055840472270471049\ny = 638728478116949861246791167518480580\n"
+    );
+    assert!(stderr(&output).starts_with("solved 969 equations in "), "stderr: {}", stderr(&output));
+
+    // The example of the problem statement
+    let output = pell(&["euler66", "--max-d", "7"]);
+    assert_eq!(stdout(&output), "D = 5\nx = 9\ny = 4\n");
+
+    let output = pell(&["euler66", "--max-d", "1"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stderr(&output), "error: D must be > 1, got 1\n");
+}
+
+#[test]
+fn test_exit_codes() {
+    // 1: no solution, 2: usage, 3: invalid input, 4: limit reached, 5: I/O
+    assert_eq!(pell(&["negative", "3"]).status.code(), Some(1));
+    assert_eq!(pell(&["solve"]).status.code(), Some(2));
+    assert_eq!(pell(&["solve", "1"]).status.code(), Some(3));
+    assert_eq!(pell(&["solve", "0x"]).status.code(), Some(2));
+    assert_eq!(pell(&["solve", "991", "--max-steps", "5"]).status.code(), Some(4));
+    assert_eq!(pell(&["solve", "2", "--cache-dir", "/dev/null/cache"]).status.code(), Some(5));
+}
+
+#[test]
+fn test_json_format() {
+    let output = pell(&["solve", "2", "--count", "2", "--format", "json"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "{\"d\":2,\"k\":1,\"x\":\"3\",\"y\":\"2\"}\n{\"d\":2,\"k\":2,\"x\":\"17\",\"y\":\"12\"}\n");
+
+    let output = pell(&["--format", "json", "search", "--min-d", "10", "--max-d", "14", "--period-over", "4"]);
+    assert_eq!(stdout(&output), "{\"d\":13,\"period\":5,\"digits\":3}\n");
+
+    let output = pell(&["records", "--max-d", "3", "--format", "json"]);
+    assert_eq!(stdout(&output), "{\"d\":2,\"period\":1}\n{\"d\":3,\"period\":2}\n");
+
+    let output = pell(&["solve", "16", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(stdout(&output).is_empty());
+    assert_eq!(
+        stderr(&output),
+        "{\"error\":{\"kind\":\"perfect_square\",\"exit_code\":3,\"message\":\"D must be non-square, got 16 which is 4²\"}}\n"
+    );
+
+    let output = pell(&["negative", "34", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("{\"error\":{\"kind\":\"negative_unsolvable\",\"exit_code\":1,"));
+
+    let output = pell(&["solve", "991", "--max-steps", "5", "--format", "json"]);
+    assert!(stderr(&output).starts_with("{\"error\":{\"kind\":\"limit_exceeded\",\"exit_code\":4,"));
+
+    let output = pell(&["general", "3", "2", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).is_empty());
+    assert!(stderr(&output).starts_with("{\"error\":{\"kind\":\"no_solutions\",\"exit_code\":1,"));
+
+    let output = pell(&["verify", "2", "18", "12", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).is_empty());
+    assert!(stderr(&output).starts_with("{\"error\":{\"kind\":\"invalid_solution\",\"exit_code\":1,"));
+
+    // Usage errors are reported as JSON too, with clap's exit status
+    for args in [&["--format", "json", "solve", "abc"][..], &["--format=json", "solve", "2", "--count", "x"]] {
+        let output = pell(args);
+        assert_eq!(output.status.code(), Some(2), "Exit status for {:?}", args);
+        let text = stderr(&output);
+        let expected = "{\"error\":{\"kind\":\"usage\",\"exit_code\":2,\"message\":\"invalid value";
+        assert!(text.starts_with(expected), "stderr: {}", text);
+        assert_eq!(text.lines().count(), 1, "stderr: {}", text);
+    }
+    assert!(pell(&["--format", "json", "--version"]).status.success(), "Version is not an error");
+
+    // Quotes in messages are escaped
+    let dir = scratch_dir("json-config");
+    let config = dir.join("pell.toml");
+    fs::write(&config, "format = \"json\"\nthreads = \"many\"\n").unwrap();
+    let output = pell(&["--config", config.to_str().unwrap(), "--format", "json", "solve", "2"]);
+    assert_eq!(output.status.code(), Some(3));
+    let text = stderr(&output);
+    assert!(text.starts_with("{\"error\":{\"kind\":\"config\",\"exit_code\":3,\"message\":\"invalid config file"), "stderr: {}", text);
+    assert!(text.contains("\\\"many\\\""), "stderr: {}", text);
+}
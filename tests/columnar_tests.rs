@@ -0,0 +1,117 @@
+//! Tests for Arrow and Parquet export
+
+#![cfg(feature = "arrow")]
+
+use arrow_array::{Array, BinaryArray, Float64Array, StringArray, UInt64Array};
+use pell991::{
+    analyze_d, pell_min_solution, regulator_rows_to_record_batch, regulator_table, reports_to_record_batch,
+    solutions_to_record_batch, BigInt, BigIntEncoding, DReport, PellSolution,
+};
+
+fn sample_solutions() -> Vec<PellSolution> {
+    let (x, y) = pell_min_solution(991).unwrap();
+    vec![
+        PellSolution::with_index(991, 1, x, y),
+        PellSolution::new(2, BigInt::from(-3), BigInt::from(2)),
+    ]
+}
+
+#[test]
+fn test_decimal_columns() {
+    let batch = solutions_to_record_batch(&sample_solutions(), BigIntEncoding::Decimal).unwrap();
+    let x = batch.column_by_name("x").unwrap().as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(x.value(0), "379516400906811930638014896080");
+    assert_eq!(x.value(1), "-3");
+
+    let k = batch.column_by_name("k").unwrap().as_any().downcast_ref::<UInt64Array>().unwrap();
+    assert_eq!(k.value(0), 1);
+    assert!(k.is_null(1), "an unknown index is null");
+}
+
+#[test]
+fn test_binary_columns_round_trip() {
+    let solutions = sample_solutions();
+    let batch = solutions_to_record_batch(&solutions, BigIntEncoding::Binary).unwrap();
+    let y = batch.column_by_name("y").unwrap().as_any().downcast_ref::<BinaryArray>().unwrap();
+    let x = batch.column_by_name("x").unwrap().as_any().downcast_ref::<BinaryArray>().unwrap();
+    for (i, solution) in solutions.iter().enumerate() {
+        assert_eq!(BigInt::from_signed_bytes_le(x.value(i)), solution.x, "x of row {}", i);
+        assert_eq!(BigInt::from_signed_bytes_le(y.value(i)), solution.y, "y of row {}", i);
+    }
+}
+
+#[test]
+fn test_report_columns() {
+    let reports: Vec<DReport> = [4, 13, 991].into_iter().map(analyze_d).collect();
+    let batch = reports_to_record_batch(&reports).unwrap();
+    assert_eq!(batch.num_columns(), 10);
+
+    let period = batch.column_by_name("period").unwrap().as_any().downcast_ref::<UInt64Array>().unwrap();
+    assert!(period.is_null(0), "4 is a square");
+    assert_eq!(period.value(1), 5);
+    assert_eq!(period.value(2), reports[2].period.unwrap());
+}
+
+#[test]
+fn test_regulator_columns() {
+    let rows = regulator_table(2..=50);
+    let batch = regulator_rows_to_record_batch(&rows).unwrap();
+    assert_eq!(batch.num_rows(), rows.len());
+    let names: Vec<String> = batch.schema().fields().iter().map(|f| f.name().clone()).collect();
+    assert_eq!(names, ["d", "regulator", "period", "class_number"]);
+
+    let regulator = batch.column_by_name("regulator").unwrap().as_any().downcast_ref::<Float64Array>().unwrap();
+    let class_number = batch.column_by_name("class_number").unwrap().as_any().downcast_ref::<UInt64Array>().unwrap();
+    for (i, row) in rows.iter().enumerate() {
+        assert_eq!(regulator.value(i), row.regulator, "regulator of row {}", i);
+        assert_eq!(Some(class_number.value(i)), row.class_number, "class number of row {}", i);
+    }
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_regulator_parquet() {
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let path = std::env::temp_dir().join(format!("pell991-regulators-{}.parquet", std::process::id()));
+    let rows = regulator_table(2..=200);
+    pell991::write_regulator_parquet(&rows, std::fs::File::create(&path).unwrap()).unwrap();
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+        .unwrap()
+        .build()
+        .unwrap();
+    let batches: Vec<_> = reader.map(Result::unwrap).collect();
+    std::fs::remove_file(&path).unwrap();
+    let expected = regulator_rows_to_record_batch(&rows).unwrap();
+    assert_eq!(batches[0].columns(), expected.columns(), "columns should survive the file");
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_parquet_round_trip() {
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let path = std::env::temp_dir().join(format!("pell991-columnar-{}.parquet", std::process::id()));
+    let solutions = sample_solutions();
+    pell991::write_solutions_parquet(&solutions, BigIntEncoding::Decimal, std::fs::File::create(&path).unwrap()).unwrap();
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+        .unwrap()
+        .build()
+        .unwrap();
+    let batches: Vec<_> = reader.map(Result::unwrap).collect();
+    std::fs::remove_file(&path).unwrap();
+
+    let expected = solutions_to_record_batch(&solutions, BigIntEncoding::Decimal).unwrap();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].columns(), expected.columns(), "columns should survive the file");
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_write_parquet_without_batches_writes_nothing() {
+    let mut out = Vec::new();
+    pell991::write_parquet(&[], &mut out).unwrap();
+    assert!(out.is_empty());
+}
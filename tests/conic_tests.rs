@@ -0,0 +1,73 @@
+use num_bigint::BigInt;
+use pell991::{pell_min_solution, pell_solution_k, ConicPoint};
+
+#[test]
+fn test_new_checks_the_conic() {
+    assert!(ConicPoint::new(13, BigInt::from(649), BigInt::from(180)).is_some(), "(649, 180) is on D = 13");
+    assert!(ConicPoint::new(13, BigInt::from(649), BigInt::from(181)).is_none(), "(649, 181) is not");
+    assert!(ConicPoint::new(2, BigInt::from(-3), BigInt::from(2)).is_some(), "the branch x < 0 is on the conic");
+    assert!(ConicPoint::new(4, BigInt::from(-1), BigInt::from(0)).is_some(), "(±1, 0) lies on every conic");
+    assert!(ConicPoint::new(4, BigInt::from(1), BigInt::from(1)).is_none(), "D = 4 has no other points");
+    assert!(ConicPoint::new(0, BigInt::from(1), BigInt::from(5)).is_some(), "D = 0 allows any y with x = ±1");
+}
+
+#[test]
+fn test_fundamental_matches_min_solution() {
+    let point = ConicPoint::fundamental(991).unwrap();
+    let (x, y) = pell_min_solution(991).unwrap();
+    assert_eq!((point.x(), point.y()), (&x, &y), "fundamental point of D = 991");
+    assert_eq!(point.d(), 991, "the point keeps its D");
+    assert!(ConicPoint::fundamental(16).is_err(), "perfect squares have no fundamental solution");
+}
+
+#[test]
+fn test_multiples_are_the_solutions() {
+    for d in [2, 7, 13, 61, 991] {
+        let p = ConicPoint::fundamental(d).unwrap();
+        let (x1, y1) = pell_min_solution(d).unwrap();
+        for k in 1..=12 {
+            let (x, y) = pell_solution_k(d, &x1, &y1, k).unwrap();
+            let expected: (BigInt, BigInt) = (&p * k as i64).into();
+            assert_eq!(expected, (x, y), "{k}·P for D = {d}");
+        }
+        assert!(p.multiple(0).is_identity(), "0·P is the identity for D = {d}");
+    }
+}
+
+#[test]
+fn test_group_axioms() {
+    let p = ConicPoint::fundamental(61).unwrap();
+    let q = &p * 3;
+    let r = -(&p * 5);
+    let identity = ConicPoint::identity(61);
+
+    assert_eq!(&p + &identity, p, "identity on the right");
+    assert_eq!(&identity + &p, p, "identity on the left");
+    assert!((&p + &-&p).is_identity(), "P + (-P) is the identity");
+    assert_eq!(&p + &q, &q + &p, "the group is commutative");
+    assert_eq!(&(&p + &q) + &r, &p + &(&q + &r), "the group is associative");
+    assert_eq!(&q - &p, &p * 2, "3P - P = 2P");
+    assert_eq!(&p * -4, -(&p * 4), "negative multiples are inverses");
+    assert_eq!(p.clone() + q.clone(), &p * 4, "owned and borrowed addition agree");
+}
+
+#[test]
+fn test_sums_stay_on_the_conic() {
+    let p = ConicPoint::new(7, BigInt::from(-8), BigInt::from(3)).unwrap();
+    let q = ConicPoint::fundamental(7).unwrap();
+    for point in [&p + &q, &p * 7, &q - &p, -&p * -3] {
+        let (x, y) = point.clone().into();
+        assert!(ConicPoint::new(7, x, y).is_some(), "{point} is on the conic");
+    }
+}
+
+#[test]
+#[should_panic(expected = "different conics")]
+fn test_adding_different_d_panics() {
+    let _ = ConicPoint::fundamental(2).unwrap() + ConicPoint::fundamental(3).unwrap();
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(ConicPoint::fundamental(13).unwrap().to_string(), "(649, 180)", "Display");
+}
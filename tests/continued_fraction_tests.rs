@@ -0,0 +1,184 @@
+//! Tests for the continued fraction expansion of √D
+
+use pell991::continued_fraction::{approximation_report, cf_structure_exceptions, convergence_series};
+use pell991::{cf_period_is_palindromic, cf_structure, continued_fraction, is_valid_pell_d, nth_convergent, pell_min_solution_with_period, verify_pell_solution, PellError};
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+#[test]
+fn test_known_expansions() {
+    let known: [(u64, u64, &[u64]); 5] = [
+        (2, 1, &[2]),
+        (3, 1, &[1, 2]),
+        (7, 2, &[1, 1, 1, 4]),
+        (13, 3, &[1, 1, 1, 1, 6]),
+        (61, 7, &[1, 4, 3, 1, 2, 2, 1, 3, 4, 1, 14]),
+    ];
+    for (d, a0, period) in known {
+        let cf = continued_fraction(d).unwrap();
+        assert_eq!(cf.a0, a0, "Wrong integer part for D = {}", d);
+        assert_eq!(cf.period, period, "Wrong period for D = {}", d);
+    }
+    assert_eq!(continued_fraction(991).unwrap().to_string().split(", ").count(), 60);
+}
+
+#[test]
+fn test_period_matches_solver() {
+    for d in (2..2000).filter(|&d| is_valid_pell_d(d)) {
+        let cf = continued_fraction(d).unwrap();
+        let (_, period) = pell_min_solution_with_period(d).unwrap();
+        assert_eq!(cf.period.len() as u64, period, "Period mismatch for D = {}", d);
+        assert_eq!(*cf.period.last().unwrap(), 2 * cf.a0, "Period should end with 2a₀ for D = {}", d);
+        // The period without its last term is a palindrome
+        let body = &cf.period[..cf.period.len() - 1];
+        assert!(body.iter().eq(body.iter().rev()), "Period body not palindromic for D = {}", d);
+    }
+}
+
+#[test]
+fn test_terms_and_convergents() {
+    let cf = continued_fraction(7).unwrap();
+    let terms: Vec<u64> = cf.terms().take(10).collect();
+    assert_eq!(terms, [2, 1, 1, 1, 4, 1, 1, 1, 4, 1]);
+
+    // The convergent at the end of the (even) period solves the Pell equation
+    let (p, q) = cf.convergents().nth(cf.period.len() - 1).unwrap();
+    assert_eq!((p.clone(), q.clone()), (BigInt::from(8), BigInt::from(3)));
+    assert!(verify_pell_solution(7, &p, &q));
+
+    let cf = continued_fraction(991).unwrap();
+    let (p, q) = cf.convergents().nth(59).unwrap();
+    assert!(verify_pell_solution(991, &p, &q));
+}
+
+#[test]
+fn test_structure_symmetries() {
+    for d in (2..5000).filter(|&d| is_valid_pell_d(d)) {
+        let structure = cf_structure(d).unwrap();
+        assert!(structure.holds(), "Symmetry broken for D = {}: {:?}", d, structure);
+        assert_eq!(structure.period_length, continued_fraction(d).unwrap().period.len() as u64);
+        assert_eq!(cf_period_is_palindromic(d), Ok(true), "Period body not palindromic for D = {}", d);
+    }
+    // Long periods and D near the top of the range
+    for d in [1_000_000_007, u64::MAX, u64::MAX - 1] {
+        assert!(cf_structure(d).unwrap().holds(), "Symmetry broken for D = {}", d);
+    }
+    assert!(cf_structure_exceptions(4_000_000..=4_010_000).is_empty());
+}
+
+#[test]
+fn test_convergence_series() {
+    for d in [2, 3, 7, 13, 61, 991, 1_000_003] {
+        let series = convergence_series(d, 300).unwrap();
+        assert_eq!(series.len(), 300);
+        let cf = continued_fraction(d).unwrap();
+        for (row, (p, q)) in series.iter().zip(cf.convergents()) {
+            assert_eq!(row.q, q, "Denominator {} of √{}", row.k, d);
+            assert!(row.scaled_error > 0.0 && row.scaled_error < 1.0, "Legendre bound for k = {}, D = {}", row.k, d);
+            // Direct evaluation is accurate enough while q² is small
+            if q < BigInt::from(10_000) {
+                let (p, q) = (p.to_f64().unwrap(), q.to_f64().unwrap());
+                let direct = ((d as f64).sqrt() - p / q).abs() * q * q;
+                assert!((row.scaled_error - direct).abs() < 1e-6, "k = {}, D = {}", row.k, d);
+            }
+        }
+        // The convergent closing an even number of periods solves the Pell equation
+        let period = cf.period.len();
+        if period <= 15 {
+            let at_solution = series[20 * period - 1].scaled_error;
+            assert!((at_solution - 0.5 / (d as f64).sqrt()).abs() < 1e-9, "End of period 20 of D = {}", d);
+        }
+    }
+    assert_eq!(convergence_series(5, 0).unwrap(), []);
+    assert_eq!(convergence_series(25, 3), Err(PellError::PerfectSquare(25)));
+}
+
+#[test]
+fn test_approximation_report() {
+    for d in [2, 3, 7, 13, 61, 94, 991, 1_000_003] {
+        let report = approximation_report(d, 200).unwrap();
+        let series = convergence_series(d, 200).unwrap();
+        for (row, point) in report.iter().zip(&series) {
+            assert_eq!((row.k, &row.q, row.scaled_error), (point.k, &point.q, point.scaled_error));
+            assert_eq!(row.pell_solution, verify_pell_solution(d, &row.p, &row.q), "k = {}, D = {}", row.k, d);
+            // Away from the bounds the floating point error decides the same way
+            if (row.scaled_error - 0.5).abs() > 1e-9 {
+                assert_eq!(row.legendre, row.scaled_error < 0.5, "Legendre for k = {}, D = {}", row.k, d);
+            }
+            if (row.scaled_error - 1.0 / 5f64.sqrt()).abs() > 1e-9 {
+                assert_eq!(row.hurwitz, row.scaled_error < 1.0 / 5f64.sqrt(), "Hurwitz for k = {}, D = {}", row.k, d);
+            }
+            if row.pell_solution {
+                assert!(row.legendre && row.hurwitz, "Pell solutions approximate best, k = {}, D = {}", row.k, d);
+            }
+        }
+        assert!(report.windows(2).all(|w| w[0].legendre || w[1].legendre), "Legendre for one of two, D = {}", d);
+        assert!(report.windows(3).all(|w| w.iter().any(|row| row.hurwitz)), "Hurwitz for one of three, D = {}", d);
+    }
+
+    let row = &approximation_report(7, 4).unwrap()[3];
+    assert_eq!(row.to_string(), "k=3: 8/3, p² - D·q² = 1, Legendre yes, Hurwitz yes, Pell solution");
+    assert_eq!(approximation_report(36, 1), Err(PellError::PerfectSquare(36)));
+}
+
+#[test]
+fn test_rendering() {
+    let cf = continued_fraction(61).unwrap();
+    assert_eq!(cf.to_latex(), r"[7; \overline{1, 4, 3, 1, 2, 2, 1, 3, 4, 1, 14}]");
+    let cfrac = continued_fraction(7).unwrap().to_latex_cfrac();
+    assert_eq!(
+        cfrac,
+        r"2 + \cfrac{1}{1 + \cfrac{1}{1 + \cfrac{1}{1 + \cfrac{1}{4 + \dotsb}}}}"
+    );
+    assert_eq!(cfrac.matches('{').count(), cfrac.matches('}').count(), "braces are balanced");
+
+    let unicode = continued_fraction(2).unwrap().to_unicode();
+    assert_eq!(unicode, "[1; 2\u{305}]");
+    // Stripping the overlines gives back the plain terms
+    let plain: String = continued_fraction(991).unwrap().to_unicode().chars().filter(|&c| c != '\u{305}').collect();
+    assert_eq!(plain.matches(", ").count(), 59);
+    assert!(plain.starts_with("[31; 2, 12, 10,") && plain.ends_with(", 62]"), "{}", plain);
+}
+
+#[test]
+fn test_nth_convergent_matches_iterator() {
+    for d in [2, 3, 7, 13, 94, 991, 1_000_003] {
+        let cf = continued_fraction(d).unwrap();
+        for (n, expected) in cf.convergents().take(150).enumerate() {
+            assert_eq!(cf.convergent(n as u64), expected, "convergent {n} of √{d}");
+        }
+    }
+}
+
+#[test]
+fn test_nth_convergent_deep() {
+    // The ends of the periods are the Pell solutions, the k-th at index k·r - 1 for even r
+    let (x1, y1) = pell991::pell_min_solution(991).unwrap();
+    let (x, y) = pell991::pell_solution_k(991, &x1, &y1, 1000).unwrap();
+    assert_eq!(nth_convergent(991, 1000 * 60 - 1).unwrap(), (x, y), "the 1000-th solution of D = 991");
+
+    let (p, q) = nth_convergent(2, 100_001).unwrap();
+    let residual = &p * &p - BigInt::from(2) * &q * &q;
+    assert_eq!(residual, BigInt::from(1), "p² - 2q² alternates between ±1, and is 1 at odd n");
+    assert_eq!(nth_convergent(49, 3), Err(PellError::PerfectSquare(49)));
+}
+
+#[test]
+fn test_invalid_d() {
+    assert_eq!(cf_structure(1), Err(PellError::InvalidD(1)));
+    assert_eq!(cf_period_is_palindromic(49), Err(PellError::PerfectSquare(49)));
+    assert_eq!(continued_fraction(0), Err(PellError::InvalidD(0)));
+    assert_eq!(continued_fraction(1), Err(PellError::InvalidD(1)));
+    assert_eq!(continued_fraction(144), Err(PellError::PerfectSquare(144)));
+}
+
+#[test]
+fn test_empty_period_is_rational() {
+    // The fields are public, so a ContinuedFraction need not come from a √D
+    let cf = pell991::ContinuedFraction { a0: 7, period: Vec::new() };
+    let seven = (BigInt::from(7), BigInt::from(1));
+    assert_eq!(cf.convergent(0), seven);
+    assert_eq!(cf.convergent(5), seven, "Every convergent of a rational a₀ is a₀/1");
+    assert_eq!(cf.convergents().collect::<Vec<_>>(), vec![seven], "The convergents end after a₀");
+    assert_eq!(cf.convergents().size_hint(), (1, Some(1)));
+}
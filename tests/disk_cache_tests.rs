@@ -0,0 +1,135 @@
+//! Tests for the persistent solution cache
+
+#![cfg(feature = "disk-cache")]
+
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+
+use num_bigint::BigInt;
+use pell991::{pell_min_solution_with_period, DiskCache};
+
+/// A fresh cache path unique to this test
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("pell991-{}-{}.cache", name, std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_solutions_survive_reopening() {
+    let path = temp_path("reopen");
+
+    let mut cache = DiskCache::open(&path).unwrap();
+    assert!(cache.is_empty());
+    for d in [2, 61, 991] {
+        cache.get_or_solve(d).unwrap();
+    }
+    drop(cache);
+
+    let reopened = DiskCache::open(&path).unwrap();
+    assert_eq!(reopened.len(), 3);
+    for d in [2, 61, 991] {
+        assert_eq!(reopened.get(d), Some(&pell_min_solution_with_period(d).unwrap()), "D = {}", d);
+    }
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_interrupted_write_is_ignored() {
+    let path = temp_path("interrupted");
+
+    let mut cache = DiskCache::open(&path).unwrap();
+    cache.get_or_solve(2).unwrap();
+    drop(cache);
+
+    // Simulate a crash in the middle of appending an entry
+    fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"991 60 3795164").unwrap();
+
+    let mut cache = DiskCache::open(&path).unwrap();
+    assert_eq!(cache.len(), 1);
+    assert!(cache.get(991).is_none());
+
+    // New entries start on a fresh line
+    cache.get_or_solve(991).unwrap();
+    drop(cache);
+    assert_eq!(DiskCache::open(&path).unwrap().len(), 2);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_corrupt_files_are_rejected() {
+    let path = temp_path("corrupt");
+
+    fs::write(&path, "not a cache\n").unwrap();
+    assert_eq!(DiskCache::open(&path).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // A complete line with a wrong solution
+    fs::write(&path, "# pell991 solution cache v1\n2 1 4 2\n").unwrap();
+    assert_eq!(DiskCache::open(&path).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // A genuine but non-minimal solution: (17, 12) is the second for D = 2
+    fs::write(&path, "# pell991 solution cache v1\n2 1 17 12\n").unwrap();
+    assert_eq!(DiskCache::open(&path).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // The fundamental solution with a stale period
+    fs::write(&path, "# pell991 solution cache v1\n13 4 649 180\n").unwrap();
+    assert_eq!(DiskCache::open(&path).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Negative solutions also satisfy the equation
+    fs::write(&path, "# pell991 solution cache v1\n2 1 -3 2\n").unwrap();
+    assert_eq!(DiskCache::open(&path).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_insert_validation_and_errors() {
+    let path = temp_path("insert");
+    let mut cache = DiskCache::open(&path).unwrap();
+
+    let err = cache.insert(2, (BigInt::from(4), BigInt::from(2)), 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = cache.insert(3, (BigInt::from(7), BigInt::from(4)), 2).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput, "(7, 4) is the second solution for D = 3");
+    let err = cache.insert(3, (BigInt::from(2), BigInt::from(1)), 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput, "The period of √3 is 2");
+    cache.insert(3, (BigInt::from(2), BigInt::from(1)), 2).unwrap();
+    assert_eq!(cache.len(), 1);
+
+    let err = cache.get_or_solve(49).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("non-square"));
+
+    assert_eq!(cache.len(), 1);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_save_compacts_and_exports() {
+    let path = temp_path("compact");
+    let export = temp_path("export");
+
+    let mut cache = DiskCache::open(&path).unwrap();
+    for d in [991, 2, 13] {
+        cache.get_or_solve(d).unwrap();
+    }
+
+    cache.save(&export).unwrap();
+    let lines: Vec<String> = fs::read_to_string(&export).unwrap().lines().map(String::from).collect();
+    assert_eq!(lines[0], "# pell991 solution cache v1");
+    assert_eq!(lines[1], "2 1 3 2");
+    assert_eq!(lines[2], "13 5 649 180");
+    assert!(lines[3].starts_with("991 60 "));
+
+    // Compacting in place keeps the cache usable for appends
+    cache.save(&path).unwrap();
+    cache.get_or_solve(61).unwrap();
+    drop(cache);
+    assert_eq!(DiskCache::open(&path).unwrap().len(), 4);
+
+    fs::remove_file(&path).unwrap();
+    fs::remove_file(&export).unwrap();
+}
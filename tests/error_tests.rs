@@ -1,12 +1,15 @@
 //! Unit tests for the error module
 
-use pell991::PellError;
+use pell991::{NegativeObstruction, PellError};
 
 #[test]
 fn test_error_display() {
     assert_eq!(format!("{}", PellError::InvalidD(0)), "D must be > 1, got 0");
     assert_eq!(format!("{}", PellError::PerfectSquare(4)), "D must be non-square, got 4 which is 2²");
     assert_eq!(format!("{}", PellError::InvalidK(0)), "k must be > 0, got 0");
+    assert_eq!(format!("{}", PellError::InvalidN(0)), "N must be non-zero, got 0");
+    assert_eq!(format!("{}", PellError::InvalidPrimeSet("4 is not prime".into())), "invalid prime set: 4 is not prime");
+    assert_eq!(format!("{}", PellError::InvalidSolution(2)), "not a positive solution of x² - 2·y² = 1");
 }
 
 #[test]
@@ -77,4 +80,63 @@ fn test_perfect_square_error_formatting() {
         assert!(error_msg.contains(&format!("{}²", expected_root)),
                 "Error message '{}' should contain '{}²'", error_msg, expected_root);
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_parse_error_display() {
+    let error = PellError::Parse("missing x".to_string());
+    assert_eq!(format!("{}", error), "invalid solution string: missing x");
+}
+
+#[test]
+fn test_error_hash_and_ord() {
+    use std::collections::{BTreeSet, HashSet};
+
+    let errors = [
+        PellError::PerfectSquare(9),
+        PellError::InvalidD(1),
+        PellError::InvalidD(0),
+        PellError::InvalidD(1),
+    ];
+
+    let unique: HashSet<_> = errors.iter().cloned().collect();
+    assert_eq!(unique.len(), 3);
+
+    let sorted: Vec<_> = errors.iter().cloned().collect::<BTreeSet<_>>().into_iter().collect();
+    assert_eq!(sorted, [PellError::InvalidD(0), PellError::InvalidD(1), PellError::PerfectSquare(9)]);
+}
+
+#[test]
+fn test_limit_exceeded_display() {
+    let error = PellError::LimitExceeded { steps: 1000 };
+    assert_eq!(error.to_string(), "no solution found within 1000 steps");
+}
+
+#[test]
+fn test_timeout_display() {
+    let error = PellError::Timeout { limit: std::time::Duration::from_millis(1500) };
+    assert_eq!(error.to_string(), "no solution found within 1.5s");
+}
+
+#[test]
+fn test_negative_unsolvable_display() {
+    let error = PellError::NegativeUnsolvable { d: 34, reason: NegativeObstruction::EvenPeriod(4) };
+    assert_eq!(error.to_string(), "x² - 34·y² = -1 has no solution: the continued fraction of √D has even period 4");
+
+    let error = PellError::NegativeUnsolvable { d: 21, reason: NegativeObstruction::PrimeFactor(3) };
+    assert_eq!(
+        error.to_string(),
+        "x² - 21·y² = -1 has no solution: D has the prime factor 3 ≡ 3 (mod 4), and -1 is not a square mod 3"
+    );
+}
+
+#[test]
+fn test_decode_error_display() {
+    let error = PellError::Decode("missing PELL magic".to_string());
+    assert_eq!(format!("{}", error), "invalid binary solution: missing PELL magic");
+}
+
+#[test]
+fn test_overflow_error_display() {
+    let error = PellError::Overflow { bits: 128 };
+    assert_eq!(format!("{}", error), "value does not fit in a 128-bit integer");
+}
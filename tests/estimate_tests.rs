@@ -0,0 +1,94 @@
+//! Tests for the estimate module
+
+use pell991::{
+    continued_fraction, decimal_digits, estimate_memory, estimate_solve_cost, pell_min_solution, predicted_digits,
+    pell_solutions, pell_solutions_range, PellError,
+};
+
+#[test]
+fn test_predicted_digits_match_actual() {
+    for d in [2, 3, 5, 7, 13, 61, 109, 991] {
+        let solutions = pell_solutions(d, 60).unwrap();
+        for (i, (x, y)) in solutions.iter().enumerate() {
+            let k = i as u64 + 1;
+            let predicted = predicted_digits(d, k).unwrap();
+            assert_eq!(predicted, (decimal_digits(x), decimal_digits(y)),
+                "Prediction mismatch for D = {}, k = {}", d, k);
+        }
+    }
+}
+
+#[test]
+fn test_predicted_digits_large_k() {
+    // Digit counts grow linearly in k
+    let (x_1000, _) = predicted_digits(2, 1000).unwrap();
+    let (x_2000, _) = predicted_digits(2, 2000).unwrap();
+    assert!((x_2000 as i64 - 2 * x_1000 as i64).abs() <= 1);
+
+    let (x_huge, y_huge) = predicted_digits(991, 1_000_000_000).unwrap();
+    assert!(x_huge > y_huge);
+    assert!(x_huge > 29_000_000_000);
+}
+
+#[test]
+fn test_predicted_digits_errors() {
+    assert_eq!(predicted_digits(2, 0), Err(PellError::InvalidK(0)));
+    assert_eq!(predicted_digits(1, 5), Err(PellError::InvalidD(1)));
+    assert_eq!(predicted_digits(36, 5), Err(PellError::PerfectSquare(36)));
+}
+
+#[test]
+fn test_estimate_memory_bounds_actual_size() {
+    for (d, k, count) in [(2, 1, 1), (61, 1, 200), (991, 1, 500), (991, 300, 100)] {
+        let solutions = pell_solutions_range(d, k, k + count).unwrap();
+        let digit_bytes: u64 = solutions.iter().map(|(x, y)| (x.bits() + y.bits()).div_ceil(8)).sum();
+        let estimate = estimate_memory(d, k, count).unwrap();
+        assert!(estimate >= digit_bytes, "Estimate {} below the {} bytes of digits for D = {}, k = {}", estimate, digit_bytes, d, k);
+        assert!(estimate <= 2 * digit_bytes + 100 * count + 1000, "Estimate {} far above {} for D = {}, k = {}", estimate, digit_bytes, d, k);
+    }
+}
+
+#[test]
+fn test_estimate_memory_edge_cases() {
+    assert_eq!(estimate_memory(991, 1, 0), Ok(0));
+    assert_eq!(estimate_memory(991, 0, 10), Err(PellError::InvalidK(0)));
+    assert_eq!(estimate_memory(16, 1, 10), Err(PellError::PerfectSquare(16)));
+    assert_eq!(estimate_memory(991, u64::MAX, u64::MAX), Ok(u64::MAX));
+    assert!(estimate_memory(991, 1, 2000).unwrap() > estimate_memory(991, 1, 1000).unwrap());
+    assert!(estimate_memory(991, 1000, 10).unwrap() > estimate_memory(991, 1, 10).unwrap());
+}
+
+#[test]
+fn test_solve_cost_steps_are_exact() {
+    for d in [2, 3, 5, 13, 61, 94, 991, 1_000_099] {
+        let cost = estimate_solve_cost(d).unwrap();
+        let cf = continued_fraction(d).unwrap();
+        assert_eq!(cost.period, cf.period.len() as u64, "period of D = {}", d);
+        // The convergent closing `steps` terms after a₀ is the fundamental solution
+        let (x, _) = cf.convergents().nth(cost.steps as usize - 1).unwrap();
+        assert_eq!(x, pell_min_solution(d).unwrap().0, "steps of D = {}", d);
+    }
+}
+
+#[test]
+fn test_solve_cost_predicts_digits() {
+    for d in (100_000..101_000).filter(|&d| pell991::is_valid_pell_d(d)) {
+        let cost = estimate_solve_cost(d).unwrap();
+        let actual = decimal_digits(&pell_min_solution(d).unwrap().0);
+        if cost.steps >= 200 {
+            let ratio = cost.digits as f64 / actual as f64;
+            assert!((0.8..1.2).contains(&ratio), "D = {}: predicted {}, actual {}", d, cost.digits, actual);
+        }
+    }
+}
+
+#[test]
+fn test_solve_cost_orders_work() {
+    let costs: Vec<u64> = [2, 991, 1_000_099, 10_000_036]
+        .iter()
+        .map(|&d| estimate_solve_cost(d).unwrap().limb_operations)
+        .collect();
+    assert!(costs.windows(2).all(|w| w[0] < w[1]), "{:?}", costs);
+    assert_eq!(estimate_solve_cost(1), Err(PellError::InvalidD(1)));
+    assert_eq!(estimate_solve_cost(64), Err(PellError::PerfectSquare(64)));
+}
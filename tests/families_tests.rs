@@ -0,0 +1,78 @@
+//! Tests for the families of D close to a perfect square
+
+use pell991::families::scan_d_families;
+use pell991::{classify_d, continued_fraction, pell_min_solution, BigInt, DFamily};
+
+#[test]
+fn test_named_families() {
+    for n in 5..200u64 {
+        assert_eq!(classify_d(n * n + 1), DFamily::SquarePlusOne { n }, "D = {}² + 1", n);
+        assert_eq!(classify_d(n * n - 1), DFamily::SquareMinusOne { n }, "D = {}² - 1", n);
+        assert_eq!(classify_d(n * n + 2), DFamily::SquarePlusTwo { n }, "D = {}² + 2", n);
+        assert_eq!(classify_d(n * n - 2), DFamily::SquareMinusTwo { n }, "D = {}² - 2", n);
+        assert_eq!(classify_d(n * n + 4), DFamily::SquarePlusFour { n }, "D = {}² + 4", n);
+        assert_eq!(classify_d(n * n - 4), DFamily::SquareMinusFour { n }, "D = {}² - 4", n);
+    }
+}
+
+#[test]
+fn test_small_d_prefer_smaller_offsets() {
+    assert_eq!(classify_d(2), DFamily::SquarePlusOne { n: 1 }, "2 = 1² + 1 = 2² - 2");
+    assert_eq!(classify_d(3), DFamily::SquareMinusOne { n: 2 }, "3 = 2² - 1 = 1² + 2");
+    assert_eq!(classify_d(5), DFamily::SquarePlusOne { n: 2 }, "5 = 2² + 1 = 3² - 4");
+    assert_eq!(classify_d(8), DFamily::SquareMinusOne { n: 3 }, "8 = 3² - 1 = 2² + 4");
+}
+
+#[test]
+fn test_richaud_degert() {
+    for d in 2..5000u64 {
+        let family = classify_d(d);
+        match (family.n(), family.offset()) {
+            (Some(n), Some(r)) => {
+                assert_eq!(d as i64, (n * n) as i64 + r, "{} should be {}", d, family);
+                if let DFamily::RichaudDegert { .. } = family {
+                    assert_eq!((4 * n as i64) % r, 0, "r | 4n for D = {}", d);
+                    assert!(-(n as i64) < r && r <= n as i64, "-n < r ≤ n for D = {}", d);
+                }
+            }
+            _ => assert_eq!(family, DFamily::Other),
+        }
+    }
+}
+
+#[test]
+fn test_families_have_short_periods() {
+    // The periods stay bounded however large D gets, unlike √D in general
+    for (d, family) in scan_d_families(2..=20_000) {
+        let period = continued_fraction(d).unwrap().period.len();
+        assert!(period <= 12, "{} = {} has period {}", d, family, period);
+    }
+    let (x, y) = pell_min_solution(31 * 31 + 1).unwrap();
+    assert_eq!((x, y), (BigInt::from(2 * 31 * 31 + 1), BigInt::from(2 * 31)), "(2n² + 1, 2n) for n² + 1");
+}
+
+#[test]
+fn test_other_and_invalid() {
+    assert_eq!(classify_d(991), DFamily::Other);
+    assert_eq!(classify_d(0), DFamily::Other);
+    assert_eq!(classify_d(1), DFamily::Other);
+    assert_eq!(classify_d(49), DFamily::Other, "perfect squares are in no family");
+    assert_eq!((DFamily::Other.n(), DFamily::Other.offset()), (None, None));
+    assert_eq!(classify_d(u64::MAX), DFamily::SquareMinusOne { n: 1 << 32 });
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(DFamily::SquarePlusOne { n: 31 }.to_string(), "31² + 1");
+    assert_eq!(DFamily::RichaudDegert { n: 32, r: -8 }.to_string(), "32² - 8");
+    assert_eq!(DFamily::Other.to_string(), "other");
+}
+
+#[test]
+fn test_scan_skips_other() {
+    let scanned: Vec<(u64, DFamily)> = scan_d_families(2..=1000).collect();
+    let expected: Vec<(u64, DFamily)> =
+        (2..=1000).map(|d| (d, classify_d(d))).filter(|(_, family)| *family != DFamily::Other).collect();
+    assert_eq!(scanned, expected);
+    assert!(scanned.iter().all(|(d, _)| *d != 991));
+}
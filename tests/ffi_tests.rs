@@ -0,0 +1,108 @@
+//! Tests for the C interface
+
+#![cfg(feature = "ffi")]
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::ptr;
+
+use pell991::ffi::*;
+
+/// Take ownership of a string returned by the library
+fn take_string(s: *mut c_char) -> String {
+    assert!(!s.is_null(), "string should be set");
+    let owned = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+    unsafe { pell991_string_free(s) };
+    owned
+}
+
+#[test]
+fn test_min_solution_strings() {
+    let (mut x, mut y) = (ptr::null_mut(), ptr::null_mut());
+    let status = unsafe { pell991_min_solution(991, &mut x, &mut y) };
+    assert_eq!(status, Pell991Status::Ok);
+    assert_eq!(take_string(x), "379516400906811930638014896080");
+    assert_eq!(take_string(y), "12055735790331359447442538767");
+}
+
+#[test]
+fn test_solution_k_strings() {
+    let (mut x, mut y) = (ptr::null_mut(), ptr::null_mut());
+    let status = unsafe { pell991_solution_k(2, 3, &mut x, &mut y) };
+    assert_eq!(status, Pell991Status::Ok);
+    assert_eq!((take_string(x), take_string(y)), ("99".to_string(), "70".to_string()));
+}
+
+#[test]
+fn test_errors_clear_outputs() {
+    let cases = [(1, 1, Pell991Status::InvalidD), (9, 1, Pell991Status::PerfectSquare), (2, 0, Pell991Status::InvalidK)];
+    for (d, k, expected) in cases {
+        let dangling = ptr::dangling_mut::<c_char>();
+        let (mut x, mut y) = (dangling, dangling);
+        let status = unsafe { pell991_solution_k(d, k, &mut x, &mut y) };
+        assert_eq!(status, expected, "D = {}, k = {}", d, k);
+        assert!(x.is_null() && y.is_null(), "outputs should be cleared for D = {}, k = {}", d, k);
+
+        let dangling = ptr::dangling_mut::<u64>();
+        let mut x = Pell991Limbs { limbs: dangling, len: 1 };
+        let mut y = Pell991Limbs { limbs: dangling, len: 1 };
+        let status = unsafe { pell991_solution_k_limbs(d, k, &mut x, &mut y) };
+        assert_eq!(status, expected, "D = {}, k = {}", d, k);
+        assert!(x.limbs.is_null() && x.len == 0 && y.limbs.is_null() && y.len == 0,
+            "limbs should be emptied for D = {}, k = {}", d, k);
+    }
+
+    let status = unsafe { pell991_min_solution(2, ptr::null_mut(), ptr::null_mut()) };
+    assert_eq!(status, Pell991Status::InvalidArgument);
+}
+
+#[test]
+fn test_limbs() {
+    let mut x = Pell991Limbs { limbs: ptr::null_mut(), len: 0 };
+    let mut y = Pell991Limbs { limbs: ptr::null_mut(), len: 0 };
+    let status = unsafe { pell991_min_solution_limbs(991, &mut x, &mut y) };
+    assert_eq!(status, Pell991Status::Ok);
+
+    let limbs = |n: &Pell991Limbs| unsafe { std::slice::from_raw_parts(n.limbs, n.len) }.to_vec();
+    let (x1, y1) = pell991::pell_min_solution(991).unwrap();
+    assert_eq!(limbs(&x), x1.magnitude().to_u64_digits());
+    assert_eq!(limbs(&y), y1.magnitude().to_u64_digits());
+    unsafe {
+        pell991_limbs_free(x);
+        pell991_limbs_free(y);
+    }
+
+    let mut x = Pell991Limbs { limbs: ptr::null_mut(), len: 0 };
+    let mut y = Pell991Limbs { limbs: ptr::null_mut(), len: 0 };
+    let status = unsafe { pell991_solution_k_limbs(2, 2, &mut x, &mut y) };
+    assert_eq!(status, Pell991Status::Ok);
+    assert_eq!((limbs(&x), limbs(&y)), (vec![17], vec![12]));
+    unsafe {
+        pell991_limbs_free(x);
+        pell991_limbs_free(y);
+    }
+}
+
+#[test]
+fn test_verify() {
+    let check = |x: &str, y: &str| {
+        let (x, y) = (CString::new(x).unwrap(), CString::new(y).unwrap());
+        let mut valid = false;
+        let status = unsafe { pell991_verify(2, x.as_ptr(), y.as_ptr(), &mut valid) };
+        (status, valid)
+    };
+    assert_eq!(check("17", "12"), (Pell991Status::Ok, true));
+    assert_eq!(check("17", "13"), (Pell991Status::Ok, false));
+    assert_eq!(check("seventeen", "12").0, Pell991Status::InvalidArgument);
+}
+
+#[test]
+fn test_status_messages() {
+    let message = |status| unsafe { CStr::from_ptr(pell991_status_message(status)) }.to_str().unwrap();
+    assert_eq!(message(Pell991Status::PerfectSquare as c_int), "D must not be a perfect square");
+    assert_eq!(message(Pell991Status::Internal as c_int), "internal error in the library");
+
+    // C may pass any int where the enum is expected
+    for unknown in [-1, 7, c_int::MAX] {
+        assert_eq!(message(unknown), "unknown status", "status {}", unknown);
+    }
+}
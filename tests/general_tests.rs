@@ -0,0 +1,170 @@
+//! Tests for the generalized Pell equation x² - D·y² = N
+
+use std::collections::BTreeSet;
+
+use num_bigint::BigInt;
+use pell991::{
+    is_valid_pell_d, min_n_for_square, pell_general_class_solutions, pell_general_class_solutions_with_fundamental,
+    pell_general_fundamental_solutions, pell_general_fundamental_solutions_with_fundamental, pell_min_solution,
+    PellError,
+};
+
+fn classes(d: u64, n: i64) -> Vec<(i64, i64)> {
+    pell_general_fundamental_solutions(d, n)
+        .unwrap()
+        .into_iter()
+        .map(|(x, y)| (x.try_into().unwrap(), y.try_into().unwrap()))
+        .collect()
+}
+
+#[test]
+fn test_known_classes() {
+    assert_eq!(classes(2, 7), [(-3, 1), (3, 1)]);
+    assert_eq!(classes(2, 1), [(1, 0)]);
+    assert_eq!(classes(2, -1), [(1, 1)]);
+    assert_eq!(classes(5, 4), [(2, 0), (-3, 1), (3, 1)]);
+    // (12, 3) = 3·(4, 1) is imprimitive, (36, 10) = 2·(18, 5)
+    assert_eq!(classes(13, 27), [(-12, 3), (12, 3), (-40, 11), (40, 11)]);
+    assert_eq!(classes(13, -4), [(-3, 1), (3, 1), (36, 10)]);
+    assert_eq!(classes(61, -3), [(-5639, 722), (5639, 722)]);
+}
+
+#[test]
+fn test_unsolvable() {
+    // x² ≡ 2 (mod 3) has no solution
+    assert!(classes(3, 2).is_empty());
+    // -1 is not a norm when the period of √D is even
+    assert!(classes(3, -1).is_empty());
+    assert!(classes(34, -1).is_empty());
+}
+
+#[test]
+fn test_classes_satisfy_equation() {
+    for d in (2..60).filter(|&d| is_valid_pell_d(d)) {
+        for n in (-50..=50).filter(|&n| n != 0) {
+            for (x, y) in pell_general_fundamental_solutions(d, n).unwrap() {
+                assert_eq!(&x * &x - BigInt::from(d) * &y * &y, BigInt::from(n), "D = {}, N = {}", d, n);
+                assert!(y >= BigInt::ZERO, "D = {}, N = {}", d, n);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_classes_cover_small_solutions() {
+    // Every solution found by brute force lies in the orbit of some class
+    for d in (2..30).filter(|&d| is_valid_pell_d(d)) {
+        for n in (-30..=30).filter(|&n| n != 0) {
+            let mut orbits = BTreeSet::new();
+            for (x, y) in pell_general_fundamental_solutions(d, n).unwrap() {
+                for (x, y) in pell_general_class_solutions(d, &x, &y, 8).unwrap() {
+                    orbits.insert((x.magnitude().clone(), y.magnitude().clone()));
+                }
+            }
+            for y in 0i64..200 {
+                let x2 = n + d as i64 * y * y;
+                if x2 < 0 {
+                    continue;
+                }
+                let x = (x2 as f64).sqrt() as i64;
+                if let Some(x) = [x - 1, x, x + 1].into_iter().find(|&x| x >= 0 && x * x == x2) {
+                    let key = (BigInt::from(x).magnitude().clone(), BigInt::from(y).magnitude().clone());
+                    assert!(orbits.contains(&key), "({}, {}) missed for D = {}, N = {}", x, y, d, n);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_class_solutions() {
+    let solutions = pell_general_class_solutions(2, &BigInt::from(-3), &BigInt::from(1), 3).unwrap();
+    assert_eq!(solutions, [
+        (BigInt::from(-3), BigInt::from(1)),
+        (BigInt::from(5), BigInt::from(3)),
+        (BigInt::from(27), BigInt::from(19)),
+    ]);
+    assert!(pell_general_class_solutions(2, &BigInt::from(3), &BigInt::from(1), 0).unwrap().is_empty());
+}
+
+#[test]
+fn test_invalid_input() {
+    assert_eq!(pell_general_fundamental_solutions(2, 0), Err(PellError::InvalidN(0)));
+    assert_eq!(pell_general_fundamental_solutions(1, 7), Err(PellError::InvalidD(1)));
+    assert_eq!(pell_general_fundamental_solutions(9, 7), Err(PellError::PerfectSquare(9)));
+    assert_eq!(pell_general_class_solutions(16, &BigInt::from(4), &BigInt::from(0), 2), Err(PellError::PerfectSquare(16)));
+}
+
+/// The smallest n in 1..=limit with D·n² + c a perfect square, by trial
+fn brute_force_min_n(d: u64, c: i64, limit: i128) -> Option<(i128, i128)> {
+    (1..=limit).find_map(|n| {
+        // Small enough for f64 to take exact square roots
+        let value = d as i128 * n * n + c as i128;
+        let m = (value.max(0) as f64).sqrt().round() as i128;
+        (m * m == value).then_some((n, m))
+    })
+}
+
+#[test]
+fn test_min_n_for_square_matches_brute_force() {
+    const LIMIT: i128 = 2000;
+    for d in (2..60).filter(|&d| is_valid_pell_d(d)) {
+        for c in -30..=30 {
+            let found = min_n_for_square(d, c).unwrap();
+            match brute_force_min_n(d, c, LIMIT) {
+                Some((n, m)) => assert_eq!(
+                    found,
+                    Some((BigInt::from(n), BigInt::from(m))),
+                    "D = {}, c = {}",
+                    d,
+                    c
+                ),
+                None => assert!(
+                    found.as_ref().is_none_or(|(n, _)| *n > BigInt::from(LIMIT)),
+                    "D = {}, c = {}: {:?} was missed by the search",
+                    d,
+                    c,
+                    found
+                ),
+            }
+            if let Some((n, m)) = found {
+                assert_eq!(&m * &m, BigInt::from(d) * &n * &n + c, "D = {}, c = {}", d, c);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_min_n_for_square_special_cases() {
+    let (x1, y1) = pell_min_solution(991).unwrap();
+    assert_eq!(min_n_for_square(991, 1).unwrap(), Some((y1.clone(), x1.clone())));
+    // 991·n² + 4 is square at n = 2y₁ and no earlier
+    assert_eq!(min_n_for_square(991, 4).unwrap(), Some((2 * &y1, 2 * &x1)));
+    assert_eq!(min_n_for_square(991, -1).unwrap(), None, "991 ≡ 3 (mod 4)");
+    assert_eq!(min_n_for_square(13, 0).unwrap(), None);
+
+    assert_eq!(min_n_for_square(1, 1), Err(PellError::InvalidD(1)));
+    assert_eq!(min_n_for_square(49, 0), Err(PellError::PerfectSquare(49)));
+    assert_eq!(min_n_for_square(49, -1), Err(PellError::PerfectSquare(49)));
+}
+
+#[test]
+fn test_with_fundamental_matches_solving() {
+    for (d, n) in [(2, 7), (5, -4), (13, 27), (61, -3), (991, 9)] {
+        let (x1, y1) = pell_min_solution(d).unwrap();
+        let classes = pell_general_fundamental_solutions(d, n).unwrap();
+        let with_fundamental = pell_general_fundamental_solutions_with_fundamental(d, n, &x1, &y1).unwrap();
+        assert_eq!(with_fundamental, classes, "D = {d}, N = {n}");
+        for (x, y) in &classes {
+            assert_eq!(
+                pell_general_class_solutions_with_fundamental(d, x, y, &x1, &y1, 4).unwrap(),
+                pell_general_class_solutions(d, x, y, 4).unwrap(),
+                "class of ({x}, {y}) for D = {d}"
+            );
+        }
+    }
+
+    let (x1, y1) = (BigInt::from(3), BigInt::from(2));
+    assert_eq!(pell_general_fundamental_solutions_with_fundamental(2, 0, &x1, &y1), Err(PellError::InvalidN(0)));
+    assert_eq!(pell_general_fundamental_solutions_with_fundamental(9, 7, &x1, &y1), Err(PellError::PerfectSquare(9)));
+}
@@ -0,0 +1,73 @@
+//! Tests for the parallel verification harness
+
+#![cfg(feature = "rayon")]
+
+use pell991::harness::{verify_all, Check, Checks};
+use pell991::{is_square_u64, pell_min_solution, AutoSolver, BigInt, BsgsSolver, PellError, Solver};
+
+/// A broken backend: off by one for D ≡ 0 (mod 7), and doubled solutions for D ≡ 3 (mod 7)
+struct FaultySolver;
+
+impl Solver for FaultySolver {
+    fn name(&self) -> &'static str {
+        "faulty"
+    }
+
+    fn min_solution(&self, d: u64) -> Result<(BigInt, BigInt), PellError> {
+        let (x, y) = pell_min_solution(d)?;
+        Ok(match d % 7 {
+            0 => (x + 1, y),
+            3 => (&x * &x + d * &y * &y, 2 * &x * &y),
+            _ => (x, y),
+        })
+    }
+}
+
+#[test]
+fn test_reference_algorithms_are_clean() {
+    let report = verify_all(2..=3000, &Checks::all(), 0);
+    assert_eq!(report.checked, 3000 - 1 - 53, "squares 4..=2916 are skipped");
+    assert!(report.is_clean(), "{:?}", report.anomalies);
+
+    for solver in [Box::new(BsgsSolver::new()) as Box<dyn Solver>, Box::new(AutoSolver)] {
+        let report = verify_all(10_000..=10_500, &Checks::new().solutions(solver), 2);
+        assert!(report.is_clean(), "{:?}", report.anomalies);
+    }
+}
+
+#[test]
+fn test_faulty_solver_is_caught() {
+    let report = verify_all(2..=100, &Checks::all().solutions(Box::new(FaultySolver)), 3);
+    let expected: Vec<u64> = (2..=100u64).filter(|d| matches!(d % 7, 0 | 3) && !is_square_u64(*d)).collect();
+    let anomalous: Vec<u64> = report.anomalies.iter().map(|anomaly| anomaly.d).collect();
+    assert_eq!(anomalous, expected, "one anomaly per broken D, in order");
+    assert!(report.anomalies.iter().all(|anomaly| anomaly.check == Check::Solution));
+
+    let not_minimal = report.anomalies.iter().find(|anomaly| anomaly.d == 3).unwrap();
+    assert_eq!(
+        not_minimal.to_string(),
+        "D = 3: solution check failed: faulty returned (7, 4), the minimal solution is (2, 1)"
+    );
+    let wrong = report.anomalies.iter().find(|anomaly| anomaly.d == 7).unwrap();
+    assert!(wrong.details.contains("not a solution"), "{}", wrong);
+}
+
+#[test]
+fn test_thread_count_does_not_change_the_report() {
+    let checks = Checks::all().solutions(Box::new(FaultySolver));
+    let single = verify_all(2..=400, &checks, 1);
+    for threads in [2, 8] {
+        assert_eq!(verify_all(2..=400, &checks, threads), single, "{} threads", threads);
+    }
+}
+
+#[test]
+fn test_empty_battery_and_range() {
+    let report = verify_all(2..=1000, &Checks::new(), 2);
+    assert_eq!(report.checked, 1000 - 1 - 30);
+    assert!(report.is_clean());
+
+    #[allow(clippy::reversed_empty_ranges)]
+    let report = verify_all(10..=1, &Checks::all(), 2);
+    assert_eq!(report.checked, 0);
+}
@@ -0,0 +1,110 @@
+//! Tests for the invariant checkers
+
+use num_bigint::BigInt;
+use pell991::invariants::{check_cf_invariants, check_group_law, check_recurrence, check_solution_invariants};
+use pell991::{continued_fraction, pell_solutions, ContinuedFraction, IdentityViolation, InvariantViolation};
+
+fn pair(x: i64, y: i64) -> (BigInt, BigInt) {
+    (BigInt::from(x), BigInt::from(y))
+}
+
+fn violated<T: std::fmt::Debug>(result: Result<T, InvariantViolation>) -> &'static str {
+    result.expect_err("an invariant should fail").invariant
+}
+
+#[test]
+fn test_solver_output_satisfies_all_invariants() {
+    for d in [2, 3, 5, 7, 13, 61, 109, 991, 4_294_967_297] {
+        let solutions = pell_solutions(d, 6).unwrap();
+        assert_eq!(check_solution_invariants(d, &solutions), Ok(()), "solutions of D = {d}");
+        assert_eq!(check_cf_invariants(&continued_fraction(d).unwrap()), Ok(()), "expansion of √{d}");
+        for a in &solutions {
+            for b in &solutions {
+                assert_eq!(check_group_law(d, a, b), Ok(()), "group law for D = {d}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_solution_violations() {
+    assert_eq!(violated(check_solution_invariants(9, &[])), "valid_d");
+    assert_eq!(violated(check_solution_invariants(2, &[pair(-3, 2)])), "positive");
+    assert_eq!(violated(check_solution_invariants(2, &[pair(3, 1)])), "norm");
+    assert_eq!(
+        violated(check_solution_invariants(2, &[pair(3, 2), pair(99, 70)])),
+        "recurrence",
+        "the second solution was skipped"
+    );
+    assert_eq!(check_solution_invariants(2, &[]), Ok(()), "an empty run is consistent");
+}
+
+#[test]
+fn test_cf_violations() {
+    let cf = |a0, period: &[u64]| ContinuedFraction { a0, period: period.to_vec() };
+
+    assert_eq!(violated(check_cf_invariants(&cf(3, &[]))), "nonempty");
+    assert_eq!(violated(check_cf_invariants(&cf(3, &[1, 1, 1, 1, 5]))), "period_end");
+    assert_eq!(violated(check_cf_invariants(&cf(3, &[1, 4, 4, 1, 6]))), "bounds");
+    assert_eq!(violated(check_cf_invariants(&cf(3, &[1, 2, 1, 1, 6]))), "palindrome");
+    assert_eq!(violated(check_cf_invariants(&cf(3, &[2, 2, 6]))), "recovered_d");
+    assert_eq!(check_cf_invariants(&cf(3, &[1, 1, 1, 1, 6])), Ok(()), "√13 = [3; (1, 1, 1, 1, 6)]");
+}
+
+#[test]
+fn test_group_law_holds_for_any_pairs() {
+    // Not solutions, but the norm is still multiplicative
+    assert_eq!(check_group_law(7, &pair(2, 5), &pair(-4, 3)), Ok(()));
+    assert_eq!(check_group_law(991, &pair(0, 0), &pair(1, 1)), Ok(()));
+}
+
+#[test]
+fn test_violation_display() {
+    let err = check_solution_invariants(2, &[pair(3, 1)]).unwrap_err();
+    assert_eq!(err.to_string(), "invariant `norm` violated: solution 1 = (3, 1) does not satisfy x² - 2·y² = 1");
+}
+
+fn failing(result: Result<(), IdentityViolation>) -> (u64, &'static str) {
+    let violation = result.expect_err("an identity should fail");
+    (violation.k, violation.identity)
+}
+
+#[test]
+fn test_recurrence_accepts_solver_output() {
+    for d in [2, 3, 7, 61, 991, 4_294_967_297] {
+        assert_eq!(check_recurrence(d, &pell_solutions(d, 30).unwrap()), Ok(()), "solutions of D = {d}");
+    }
+    assert_eq!(check_recurrence(2, &[]), Ok(()), "an empty run is consistent");
+}
+
+#[test]
+fn test_recurrence_reports_the_first_bad_index() {
+    let solutions = pell_solutions(7, 12).unwrap();
+
+    let mut negative = solutions.clone();
+    negative[3].1 = -negative[3].1.clone();
+    assert_eq!(failing(check_recurrence(7, &negative)), (4, "positive"));
+
+    let mut off_by_one = solutions.clone();
+    off_by_one[8].0 += 1;
+    off_by_one[10].0 += 1;
+    assert_eq!(failing(check_recurrence(7, &off_by_one)), (9, "norm"), "the earlier of two bad entries");
+
+    // Solutions of the equation, but not consecutive ones
+    let mut skipped = solutions.clone();
+    skipped.remove(4);
+    assert_eq!(failing(check_recurrence(7, &skipped)), (5, "recurrence"));
+    let mut skipped = solutions.clone();
+    skipped.remove(5);
+    assert_eq!(failing(check_recurrence(7, &skipped)), (6, "composition"), "x₆ + y₆√7 is not the square of the third");
+
+    // Solutions of D = 7 presented as solutions of D = 2
+    assert_eq!(failing(check_recurrence(2, &solutions)), (1, "norm"));
+    assert_eq!(failing(check_recurrence(16, &solutions)), (0, "valid_d"));
+}
+
+#[test]
+fn test_identity_violation_display() {
+    let err = check_recurrence(2, &[pair(3, 2), pair(17, 12), pair(577, 408)]).unwrap_err();
+    assert_eq!(err.to_string(), "identity `recurrence` fails at k = 3: got (577, 408), expected (99, 70)");
+}
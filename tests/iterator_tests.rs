@@ -1,6 +1,7 @@
 //! Tests for the PellSolutionIterator
 
-use pell991::{PellSolutionIterator, pell_solutions, verify_pell_solution};
+use pell991::{PellError, PellSolutionIterator, SearchBound, pell_solutions, search_solutions, verify_pell_solution};
+use num_bigint::BigInt;
 
 #[test]
 fn test_iterator_basic_functionality() {
@@ -150,4 +151,192 @@ fn test_iterator_chaining() {
     for (x, y) in first_batch.iter().chain(second_batch.iter()) {
         assert!(verify_pell_solution(7, x, y));
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_iterator_size_hint_and_fuse() {
+    let iter = PellSolutionIterator::new(2).unwrap();
+    assert_eq!(iter.size_hint(), (usize::MAX, None));
+
+    // Adapters propagate the hint
+    assert_eq!(iter.take(5).size_hint(), (5, Some(5)));
+
+    // FusedIterator lets generic code rely on `fuse()` being free
+    fn assert_fused<I: std::iter::FusedIterator>(_: &I) {}
+    assert_fused(&PellSolutionIterator::new(2).unwrap());
+}
+
+#[test]
+fn test_iterator_clone() {
+    let mut original = PellSolutionIterator::new(61).unwrap();
+    original.next();
+    original.next();
+
+    // A clone continues independently from the same position
+    let mut cloned = original.clone();
+    assert_eq!(cloned.current_k(), original.current_k());
+    assert_eq!(cloned.next(), original.next());
+    cloned.next();
+    assert_eq!(cloned.current_k(), original.current_k() + 1);
+    assert!(format!("{:?}", cloned).contains("PellSolutionIterator"));
+}
+
+#[test]
+fn test_iterator_start_at() {
+    for d in [2, 13, 991] {
+        let expected = pell_solutions(d, 20).unwrap();
+        for k in [1u64, 2, 7, 15] {
+            let iter = PellSolutionIterator::start_at(d, k).unwrap();
+            assert_eq!(iter.current_k(), k);
+            let page: Vec<_> = iter.take(5).collect();
+            assert_eq!(page, expected[(k - 1) as usize..(k + 4) as usize], "D = {}, k = {}", d, k);
+        }
+    }
+
+    // reset() still returns to the first solution
+    let mut iter = PellSolutionIterator::start_at(2, 10).unwrap();
+    iter.reset();
+    assert_eq!(iter.current_k(), 1);
+    assert_eq!(iter.next().unwrap().0.to_string(), "3");
+}
+
+#[test]
+fn test_iterator_start_at_errors() {
+    assert_eq!(PellSolutionIterator::start_at(2, 0).unwrap_err(), pell991::PellError::InvalidK(0));
+    assert_eq!(PellSolutionIterator::start_at(4, 1).unwrap_err(), pell991::PellError::PerfectSquare(4));
+}
+
+#[test]
+fn test_iterator_skip_to() {
+    let expected = pell_solutions(991, 30).unwrap();
+    let mut iter = PellSolutionIterator::new(991).unwrap();
+
+    // Forwards, backwards and onto the current position
+    for k in [25u64, 3, 3, 30, 1] {
+        iter.skip_to(k).unwrap();
+        assert_eq!(iter.current_k(), k);
+        assert_eq!(iter.next().unwrap(), expected[(k - 1) as usize], "skip_to({})", k);
+        assert_eq!(iter.current_k(), k + 1);
+    }
+
+    // An invalid index leaves the iterator where it was
+    assert_eq!(iter.skip_to(0), Err(pell991::PellError::InvalidK(0)));
+    assert_eq!(iter.current_k(), 2);
+    assert_eq!(iter.next().unwrap(), expected[1]);
+}
+
+#[test]
+fn test_iterator_take_while_digits() {
+    use pell991::decimal_digits;
+
+    for (d, max_digits) in [(2u64, 1u32), (2, 10), (3, 20), (991, 30), (991, 29), (991, 200)] {
+        let within: Vec<_> = PellSolutionIterator::new(d).unwrap().take_while_digits(max_digits).collect();
+        let expected: Vec<_> = PellSolutionIterator::new(d)
+            .unwrap()
+            .take_while(|(x, _)| decimal_digits(x) <= max_digits as u64)
+            .collect();
+        assert_eq!(within, expected, "D = {}, max_digits = {}", d, max_digits);
+    }
+
+    // x₁ = 379516400906811930638014896080 has 30 digits
+    assert_eq!(PellSolutionIterator::new(991).unwrap().take_while_digits(29).count(), 0);
+
+    // Exactly at a power of ten: x = 10 for D = 11 has two digits
+    assert_eq!(PellSolutionIterator::new(11).unwrap().take_while_digits(1).count(), 0);
+    assert_eq!(PellSolutionIterator::new(11).unwrap().take_while_digits(2).count(), 1);
+
+    // Stays exhausted
+    let mut iter = PellSolutionIterator::new(2).unwrap().take_while_digits(1);
+    assert!(iter.next().is_some());
+    assert!(iter.next().is_none());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn test_iterator_lending_mode() {
+    let expected = pell_solutions(61, 10).unwrap();
+    let mut iter = PellSolutionIterator::new(61).unwrap();
+
+    for (k, (x, y)) in expected.iter().enumerate() {
+        assert_eq!(iter.current_k(), k as u64 + 1);
+        assert_eq!(iter.current(), (x, y));
+        assert!(verify_pell_solution(61, iter.current().0, iter.current().1));
+        iter.advance();
+    }
+
+    // Lending and owning iteration can be mixed
+    let mut mixed = PellSolutionIterator::new(61).unwrap();
+    mixed.advance();
+    assert_eq!(mixed.next().unwrap(), expected[1]);
+    assert_eq!(mixed.current(), (&expected[2].0, &expected[2].1));
+}
+
+#[test]
+fn test_iterator_pairs() {
+    let expected = pell_solutions(13, 11).unwrap();
+    let pairs: Vec<_> = PellSolutionIterator::new(13).unwrap().pairs().take(10).collect();
+
+    assert_eq!(pairs.len(), 10);
+    for (k, (current, next)) in pairs.iter().enumerate() {
+        assert_eq!(current, &expected[k]);
+        assert_eq!(next, &expected[k + 1]);
+    }
+
+    // Pairs start wherever the iterator is positioned
+    let mut iter = PellSolutionIterator::new(13).unwrap();
+    iter.skip_to(5).unwrap();
+    let (first, _) = iter.pairs().next().unwrap();
+    assert_eq!(first, expected[4]);
+}
+
+#[test]
+fn test_iterator_with_known_fundamental() {
+    use pell991::pell_min_solution;
+
+    let (x1, y1) = pell_min_solution(991).unwrap();
+    let known = PellSolutionIterator::with_fundamental(991, x1, y1);
+    let solved = PellSolutionIterator::new(991).unwrap();
+
+    assert_eq!(known.d_value(), 991);
+    assert_eq!(known.current_k(), 1);
+    assert_eq!(known.take(8).collect::<Vec<_>>(), solved.take(8).collect::<Vec<_>>());
+
+    // The pair is trusted in debug builds too: the second solution yields every other one
+    let squared = PellSolutionIterator::with_fundamental(2, BigInt::from(17), BigInt::from(12));
+    let every_other: Vec<_> = PellSolutionIterator::new(2).unwrap().skip(1).step_by(2).take(4).collect();
+    assert_eq!(squared.take(4).collect::<Vec<_>>(), every_other);
+}
+
+#[test]
+fn test_search_solutions_by_index() {
+    let solutions = pell_solutions(13, 30).unwrap();
+    let even_y = search_solutions(13, SearchBound::MaxIndex(30), |_, y| !y.bit(0)).unwrap();
+
+    let expected: Vec<u64> = (1..=30).filter(|&k| !solutions[k as usize - 1].1.bit(0)).collect();
+    assert_eq!(even_y.iter().map(|s| s.k.unwrap()).collect::<Vec<_>>(), expected);
+    for solution in &even_y {
+        let (x, y) = &solutions[solution.k.unwrap() as usize - 1];
+        assert_eq!((&solution.x, &solution.y), (x, y), "matches are the k-th solutions");
+        assert_eq!(solution.d, 13);
+    }
+}
+
+#[test]
+fn test_search_solutions_by_digits() {
+    let mut seen = 0;
+    let all = search_solutions(991, SearchBound::MaxDigits(300), |x, _| {
+        seen += 1;
+        x.to_string().len() <= 300
+    })
+    .unwrap();
+    assert_eq!(all.len(), seen, "the predicate sees exactly the solutions within the bound");
+    assert_eq!(all.len(), 10, "x₁ has 30 digits, so x₁₀ has 300 and x₁₁ has 330");
+}
+
+#[test]
+fn test_search_solutions_edge_cases() {
+    assert!(search_solutions(2, SearchBound::MaxIndex(0), |_, _| true).unwrap().is_empty());
+    assert!(search_solutions(2, SearchBound::MaxDigits(0), |_, _| true).unwrap().is_empty(), "x₁ = 3 has a digit");
+    assert!(search_solutions(2, SearchBound::MaxIndex(50), |_, _| false).unwrap().is_empty());
+    assert_eq!(search_solutions(9, SearchBound::MaxIndex(5), |_, _| true), Err(PellError::PerfectSquare(9)));
+}
+
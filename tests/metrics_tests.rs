@@ -0,0 +1,81 @@
+//! Tests for the work counters
+
+#![cfg(feature = "metrics")]
+
+use pell991::{
+    metrics, pell_min_solution, pell_min_solution_bsgs, pell_min_solution_chakravala, pell_solution_k,
+    pell_solution_k_x, pell_solution_k_y, pell_solutions, verify_pell_solution, Metrics,
+};
+
+#[test]
+fn test_cf_solve_counts() {
+    let (_, work) = metrics::measure(|| pell_min_solution(991).unwrap());
+    assert_eq!(work.cf_steps, 59);
+    // 2 multiplications per step, and 3 for the single solution check at the end of the period
+    assert_eq!(work.bigint_multiplications, 59 * 2 + 3);
+    assert_eq!(work.recurrence_steps, 0);
+    assert_eq!(work.exponentiations, 0);
+
+    // Odd period 11: the end of the first period is skipped without a check
+    let (_, work) = metrics::measure(|| pell_min_solution(61).unwrap());
+    assert_eq!(work.cf_steps, 21);
+    assert_eq!(work.bigint_multiplications, 21 * 2 + 3);
+}
+
+#[test]
+fn test_sequence_counts() {
+    let (x1, y1) = pell_min_solution(2).unwrap();
+
+    let (solutions, work) = metrics::measure(|| pell_solutions(2, 10).unwrap());
+    assert_eq!(solutions.len(), 10);
+    assert_eq!(work.recurrence_steps, 9);
+
+    // One first-order step (4 multiplications), then the second-order recurrence (2 each)
+    let (_, solve_work) = metrics::measure(|| pell_min_solution(2).unwrap());
+    assert_eq!(work.since(&solve_work).bigint_multiplications, 4 + 8 * 2);
+
+    let (_, work) = metrics::measure(|| pell_solution_k(2, &x1, &y1, 1000).unwrap());
+    assert_eq!(work.exponentiations, 1);
+    assert_eq!(work.recurrence_steps, 0);
+    assert!(work.bigint_multiplications < 200, "Exponentiation should be logarithmic in k");
+
+    // 1000 has 10 bits: two multiplications each, plus three to check (x₁, y₁) and two to recover y
+    let (_, x_work) = metrics::measure(|| pell_solution_k_x(2, &x1, &y1, 1000).unwrap());
+    let (_, y_work) = metrics::measure(|| pell_solution_k_y(2, &x1, &y1, 1000).unwrap());
+    assert_eq!(x_work.bigint_multiplications, 3 + 10 * 2);
+    assert_eq!(y_work.bigint_multiplications, 3 + 10 * 2 + 2);
+    assert!(y_work.bigint_multiplications < work.bigint_multiplications);
+
+    let (valid, work) = metrics::measure(|| verify_pell_solution(2, &x1, &y1));
+    assert!(valid);
+    assert_eq!(work.verifications, 1);
+}
+
+#[test]
+fn test_other_algorithms_count_steps() {
+    let (_, chakravala) = metrics::measure(|| pell_min_solution_chakravala(991).unwrap());
+    let (_, bsgs) = metrics::measure(|| pell_min_solution_bsgs(991).unwrap());
+    assert!(chakravala.cf_steps > 0 && chakravala.bigint_multiplications > 0);
+    assert!(bsgs.cf_steps > 0 && bsgs.bigint_multiplications > 0);
+}
+
+#[test]
+fn test_snapshot_and_reset() {
+    metrics::reset();
+    assert_eq!(metrics::snapshot(), Metrics::default());
+
+    pell_min_solution(13).unwrap();
+    let after = metrics::snapshot();
+    assert_eq!(after.cf_steps, 9);
+
+    // Counters are per thread
+    let other = std::thread::spawn(metrics::snapshot).join().unwrap();
+    assert_eq!(other, Metrics::default());
+
+    metrics::reset();
+    assert_eq!(metrics::snapshot(), Metrics::default());
+    assert_eq!(after.since(&Metrics::default()), after);
+
+    // A reset between the snapshots leaves nothing to subtract from, rather than overflowing
+    assert_eq!(metrics::snapshot().since(&after), Metrics::default());
+}
@@ -0,0 +1,109 @@
+//! Tests for the negative Pell equation x² - D·y² = -1
+
+use num_bigint::BigInt;
+use pell991::{
+    continued_fraction, is_valid_pell_d, negative_pell_density, pell_min_solution_with_period, pell_negative_solution,
+    pell_negative_solution_with_fundamental, squarefree_part, NegativeObstruction, PellError,
+};
+
+fn unsolvable(d: u64, reason: NegativeObstruction) -> Result<(BigInt, BigInt), PellError> {
+    Err(PellError::NegativeUnsolvable { d, reason })
+}
+
+#[test]
+fn test_known_solutions() {
+    let cases: [(u64, &str, &str); 5] = [
+        (2, "1", "1"),
+        (5, "2", "1"),
+        (10, "3", "1"),
+        (13, "18", "5"),
+        (61, "29718", "3805"),
+    ];
+    for (d, x, y) in cases {
+        let (sx, sy) = pell_negative_solution(d).unwrap();
+        assert_eq!((sx.to_string(), sy.to_string()), (x.to_string(), y.to_string()), "D = {}", d);
+    }
+}
+
+#[test]
+fn test_solutions_satisfy_equation() {
+    for d in (2..2000).filter(|&d| is_valid_pell_d(d)) {
+        if let Ok((x, y)) = pell_negative_solution(d) {
+            assert_eq!(&x * &x - BigInt::from(d) * &y * &y, BigInt::from(-1), "D = {}", d);
+        }
+    }
+}
+
+#[test]
+fn test_solvable_exactly_for_odd_periods() {
+    for d in (2..2000).filter(|&d| is_valid_pell_d(d)) {
+        let odd_period = continued_fraction(d).unwrap().period.len() % 2 == 1;
+        assert_eq!(pell_negative_solution(d).is_ok(), odd_period, "D = {}", d);
+    }
+}
+
+#[test]
+fn test_obstructions() {
+    assert_eq!(pell_negative_solution(8), unsolvable(8, NegativeObstruction::DivisibleBy4));
+    assert_eq!(pell_negative_solution(3), unsolvable(3, NegativeObstruction::PrimeFactor(3)));
+    assert_eq!(pell_negative_solution(21), unsolvable(21, NegativeObstruction::PrimeFactor(3)));
+    assert_eq!(pell_negative_solution(991), unsolvable(991, NegativeObstruction::PrimeFactor(991)));
+    // 34 = 2·17 passes every congruence test, but √34 = [5; (1, 4, 1, 10)]
+    assert_eq!(pell_negative_solution(34), unsolvable(34, NegativeObstruction::EvenPeriod(4)));
+}
+
+#[test]
+fn test_invalid_d() {
+    assert_eq!(pell_negative_solution(1), Err(PellError::InvalidD(1)));
+    assert_eq!(pell_negative_solution(25), Err(PellError::PerfectSquare(25)));
+}
+
+#[test]
+fn test_density_matches_solver() {
+    let density = negative_pell_density(1..=3000);
+    let squarefree: Vec<u64> = (2..=3000).filter(|&d| squarefree_part(d) == d).collect();
+    let solvable: Vec<u64> = squarefree.iter().copied().filter(|&d| pell_negative_solution(d).is_ok()).collect();
+
+    assert_eq!(density.squarefree, squarefree.len() as u64, "1 and D with square factors are skipped");
+    assert_eq!(density.solvable, solvable.len() as u64);
+    assert_eq!(density.density(), solvable.len() as f64 / squarefree.len() as f64);
+
+    for (residue, class) in density.by_residue_mod_8.iter().enumerate() {
+        let in_class = |d: &&u64| **d % 8 == residue as u64;
+        assert_eq!(class.squarefree, squarefree.iter().filter(in_class).count() as u64, "D ≡ {} (mod 8)", residue);
+        assert_eq!(class.solvable, solvable.iter().filter(in_class).count() as u64, "D ≡ {} (mod 8)", residue);
+    }
+}
+
+#[test]
+fn test_density_congruence_classes() {
+    let density = negative_pell_density(2..=5000);
+    for residue in [0, 3, 4, 6, 7] {
+        assert_eq!(density.by_residue_mod_8[residue].solvable, 0, "no solutions for D ≡ {} (mod 8)", residue);
+    }
+    for residue in [0, 4] {
+        assert_eq!(density.by_residue_mod_8[residue].squarefree, 0, "D ≡ {} (mod 8) is divisible by 4", residue);
+    }
+    assert!(density.by_residue_mod_8[5].solvable > density.by_residue_mod_8[1].solvable);
+}
+
+#[test]
+fn test_density_empty_range() {
+    let density = negative_pell_density(0..=1);
+    assert_eq!((density.squarefree, density.solvable), (0, 0));
+    assert_eq!(density.density(), 0.0);
+}
+
+#[test]
+fn test_with_fundamental_matches_expansion() {
+    for d in (2..2000).filter(|&d| is_valid_pell_d(d)) {
+        let ((x1, y1), period) = pell_min_solution_with_period(d).unwrap();
+        assert_eq!(
+            pell_negative_solution_with_fundamental(d, &x1, &y1, period),
+            pell_negative_solution(d),
+            "D = {d}"
+        );
+    }
+    let (x1, y1) = (BigInt::from(3), BigInt::from(2));
+    assert_eq!(pell_negative_solution_with_fundamental(4, &x1, &y1, 1), Err(PellError::PerfectSquare(4)));
+}
@@ -0,0 +1,62 @@
+//! Tests for parallel solution generation
+
+#![cfg(feature = "rayon")]
+
+use pell991::{
+    par_solutions, pell_min_solutions_for, pell_solutions, pell_solutions_parallel, pell_solutions_range, solve_range,
+    PellError,
+};
+use rayon::prelude::*;
+
+#[test]
+fn test_par_solutions_matches_sequential() {
+    for (d, start, end) in [(2, 1, 2), (2, 1, 500), (61, 7, 93), (991, 100, 401)] {
+        let parallel: Vec<_> = par_solutions(d, start..end).unwrap().collect();
+        let sequential = pell_solutions_range(d, start, end).unwrap();
+        assert_eq!(parallel, sequential, "D = {}, range {}..{}", d, start, end);
+    }
+}
+
+#[test]
+fn test_par_solutions_in_custom_pool() {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+    let parallel: Vec<_> = pool.install(|| par_solutions(13, 1..50).unwrap().collect());
+    assert_eq!(parallel, pell_solutions_range(13, 1, 50).unwrap());
+}
+
+#[test]
+fn test_par_solutions_empty_and_errors() {
+    assert_eq!(par_solutions(2, 5..5).unwrap().count(), 0);
+    let (start, end) = (9, 5);
+    assert_eq!(par_solutions(2, start..end).unwrap().count(), 0);
+    assert!(matches!(par_solutions(2, 0..5), Err(PellError::InvalidK(0))));
+    assert!(matches!(par_solutions(9, 1..5), Err(PellError::PerfectSquare(9))));
+}
+
+#[test]
+fn test_pell_solutions_parallel() {
+    for (d, count) in [(2, 0), (2, 1), (13, 1000), (991, 257)] {
+        let parallel = pell_solutions_parallel(d, count).unwrap();
+        assert_eq!(parallel, pell_solutions(d, count).unwrap(), "D = {}, count = {}", d, count);
+    }
+    assert_eq!(pell_solutions_parallel(16, 10), Err(PellError::PerfectSquare(16)));
+    assert_eq!(pell_solutions_parallel(1, 10), Err(PellError::InvalidD(1)));
+}
+
+#[test]
+fn test_pell_min_solutions_for_matches_solve_range() {
+    let parallel = pell_min_solutions_for(0..2000);
+    let sequential: Vec<_> = solve_range(0..2000).collect();
+    assert_eq!(parallel, sequential);
+    assert!(parallel.windows(2).all(|pair| pair[0].0 < pair[1].0), "Results should be in order of D");
+}
+
+#[test]
+fn test_pell_min_solutions_for_arbitrary_sets() {
+    let ds = vec![991, 4, 2, 1_000_003, 61];
+    let results = pell_min_solutions_for(ds);
+    let order: Vec<u64> = results.iter().map(|(d, _)| *d).collect();
+    assert_eq!(order, [991, 2, 1_000_003, 61], "Squares should be skipped and the order kept");
+    assert!(results.iter().all(|(_, result)| result.is_ok()));
+    assert!(pell_min_solutions_for(Vec::new()).is_empty());
+}
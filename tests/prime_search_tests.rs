@@ -0,0 +1,69 @@
+//! Tests for the searches of prime solution coordinates
+
+use pell991::random::is_probable_prime_big;
+use pell991::{find_prime_x, find_prime_y, pell_solutions, PellError, SplitMix64};
+
+#[test]
+fn test_prime_x_indices() {
+    assert_eq!(find_prime_x(2, 100).unwrap(), [1, 2, 4, 8], "D = 2");
+    assert_eq!(find_prime_x(3, 100).unwrap(), [1, 2, 4, 16], "D = 3");
+    assert_eq!(find_prime_x(6, 100).unwrap(), [1, 4], "D = 6");
+    assert_eq!(find_prime_x(7, 100).unwrap(), [2, 4, 32], "D = 7");
+    assert_eq!(find_prime_x(13, 100).unwrap(), [] as [u64; 0], "x₁ = 649 = 11·59, and no later x is prime");
+
+    // The bound is inclusive, and only the powers of two up to it are visited
+    assert_eq!(find_prime_x(3, 16).unwrap(), [1, 2, 4, 16], "D = 3");
+    assert_eq!(find_prime_x(3, 15).unwrap(), [1, 2, 4], "D = 3");
+    assert_eq!(find_prime_x(2, 1).unwrap(), [1], "D = 2");
+    assert_eq!(find_prime_x(2, 0).unwrap(), [] as [u64; 0], "no index to consider");
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn test_prime_x_squares_its_way_up() {
+    use pell991::metrics::measure;
+
+    // Doubling the bound costs one squaring, where walking the solutions would take 128 steps
+    pell991::pell_min_solution(7).unwrap();
+    let (_, short) = measure(|| find_prime_x(7, 128).unwrap());
+    let (_, long) = measure(|| find_prime_x(7, 256).unwrap());
+    assert_eq!(long.recurrence_steps, 0);
+    assert_eq!(long.bigint_multiplications - short.bigint_multiplications, 1);
+}
+
+#[test]
+fn test_prime_x_agrees_with_testing_every_index() {
+    let mut rng = SplitMix64::new(3);
+    for d in [2, 3, 5, 6, 7, 11] {
+        let every: Vec<u64> = pell_solutions(d, 40)
+            .unwrap()
+            .iter()
+            .zip(1..)
+            .filter(|((x, _), _)| is_probable_prime_big(x, 20, &mut rng))
+            .map(|(_, k)| k)
+            .collect();
+        assert_eq!(find_prime_x(d, 40).unwrap(), every, "only powers of two give a prime x for D = {}", d);
+    }
+}
+
+#[test]
+fn test_prime_y_only_at_the_first_index() {
+    let mut rng = SplitMix64::new(4);
+    for d in [2, 3, 6, 7, 11, 13] {
+        let solutions = pell_solutions(d, 30).unwrap();
+        assert!(
+            solutions[1..].iter().all(|(_, y)| !is_probable_prime_big(y, 20, &mut rng)),
+            "yₖ is composite for k > 1 when D = {}",
+            d
+        );
+        let expected: Vec<u64> = if is_probable_prime_big(&solutions[0].1, 20, &mut rng) { vec![1] } else { vec![] };
+        assert_eq!(find_prime_y(d, 30).unwrap(), expected, "D = {}", d);
+    }
+    assert_eq!(find_prime_y(2, 0).unwrap(), [] as [u64; 0], "no index to consider");
+}
+
+#[test]
+fn test_invalid_d() {
+    assert!(matches!(find_prime_x(1, 10), Err(PellError::InvalidD(1))));
+    assert!(matches!(find_prime_y(16, 10), Err(PellError::PerfectSquare(16))));
+}
@@ -0,0 +1,70 @@
+//! Tests for the Python bindings, run in an embedded interpreter
+
+#![cfg(feature = "python")]
+
+use pell991::BigInt;
+use pyo3::prelude::*;
+use pyo3::types::{PyInt, PyModule};
+
+/// Run `f` with a fresh instance of the `pell991` module
+fn with_module<F>(f: F)
+where
+    F: for<'py> FnOnce(&Bound<'py, PyModule>) -> PyResult<()>,
+{
+    Python::initialize();
+    Python::attach(|py| {
+        let module = PyModule::new(py, "pell991")?;
+        pell991::python::pell991(&module)?;
+        f(&module)
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_min_solution_returns_python_ints() {
+    with_module(|module| {
+        let solution = module.getattr("min_solution")?.call1((991,))?;
+        let x = solution.get_item(0)?;
+        assert!(x.is_instance_of::<PyInt>(), "x should be a Python int");
+        assert_eq!(x.str()?.to_str()?, "379516400906811930638014896080");
+        Ok(())
+    });
+}
+
+#[test]
+fn test_solution_k_and_verify() {
+    with_module(|module| {
+        let (x, y): (BigInt, BigInt) = module.getattr("solution_k")?.call1((2, 3))?.extract()?;
+        assert_eq!((x.to_string(), y.to_string()), ("99".to_string(), "70".to_string()));
+
+        let verify = module.getattr("verify")?;
+        assert!(verify.call1((2, 99, 70))?.extract::<bool>()?);
+        assert!(!verify.call1((2, 99, 71))?.extract::<bool>()?);
+        Ok(())
+    });
+}
+
+#[test]
+fn test_solutions_and_continued_fraction() {
+    with_module(|module| {
+        let solutions: Vec<(BigInt, BigInt)> = module.getattr("solutions")?.call1((2, 3))?.extract()?;
+        assert_eq!(solutions.len(), 3);
+        assert_eq!(solutions[2].0, BigInt::from(99));
+
+        let (a0, period): (u64, Vec<u64>) = module.getattr("continued_fraction")?.call1((13,))?.extract()?;
+        assert_eq!((a0, period), (3, vec![1, 1, 1, 1, 6]));
+        Ok(())
+    });
+}
+
+#[test]
+fn test_errors_raise_value_error() {
+    with_module(|module| {
+        let err = module.getattr("min_solution")?.call1((9,)).unwrap_err();
+        Python::attach(|py| {
+            assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py), "perfect squares raise ValueError");
+            assert_eq!(err.value(py).str().unwrap().to_str().unwrap(), "D must be non-square, got 9 which is 3²");
+        });
+        Ok(())
+    });
+}
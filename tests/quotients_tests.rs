@@ -0,0 +1,67 @@
+//! Tests for the partial quotient distribution
+
+use pell991::quotients::{gauss_kuzmin_probability, QuotientBucket};
+use pell991::{continued_fraction, partial_quotient_distribution, period_quotient_distribution, PellError};
+
+#[test]
+fn test_gauss_kuzmin_probabilities() {
+    let known = [(1, 0.41504), (2, 0.16993), (3, 0.09311), (10, 0.01197)];
+    for (k, p) in known {
+        assert!((gauss_kuzmin_probability(k) - p).abs() < 1e-5, "Wrong probability for k = {}", k);
+    }
+    let sum: f64 = (1..=100_000).map(gauss_kuzmin_probability).sum();
+    assert!((sum - 1.0).abs() < 1e-4, "the probabilities sum to 1, got {}", sum);
+}
+
+#[test]
+fn test_period_counts_match_expansion() {
+    for d in [61, 94, 991, 9949] {
+        let period = continued_fraction(d).unwrap().period;
+        let distribution = period_quotient_distribution(d, 5).unwrap();
+        let inner = &period[..period.len() - 1];
+        assert_eq!(distribution.total, inner.len() as u64, "D = {}", d);
+        for bucket in &distribution.buckets {
+            let count = inner.iter().filter(|&&a| a == bucket.quotient).count() as u64;
+            assert_eq!(bucket.count, count, "Wrong count of {} for D = {}", bucket.quotient, d);
+        }
+        let tail = inner.iter().filter(|&&a| a > 5).count() as u64;
+        assert_eq!(distribution.tail.count, tail, "Wrong tail for D = {}", d);
+        assert_eq!(distribution.tail.quotient, 6, "the tail starts after the last bucket");
+    }
+}
+
+#[test]
+fn test_expected_frequencies_sum_to_one() {
+    let distribution = period_quotient_distribution(991, 7).unwrap();
+    let expected: f64 = distribution.buckets.iter().chain([&distribution.tail]).map(|b| b.expected).sum();
+    let observed: f64 = distribution.buckets.iter().chain([&distribution.tail]).map(|b| b.observed).sum();
+    assert!((expected - 1.0).abs() < 1e-12, "buckets and tail cover every quotient");
+    assert!((observed - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_range_follows_the_law() {
+    let distribution = partial_quotient_distribution(2..=20_000, 8);
+    assert!(distribution.total > 700_000);
+    for QuotientBucket { quotient, observed, expected, .. } in &distribution.buckets {
+        assert!((observed - expected).abs() < 0.02, "{} occurs with frequency {}, expected {}", quotient, observed, expected);
+    }
+    assert!(distribution.total_variation < 0.03, "total variation {}", distribution.total_variation);
+}
+
+#[test]
+fn test_range_adds_up_periods() {
+    let range = partial_quotient_distribution(90..=100, 4);
+    let total: u64 = (90..=100).filter_map(|d| period_quotient_distribution(d, 4).ok()).map(|p| p.total).sum();
+    assert_eq!(range.total, total, "invalid D like 100 are skipped");
+
+    let empty = partial_quotient_distribution(0..=1, 4);
+    assert_eq!(empty.total, 0);
+    assert_eq!((empty.chi_squared, empty.total_variation), (0.0, 0.0));
+}
+
+#[test]
+fn test_invalid_d() {
+    assert_eq!(period_quotient_distribution(1, 5), Err(PellError::InvalidD(1)));
+    assert!(matches!(period_quotient_distribution(81, 5), Err(PellError::PerfectSquare { .. })), "81 is 9²");
+}
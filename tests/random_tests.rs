@@ -0,0 +1,98 @@
+//! Tests for injectable randomness
+
+use num_bigint::BigInt;
+use pell991::random::{is_probable_prime, is_probable_prime_big, random_moduli, verify_pell_solution_random};
+use pell991::{is_prime, pell_solutions, RandomSource, SplitMix64};
+
+#[test]
+fn test_splitmix_reference_values() {
+    let mut rng = SplitMix64::new(0);
+    let values: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+    assert_eq!(values, [0xE220_A839_7B1D_CDAF, 0x6E78_9E6A_A1B9_65F4, 0x06C4_5D18_8009_454F]);
+}
+
+#[test]
+fn test_seed_resumes_the_stream() {
+    let mut rng = SplitMix64::new(99);
+    rng.next_u64();
+    let mut resumed = SplitMix64::new(rng.seed());
+    assert_eq!(rng.next_u64(), resumed.next_u64(), "a generator built from seed() continues the stream");
+}
+
+#[test]
+fn test_next_below_is_in_range() {
+    let mut rng = SplitMix64::new(5);
+    for bound in [1, 2, 3, 10, 1 << 63, u64::MAX] {
+        for _ in 0..100 {
+            assert!(rng.next_below(bound) < bound, "value below {}", bound);
+        }
+    }
+    // A mutable reference is a source too, so generators can be lent out
+    fn draw<R: RandomSource>(mut source: R) -> u64 {
+        source.next_below(7)
+    }
+    assert!(draw(&mut rng) < 7);
+}
+
+#[test]
+fn test_probable_prime_matches_trial_division() {
+    let mut rng = SplitMix64::new(11);
+    for n in 0..20_000 {
+        assert_eq!(is_probable_prime(n, 10, &mut rng), is_prime(n), "n = {}", n);
+    }
+    for carmichael in [561, 1105, 1729, 2465, 2821, 6601, 8911, 3_215_031_751] {
+        assert!(!is_probable_prime(carmichael, 20, &mut rng), "{} is composite", carmichael);
+    }
+    assert!(is_probable_prime(18_446_744_073_709_551_557, 20, &mut rng));
+    assert!(!is_probable_prime(4_294_967_297, 20, &mut rng), "2³² + 1 = 641 · 6700417");
+}
+
+#[test]
+fn test_big_miller_rabin() {
+    let mut rng = SplitMix64::new(11);
+    for n in (0..2000u64).chain([u64::MAX - 58, u64::MAX]) {
+        assert_eq!(is_probable_prime_big(&BigInt::from(n), 20, &mut rng), is_prime(n), "n = {}", n);
+    }
+
+    let two = BigInt::from(2);
+    for e in [61u32, 89, 107, 127, 521] {
+        assert!(is_probable_prime_big(&(two.pow(e) - 1), 20, &mut rng), "2^{} - 1 is a Mersenne prime", e);
+    }
+    for e in [67u32, 101, 128, 257] {
+        assert!(!is_probable_prime_big(&(two.pow(e) - 1), 20, &mut rng), "2^{} - 1 is composite", e);
+    }
+    // 2⁶⁴ + 13 is the first prime past u64, and a product of two 64-bit primes is not prime
+    let p = BigInt::from(u64::MAX) + 14;
+    assert!(is_probable_prime_big(&p, 20, &mut rng), "2^64 + 13 is prime");
+    assert!(!is_probable_prime_big(&(&p * &p), 20, &mut rng), "a square is composite");
+    assert!(!is_probable_prime_big(&BigInt::from(-7), 20, &mut rng), "negative values are not prime");
+}
+
+#[test]
+fn test_random_moduli_are_reproducible_primes() {
+    let moduli = random_moduli(5, &mut SplitMix64::new(3));
+    assert_eq!(moduli, random_moduli(5, &mut SplitMix64::new(3)), "the same seed gives the same moduli");
+    assert_ne!(moduli, random_moduli(5, &mut SplitMix64::new(4)));
+
+    let mut rng = SplitMix64::new(0);
+    for &p in &moduli {
+        assert!(p >= 1 << 63, "{} has 64 bits", p);
+        assert!(is_probable_prime(p, 40, &mut rng), "{} is prime", p);
+    }
+    let mut sorted = moduli.clone();
+    sorted.sort_unstable();
+    sorted.dedup();
+    assert_eq!(sorted.len(), 5, "moduli are distinct");
+}
+
+#[test]
+fn test_random_verification() {
+    let mut rng = SplitMix64::new(17);
+    for (x, y) in pell_solutions(61, 20).unwrap() {
+        assert!(verify_pell_solution_random(61, &x, &y, 3, &mut rng));
+        assert!(!verify_pell_solution_random(61, &x, &(&y + BigInt::from(1)), 3, &mut rng));
+    }
+
+    let (x, y) = (BigInt::from(4), BigInt::from(1));
+    assert!(verify_pell_solution_random(2, &x, &y, 0, &mut rng), "no rounds always pass");
+}
@@ -0,0 +1,111 @@
+//! Tests for the record-setting D values
+
+use pell991::{
+    continued_fraction, pell_min_solution, period_length, DigitRecords, PellError, PeriodRecord, PeriodRecords,
+};
+
+#[test]
+fn test_period_length_matches_expansion() {
+    for d in (2..2000).filter(|d| continued_fraction(*d).is_ok()) {
+        let expected = continued_fraction(d).unwrap().period.len() as u64;
+        assert_eq!(period_length(d), Ok(expected), "Wrong period length for D = {}", d);
+    }
+    assert_eq!(period_length(u64::MAX), Ok(2));
+}
+
+#[test]
+fn test_period_length_invalid_d() {
+    assert_eq!(period_length(1), Err(PellError::InvalidD(1)));
+    assert!(matches!(period_length(49), Err(PellError::PerfectSquare { .. })), "49 is 7²");
+}
+
+#[test]
+fn test_known_records() {
+    let expected = [
+        (2, 1), (3, 2), (7, 4), (13, 5), (19, 6), (31, 8), (43, 10), (46, 12), (94, 16),
+        (139, 18), (151, 20), (166, 22), (211, 26),
+    ];
+    let records: Vec<(u64, u64)> = PeriodRecords::new().take(expected.len()).map(|r| (r.d, r.period)).collect();
+    assert_eq!(records, expected);
+}
+
+#[test]
+fn test_records_against_brute_force() {
+    let mut record = 0;
+    let mut expected = Vec::new();
+    for d in 2..=5000 {
+        if let Ok(period) = period_length(d) {
+            if period > record {
+                record = period;
+                expected.push(PeriodRecord { d, period });
+            }
+        }
+    }
+    let records: Vec<PeriodRecord> = PeriodRecords::new().take_while(|r| r.d <= 5000).collect();
+    assert_eq!(records, expected);
+}
+
+#[test]
+fn test_resume_continues_the_scan() {
+    let full: Vec<PeriodRecord> = PeriodRecords::new().take(20).collect();
+
+    let mut scan = PeriodRecords::new();
+    let first: Vec<PeriodRecord> = scan.by_ref().take(8).collect();
+    assert_eq!(scan.next_d(), Some(first[7].d + 1), "the scan stops right after the last record");
+    assert_eq!(scan.record(), first[7].period);
+
+    let rest = PeriodRecords::resume(scan.next_d().unwrap(), scan.record()).take(12);
+    assert_eq!(first.into_iter().chain(rest).collect::<Vec<_>>(), full, "resuming loses no records");
+}
+
+#[test]
+fn test_resume_mid_gap() {
+    // Between records 94 (period 16) and 139 (period 18)
+    let next = PeriodRecords::resume(120, 16).next().unwrap();
+    assert_eq!(next, PeriodRecord { d: 139, period: 18 });
+
+    // A high-water mark above every period up to D reports nothing below it
+    let next = PeriodRecords::resume(2, 18).next().unwrap();
+    assert_eq!(next, PeriodRecord { d: 151, period: 20 });
+}
+
+#[test]
+fn test_scan_ends_at_u64_max() {
+    // u64::MAX = a₀² + 2a₀ with a₀ = 2³² - 1, so its period is (a₀, 2a₀)
+    let mut scan = PeriodRecords::resume(u64::MAX, 1);
+    assert_eq!(scan.next(), Some(PeriodRecord { d: u64::MAX, period: 2 }));
+    assert_eq!(scan.next_d(), None);
+    assert_eq!(scan.next(), None, "the iterator is fused");
+}
+
+#[test]
+fn test_known_digit_records() {
+    let expected = [
+        (2, 1), (10, 2), (13, 3), (29, 4), (46, 5), (61, 10), (109, 15), (181, 19), (277, 21),
+        (409, 23), (421, 34), (541, 37), (661, 38), (1021, 48),
+    ];
+    let records: Vec<(u64, u64)> = DigitRecords::new().take(expected.len()).map(|r| (r.d, r.digits)).collect();
+    assert_eq!(records, expected);
+}
+
+#[test]
+fn test_digit_records_carry_the_solution() {
+    for record in DigitRecords::new().take_while(|r| r.d <= 1000) {
+        assert_eq!(pell_min_solution(record.d), Ok((record.x.clone(), record.y.clone())), "D = {}", record.d);
+        assert_eq!(record.x.to_string().len() as u64, record.digits, "D = {}", record.d);
+    }
+}
+
+#[test]
+fn test_digit_records_resume() {
+    let full: Vec<u64> = DigitRecords::new().take(12).map(|r| r.d).collect();
+
+    let mut scan = DigitRecords::new();
+    let first: Vec<u64> = scan.by_ref().take(5).map(|r| r.d).collect();
+    let rest = DigitRecords::resume(scan.next_d().unwrap(), scan.record()).take(7).map(|r| r.d);
+    assert_eq!(first.into_iter().chain(rest).collect::<Vec<_>>(), full, "resuming loses no records");
+
+    // 991 is famous for its solution, but 661 already needs more digits
+    let next = DigitRecords::resume(662, 38).next().unwrap();
+    assert_eq!((next.d, next.digits), (1021, 48));
+}
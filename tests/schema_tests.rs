@@ -0,0 +1,55 @@
+//! Tests for JSON Schema generation
+
+#![cfg(feature = "schemars")]
+
+use pell991::{ContinuedFraction, DReport, PellError, PellSolution};
+use schemars::schema_for;
+use serde_json::Value;
+
+#[test]
+fn test_solution_schema_describes_decimal_strings() {
+    let schema = schema_for!(PellSolution);
+    let properties = &schema.as_value()["properties"];
+
+    for coordinate in ["x", "y"] {
+        assert_eq!(properties[coordinate]["type"], "string", "{} is a decimal string", coordinate);
+        assert_eq!(properties[coordinate]["pattern"], "^-?[0-9]+$", "{} has a digit pattern", coordinate);
+    }
+    assert_eq!(properties["d"]["type"], "integer");
+
+    let required: Vec<&str> = schema.as_value()["required"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| field.as_str().unwrap())
+        .collect();
+    assert_eq!(required, ["d", "x", "y"], "k is optional");
+}
+
+#[test]
+fn test_schemas_cover_serialized_fields() {
+    let cases: [(Value, Value); 2] = [
+        (
+            schema_for!(DReport).to_value(),
+            serde_json::to_value(pell991::analyze_d(991)).unwrap(),
+        ),
+        (
+            schema_for!(ContinuedFraction).to_value(),
+            serde_json::to_value(pell991::continued_fraction(13).unwrap()).unwrap(),
+        ),
+    ];
+    for (schema, value) in cases {
+        let properties = schema["properties"].as_object().unwrap();
+        for field in value.as_object().unwrap().keys() {
+            assert!(properties.contains_key(field), "{} should be in the schema {}", field, schema);
+        }
+    }
+}
+
+#[test]
+fn test_error_schema_lists_variants() {
+    let schema = schema_for!(PellError).to_value().to_string();
+    for variant in ["InvalidD", "PerfectSquare", "LimitExceeded", "Timeout", "NegativeUnsolvable", "Decode"] {
+        assert!(schema.contains(variant), "{} should be in the error schema", variant);
+    }
+}
@@ -0,0 +1,116 @@
+//! Tests for serde support
+
+#![cfg(feature = "serde")]
+
+use std::time::Duration;
+
+use pell991::{
+    continued_fraction, pell_min_solution, DReport, NegativeObstruction, PellError, PellSolution,
+    PellSolutionIterator,
+};
+
+#[test]
+fn test_iterator_state_round_trip() {
+    let mut iter = PellSolutionIterator::new(991).unwrap();
+    iter.next();
+    iter.next();
+
+    let json = serde_json::to_string(&iter).unwrap();
+    assert!(json.contains("\"d\":991"));
+    assert!(json.contains("\"k\":3"));
+    assert!(json.contains("\"x\":\""), "x should be a decimal string: {}", json);
+
+    let mut restored: PellSolutionIterator = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.current_k(), 3);
+    assert_eq!(restored.d_value(), 991);
+    assert_eq!(restored.next(), iter.next());
+    assert_eq!(restored.next(), iter.next());
+
+    // The fundamental solution is recovered, so reset still works
+    restored.reset();
+    assert_eq!(restored.next(), PellSolutionIterator::new(991).unwrap().next());
+}
+
+#[test]
+fn test_iterator_state_rejects_invalid_positions() {
+    let invalid = [
+        r#"{"d":2,"k":0,"x":"3","y":"2"}"#,          // k must be at least 1
+        r#"{"d":2,"k":1,"x":"4","y":"2"}"#,          // (4, 2) does not solve D = 2
+        r#"{"d":2,"k":1,"x":"17","y":"12"}"#,        // (17, 12) is the second solution, not the first
+        r#"{"d":2,"k":3,"x":"17","y":"12"}"#,        // ... nor the third
+        r#"{"d":2,"k":1,"x":"-3","y":"2"}"#,         // solutions are positive
+        r#"{"d":2,"k":4000000000,"x":"3","y":"2"}"#, // rejected without computing the k-th solution
+        r#"{"d":4,"k":1,"x":"1","y":"0"}"#,          // D = 4 is a square
+        r#"{"d":2,"k":1,"x":"3x","y":"2"}"#,         // not a decimal integer
+    ];
+    for json in invalid {
+        assert!(serde_json::from_str::<PellSolutionIterator>(json).is_err(), "{} should be rejected", json);
+    }
+}
+
+#[test]
+fn test_solution_bigints_are_decimal_strings() {
+    let (x, y) = pell_min_solution(991).unwrap();
+    let solution = PellSolution::with_index(991, 1, x, y);
+
+    let json = serde_json::to_string(&solution).unwrap();
+    assert_eq!(
+        json,
+        r#"{"d":991,"x":"379516400906811930638014896080","y":"12055735790331359447442538767","k":1}"#
+    );
+    let restored: PellSolution = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, solution);
+    assert_eq!(restored.k, Some(1));
+}
+
+#[test]
+fn test_bigints_round_trip_through_binary_formats() {
+    // bincode is not self-describing, so the decimal strings must be asked for by type
+    let (x, y) = pell_min_solution(991).unwrap();
+    let solution = PellSolution::with_index(991, 1, x, y);
+    let bytes = bincode::serialize(&solution).unwrap();
+    assert_eq!(bincode::deserialize::<PellSolution>(&bytes).unwrap(), solution);
+
+    let mut iter = PellSolutionIterator::new(13).unwrap();
+    iter.next();
+    let bytes = bincode::serialize(&iter).unwrap();
+    let mut restored: PellSolutionIterator = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(restored.next(), iter.next(), "the restored iterator continues from k = 2");
+}
+
+#[test]
+fn test_solution_accepts_integer_coordinates() {
+    let solution: PellSolution = serde_json::from_str(r#"{"d":2,"x":-17,"y":12,"k":null}"#).unwrap();
+    assert_eq!(solution.x.to_string(), "-17");
+    assert!(solution.verify(), "(-17, 12) solves D = 2");
+    assert!(serde_json::from_str::<PellSolution>(r#"{"d":2,"x":"1.5","y":"2","k":null}"#).is_err());
+}
+
+#[test]
+fn test_error_round_trip() {
+    let errors = [
+        PellError::InvalidD(1),
+        PellError::Parse("missing x".to_string()),
+        PellError::LimitExceeded { steps: 10 },
+        PellError::Timeout { limit: Duration::from_millis(1500) },
+        PellError::NegativeUnsolvable { d: 3, reason: NegativeObstruction::PrimeFactor(3) },
+    ];
+    for error in errors {
+        let json = serde_json::to_string(&error).unwrap();
+        let restored: PellError = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, error, "{} should round-trip", json);
+    }
+    assert_eq!(serde_json::to_string(&PellError::InvalidD(1)).unwrap(), r#"{"InvalidD":1}"#);
+}
+
+#[test]
+fn test_continued_fraction_and_report_round_trip() {
+    let cf = continued_fraction(13).unwrap();
+    let json = serde_json::to_string(&cf).unwrap();
+    assert_eq!(json, r#"{"a0":3,"period":[1,1,1,1,6]}"#);
+    assert_eq!(serde_json::from_str::<pell991::ContinuedFraction>(&json).unwrap(), cf);
+
+    let report = pell991::analyze_d(13);
+    let restored: DReport = serde_json::from_str(&serde_json::to_string(&report).unwrap()).unwrap();
+    assert_eq!(restored, report);
+}
@@ -0,0 +1,154 @@
+//! Tests for the HTTP service
+
+#![cfg(feature = "server")]
+
+use std::time::Duration;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use pell991::server::{router, ServerConfig};
+use pell991::{analyze_d, pell_solutions, DReport, PellSolution};
+use serde_json::Value;
+use tower::ServiceExt;
+
+async fn get(app: &Router, uri: &str) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).expect("the body is JSON"))
+}
+
+fn unlimited() -> Router {
+    router(ServerConfig::new().no_rate_limit())
+}
+
+#[tokio::test]
+async fn test_solve() {
+    let (status, body) = get(&unlimited(), "/solve/991").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let solution: PellSolution = serde_json::from_value(body).unwrap();
+    assert_eq!(solution.d, 991);
+    assert_eq!(solution.k, Some(1));
+    assert_eq!(solution.x.to_string(), "379516400906811930638014896080");
+    assert!(solution.verify(), "the served solution solves the equation");
+}
+
+#[tokio::test]
+async fn test_solutions_with_count() {
+    let (status, body) = get(&unlimited(), "/solutions/2?count=4").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let solutions: Vec<PellSolution> = serde_json::from_value(body).unwrap();
+    let expected = pell_solutions(2, 4).unwrap();
+    assert_eq!(solutions.len(), 4);
+    for (k, (solution, (x, y))) in solutions.iter().zip(expected).enumerate() {
+        assert_eq!((&solution.x, &solution.y), (&x, &y), "solution {} matches pell_solutions", k + 1);
+        assert_eq!(solution.k, Some(k as u64 + 1), "solutions are numbered from 1");
+    }
+}
+
+#[tokio::test]
+async fn test_solutions_default_and_maximum_count() {
+    let app = router(ServerConfig::new().no_rate_limit().max_count(20));
+
+    let (_, body) = get(&app, "/solutions/13").await;
+    assert_eq!(body.as_array().unwrap().len(), 10, "10 solutions without a count");
+
+    let (status, body) = get(&app, "/solutions/13?count=21").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "count_too_large");
+}
+
+#[tokio::test]
+async fn test_analyze() {
+    let app = unlimited();
+    let (status, body) = get(&app, "/analyze/991").await;
+    assert_eq!(status, StatusCode::OK);
+    let report: DReport = serde_json::from_value(body).unwrap();
+    assert_eq!(report, analyze_d(991));
+
+    let (status, body) = get(&app, "/analyze/16").await;
+    assert_eq!(status, StatusCode::OK, "invalid D values are still analyzed");
+    assert_eq!(body["is_valid"], false);
+}
+
+#[tokio::test]
+async fn test_invalid_input() {
+    let app = unlimited();
+
+    let (status, body) = get(&app, "/solve/4").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "perfect_square");
+    assert_eq!(body["message"], "D must be non-square, got 4 which is 2²");
+
+    let (status, body) = get(&app, "/solve/1").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_d");
+
+    let (status, body) = get(&app, "/solve/seven").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_request", "path errors are JSON too");
+
+    let (status, body) = get(&app, "/solutions/2?count=-1").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_request", "query errors are JSON too");
+}
+
+#[tokio::test]
+async fn test_limits() {
+    let app = router(ServerConfig::new().no_rate_limit().max_steps(10));
+    let (status, body) = get(&app, "/solve/991").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"], "limit_exceeded");
+
+    let app = router(ServerConfig::new().no_rate_limit().timeout(Duration::ZERO));
+    for uri in ["/solve/991", "/solutions/991", "/analyze/991"] {
+        let (status, body) = get(&app, uri).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE, "{uri} times out");
+        assert_eq!(body["error"], "timeout");
+    }
+}
+
+#[tokio::test]
+async fn test_response_budget() {
+    let app = router(ServerConfig::new().no_rate_limit().max_response_bytes(10_000));
+    let (status, body) = get(&app, "/solutions/991?count=100").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "100 solutions of D = 991 take far more than 10 kB");
+    assert_eq!(body["error"], "response_too_large");
+
+    let (status, body) = get(&app, "/solutions/2?count=10").await;
+    assert_eq!(status, StatusCode::OK, "small answers fit the budget");
+    assert_eq!(body.as_array().unwrap().len(), 10);
+}
+
+#[tokio::test]
+async fn test_analyze_class_number_limits() {
+    // 10¹⁰ + 1 solves in one step, but counting its ideal classes takes far longer
+    let (status, body) = get(&unlimited(), "/analyze/10000000001").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["class_number"], Value::Null, "no class number above CLASS_NUMBER_MAX_D by default");
+    assert_eq!(body["period"], 1);
+
+    let app = router(ServerConfig::new().no_rate_limit().class_number_max_d(0));
+    let (_, body) = get(&app, "/analyze/991").await;
+    assert_eq!(body["class_number"], Value::Null, "class numbers can be turned off");
+
+    let app = router(ServerConfig::new().no_rate_limit().class_number_max_d(u64::MAX).timeout(Duration::from_millis(50)));
+    let (status, body) = get(&app, "/analyze/10000000001").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE, "the timeout covers the class number");
+    assert_eq!(body["error"], "timeout");
+}
+
+#[tokio::test]
+async fn test_rate_limit() {
+    let app = router(ServerConfig::new().rate_limit(1, 2));
+
+    assert_eq!(get(&app, "/solve/2").await.0, StatusCode::OK);
+    assert_eq!(get(&app, "/solve/2").await.0, StatusCode::OK);
+
+    let response = app.oneshot(Request::get("/solve/2").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS, "the burst is used up");
+    assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+}
@@ -0,0 +1,286 @@
+//! Tests for the PellSolution type
+
+use std::collections::{BTreeMap, HashSet};
+
+use pell991::{d_for_solution, pell_min_solution, pell_solution_k, pell_solutions, write_digits, write_solution, PellError, PellSolution};
+use num_bigint::BigInt;
+
+#[test]
+fn test_parse_keyed_form() {
+    let solution: PellSolution = "x=3, y=2, d=2".parse().unwrap();
+    assert_eq!(solution.d, 2);
+    assert_eq!(solution.x, BigInt::from(3));
+    assert_eq!(solution.y, BigInt::from(2));
+    assert!(solution.verify());
+
+    // Keys may appear in any order and whitespace is ignored
+    let reordered: PellSolution = " d = 2 ,y=2,  x=3 ".parse().unwrap();
+    assert_eq!(solution, reordered);
+}
+
+#[test]
+fn test_parse_plain_form_infers_d() {
+    let solution: PellSolution = "17 12".parse().unwrap();
+    assert_eq!(solution.d, 2);
+    assert!(solution.verify());
+
+    // Keyed form without d also infers it
+    let keyed: PellSolution = "x=2, y=1".parse().unwrap();
+    assert_eq!(keyed.d, 3);
+}
+
+#[test]
+fn test_display_round_trip() {
+    let (x, y) = pell_min_solution(991).unwrap();
+    let solution = PellSolution::new(991, x, y);
+
+    let text = solution.to_string();
+    assert_eq!(
+        text,
+        "x=379516400906811930638014896080, y=12055735790331359447442538767, d=991"
+    );
+
+    let parsed: PellSolution = text.parse().unwrap();
+    assert_eq!(parsed, solution);
+    assert!(parsed.verify());
+
+    // The plain form recovers the same D
+    let plain: PellSolution = format!("{} {}", solution.x, solution.y).parse().unwrap();
+    assert_eq!(plain, solution);
+}
+
+#[test]
+fn test_parse_errors() {
+    let cases = [
+        "",
+        "3",
+        "3 2 1",
+        "x=3, y=abc, d=2",
+        "x=3, y=2, d=-2",
+        "x=3, d=2",
+        "x=3, y=2, z=1",
+        "x=3; y=2",
+        "x=3, y=2, x=17",
+        "x=17, y=12, d=2, d=3",
+        "x=17, y=12, k=2, k=2",
+        "5 0",   // y = 0 gives no D
+        "4 2",   // 15 is not divisible by 4
+        "1 1",   // D = 0
+    ];
+
+    for case in cases {
+        assert!(
+            matches!(case.parse::<PellSolution>(), Err(PellError::Parse(_))),
+            "'{}' should fail to parse",
+            case
+        );
+    }
+}
+
+#[test]
+fn test_parsed_invalid_solution_fails_verification() {
+    // Parsing does not verify; the caller can re-check the equation
+    let solution: PellSolution = "x=4, y=2, d=2".parse().unwrap();
+    assert!(!solution.verify());
+}
+
+#[test]
+fn test_solution_ordering() {
+    let mut solutions: Vec<PellSolution> = pell_solutions(2, 4)
+        .unwrap()
+        .into_iter()
+        .rev()
+        .map(|(x, y)| PellSolution::new(2, x, y))
+        .collect();
+    solutions.push("x=2, y=1, d=3".parse().unwrap());
+
+    solutions.sort();
+
+    // Grouped by D, then ordered by magnitude (which is the solution index)
+    let keys: Vec<_> = solutions.iter().map(|s| (s.d, s.x.to_string())).collect();
+    assert_eq!(keys, [
+        (2, "3".to_string()),
+        (2, "17".to_string()),
+        (2, "99".to_string()),
+        (2, "577".to_string()),
+        (3, "2".to_string()),
+    ]);
+}
+
+#[test]
+fn test_solution_deduplication() {
+    let mut set = HashSet::new();
+    set.insert("x=3, y=2, d=2".parse::<PellSolution>().unwrap());
+    set.insert("3 2".parse::<PellSolution>().unwrap());
+    set.insert("17 12".parse::<PellSolution>().unwrap());
+    assert_eq!(set.len(), 2);
+
+    let mut by_solution = BTreeMap::new();
+    by_solution.insert("17 12".parse::<PellSolution>().unwrap(), 2);
+    by_solution.insert("3 2".parse::<PellSolution>().unwrap(), 1);
+    assert_eq!(by_solution.values().copied().collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+fn test_solution_index() {
+    let indexed = PellSolution::with_index(2, 2, BigInt::from(17), BigInt::from(12));
+    assert_eq!(indexed.to_string(), "x=17, y=12, d=2, k=2");
+
+    let parsed: PellSolution = "x=17, y=12, d=2, k=2".parse().unwrap();
+    assert_eq!(parsed.k, Some(2));
+    assert!("x=17, y=12, k=two".parse::<PellSolution>().is_err());
+
+    // The index is metadata: it does not affect equality, hashing or ordering
+    let plain = PellSolution::new(2, BigInt::from(17), BigInt::from(12));
+    assert_eq!(plain.k, None);
+    assert_eq!(plain, indexed);
+    let set: HashSet<_> = [plain, indexed].into_iter().collect();
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn test_iterator_yields_solutions_with_context() {
+    let mut solutions = pell991::PellSolutionIterator::new(991).unwrap().into_solutions();
+    let first = solutions.next().unwrap();
+    assert_eq!((first.d, first.k), (991, Some(1)));
+    assert_eq!((first.x.clone(), first.y.clone()), pell_min_solution(991).unwrap());
+
+    solutions.inner_mut().skip_to(10).unwrap();
+    let tenth = solutions.next().unwrap();
+    assert_eq!(tenth.k, Some(10));
+    assert!(tenth.verify());
+
+    let round_trip: PellSolution = tenth.to_string().parse().unwrap();
+    assert_eq!(round_trip.k, Some(10));
+    assert_eq!(round_trip, tenth);
+}
+
+#[test]
+fn test_write_solution_matches_display() {
+    let (x1, y1) = pell_min_solution(991).unwrap();
+    // About 3000 digits, so several levels of blocks are used
+    for k in [1, 2, 40, 100] {
+        let (x, y) = pell_solution_k(991, &x1, &y1, k).unwrap();
+        let solution = PellSolution::with_index(991, k, x, y);
+
+        let mut out = Vec::new();
+        write_solution(&solution, &mut out, 10).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), solution.to_string(), "Streamed output differs for k = {}", k);
+    }
+}
+
+#[test]
+fn test_write_solution_radix() {
+    let (x1, y1) = pell_min_solution(991).unwrap();
+    let (x, y) = pell_solution_k(991, &x1, &y1, 60).unwrap();
+    let solution = PellSolution::new(991, x.clone(), y.clone());
+
+    for radix in [2, 16, 36] {
+        let mut out = Vec::new();
+        write_solution(&solution, &mut out, radix).unwrap();
+        let expected = format!("x={}, y={}, d=991", x.to_str_radix(radix), y.to_str_radix(radix));
+        assert_eq!(String::from_utf8(out).unwrap(), expected, "Radix {} output differs", radix);
+    }
+
+    // Zero and negative values, and exact powers of the block size
+    let block = BigInt::from(10).pow(512);
+    let solution = PellSolution::new(2, -&block, BigInt::from(0));
+    let mut out = Vec::new();
+    write_solution(&solution, &mut out, 10).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), solution.to_string());
+}
+
+#[test]
+fn test_write_digits() {
+    let (x1, y1) = pell_min_solution(991).unwrap();
+    let (x, _) = pell_solution_k(991, &x1, &y1, 100).unwrap();
+    for n in [x.clone(), -x, BigInt::from(0), BigInt::from(10).pow(1024)] {
+        for radix in [2, 10, 36] {
+            let mut out = Vec::new();
+            write_digits(&n, &mut out, radix).unwrap();
+            assert_eq!(String::from_utf8(out).unwrap(), n.to_str_radix(radix), "Radix {} digits differ", radix);
+        }
+    }
+}
+
+#[test]
+fn test_tuple_conversions() {
+    let (x, y) = pell_min_solution(991).unwrap();
+    let solution = PellSolution::from((991, x.clone(), y.clone()));
+    assert_eq!(<(BigInt, BigInt)>::from(solution.clone()), (x.clone(), y.clone()));
+    assert_eq!(PellSolution::try_from((x, y)).unwrap(), solution, "D is recovered from the pair");
+
+    assert_eq!(
+        PellSolution::try_from((BigInt::from(4), BigInt::from(2))),
+        Err(PellError::Parse("(x² - 1) is not divisible by y² for x=4, y=2".to_string()))
+    );
+}
+
+#[test]
+fn test_machine_integer_conversions() {
+    let solution = PellSolution::from((2u64, 577u128, 408u128));
+    assert!(solution.verify());
+    assert_eq!(<(u128, u128)>::try_from(&solution), Ok((577, 408)));
+
+    // x₅₀ < 2¹²⁸ ≤ x₅₁ for D = 2
+    let (x1, y1) = pell_min_solution(2).unwrap();
+    let (x, y) = pell_solution_k(2, &x1, &y1, 50).unwrap();
+    assert!(<(u128, u128)>::try_from(&PellSolution::new(2, x, y)).is_ok());
+    let (x, y) = pell_solution_k(2, &x1, &y1, 51).unwrap();
+    assert_eq!(<(u128, u128)>::try_from(&PellSolution::new(2, x, y)), Err(PellError::Overflow { bits: 128 }));
+
+    let negative = PellSolution::new(2, BigInt::from(-3), BigInt::from(2));
+    assert_eq!(<(u128, u128)>::try_from(&negative), Err(PellError::Overflow { bits: 128 }));
+}
+
+#[test]
+fn test_limbs_round_trip() {
+    let (x1, y1) = pell_min_solution(991).unwrap();
+    let (x, y) = pell_solution_k(991, &x1, &y1, 40).unwrap();
+    let solution = PellSolution::new(991, x, y);
+
+    let (x_limbs, y_limbs) = solution.to_limbs();
+    assert_eq!(x_limbs.len(), solution.x.bits().div_ceil(64) as usize, "one limb per 64 bits");
+    assert_eq!(PellSolution::from_limbs(991, &x_limbs, &y_limbs), solution);
+
+    let trivial = PellSolution::from_limbs(5, &[1, 0], &[]);
+    assert_eq!((trivial.x, trivial.y), (BigInt::from(1), BigInt::from(0)), "trailing zeros and empty limbs");
+}
+
+#[test]
+fn test_d_for_solution_recovers_d() {
+    for d in [2u64, 3, 7, 61, 991, 1_000_003, u64::MAX] {
+        for (x, y) in pell_solutions(d, 5).unwrap() {
+            assert_eq!(d_for_solution(&x, &y), Some(BigInt::from(d)), "solution of D = {}", d);
+            assert_eq!(d_for_solution(&-&x, &-&y), Some(BigInt::from(d)), "signs do not matter");
+        }
+    }
+
+    // Beyond u64: x = 2^70, y = 1 solves D = 2^140 - 1
+    let x = BigInt::from(1) << 70;
+    assert_eq!(d_for_solution(&x, &BigInt::from(1)), Some((BigInt::from(1) << 140) - 1));
+}
+
+#[test]
+fn test_d_for_solution_rejects_non_solutions() {
+    let pair = |x: i64, y: i64| (BigInt::from(x), BigInt::from(y));
+    for (x, y) in [pair(3, 0), pair(1, 0), pair(4, 2), pair(18, 5), pair(0, 1), pair(1, 1), pair(10, 4)] {
+        assert_eq!(d_for_solution(&x, &y), None, "({}, {})", x, y);
+    }
+}
+
+#[test]
+fn test_rendering() {
+    let solution = PellSolution::new(2, BigInt::from(3), BigInt::from(2));
+    assert_eq!(solution.to_latex(), r"3^{2} - 2 \cdot 2^{2} = 1");
+    assert_eq!(solution.to_unicode(), "3² − 2·2² = 1");
+
+    // Negative coordinates are parenthesized
+    let solution = PellSolution::new(2, BigInt::from(-3), BigInt::from(-2));
+    assert_eq!(solution.to_latex(), r"(-3)^{2} - 2 \cdot (-2)^{2} = 1");
+    assert_eq!(solution.to_unicode(), "(−3)² − 2·(−2)² = 1");
+
+    let (x, y) = pell_min_solution(991).unwrap();
+    let latex = PellSolution::new(991, x.clone(), y.clone()).to_latex();
+    assert_eq!(latex, format!(r"{x}^{{2}} - 991 \cdot {y}^{{2}} = 1"));
+}
@@ -1,7 +1,11 @@
 //! Unit tests for the solver module
 
 use pell991::{
-    pell_min_solution, pell_solution_k, pell_solutions, verify_pell_solution, PellError
+    pell_min_solution, pell_min_solution_with_period, pell_solution_k, pell_solution_k_x, pell_solution_k_y, pell_solutions,
+    pell_solutions_range, for_each_solution,
+    verify_pell_solution, verify_pell_solution_mod, is_valid_pell_d, pell_min_solution_limited, pell_min_solution_with_options,
+    pell_min_solution_u128, count_solutions_below, solutions_below, solution_near, last_digits,
+    solve_many, solve_range, PellError, PellSolutionIterator, SolveOptions
 };
 use num_bigint::BigInt;
 use num_traits::One;
@@ -174,4 +178,383 @@ fn test_batch_solution_generation() {
         assert_eq!(*x, x_individual, "Batch and individual solutions differ for k = {}", i + 1);
         assert_eq!(*y, y_individual, "Batch and individual solutions differ for k = {}", i + 1);
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_pell_min_solution_with_period() {
+    // Known continued fraction periods of √D
+    let known_periods = [
+        (2, 1),   // [1; 2]
+        (3, 2),   // [1; 1, 2]
+        (7, 4),   // [2; 1, 1, 1, 4]
+        (13, 5),  // [3; 1, 1, 1, 1, 6]
+        (61, 11),
+        (94, 16),
+        (991, 60),
+    ];
+
+    for (d, expected_period) in known_periods {
+        let ((x, y), period) = pell_min_solution_with_period(d).unwrap();
+        assert_eq!(period, expected_period, "Wrong period for D = {}", d);
+        assert_eq!((x, y), pell_min_solution(d).unwrap(), "Solution mismatch for D = {}", d);
+    }
+
+    // Errors match pell_min_solution
+    assert_eq!(pell_min_solution_with_period(1), Err(PellError::InvalidD(1)));
+    assert_eq!(pell_min_solution_with_period(25), Err(PellError::PerfectSquare(25)));
+}
+
+#[test]
+fn test_pell_solutions_range() {
+    let d = 13;
+    let all = pell_solutions(d, 12).unwrap();
+
+    // Any window matches the corresponding slice of the full sequence
+    for (start, end) in [(1, 4), (5, 12), (12, 13), (3, 4)] {
+        let range = pell_solutions_range(d, start, end).unwrap();
+        assert_eq!(range.as_slice(), &all[(start - 1) as usize..(end - 1) as usize],
+            "Range {}..{} mismatch", start, end);
+    }
+
+    // Empty ranges
+    assert!(pell_solutions_range(d, 5, 5).unwrap().is_empty());
+    assert!(pell_solutions_range(d, 7, 3).unwrap().is_empty());
+
+    // Deep ranges agree with direct exponentiation
+    let (x1, y1) = pell_min_solution(d).unwrap();
+    let deep = pell_solutions_range(d, 1000, 1003).unwrap();
+    assert_eq!(deep.len(), 3);
+    for (i, (x, y)) in deep.iter().enumerate() {
+        let expected = pell_solution_k(d, &x1, &y1, 1000 + i as u64).unwrap();
+        assert_eq!((x, y), (&expected.0, &expected.1));
+    }
+
+    // Error handling
+    assert_eq!(pell_solutions_range(d, 0, 3), Err(PellError::InvalidK(0)));
+    assert_eq!(pell_solutions_range(16, 1, 3), Err(PellError::PerfectSquare(16)));
+}
+
+#[test]
+fn test_for_each_solution() {
+    let d = 7;
+    let expected = pell_solutions(d, 8).unwrap();
+
+    let mut visited = Vec::new();
+    for_each_solution(d, 8, |k, x, y| visited.push((k, x.clone(), y.clone()))).unwrap();
+
+    assert_eq!(visited.len(), expected.len());
+    for ((k, x, y), (ex, ey)) in visited.iter().zip(expected.iter()) {
+        assert_eq!((x, y), (ex, ey), "Mismatch at k = {}", k);
+    }
+    let indices: Vec<u64> = visited.iter().map(|(k, _, _)| *k).collect();
+    assert_eq!(indices, (1..=8).collect::<Vec<u64>>());
+
+    // Zero count never invokes the callback, even for invalid D
+    let mut calls = 0;
+    for_each_solution(4, 0, |_, _, _| calls += 1).unwrap();
+    assert_eq!(calls, 0);
+
+    assert_eq!(for_each_solution(4, 3, |_, _, _| {}), Err(PellError::PerfectSquare(4)));
+}
+
+#[test]
+fn test_batch_iterator_and_range_agree() {
+    for d in [2, 13, 61, 991] {
+        let batch = pell_solutions(d, 12).unwrap();
+        let streamed: Vec<_> = PellSolutionIterator::new(d).unwrap().take(12).collect();
+        let ranged = pell_solutions_range(d, 1, 13).unwrap();
+        assert_eq!(batch, streamed, "Batch and iterator differ for D = {}", d);
+        assert_eq!(batch, ranged, "Batch and range differ for D = {}", d);
+
+        let (x1, y1) = pell_min_solution(d).unwrap();
+        let shared: Vec<_> = PellSolutionIterator::with_fundamental(d, x1, y1).take(12).collect();
+        assert_eq!(batch, shared, "Iterator from a shared fundamental differs for D = {}", d);
+    }
+}
+
+#[test]
+fn test_step_limit() {
+    // The solution for D = 991 (even period 60) is the convergent after 59 steps
+    assert_eq!(pell_min_solution_limited(991, 59).unwrap(), pell_min_solution(991).unwrap());
+    assert_eq!(pell_min_solution_limited(991, 58), Err(PellError::LimitExceeded { steps: 58 }));
+
+    // Odd period 11 for D = 61: the solution needs two passes, 21 steps
+    assert!(pell_min_solution_limited(61, 21).is_ok());
+    assert_eq!(pell_min_solution_limited(61, 20), Err(PellError::LimitExceeded { steps: 20 }));
+
+    // Validation happens before any step is taken
+    assert_eq!(pell_min_solution_limited(1, 0), Err(PellError::InvalidD(1)));
+    assert_eq!(pell_min_solution_limited(9, 0), Err(PellError::PerfectSquare(9)));
+}
+
+#[test]
+fn test_solve_options() {
+    let unlimited = SolveOptions::new();
+    assert_eq!(pell_min_solution_with_options(991, &unlimited).unwrap(), pell_min_solution(991).unwrap());
+
+    let limited = SolveOptions::new().max_steps(1000);
+    for d in 2..200u64 {
+        if !is_valid_pell_d(d) {
+            continue;
+        }
+        assert_eq!(
+            pell_min_solution_with_options(d, &limited).unwrap(),
+            pell_min_solution(d).unwrap(),
+            "Limited solve differs for D = {}", d
+        );
+    }
+}
+
+#[test]
+fn test_timeout() {
+    use std::time::Duration;
+
+    // A zero budget expires before the first step
+    let expired = SolveOptions::new().timeout(Duration::ZERO);
+    assert_eq!(
+        pell_min_solution_with_options(991, &expired),
+        Err(PellError::Timeout { limit: Duration::ZERO })
+    );
+
+    let generous = SolveOptions::new().timeout(Duration::from_secs(60)).max_steps(1000);
+    assert_eq!(pell_min_solution_with_options(991, &generous).unwrap(), pell_min_solution(991).unwrap());
+
+    // Invalid D is reported as such, not as a timeout
+    assert_eq!(pell_min_solution_with_options(4, &expired), Err(PellError::PerfectSquare(4)));
+}
+
+#[test]
+fn test_progress_callback() {
+    use std::sync::{Arc, Mutex};
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    let options = SolveOptions::new().on_progress(move |event| sink.lock().unwrap().push(event));
+
+    // D = 61 has odd period 11: the period end is seen at step 11, the solution at step 21
+    let (x, _) = pell_min_solution_with_options(61, &options).unwrap();
+    let events = events.lock().unwrap();
+
+    assert_eq!(events.len(), 21);
+    assert!(events.iter().enumerate().all(|(i, e)| e.d == 61 && e.steps == i as u64 + 1));
+    assert!(events.windows(2).all(|w| w[0].convergent_bits <= w[1].convergent_bits));
+    assert_eq!(events.last().unwrap().convergent_bits, x.bits());
+
+    assert_eq!(events[9].period, None);
+    assert_eq!(events[10].period, Some(11));
+    assert_eq!(events.last().unwrap().estimated_fraction, 1.0);
+    assert!(events.iter().all(|e| (0.0..=1.0).contains(&e.estimated_fraction)));
+}
+
+#[test]
+fn test_progress_callback_with_limit() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    let calls = Arc::new(AtomicU64::new(0));
+    let counter = Arc::clone(&calls);
+    let options = SolveOptions::new()
+        .max_steps(10)
+        .on_progress(move |_| { counter.fetch_add(1, Ordering::Relaxed); });
+
+    assert_eq!(pell_min_solution_with_options(991, &options), Err(PellError::LimitExceeded { steps: 10 }));
+    assert_eq!(calls.load(Ordering::Relaxed), 10);
+    assert!(format!("{:?}", options).contains("<callback>"));
+}
+
+#[test]
+fn test_solve_range() {
+    let results: Vec<_> = solve_range(0..=50).collect();
+
+    // 0, 1, 4, 9, 16, 25, 36, 49 are skipped
+    assert_eq!(results.len(), 51 - 8);
+    assert!(results.iter().all(|(d, _)| is_valid_pell_d(*d)));
+    for (d, result) in results {
+        let (x, y) = result.unwrap();
+        assert_eq!((x.clone(), y.clone()), pell_min_solution(d).unwrap());
+        assert!(verify_pell_solution(d, &x, &y));
+    }
+
+    // Any iterator of D values works, and the order is preserved
+    let ds: Vec<u64> = solve_range([991, 4, 2]).map(|(d, _)| d).collect();
+    assert_eq!(ds, [991, 2]);
+    assert_eq!(solve_range(2..2).count(), 0);
+}
+
+#[test]
+fn test_solution_k_matches_recurrence() {
+    // Covers every combination of set and unset exponent bits up to k = 40
+    for d in [2, 61, 991] {
+        let (x1, y1) = pell_min_solution(d).unwrap();
+        let sequence = pell_solutions(d, 40).unwrap();
+        for (index, expected) in sequence.iter().enumerate() {
+            let k = index as u64 + 1;
+            let solution = pell_solution_k(d, &x1, &y1, k).unwrap();
+            assert_eq!(&solution, expected, "pell_solution_k differs from the recurrence for D = {}, k = {}", d, k);
+        }
+    }
+}
+
+#[test]
+fn test_solve_many() {
+    let results = solve_many(&[61, 991, 16, 1, 61, 2]);
+    assert_eq!(results.len(), 5, "Repeated D values should share one entry");
+    assert_eq!(results[&61], pell_min_solution(61));
+    assert_eq!(results[&991], pell_min_solution(991));
+    assert_eq!(results[&2].as_ref().unwrap(), &(BigInt::from(3), BigInt::from(2)));
+    assert_eq!(results[&16], Err(PellError::PerfectSquare(16)));
+    assert_eq!(results[&1], Err(PellError::InvalidD(1)));
+
+    assert!(solve_many(&[]).is_empty());
+}
+
+#[test]
+fn test_verify_pell_solution_mod() {
+    let primes = [18_446_744_073_709_551_557, 18_446_744_073_709_551_533, 1_000_000_007, 998_244_353];
+
+    for d in [2, 61, 991] {
+        let solutions = pell_solutions(d, 30).unwrap();
+        for (x, y) in &solutions {
+            assert!(verify_pell_solution_mod(d, x, y, &primes), "Solution rejected for D = {}", d);
+            // The sign does not matter, as for the exact check
+            assert!(verify_pell_solution_mod(d, &-x, &-y, &primes));
+            assert!(!verify_pell_solution_mod(d, &(x + 1), y, &primes), "Non-solution accepted for D = {}", d);
+            assert!(!verify_pell_solution_mod(d + 1, x, y, &primes), "Wrong D accepted for D = {}", d);
+        }
+    }
+
+    // A modulus dividing x² - D·y² - 1 (here 16 - 2 - 1 = 13) is fooled, hence large random primes
+    assert!(verify_pell_solution_mod(2, &BigInt::from(4), &BigInt::from(1), &[13]));
+    assert!(!verify_pell_solution(2, &BigInt::from(4), &BigInt::from(1)));
+    assert!(verify_pell_solution_mod(2, &BigInt::from(4), &BigInt::from(1), &[]));
+}
+
+#[test]
+fn test_single_coordinate_solutions() {
+    for d in [2, 61, 991] {
+        let (x1, y1) = pell_min_solution(d).unwrap();
+        for (index, (x, y)) in pell_solutions(d, 40).unwrap().iter().enumerate() {
+            let k = index as u64 + 1;
+            assert_eq!(&pell_solution_k_x(d, &x1, &y1, k).unwrap(), x, "x differs for D = {}, k = {}", d, k);
+            assert_eq!(&pell_solution_k_y(d, &x1, &y1, k).unwrap(), y, "y differs for D = {}, k = {}", d, k);
+        }
+    }
+
+    let (x1, y1) = pell_min_solution(2).unwrap();
+    assert_eq!(pell_solution_k_x(2, &x1, &y1, 0), Err(PellError::InvalidK(0)));
+    assert_eq!(pell_solution_k_y(2, &x1, &y1, 0), Err(PellError::InvalidK(0)));
+
+    // y₁ = 0 would divide by zero, and a non-solution would give wrong answers
+    let (zero, one, four) = (BigInt::from(0), BigInt::from(1), BigInt::from(4));
+    for (x, y) in [(&one, &zero), (&four, &one), (&-&x1, &y1), (&x1, &-&y1)] {
+        assert_eq!(pell_solution_k_x(2, x, y, 5), Err(PellError::InvalidSolution(2)), "x1 = {}, y1 = {}", x, y);
+        assert_eq!(pell_solution_k_y(2, x, y, 5), Err(PellError::InvalidSolution(2)), "x1 = {}, y1 = {}", x, y);
+    }
+}
+
+#[test]
+fn test_min_solution_u128_matches_bigint_solver() {
+    let mut overflows = 0;
+    for d in (2..3000).filter(|&d| is_valid_pell_d(d)) {
+        let (x, y) = pell_min_solution(d).unwrap();
+        match (u128::try_from(&x), u128::try_from(&y)) {
+            (Ok(x), Ok(y)) => assert_eq!(pell_min_solution_u128(d), Ok((x, y)), "solution for D = {}", d),
+            _ => {
+                assert_eq!(pell_min_solution_u128(d), Err(PellError::Overflow { bits: 128 }), "overflow for D = {}", d);
+                overflows += 1;
+            }
+        }
+    }
+    assert!(overflows > 0, "some D below 3000 have solutions beyond u128");
+
+    assert_eq!(pell_min_solution_u128(1), Err(PellError::InvalidD(1)));
+    assert_eq!(pell_min_solution_u128(144), Err(PellError::PerfectSquare(144)));
+    // a² + 1 has period 1 and x = 2a² + 1, which fits even at the top of the u64 range
+    let a = u64::from(u32::MAX);
+    let x = 2 * u128::from(a).pow(2) + 1;
+    assert_eq!(pell_min_solution_u128(a * a + 1), Ok((x, 2 * u128::from(a))));
+}
+
+#[test]
+fn test_count_solutions_below() {
+    for d in [2, 3, 13, 61, 991] {
+        let solutions = pell_solutions(d, 40).unwrap();
+        for (i, (x, _)) in solutions.iter().enumerate() {
+            let k = i as u64 + 1;
+            assert_eq!(count_solutions_below(d, x).unwrap(), k - 1, "the bound x{} itself is excluded for D = {}", k, d);
+            assert_eq!(count_solutions_below(d, &(x + 1)).unwrap(), k, "x{} + 1 for D = {}", k, d);
+            assert_eq!(count_solutions_below(d, &(x - 1)).unwrap(), k - 1, "x{} - 1 for D = {}", k, d);
+        }
+    }
+
+    let googol = BigInt::from(10).pow(100);
+    let by_iterator = PellSolutionIterator::new(991).unwrap().take_while(|(x, _)| *x < googol).count() as u64;
+    assert_eq!(count_solutions_below(991, &googol).unwrap(), by_iterator, "solutions of D = 991 below 10¹⁰⁰");
+    let huge = BigInt::from(10).pow(100_000);
+    assert!(count_solutions_below(2, &huge).unwrap() > 100_000, "log₁₀(3 + 2√2) < 1");
+
+    assert_eq!(count_solutions_below(2, &BigInt::from(0)).unwrap(), 0, "no positive x below 0");
+    assert_eq!(count_solutions_below(2, &BigInt::from(-5)).unwrap(), 0, "negative bounds count nothing");
+    assert_eq!(count_solutions_below(9, &googol), Err(PellError::PerfectSquare(9)));
+}
+
+#[test]
+fn test_solutions_below() {
+    let bound = BigInt::from(10).pow(60);
+    let below = solutions_below(13, &bound).unwrap();
+    let count = count_solutions_below(13, &bound).unwrap() as usize;
+    assert_eq!(below, pell_solutions(13, count).unwrap(), "the first {} solutions of D = 13", count);
+    assert!(below.iter().all(|(x, _)| *x < bound), "every x is below the bound");
+    assert!(solutions_below(991, &BigInt::from(1000)).unwrap().is_empty(), "x₁ of D = 991 has 30 digits");
+    assert_eq!(solutions_below(1, &bound), Err(PellError::InvalidD(1)));
+}
+
+#[test]
+fn test_solution_near() {
+    for d in [2, 7, 61, 991] {
+        let solutions = pell_solutions(d, 30).unwrap();
+        for (i, pair) in solutions.windows(2).enumerate() {
+            let k = i as u64 + 1;
+            let (lower, upper) = (&pair[0].0, &pair[1].0);
+            let halfway: BigInt = (lower + upper) / 2;
+            for (target, expected) in [
+                (lower.clone(), k),
+                (upper.clone(), k + 1),
+                (&halfway - 1, k),
+                (&halfway + 1, k + 1),
+            ] {
+                let near = solution_near(d, &target).unwrap();
+                assert_eq!(near.k, Some(expected), "target {} for D = {}", target, d);
+                let (x, y) = &solutions[expected as usize - 1];
+                assert_eq!((&near.x, &near.y), (x, y), "solution {} for D = {}", expected, d);
+            }
+        }
+    }
+
+    // Exactly halfway between 3 and 17 is 10, which picks the smaller k
+    assert_eq!(solution_near(2, &BigInt::from(10)).unwrap().k, Some(1), "ties go to the smaller k");
+    assert_eq!(solution_near(2, &BigInt::from(-100)).unwrap().k, Some(1), "targets below x₁ pick k = 1");
+    assert_eq!(solution_near(25, &BigInt::from(100)), Err(PellError::PerfectSquare(25)));
+}
+
+#[test]
+fn test_last_digits() {
+    for d in [2, 13, 61, 991] {
+        let (x1, y1) = pell_min_solution(d).unwrap();
+        for k in [1, 2, 3, 10, 77, 500] {
+            let (x, y) = pell_solution_k(d, &x1, &y1, k).unwrap();
+            for t in [0, 1, 5, 50] {
+                let modulus = BigInt::from(10).pow(t);
+                let expected = (&x % &modulus, &y % &modulus);
+                assert_eq!(last_digits(d, k, t).unwrap(), expected, "D = {}, k = {}, t = {}", d, k, t);
+            }
+        }
+    }
+
+    // Every solution stays on the conic modulo 10ᵗ, even far out of reach
+    let modulus = BigInt::from(10).pow(40);
+    let (x, y) = last_digits(991, u64::MAX, 40).unwrap();
+    assert_eq!((&x * &x - 991 * &y * &y - 1) % &modulus, BigInt::from(0), "x² - 991y² ≡ 1 mod 10⁴⁰");
+
+    assert_eq!(last_digits(2, 0, 5), Err(PellError::InvalidK(0)));
+    assert_eq!(last_digits(16, 3, 5), Err(PellError::PerfectSquare(16)));
+}
@@ -0,0 +1,99 @@
+//! Tests for the SQLite-backed solution cache
+
+#![cfg(feature = "sqlite")]
+
+use pell991::{
+    analyze_d, disable_sqlite_cache, enable_sqlite_cache, pell_min_solution, pell_min_solution_with_period, BigInt,
+    PellError, SqliteCache, SqliteCacheError,
+};
+
+fn temp_db(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("pell991-{}-{}.db", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn test_get_or_solve_stores_rows() {
+    let cache = SqliteCache::open_in_memory().unwrap();
+    assert!(cache.is_empty().unwrap());
+    assert_eq!(cache.get(991).unwrap(), None);
+
+    let row = cache.get_or_solve(991).unwrap();
+    let ((x, y), period) = pell_min_solution_with_period(991).unwrap();
+    assert_eq!((&row.x, &row.y, row.period), (&x, &y, period));
+    assert_eq!(Some(row.regulator), analyze_d(991).regulator, "the regulator matches analyze_d");
+
+    assert_eq!(cache.get(991).unwrap(), Some(row));
+    assert_eq!(cache.len().unwrap(), 1);
+}
+
+#[test]
+fn test_regulator_of_odd_period() {
+    let cache = SqliteCache::open_in_memory().unwrap();
+    // 18 + 5√13 is the fundamental unit; 649 + 180√13 is its square
+    let row = cache.get_or_solve(13).unwrap();
+    assert_eq!(row.period, 5);
+    assert!((row.regulator - (18.0 + 5.0 * 13f64.sqrt()).ln()).abs() < 1e-9, "regulator was {}", row.regulator);
+}
+
+#[test]
+fn test_invalid_d_is_not_stored() {
+    let cache = SqliteCache::open_in_memory().unwrap();
+    assert!(matches!(cache.get_or_solve(16), Err(SqliteCacheError::Pell(PellError::PerfectSquare(16)))));
+    assert!(cache.is_empty().unwrap());
+}
+
+#[test]
+fn test_rows_that_do_not_verify_are_misses() {
+    let cache = SqliteCache::open_in_memory().unwrap();
+    cache.insert(2, &(BigInt::from(4), BigInt::from(3)), 1).unwrap();
+    assert_eq!(cache.get(2).unwrap(), None);
+    // get_or_solve repairs the row
+    assert_eq!(cache.get_or_solve(2).unwrap().x, BigInt::from(3));
+    assert_eq!(cache.get(2).unwrap().unwrap().y, BigInt::from(2));
+}
+
+#[test]
+fn test_rows_that_are_not_fundamental_are_misses() {
+    let cache = SqliteCache::open_in_memory().unwrap();
+    // (17, 12) solves D = 2 but is the second solution, not the first
+    cache.insert(2, &(BigInt::from(17), BigInt::from(12)), 1).unwrap();
+    assert_eq!(cache.get(2).unwrap(), None, "a non-fundamental pair is a miss");
+    // The fundamental solution of D = 13 with the wrong period
+    cache.insert(13, &(BigInt::from(649), BigInt::from(180)), 4).unwrap();
+    assert_eq!(cache.get(13).unwrap(), None, "a wrong period is a miss");
+
+    assert_eq!(cache.get_or_solve(13).unwrap().period, 5, "get_or_solve repairs the row");
+}
+
+#[test]
+fn test_rows_persist_across_connections() {
+    let path = temp_db("persist");
+    SqliteCache::open(&path).unwrap().get_or_solve(61).unwrap();
+
+    let reopened = SqliteCache::open(&path).unwrap();
+    assert_eq!(reopened.get(61).unwrap().unwrap().x, BigInt::from(1766319049u64));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_global_database_is_used_by_solver() {
+    let path = temp_db("global");
+    let cache = SqliteCache::open(&path).unwrap();
+    enable_sqlite_cache(&path).unwrap();
+
+    let solution = pell_min_solution(109).unwrap();
+    let row = cache.get(109).unwrap().expect("the solver stores solved D values");
+    assert_eq!((row.x, row.y), solution);
+
+    // Rows that are not the fundamental solution with its period are solved again
+    cache.insert(2, &(BigInt::from(17), BigInt::from(12)), 4).unwrap();
+    cache.insert(13, &(BigInt::from(649), BigInt::from(180)), 4).unwrap();
+    assert_eq!(pell_min_solution_with_period(2).unwrap(), ((BigInt::from(3), BigInt::from(2)), 1));
+    assert_eq!(pell_min_solution_with_period(13).unwrap(), ((BigInt::from(649), BigInt::from(180)), 5));
+    assert_eq!(analyze_d(2).negative_pell_solvable, Some(true), "1² - 2·1² = -1");
+
+    disable_sqlite_cache();
+    std::fs::remove_file(&path).unwrap();
+}
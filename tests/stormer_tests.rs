@@ -0,0 +1,49 @@
+//! Tests for consecutive smooth numbers by Størmer's theorem
+
+use num_bigint::BigInt;
+use pell991::{smooth_consecutive_pairs, PellError};
+
+fn is_smooth(mut n: u64, primes: &[u64]) -> bool {
+    for &p in primes {
+        while n % p == 0 {
+            n /= p;
+        }
+    }
+    n == 1
+}
+
+#[test]
+fn test_matches_brute_force() {
+    // The largest 11-smooth pair is (9800, 9801)
+    let primes = [2, 3, 5, 7, 11];
+    let expected: Vec<(BigInt, BigInt)> = (1..100_000u64)
+        .filter(|&n| is_smooth(n, &primes) && is_smooth(n + 1, &primes))
+        .map(|n| (BigInt::from(n), BigInt::from(n + 1)))
+        .collect();
+    assert_eq!(smooth_consecutive_pairs(&primes).unwrap(), expected);
+}
+
+#[test]
+fn test_known_counts() {
+    // OEIS A002071 and A002072: number and largest of the pairs for the first k primes
+    let primes = [2, 3, 5, 7, 11, 13, 17];
+    let expected = [(1, 1), (4, 8), (10, 80), (23, 4374), (40, 9800), (68, 123_200), (108, 336_140)];
+    for (k, (count, largest)) in expected.into_iter().enumerate() {
+        let pairs = smooth_consecutive_pairs(&primes[..=k]).unwrap();
+        assert_eq!(pairs.len(), count, "Wrong number of pairs for {:?}", &primes[..=k]);
+        assert_eq!(pairs.last().unwrap().0, BigInt::from(largest), "Wrong largest pair for {:?}", &primes[..=k]);
+    }
+}
+
+#[test]
+fn test_prime_set_handling() {
+    // Order and duplicates do not matter
+    assert_eq!(smooth_consecutive_pairs(&[3, 2, 3]).unwrap(), smooth_consecutive_pairs(&[2, 3]).unwrap());
+    // Without 2 one member of every pair is excluded
+    assert!(smooth_consecutive_pairs(&[3, 5, 7]).unwrap().is_empty());
+    assert!(smooth_consecutive_pairs(&[]).unwrap().is_empty());
+
+    assert_eq!(smooth_consecutive_pairs(&[2, 9]), Err(PellError::InvalidPrimeSet("9 is not prime".to_string())));
+    let many: Vec<u64> = (2..100).filter(|&p| pell991::is_prime(p)).collect();
+    assert!(matches!(smooth_consecutive_pairs(&many), Err(PellError::InvalidPrimeSet(_))));
+}
@@ -0,0 +1,84 @@
+//! Tests for the proptest strategies
+
+#![cfg(feature = "proptest")]
+
+use pell991::strategies::{
+    solution, solution_index_up_to, valid_d_in, PellEquation, SolutionIndex, ValidD, DEFAULT_MAX_D, DEFAULT_MAX_K,
+    DEFAULT_MAX_N,
+};
+use pell991::{is_square_u64, is_valid_pell_d, pell_general_fundamental_solutions, PellSolution};
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::TestRunner;
+
+#[test]
+fn test_valid_d_in_covers_every_non_square() {
+    let mut runner = TestRunner::deterministic();
+    let strategy = valid_d_in(10..=30);
+    let mut seen = std::collections::BTreeSet::new();
+    for _ in 0..1000 {
+        seen.insert(strategy.new_tree(&mut runner).unwrap().current());
+    }
+    let expected: std::collections::BTreeSet<u64> = (10..=30).filter(|&d| !is_square_u64(d)).collect();
+    assert_eq!(seen, expected, "every non-square in the range and nothing else");
+}
+
+#[test]
+fn test_valid_d_in_clamps_low_bound() {
+    let mut runner = TestRunner::deterministic();
+    let strategy = valid_d_in(0..=3);
+    for _ in 0..100 {
+        let d = strategy.new_tree(&mut runner).unwrap().current();
+        assert!(d == 2 || d == 3, "only 2 and 3 are valid below 4, got {d}");
+    }
+}
+
+#[test]
+#[should_panic(expected = "no valid D")]
+fn test_valid_d_in_rejects_ranges_of_squares() {
+    let _ = valid_d_in(16..=16);
+}
+
+#[test]
+fn test_valid_d_shrinks_towards_two() {
+    let mut runner = TestRunner::deterministic();
+    let mut tree = any::<ValidD>().new_tree(&mut runner).unwrap();
+    while tree.simplify() {}
+    assert_eq!(tree.current(), ValidD(2));
+}
+
+#[test]
+fn test_equation_display() {
+    assert_eq!(PellEquation { d: 991, n: -1 }.to_string(), "x² - 991·y² = -1");
+}
+
+proptest! {
+    #[test]
+    fn prop_valid_d_is_valid(ValidD(d) in any::<ValidD>()) {
+        prop_assert!(is_valid_pell_d(d));
+        prop_assert!(d <= DEFAULT_MAX_D);
+    }
+
+    #[test]
+    fn prop_solution_index_in_range(SolutionIndex(k) in any::<SolutionIndex>()) {
+        prop_assert!((1..=DEFAULT_MAX_K).contains(&k));
+    }
+
+    #[test]
+    fn prop_equations_are_solvable_inputs(equation in any::<PellEquation>()) {
+        prop_assert!(equation.n != 0 && equation.n.abs() <= DEFAULT_MAX_N);
+        prop_assert!(pell_general_fundamental_solutions(equation.d, equation.n).is_ok());
+    }
+
+    #[test]
+    fn prop_arbitrary_solutions_verify(s in any::<PellSolution>()) {
+        prop_assert!(s.verify(), "{} does not verify", s);
+    }
+
+    #[test]
+    fn prop_solution_strategy_uses_given_ranges(s in solution(valid_d_in(500..=600), solution_index_up_to(3))) {
+        prop_assert!((500..=600).contains(&s.d));
+        prop_assert!(matches!(s.k, Some(1..=3)));
+        prop_assert!(s.verify());
+    }
+}
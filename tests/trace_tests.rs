@@ -0,0 +1,84 @@
+//! Tests for algorithm traces
+
+use num_bigint::BigInt;
+use pell991::trace::{trace_min_solution, trace_solution_k, TraceEvent};
+use pell991::{continued_fraction, pell_min_solution, pell_min_solution_with_period, pell_solution_k, PellError};
+
+#[test]
+fn test_trace_of_seven() {
+    let lines: Vec<String> = trace_min_solution(7).unwrap().iter().map(ToString::to_string).collect();
+    assert_eq!(
+        lines,
+        [
+            "n=0: (m + √D)/d = (0 + √D)/1, a=2, p/q = 2/1, p² - D·q² = -3",
+            "n=1: (m + √D)/d = (2 + √D)/3, a=1, p/q = 3/1, p² - D·q² = 2",
+            "n=2: (m + √D)/d = (1 + √D)/2, a=1, p/q = 5/2, p² - D·q² = -3",
+            "n=3: (m + √D)/d = (1 + √D)/3, a=1, p/q = 8/3, p² - D·q² = 1",
+            "solution x=8, y=3 after 3 steps, period 4",
+        ]
+    );
+}
+
+#[test]
+fn test_trace_matches_solver() {
+    for d in [2, 3, 13, 61, 109, 991] {
+        let events = trace_min_solution(d).unwrap();
+        let ((x, y), period) = pell_min_solution_with_period(d).unwrap();
+        let cf = continued_fraction(d).unwrap();
+
+        let Some(TraceEvent::Solution { x: tx, y: ty, period: tperiod, steps }) = events.last() else {
+            panic!("the trace of D = {d} ends with the solution");
+        };
+        assert_eq!((tx, ty, *tperiod), (&x, &y, period), "solution of D = {d}");
+        assert_eq!(events.len() as u64, steps + 2, "one step event per convergent for D = {d}");
+
+        let terms: Vec<u64> = events
+            .iter()
+            .filter_map(|event| match event {
+                TraceEvent::CfStep { a, .. } => Some(*a),
+                _ => None,
+            })
+            .collect();
+        assert!(terms.iter().copied().eq(cf.terms().take(terms.len())), "partial quotients of √{d}");
+
+        for event in &events {
+            if let TraceEvent::CfStep { p, q, residual, .. } = event {
+                assert_eq!(BigInt::from(*residual), p * p - BigInt::from(d) * q * q, "residual for D = {d}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_trace_of_exponentiation() {
+    let (x1, y1) = pell_min_solution(991).unwrap();
+    for k in [1, 2, 5, 8, 13] {
+        let events = trace_solution_k(991, k).unwrap();
+        let powers: Vec<(&str, u64)> = events
+            .iter()
+            .filter_map(|event| match event {
+                TraceEvent::Square { power, .. } => Some(("square", *power)),
+                TraceEvent::Multiply { power, .. } => Some(("multiply", *power)),
+                _ => None,
+            })
+            .collect();
+
+        let squarings = powers.iter().filter(|(kind, _)| *kind == "square").count() as u32;
+        let multiplications = powers.iter().filter(|(kind, _)| *kind == "multiply").count() as u32;
+        assert_eq!(squarings, 63 - k.leading_zeros(), "one squaring per bit after the first for k = {k}");
+        assert_eq!(multiplications, k.count_ones(), "one multiplication per set bit for k = {k}");
+        assert_eq!(powers.last(), Some(&("multiply", k)));
+
+        let Some(TraceEvent::Multiply { x, y, .. }) = events.last() else {
+            panic!("the trace for k = {k} ends with a multiplication");
+        };
+        assert_eq!((x.clone(), y.clone()), pell_solution_k(991, &x1, &y1, k).unwrap(), "solution {k}");
+    }
+}
+
+#[test]
+fn test_trace_errors() {
+    assert_eq!(trace_min_solution(1), Err(PellError::InvalidD(1)));
+    assert_eq!(trace_min_solution(49), Err(PellError::PerfectSquare(49)));
+    assert_eq!(trace_solution_k(2, 0), Err(PellError::InvalidK(0)));
+}
@@ -0,0 +1,77 @@
+//! Tests for the tracing instrumentation
+
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+
+use pell991::{pell_min_solution, pell_min_solution_limited, pell_solution_k, verify_pell_solution};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// Records span names and event messages
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<String>>>,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= Level::DEBUG
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(span.metadata().name().to_string());
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = MessageVisitor(String::new());
+        event.record(&mut visitor);
+        self.events.lock().unwrap().push(visitor.0);
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[test]
+fn test_solver_emits_spans_and_events() {
+    let recorder = Recorder::default();
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let (x1, y1) = pell_min_solution(61).unwrap();
+        let (x, y) = pell_solution_k(61, &x1, &y1, 5).unwrap();
+        assert!(verify_pell_solution(61, &x, &y));
+        assert!(pell_min_solution_limited(991, 3).is_err());
+    });
+
+    let spans = recorder.spans.lock().unwrap();
+    assert_eq!(*spans, ["continued_fraction", "solution_k", "continued_fraction"]);
+
+    // Per-step and verification events are at TRACE level, which is filtered out
+    let events = recorder.events.lock().unwrap();
+    assert_eq!(*events, [
+        "end of first period",
+        "solution found",
+        "exponentiation done",
+        "step limit reached",
+    ]);
+}
@@ -0,0 +1,33 @@
+//! Tests for the functions behind the uniffi bindings
+
+#![cfg(feature = "uniffi")]
+
+use pell991::uniffi_bindings::{first_solutions, min_solution, nth_solution, verify_solution};
+use pell991::{BigInt, PellError, PellSolution};
+
+#[test]
+fn test_min_solution_has_index_one() {
+    let solution = min_solution(991).unwrap();
+    assert_eq!(solution.k, Some(1));
+    assert_eq!(solution.x.to_string(), "379516400906811930638014896080");
+    assert!(verify_solution(&solution));
+}
+
+#[test]
+fn test_nth_solution_matches_sequence() {
+    let solutions = first_solutions(2, 4).unwrap();
+    let ys: Vec<String> = solutions.iter().map(|s| s.y.to_string()).collect();
+    assert_eq!(ys, ["2", "12", "70", "408"]);
+    for solution in &solutions {
+        let k = solution.k.unwrap();
+        assert_eq!(&nth_solution(2, k).unwrap(), solution, "k = {}", k);
+    }
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(min_solution(9), Err(PellError::PerfectSquare(9)));
+    assert_eq!(nth_solution(2, 0), Err(PellError::InvalidK(0)));
+    assert_eq!(first_solutions(1, 3), Err(PellError::InvalidD(1)));
+    assert!(!verify_solution(&PellSolution::new(2, BigInt::from(4), BigInt::from(3))));
+}
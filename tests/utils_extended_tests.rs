@@ -1,6 +1,10 @@
 //! Extended tests for the new utility functions
 
-use pell991::{estimate_period_length, fundamental_discriminant, is_prime, is_valid_pell_d};
+use pell991::{
+    decimal_digits, estimate_period_length, fundamental_discriminant, is_prime, is_valid_pell_d,
+    is_square_u64, pell_solutions, squarefree_part, valid_pell_d_in, DSieve,
+};
+use num_bigint::BigInt;
 
 #[test]
 fn test_estimate_period_length() {
@@ -170,4 +174,78 @@ fn test_mathematical_properties() {
                       "{}² + 1 = {} validity check failed", i, d);
         }
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_decimal_digits() {
+    assert_eq!(decimal_digits(&BigInt::from(0)), 1);
+    assert_eq!(decimal_digits(&BigInt::from(7)), 1);
+    assert_eq!(decimal_digits(&BigInt::from(-7)), 1);
+
+    // Exact boundaries around powers of ten, where the bit-length estimate is ambiguous
+    let mut power = BigInt::from(1);
+    for exponent in 1..=400u64 {
+        power *= 10;
+        assert_eq!(decimal_digits(&(&power - 1)), exponent, "Failed for 10^{} - 1", exponent);
+        assert_eq!(decimal_digits(&power), exponent + 1, "Failed for 10^{}", exponent);
+        assert_eq!(decimal_digits(&-&power), exponent + 1, "Failed for -10^{}", exponent);
+    }
+
+    // Agrees with string length on Pell solutions
+    for (x, y) in pell_solutions(991, 20).unwrap() {
+        assert_eq!(decimal_digits(&x), x.to_string().len() as u64);
+        assert_eq!(decimal_digits(&y), y.to_string().len() as u64);
+    }
+}
+
+#[test]
+fn test_squarefree_part() {
+    let cases = [
+        (0, 0), (1, 1), (2, 2), (4, 1), (8, 2), (12, 3), (18, 2), (72, 2),
+        (991, 991), (991 * 991, 1), (991 * 997, 991 * 997), (991 * 991 * 6, 6),
+        (4_294_967_291 * 4_294_967_291, 1), // square of the largest 32-bit prime
+    ];
+    for (n, expected) in cases {
+        assert_eq!(squarefree_part(n), expected, "Wrong squarefree part for {}", n);
+    }
+
+    // n / squarefree_part(n) is always a perfect square
+    for n in 1..2000 {
+        let part = squarefree_part(n);
+        assert_eq!(n % part, 0);
+        assert!(is_square_u64(n / part), "{} / {} is not a square", n, part);
+    }
+}
+
+#[test]
+fn test_valid_pell_d_in() {
+    for (start, end) in [(0, 0), (0, 2), (0, 200), (4, 5), (15, 50), (99_990, 100_010)] {
+        let walked: Vec<u64> = valid_pell_d_in(start..end).collect();
+        let filtered: Vec<u64> = (start..end).filter(|&d| is_valid_pell_d(d)).collect();
+        assert_eq!(walked, filtered, "Range {}..{}", start, end);
+    }
+
+    // Near the top of u64 the next square overflows
+    let top: Vec<u64> = valid_pell_d_in(u64::MAX - 3..u64::MAX).collect();
+    assert_eq!(top, [u64::MAX - 3, u64::MAX - 2, u64::MAX - 1]);
+    let below_square = 4_294_967_295u64 * 4_294_967_295;
+    assert!(!valid_pell_d_in(below_square..below_square + 1).any(|_| true));
+}
+
+#[test]
+fn test_d_sieve() {
+    for (start, end) in [(0, 0), (0, 1), (0, 1000), (1, 65), (63, 130), (10_000, 12_345)] {
+        let sieve = DSieve::new(start..end);
+        let expected: Vec<u64> = (start..end).filter(|&d| is_valid_pell_d(d)).collect();
+        assert_eq!(sieve.range(), start..end);
+        assert_eq!(sieve.iter().collect::<Vec<_>>(), expected, "Range {}..{}", start, end);
+        assert_eq!(sieve.count(), expected.len(), "Range {}..{}", start, end);
+    }
+
+    // Lookups outside the range fall back to the direct check
+    let sieve = DSieve::new(100..200);
+    assert!(sieve.is_valid(991));
+    assert!(!sieve.is_valid(1024));
+    assert!(!sieve.is_valid(1));
+    assert!(sieve.is_valid(101));
+    assert!(!sieve.is_valid(169));
+}
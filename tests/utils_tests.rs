@@ -100,4 +100,37 @@ fn test_performance_edge_cases() {
         assert_eq!(isqrt_u64(square), root as u64);
         assert!(is_square_u64(square));
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_isqrt_near_u64_limits() {
+    // Neighbours of large squares, where floating point guesses are unreliable
+    for r in [(1u64 << 32) - 1, (1 << 32) - 2, 3_037_000_499, 1 << 31, 4_000_000_007] {
+        let square = r * r;
+        assert_eq!(isqrt_u64(square), r, "Failed for {}²", r);
+        assert_eq!(isqrt_u64(square - 1), r - 1, "Failed for {}² - 1", r);
+        if let Some(above) = square.checked_add(2 * r) {
+            assert_eq!(isqrt_u64(above), r, "Failed for ({} + 1)² - 1", r);
+        }
+        assert!(is_square_u64(square));
+        assert!(!is_square_u64(square - 1));
+    }
+}
+
+#[test]
+fn test_const_evaluation() {
+    const ROOT: u64 = isqrt_u64(1_000_000_007);
+    const TABLE: [u64; 5] = {
+        let mut table = [0; 5];
+        let mut i = 0;
+        while i < table.len() {
+            table[i] = isqrt_u64(10u64.pow(2 * i as u32 + 1));
+            i += 1;
+        }
+        table
+    };
+
+    assert_eq!(ROOT, 31622);
+    const { assert!(is_square_u64(991 * 991)) };
+    const { assert!(pell991::is_valid_pell_d(991)) };
+    assert_eq!(TABLE, [3, 31, 316, 3162, 31622]);
+}